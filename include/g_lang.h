@@ -0,0 +1,51 @@
+/*
+ * The C API of g-lang, exported by libg_lang (see src/capi.rs).
+ *
+ * Engines and values are opaque. Free engines with gl_engine_free, values
+ * the engine returns or the host did not hand over with gl_value_free, and
+ * strings from gl_value_to_string with gl_string_free. An engine must only
+ * be used from one thread at a time.
+ */
+
+#ifndef G_LANG_H
+#define G_LANG_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct GlEngine GlEngine;
+typedef struct GlValue GlValue;
+
+/* A function scripts can call. The arguments stay owned by the engine; the
+ * returned value is handed over to it, and NULL means null. */
+typedef GlValue *(*GlCallback)(void *user_data, const GlValue *const *args, size_t argc);
+
+GlEngine *gl_engine_new(void);
+void gl_engine_free(GlEngine *engine);
+GlValue *gl_engine_eval(GlEngine *engine, const char *source);
+void gl_engine_register_fn(GlEngine *engine, const char *name, GlCallback callback, void *user_data);
+
+GlValue *gl_value_null(void);
+GlValue *gl_value_from_number(double number);
+GlValue *gl_value_from_int(int64_t number);
+GlValue *gl_value_from_string(const char *text);
+GlValue *gl_value_error(const char *message);
+
+bool gl_value_is_error(const GlValue *value);
+bool gl_value_is_null(const GlValue *value);
+bool gl_value_as_number(const GlValue *value, double *out);
+char *gl_value_to_string(const GlValue *value);
+
+void gl_value_free(GlValue *value);
+void gl_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
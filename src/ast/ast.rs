@@ -1,4 +1,5 @@
 use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
 /// A program is a sequence of statements.
@@ -7,7 +8,7 @@ pub type Program = Vec<Stmt>;
 /// Statements — constructs that do not produce a value on their own.
 ///
 /// Includes declarations, control flow, assignments, and expression statements.
-#[derive(PartialEq, Debug, Clone, Hash)]
+#[derive(PartialEq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Stmt {
     /// `let x = expr;`
     LetStmt(Ident, Expr),
@@ -62,10 +63,18 @@ pub enum Stmt {
     ContinueStmt,
     /// `throw expr`
     ThrowStmt(Expr),
+    /// Source line and column of the statement that follows. Only present
+    /// when the program was parsed with markers (`Parser::parse_with_markers`),
+    /// for coverage and the linter.
+    LineMarker(u16, u16),
 }
 
 /// Expressions — constructs that evaluate to an [`Object`].
-#[derive(PartialEq, Debug, Clone, Hash)]
+///
+/// `Clone` and `Drop` are written by hand so that operator chains, which
+/// nest on the left (`a + b + c` is `(a + b) + c`), are handled in a loop
+/// instead of one recursion per operator.
+#[derive(PartialEq, Debug, Hash, Serialize, Deserialize)]
 pub enum Expr {
     IdentExpr(Ident),
     LitExpr(Literal),
@@ -132,8 +141,89 @@ pub enum Expr {
     AwaitExpr(Box<Expr>),
 }
 
+impl Clone for Expr {
+    fn clone(&self) -> Self {
+        match self {
+            Expr::InfixExpr(..) => {
+                let mut operators = Vec::new();
+                let mut left = self;
+                while let Expr::InfixExpr(op, inner, right) = left {
+                    operators.push((op, right));
+                    left = inner;
+                }
+                let mut expr = left.clone();
+                for (op, right) in operators.into_iter().rev() {
+                    expr = Expr::InfixExpr(op.clone(), Box::new(expr), right.clone());
+                }
+                expr
+            }
+            Expr::IdentExpr(ident) => Expr::IdentExpr(ident.clone()),
+            Expr::LitExpr(literal) => Expr::LitExpr(literal.clone()),
+            Expr::PrefixExpr(op, operand) => Expr::PrefixExpr(op.clone(), operand.clone()),
+            Expr::IfExpr { cond, consequence, alternative } => Expr::IfExpr {
+                cond: cond.clone(),
+                consequence: consequence.clone(),
+                alternative: alternative.clone(),
+            },
+            Expr::FnExpr { params, body } => Expr::FnExpr { params: params.clone(), body: body.clone() },
+            Expr::CallExpr { function, arguments } => Expr::CallExpr {
+                function: function.clone(),
+                arguments: arguments.clone(),
+            },
+            Expr::ArrayExpr(items) => Expr::ArrayExpr(items.clone()),
+            Expr::HashExpr(pairs) => Expr::HashExpr(pairs.clone()),
+            Expr::IndexExpr { array, index } => Expr::IndexExpr { array: array.clone(), index: index.clone() },
+            Expr::MethodCallExpr { object, method, arguments } => Expr::MethodCallExpr {
+                object: object.clone(),
+                method: method.clone(),
+                arguments: arguments.clone(),
+            },
+            Expr::StructLiteral { name, fields } => Expr::StructLiteral { name: name.clone(), fields: fields.clone() },
+            Expr::ThisExpr => Expr::ThisExpr,
+            Expr::FieldAccessExpr { object, field } => Expr::FieldAccessExpr {
+                object: object.clone(),
+                field: field.clone(),
+            },
+            Expr::WhileExpr { cond, body } => Expr::WhileExpr { cond: cond.clone(), body: body.clone() },
+            Expr::ForExpr { ident, iterable, body } => Expr::ForExpr {
+                ident: ident.clone(),
+                iterable: iterable.clone(),
+                body: body.clone(),
+            },
+            Expr::CStyleForExpr { init, cond, update, body } => Expr::CStyleForExpr {
+                init: init.clone(),
+                cond: cond.clone(),
+                update: update.clone(),
+                body: body.clone(),
+            },
+            Expr::TryCatchExpr { try_body, catch_ident, catch_body, finally_body } => Expr::TryCatchExpr {
+                try_body: try_body.clone(),
+                catch_ident: catch_ident.clone(),
+                catch_body: catch_body.clone(),
+                finally_body: finally_body.clone(),
+            },
+            Expr::AsyncFnExpr { params, body } => Expr::AsyncFnExpr { params: params.clone(), body: body.clone() },
+            Expr::AwaitExpr(inner) => Expr::AwaitExpr(inner.clone()),
+        }
+    }
+}
+
+impl Drop for Expr {
+    fn drop(&mut self) {
+        // Unlink the chain one operator at a time; each unlinked node has a
+        // placeholder on its left, so dropping it does not descend further.
+        let Expr::InfixExpr(_, left, _) = self else {
+            return;
+        };
+        let mut next = std::mem::replace(&mut **left, Expr::ThisExpr);
+        while let Expr::InfixExpr(_, left, _) = &mut next {
+            next = std::mem::replace(&mut **left, Expr::ThisExpr);
+        }
+    }
+}
+
 /// Runtime literal values as they appear in source.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum Literal {
     IntLiteral(i64),
     BigIntLiteral(BigInt),
@@ -163,7 +253,7 @@ impl Hash for Literal {
 /// a slot index that corresponds to its position in the environment's
 /// `slots` vector. `UNSET` indicates that name-based lookup should be used
 /// instead (e.g. for variables captured from enclosing scopes).
-#[derive(PartialEq, Debug, Eq, Clone, Copy, Hash)]
+#[derive(PartialEq, Debug, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
 pub struct SlotIndex(pub u16);
 
 impl SlotIndex {
@@ -180,7 +270,7 @@ impl SlotIndex {
 /// The `name` field is always populated and serves as the fallback for
 /// name-based lookups. The `slot` field is filled in by the compiler pass
 /// for O(1) access within the correct scope.
-#[derive(PartialEq, Debug, Eq, Clone, Hash)]
+#[derive(PartialEq, Debug, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct Ident {
     pub name: String,
     pub slot: SlotIndex,
@@ -196,7 +286,7 @@ impl Ident {
 }
 
 /// Unary operators.
-#[derive(PartialEq, Debug, Clone, Hash)]
+#[derive(PartialEq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Prefix {
     PrefixPlus,
     PrefixMinus,
@@ -204,7 +294,7 @@ pub enum Prefix {
 }
 
 /// Binary operators.
-#[derive(PartialEq, Debug, Clone, Hash)]
+#[derive(PartialEq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Infix {
     Plus,
     Minus,
@@ -226,6 +316,7 @@ pub enum Infix {
 /// Variants are ordered from lowest to highest binding strength so that
 /// `PartialOrd` comparisons work correctly.
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum Precedence {
     PLowest,
     POr,          // Lowest logical operator
@@ -240,7 +331,7 @@ pub(crate) enum Precedence {
 }
 
 /// Import specifier: `import foo::*`, `import foo::{a, b}`, or `import foo::bar`.
-#[derive(PartialEq, Debug, Clone, Hash)]
+#[derive(PartialEq, Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum ImportItems {
     All,
     Specific(Vec<String>),
@@ -7,5 +7,8 @@
 //! - [`Stmt`](ast::Stmt) — statements that perform actions (declarations, control flow)
 //! - [`SlotIndex`](ast::SlotIndex) — compile-time indices for O(1) variable access
 //! - [`Precedence`](ast::Precedence) — operator precedence levels for the Pratt parser
+//! - [`Visitor`](visitor::Visitor) — read-only traversal used by analyses such as the linter
 
+#[allow(clippy::module_inception)]
 pub mod ast;
+pub mod visitor;
@@ -0,0 +1,143 @@
+//! Read-only traversal of the AST.
+//!
+//! A [`Visitor`] overrides the `visit_*` methods for the nodes it cares
+//! about and calls the matching `walk_*` function to keep descending into
+//! their children. The defaults just walk, so an empty implementation
+//! visits every node.
+
+use crate::ast::ast::{Expr, Stmt};
+
+pub trait Visitor: Sized {
+    /// Called for a whole program, a function body and the body of a
+    /// control-flow construct.
+    fn visit_block(&mut self, block: &[Stmt]) {
+        walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+}
+
+pub fn walk_block<V: Visitor>(visitor: &mut V, block: &[Stmt]) {
+    for stmt in block {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_stmt<V: Visitor>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::LetStmt(_, expr)
+        | Stmt::AssignStmt(_, expr)
+        | Stmt::ReturnStmt(expr)
+        | Stmt::ExprStmt(expr)
+        | Stmt::ExprValueStmt(expr)
+        | Stmt::ThrowStmt(expr) => visitor.visit_expr(expr),
+        Stmt::MultiLetStmt { values, .. } | Stmt::TupleAssignStmt { values, .. } => {
+            for value in values {
+                visitor.visit_expr(value);
+            }
+        }
+        Stmt::FieldAssignStmt { object, value, .. } => {
+            visitor.visit_expr(object);
+            visitor.visit_expr(value);
+        }
+        Stmt::IndexAssignStmt { target, index, value } => {
+            visitor.visit_expr(target);
+            visitor.visit_expr(index);
+            visitor.visit_expr(value);
+        }
+        Stmt::FnStmt { body, .. } => visitor.visit_block(body),
+        Stmt::StructStmt { fields, methods, .. } => {
+            for (_, expr) in fields.iter().chain(methods) {
+                visitor.visit_expr(expr);
+            }
+        }
+        Stmt::ImportStmt { .. } | Stmt::BreakStmt | Stmt::ContinueStmt | Stmt::LineMarker(..) => {}
+    }
+}
+
+pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::IdentExpr(_) | Expr::LitExpr(_) | Expr::ThisExpr => {}
+        Expr::PrefixExpr(_, operand) | Expr::AwaitExpr(operand) => visitor.visit_expr(operand),
+        Expr::InfixExpr(_, left, right) => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::IfExpr { cond, consequence, alternative } => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(consequence);
+            if let Some(alternative) = alternative {
+                visitor.visit_block(alternative);
+            }
+        }
+        Expr::FnExpr { body, .. } | Expr::AsyncFnExpr { body, .. } => visitor.visit_block(body),
+        Expr::CallExpr { function, arguments } => {
+            visitor.visit_expr(function);
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::MethodCallExpr { object, arguments, .. } => {
+            visitor.visit_expr(object);
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::ArrayExpr(items) => {
+            for item in items {
+                visitor.visit_expr(item);
+            }
+        }
+        Expr::HashExpr(pairs) => {
+            for (key, value) in pairs {
+                visitor.visit_expr(key);
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::IndexExpr { array, index } => {
+            visitor.visit_expr(array);
+            visitor.visit_expr(index);
+        }
+        Expr::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::FieldAccessExpr { object, .. } => visitor.visit_expr(object),
+        Expr::WhileExpr { cond, body } => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(body);
+        }
+        Expr::ForExpr { iterable, body, .. } => {
+            visitor.visit_expr(iterable);
+            visitor.visit_block(body);
+        }
+        Expr::CStyleForExpr { init, cond, update, body } => {
+            if let Some(init) = init {
+                visitor.visit_stmt(init);
+            }
+            if let Some(cond) = cond {
+                visitor.visit_expr(cond);
+            }
+            if let Some(update) = update {
+                visitor.visit_stmt(update);
+            }
+            visitor.visit_block(body);
+        }
+        Expr::TryCatchExpr { try_body, catch_body, finally_body, .. } => {
+            visitor.visit_block(try_body);
+            if let Some(catch_body) = catch_body {
+                visitor.visit_block(catch_body);
+            }
+            if let Some(finally_body) = finally_body {
+                visitor.visit_block(finally_body);
+            }
+        }
+    }
+}
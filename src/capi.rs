@@ -0,0 +1,251 @@
+//! A C interface to [`Engine`], for hosts written in C, C++, Go, Python or
+//! anything else that can call a shared library.
+//!
+//! Building the crate produces `libg_lang.so` (`g_lang.dll`, `libg_lang.dylib`)
+//! next to the Rust library; `include/g_lang.h` declares what it exports.
+//! Engines and values are opaque pointers the host frees with
+//! [`gl_engine_free`] and [`gl_value_free`], and strings returned to it are
+//! freed with [`gl_string_free`]. An engine must only be used from one
+//! thread at a time, and registered callbacks run on the thread that calls
+//! [`gl_engine_eval`].
+
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::ptr;
+
+use crate::engine::Engine;
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// An engine with the runtime its async parts run on.
+pub struct GlEngine {
+    engine: Engine,
+    runtime: tokio::runtime::Runtime,
+}
+
+/// The result of an evaluation, an argument passed to a callback or what a
+/// callback returns.
+pub enum GlValue {
+    Value(Object),
+    Error(String),
+}
+
+/// A function the host registers: it gets `user_data` as registered and the
+/// `argc` arguments, which stay owned by the engine, and returns a value it
+/// hands over to the engine, or null for `null`.
+pub type GlCallback = extern "C" fn(user_data: *mut c_void, args: *const *const GlValue, argc: usize) -> *mut GlValue;
+
+/// `user_data` crosses into closures that must be `Send + Sync`; the host
+/// promised to use the engine from one thread at a time.
+#[derive(Clone, Copy)]
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    // A method, so closures capture the wrapper rather than the pointer.
+    fn get(self) -> *mut c_void {
+        self.0
+    }
+}
+
+fn into_raw(value: GlValue) -> *mut GlValue {
+    Box::into_raw(Box::new(value))
+}
+
+/// `text` as a C string the host frees with [`gl_string_free`]. NUL bytes,
+/// which C strings cannot hold, are left out.
+fn c_string(text: String) -> *mut c_char {
+    CString::new(text.replace('\0', "")).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Creates an engine, or returns null if its runtime could not be started.
+#[unsafe(no_mangle)]
+pub extern "C" fn gl_engine_new() -> *mut GlEngine {
+    match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => Box::into_raw(Box::new(GlEngine { engine: Engine::new(), runtime })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Frees an engine created by [`gl_engine_new`]. Null is ignored.
+///
+/// # Safety
+///
+/// `engine` must be null or a pointer from [`gl_engine_new`] not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_engine_free(engine: *mut GlEngine) {
+    if !engine.is_null() {
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// Runs the UTF-8 source `source` and returns the value of its last
+/// expression, or an error value saying why it failed. Like
+/// [`Engine::eval`], what it defines stays in the engine.
+///
+/// # Safety
+///
+/// `engine` must come from [`gl_engine_new`] and `source` must be a valid
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_engine_eval(engine: *mut GlEngine, source: *const c_char) -> *mut GlValue {
+    let GlEngine { engine, runtime } = unsafe { &mut *engine };
+    let Ok(source) = unsafe { CStr::from_ptr(source) }.to_str() else {
+        return into_raw(GlValue::Error("source is not valid UTF-8".to_string()));
+    };
+    match runtime.block_on(engine.eval(source)) {
+        Ok(value) => into_raw(GlValue::Value(value)),
+        Err(e) => into_raw(GlValue::Error(e.to_string())),
+    }
+}
+
+/// Makes `callback` callable from scripts as the global function `name`,
+/// like [`Engine::register_fn`]. An error value it returns fails the call
+/// with that message.
+///
+/// # Safety
+///
+/// `engine` must come from [`gl_engine_new`] and `name` must be a valid
+/// NUL-terminated string. `user_data` must stay valid for as long as the
+/// engine lives.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_engine_register_fn(
+    engine: *mut GlEngine,
+    name: *const c_char,
+    callback: GlCallback,
+    user_data: *mut c_void,
+) {
+    let engine = unsafe { &mut *engine };
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+    let user_data = UserData(user_data);
+    engine.engine.register_fn(&name, move |args| {
+        let args: Vec<GlValue> = args.into_iter().map(GlValue::Value).collect();
+        let pointers: Vec<*const GlValue> = args.iter().map(|arg| arg as *const GlValue).collect();
+        let result = callback(user_data.get(), pointers.as_ptr(), pointers.len());
+        if result.is_null() {
+            return Ok(Object::Null);
+        }
+        match *unsafe { Box::from_raw(result) } {
+            GlValue::Value(value) => Ok(value),
+            GlValue::Error(message) => Err(RuntimeError::InvalidOperation(message)),
+        }
+    });
+}
+
+/// A `null` value.
+#[unsafe(no_mangle)]
+pub extern "C" fn gl_value_null() -> *mut GlValue {
+    into_raw(GlValue::Value(Object::Null))
+}
+
+/// A float value.
+#[unsafe(no_mangle)]
+pub extern "C" fn gl_value_from_number(number: f64) -> *mut GlValue {
+    into_raw(GlValue::Value(Object::Float(number)))
+}
+
+/// An integer value.
+#[unsafe(no_mangle)]
+pub extern "C" fn gl_value_from_int(number: i64) -> *mut GlValue {
+    into_raw(GlValue::Value(Object::Integer(number)))
+}
+
+/// A string value holding a copy of `text`; invalid UTF-8 is replaced.
+///
+/// # Safety
+///
+/// `text` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_from_string(text: *const c_char) -> *mut GlValue {
+    let text = unsafe { CStr::from_ptr(text) }.to_string_lossy().into_owned();
+    into_raw(GlValue::Value(Object::from(text)))
+}
+
+/// An error value, for a callback to fail with `message`.
+///
+/// # Safety
+///
+/// `message` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_error(message: *const c_char) -> *mut GlValue {
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned();
+    into_raw(GlValue::Error(message))
+}
+
+/// Whether `value` is an error.
+///
+/// # Safety
+///
+/// `value` must be a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_is_error(value: *const GlValue) -> bool {
+    matches!(unsafe { &*value }, GlValue::Error(_))
+}
+
+/// Whether `value` is `null`.
+///
+/// # Safety
+///
+/// `value` must be a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_is_null(value: *const GlValue) -> bool {
+    matches!(unsafe { &*value }, GlValue::Value(Object::Null))
+}
+
+/// Stores the number `value` holds in `out` and returns true, or returns
+/// false if it is not an integer or a float.
+///
+/// # Safety
+///
+/// `value` must be a live value and `out` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_as_number(value: *const GlValue, out: *mut f64) -> bool {
+    let number = match unsafe { &*value } {
+        GlValue::Value(Object::Integer(i)) => *i as f64,
+        GlValue::Value(Object::Float(f)) => *f,
+        _ => return false,
+    };
+    unsafe { *out = number };
+    true
+}
+
+/// `value` as text, the way `println` prints it, or an error's message.
+/// Free it with [`gl_string_free`].
+///
+/// # Safety
+///
+/// `value` must be a live value.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_to_string(value: *const GlValue) -> *mut c_char {
+    match unsafe { &*value } {
+        GlValue::Value(value) => c_string(value.to_string()),
+        GlValue::Error(message) => c_string(message.clone()),
+    }
+}
+
+/// Frees a value the engine returned or one the host made and did not hand
+/// over. Null is ignored.
+///
+/// # Safety
+///
+/// `value` must be null or a value owned by the host.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_value_free(value: *mut GlValue) {
+    if !value.is_null() {
+        drop(unsafe { Box::from_raw(value) });
+    }
+}
+
+/// Frees a string returned by [`gl_value_to_string`]. Null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string from [`gl_value_to_string`] not freed
+/// yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gl_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
@@ -0,0 +1,345 @@
+//! Embedding G-lang in a Rust program.
+//!
+//! An [`Engine`] runs source code the way `gl run` does, but returns the
+//! result or the error instead of printing it. It keeps one global
+//! environment across calls, so what one call defines the next can use:
+//!
+//! ```no_run
+//! use g_lang::Engine;
+//! use g_lang::vm::obj::Object;
+//!
+//! # async fn demo() -> Result<(), g_lang::LangError> {
+//! let mut engine = Engine::new();
+//! engine.eval("fn double(x) { return x * 2; }").await?;
+//! assert_eq!(engine.eval("double(21)").await?, Object::Integer(42));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Functions a script defines can be called back with [`Engine::call`], or
+//! [`Engine::call_sync`] from code that is not async.
+//! Before running untrusted code, take away what it should not reach with
+//! [`Engine::set_capabilities`] and bound what it may use with
+//! [`Engine::set_limits`].
+//! The host can give scripts functions of its own with
+//! [`Engine::register_fn`] and [`Engine::register_async_fn`], and hand them
+//! Rust values with methods of their own through a [`NativeType`].
+
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::ast::ast::Program;
+use crate::lexer::lexer::{Lexer, LexerError};
+use crate::lexer::token::{Location, Token, Tokens};
+use crate::parser::await_ctx_helpers::validate_await_usage;
+use crate::parser::depth_check::with_nesting_limit;
+use crate::parser::parser::Parser;
+use crate::parser::parser_errors::convert_nom_error;
+use crate::vm::compiler::Compiler;
+use crate::vm::limits::{ActiveLimits, ResourceLimits};
+use crate::vm::ops::calls::call_async_function_vm;
+use crate::vm::obj::{NativeCallable, NativeFunctionData, NativeMethods, NativeObject, Object};
+use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::module_registry::ModuleRegistry;
+use crate::vm::runtime::runtime_errors::{ParserError, RuntimeError};
+use crate::vm::sandbox::Capabilities;
+use crate::vm::vm::VirtualMachine;
+
+/// Why an [`Engine`] could not run some source code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LangError {
+    Lexer(LexerError),
+    Parser(ParserError),
+    Compiler(String),
+    /// The script failed while running, threw an exception nothing caught,
+    /// or called `exit` ([`RuntimeError::Exit`]).
+    Runtime(RuntimeError),
+}
+
+impl LangError {
+    /// Where in the source the error was found, if known.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            LangError::Lexer(e) => Some(e.location()),
+            LangError::Parser(e) => e.location(),
+            LangError::Runtime(RuntimeError::ModuleError { location, .. }) => *location,
+            LangError::Compiler(_) | LangError::Runtime(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for LangError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LangError::Lexer(e) => write!(f, "{}", e),
+            LangError::Parser(e) => write!(f, "{}", e),
+            LangError::Compiler(e) => write!(f, "{}", e),
+            LangError::Runtime(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LangError {}
+
+impl From<LexerError> for LangError {
+    fn from(e: LexerError) -> Self {
+        LangError::Lexer(e)
+    }
+}
+
+impl From<ParserError> for LangError {
+    fn from(e: ParserError) -> Self {
+        LangError::Parser(e)
+    }
+}
+
+impl From<RuntimeError> for LangError {
+    fn from(e: RuntimeError) -> Self {
+        LangError::Runtime(e)
+    }
+}
+
+/// An interpreter for a host program to run scripts in.
+pub struct Engine {
+    globals: Arc<Mutex<Environment>>,
+    module_registry: Arc<Mutex<ModuleRegistry>>,
+    limits: ResourceLimits,
+    /// Runtime of [`Engine::call_sync`], started on its first use.
+    sync_runtime: Option<tokio::runtime::Runtime>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    /// An engine with only the builtins defined, resolving imports from the
+    /// current directory and without resource limits.
+    pub fn new() -> Self {
+        Engine {
+            globals: Arc::new(Mutex::new(Environment::new_root())),
+            module_registry: Arc::new(Mutex::new(ModuleRegistry::new(PathBuf::from(".")))),
+            limits: ResourceLimits::default(),
+            sync_runtime: None,
+        }
+    }
+
+    /// Resolves `import`s of user modules from `path` from now on.
+    pub fn set_base_path(&mut self, path: impl Into<PathBuf>) {
+        self.module_registry.lock().unwrap().base_path = path.into();
+    }
+
+    /// Enforces `limits` on every later call, each on its own.
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+    }
+
+    /// Rejects sources, and modules they import, that nest expressions more
+    /// than `limit` deep, instead of the default of
+    /// [`DEFAULT_MAX_NESTING_DEPTH`](crate::parser::depth_check::DEFAULT_MAX_NESTING_DEPTH).
+    pub fn set_max_nesting_depth(&mut self, limit: usize) {
+        self.module_registry.lock().unwrap().max_nesting_depth = limit;
+    }
+
+    /// Kills a `plugin::` module that takes longer than `timeout` to answer a
+    /// call, and fails the call, instead of waiting the default 30 seconds.
+    pub fn set_plugin_timeout(&mut self, timeout: Duration) {
+        self.module_registry.lock().unwrap().plugin_timeout = timeout;
+    }
+
+    /// Allows scripts only `capabilities` from now on: imports of modules
+    /// that need another fail, and so do calls to the stdlib functions that
+    /// need one. Modules already imported stay as they are.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.module_registry.lock().unwrap().set_capabilities(capabilities);
+    }
+
+    /// Makes `func` callable from scripts as the global function `name`,
+    /// replacing any global of that name, builtins included. It gets the
+    /// arguments as passed, so it checks their number and types itself;
+    /// an error it returns fails the call like a builtin's does.
+    pub fn register_fn<F>(&mut self, name: &str, func: F)
+    where
+        F: Fn(Vec<Object>) -> Result<Object, RuntimeError> + Send + Sync + 'static,
+    {
+        self.register(name, NativeCallable::Sync(Arc::new(func)));
+    }
+
+    /// Like [`Engine::register_fn`] for a function that returns a future.
+    /// Scripts call it like an `async fn`: the call gives a future to
+    /// `await`.
+    pub fn register_async_fn<F, Fut>(&mut self, name: &str, func: F)
+    where
+        F: Fn(Vec<Object>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Object, RuntimeError>> + Send + 'static,
+    {
+        self.register(name, NativeCallable::Async(Arc::new(move |args| Box::pin(func(args)))));
+    }
+
+    /// Frees the environments of closures that only keep each other alive,
+    /// returning how many were freed. The engine also does this on its own
+    /// as scripts create environments; call it to release memory at once,
+    /// for example after a large script is done.
+    pub fn collect_cycles(&self) -> usize {
+        let gc = Arc::clone(&self.module_registry.lock().unwrap().gc);
+        gc.collect_cycles()
+    }
+
+    fn register(&mut self, name: &str, func: NativeCallable) {
+        let function = Object::NativeFunction(Box::new(NativeFunctionData { name: name.to_string(), func }));
+        self.globals.lock().unwrap().set_by_name(name, function);
+    }
+
+    /// Runs `source` and returns the value of its last expression statement,
+    /// or `null`. Top-level definitions stay in the engine for later calls.
+    pub async fn eval(&mut self, source: &str) -> Result<Object, LangError> {
+        let max_nesting_depth = self.module_registry.lock().unwrap().max_nesting_depth;
+        let mut program = with_nesting_limit(max_nesting_depth, || parse(source))?;
+        let chunk = Compiler::compile_program(&mut program).map_err(|e| LangError::Compiler(e.to_string()))?;
+
+        let mut vm = VirtualMachine::new(Arc::clone(&self.globals), Arc::clone(&self.module_registry));
+        if self.limits != ResourceLimits::default() {
+            vm.set_limits(self.limits.clone());
+        }
+        let result = vm.run(Arc::new(chunk)).await;
+        finish(result)
+    }
+
+    /// Calls the global function `name`, `fn` or `async fn`, with `args` and
+    /// returns what it returns. The call runs like one from a script, under
+    /// the engine's limits and with its globals.
+    pub async fn call(&mut self, name: &str, args: Vec<Object>) -> Result<Object, LangError> {
+        let function = self.globals.lock().unwrap().get_by_name(name);
+        let data = match function {
+            Some(Object::Function(data) | Object::AsyncFunction(data)) => data,
+            Some(other) => {
+                return Err(RuntimeError::TypeMismatch { expected: "function".to_string(), got: other.type_name() }.into());
+            }
+            None => return Err(RuntimeError::UndefinedVariable(name.to_string()).into()),
+        };
+        let limits = (self.limits != ResourceLimits::default()).then(|| Arc::new(ActiveLimits::new(self.limits.clone())));
+        let result =
+            call_async_function_vm(*data, args, Arc::clone(&self.module_registry), Arc::clone(&self.globals), limits)
+                .await;
+        finish(result)
+    }
+
+    /// Blocking twin of [`Engine::call`] for hosts without an async runtime:
+    /// runs the call to completion on a single-threaded runtime the engine
+    /// starts on first use and keeps. Tasks the call spawns only make
+    /// progress while a `call_sync` is running. Like any `block_on`, it
+    /// panics if called from inside a tokio runtime; use [`Engine::call`]
+    /// there.
+    pub fn call_sync(&mut self, name: &str, args: Vec<Object>) -> Result<Object, LangError> {
+        let runtime = match self.sync_runtime.take() {
+            Some(runtime) => runtime,
+            None => {
+                let mut builder = tokio::runtime::Builder::new_current_thread();
+                // The timer reads the clock, which wasm32 does not have.
+                #[cfg(not(target_arch = "wasm32"))]
+                builder.enable_all();
+                builder
+                    .build()
+                    .map_err(|e| RuntimeError::InvalidOperation(format!("Could not start the runtime: {}", e)))?
+            }
+        };
+        let result = runtime.block_on(self.call(name, args));
+        self.sync_runtime = Some(runtime);
+        result
+    }
+}
+
+/// A Rust type whose values scripts can hold and call methods on.
+///
+/// [`NativeType::wrap`] turns a value into an [`Object`] that scripts see as
+/// an opaque handle of type `name`. The host gets the value back with
+/// [`Object::downcast_native`]. Methods get a shared reference, so a value
+/// that scripts change needs interior mutability:
+///
+/// ```
+/// use std::sync::Mutex;
+/// use g_lang::NativeType;
+/// use g_lang::vm::obj::Object;
+///
+/// let counter = NativeType::<Mutex<i64>>::new("Counter").method("incr", |count, _| {
+///     let mut count = count.lock().unwrap();
+///     *count += 1;
+///     Ok(Object::Integer(*count))
+/// });
+/// let handle = counter.wrap(Mutex::new(0));
+/// assert_eq!(handle.type_name(), "Counter");
+/// ```
+pub struct NativeType<T> {
+    methods: Arc<NativeMethods>,
+    marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for NativeType<T> {
+    fn clone(&self) -> Self {
+        NativeType { methods: Arc::clone(&self.methods), marker: PhantomData }
+    }
+}
+
+impl<T: Any + Send + Sync> NativeType<T> {
+    /// A type without methods, named `name` in `type()` and messages.
+    pub fn new(name: &str) -> Self {
+        let methods = NativeMethods { type_name: name.to_string(), methods: Default::default() };
+        NativeType { methods: Arc::new(methods), marker: PhantomData }
+    }
+
+    /// Adds the method `name`, replacing one of that name. Values already
+    /// wrapped keep the methods they were wrapped with.
+    pub fn method<F>(mut self, name: &str, method: F) -> Self
+    where
+        F: Fn(&T, Vec<Object>) -> Result<Object, RuntimeError> + Send + Sync + 'static,
+    {
+        let type_name = self.methods.type_name.clone();
+        let method = move |value: &(dyn Any + Send + Sync), args| match value.downcast_ref::<T>() {
+            Some(value) => method(value, args),
+            None => Err(RuntimeError::TypeMismatch { expected: type_name.clone(), got: "native value".to_string() }),
+        };
+        Arc::make_mut(&mut self.methods).methods.insert(name.to_string(), Arc::new(method));
+        self
+    }
+
+    /// Wraps `value` for scripts.
+    pub fn wrap(&self, value: T) -> Object {
+        Object::Native(Box::new(NativeObject { value: Arc::new(value), methods: Arc::clone(&self.methods) }))
+    }
+}
+
+/// Turns what a run returned into the engine's result.
+fn finish(result: Result<Object, RuntimeError>) -> Result<Object, LangError> {
+    match result? {
+        Object::Error(e) => Err(LangError::Runtime(*e)),
+        Object::ThrownValue(value) => Err(LangError::Runtime(RuntimeError::UncaughtException(value.to_string()))),
+        value => Ok(value),
+    }
+}
+
+/// Lexes and parses `source` without printing anything.
+fn parse(source: &str) -> Result<Program, LangError> {
+    let spanned_tokens = Lexer::lex_tokens(source.as_bytes())?;
+    let token_vec: Vec<Token> = spanned_tokens.iter().map(|s| s.node.clone()).collect();
+    let tokens = Tokens::new(&token_vec);
+
+    let program = match Parser::parse_syntax(tokens) {
+        Ok((_, program)) => program,
+        Err(e) => {
+            let error_index = match &e {
+                nom::Err::Error(err) | nom::Err::Failure(err) => tokens.token.len() - err.input.token.len(),
+                nom::Err::Incomplete(_) => tokens.token.len(),
+            };
+            return Err(convert_nom_error(&e, "", &spanned_tokens, error_index).into());
+        }
+    };
+    validate_await_usage(&program)?;
+    Ok(program)
+}
@@ -0,0 +1,150 @@
+//! Syntax highlighting built on the lexer.
+//!
+//! [`highlight`] splits source into [`Highlight`]s so editor plugins and the
+//! REPL can colour code exactly the way the lexer reads it. Unlike
+//! [`Lexer::lex_tokens`](super::lexer::Lexer::lex_tokens) it keeps comments
+//! and never fails: an unterminated string is highlighted up to the end of
+//! its line, and characters no token starts with are left out.
+
+use crate::lexer::lexer::{LexerState, lex_next};
+use crate::lexer::token::{Span, Token};
+
+/// What a piece of source is, for choosing its colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    /// Reserved words, plus `true`, `false` and `null`.
+    Keyword,
+    /// String literals, quotes included.
+    String,
+    Number,
+    /// `//` comments, up to the end of the line.
+    Comment,
+    Identifier,
+    Operator,
+    /// Brackets, `,`, `:`, `;`, `.` and `::`.
+    Punctuation,
+}
+
+impl HighlightKind {
+    /// Lowercase name of the kind, e.g. for a CSS class or theme scope.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HighlightKind::Keyword => "keyword",
+            HighlightKind::String => "string",
+            HighlightKind::Number => "number",
+            HighlightKind::Comment => "comment",
+            HighlightKind::Identifier => "identifier",
+            HighlightKind::Operator => "operator",
+            HighlightKind::Punctuation => "punctuation",
+        }
+    }
+}
+
+/// A highlighted piece of source. Spans use the lexer's 1-based lines and
+/// columns and end just past the last character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub kind: HighlightKind,
+    pub span: Span,
+}
+
+fn kind_of(token: &Token) -> Option<HighlightKind> {
+    let kind = match token {
+        Token::Ident(_) => HighlightKind::Identifier,
+        Token::StringLiteral(_) => HighlightKind::String,
+        Token::IntLiteral(_) | Token::BigIntLiteral(_) | Token::FloatLiteral(_) => HighlightKind::Number,
+        Token::BoolLiteral(_)
+        | Token::NullLiteral
+        | Token::If
+        | Token::Else
+        | Token::Function
+        | Token::Let
+        | Token::Return
+        | Token::Struct
+        | Token::This
+        | Token::Import
+        | Token::While
+        | Token::For
+        | Token::In
+        | Token::Break
+        | Token::Continue
+        | Token::Try
+        | Token::Catch
+        | Token::Finally
+        | Token::Throw
+        | Token::Async
+        | Token::Await => HighlightKind::Keyword,
+        Token::Assign
+        | Token::PlusAssign
+        | Token::MinusAssign
+        | Token::MultiplyAssign
+        | Token::DivideAssign
+        | Token::ModuloAssign
+        | Token::Plus
+        | Token::Minus
+        | Token::Divide
+        | Token::Multiply
+        | Token::Modulo
+        | Token::Equal
+        | Token::NotEqual
+        | Token::GreaterThanEqual
+        | Token::LessThanEqual
+        | Token::GreaterThan
+        | Token::LessThan
+        | Token::And
+        | Token::Or
+        | Token::Not => HighlightKind::Operator,
+        Token::Comma
+        | Token::Colon
+        | Token::SemiColon
+        | Token::LParen
+        | Token::RParen
+        | Token::LBrace
+        | Token::RBrace
+        | Token::LBracket
+        | Token::RBracket
+        | Token::Dot
+        | Token::DoubleColon => HighlightKind::Punctuation,
+        Token::Illegal | Token::EOF => return None,
+    };
+    Some(kind)
+}
+
+/// Classifies every token and comment in `source`, in source order.
+pub fn highlight(source: &str) -> Vec<Highlight> {
+    let mut state = LexerState::new(source.as_bytes());
+    let mut highlights = Vec::new();
+
+    loop {
+        let remaining = state.current();
+        let Some(&first) = remaining.first() else {
+            break;
+        };
+        if first.is_ascii_whitespace() {
+            state.advance_char();
+            continue;
+        }
+
+        let start = state.location();
+        let kind = if remaining.starts_with(b"//") {
+            while state.current().first().is_some_and(|&b| b != b'\n') {
+                state.advance_char();
+            }
+            Some(HighlightKind::Comment)
+        } else {
+            match lex_next(&mut state) {
+                Some(Ok(token)) => kind_of(&token.node),
+                // The lexer stops an unterminated string at the end of its line.
+                Some(Err(_)) => Some(HighlightKind::String),
+                None => {
+                    state.advance_char();
+                    None
+                }
+            }
+        };
+        if let Some(kind) = kind {
+            highlights.push(Highlight { kind, span: Span::new(start, state.location()) });
+        }
+    }
+    highlights
+}
@@ -21,7 +21,7 @@ use std::str::FromStr;
 
 use crate::lexer::token::{Location, Span, Spanned, Token};
 
-struct LexerState<'a> {
+pub(super) struct LexerState<'a> {
     input: &'a [u8],
     pos: usize,
     line: usize,
@@ -29,7 +29,7 @@ struct LexerState<'a> {
 }
 
 impl<'a> LexerState<'a> {
-    fn new(input: &'a [u8]) -> Self {
+    pub(super) fn new(input: &'a [u8]) -> Self {
         Self {
             input,
             pos: 0,
@@ -38,7 +38,7 @@ impl<'a> LexerState<'a> {
         }
     }
 
-    fn current(&self) -> &'a [u8] {
+    pub(super) fn current(&self) -> &'a [u8] {
         &self.input[self.pos..]
     }
 
@@ -73,7 +73,7 @@ impl<'a> LexerState<'a> {
         }
     }
 
-    fn advance_char(&mut self) {
+    pub(super) fn advance_char(&mut self) {
         if let Some(c) = std::str::from_utf8(&self.input[self.pos..])
             .ok()
             .and_then(|s| s.chars().next())
@@ -117,7 +117,7 @@ impl<'a> LexerState<'a> {
         }
     }
 
-    fn location(&self) -> Location {
+    pub(super) fn location(&self) -> Location {
         Location::new(self.line, self.column)
     }
 
@@ -352,7 +352,7 @@ fn parse_number(state: &mut LexerState) -> Option<Spanned<Token>> {
     }
 
     let num_str = std::str::from_utf8(&state.input[start_pos..pos]).ok()?;
-    state.pos = pos;
+    state.advance(pos - start_pos);
     let end = state.location();
 
     if has_dot {
@@ -377,6 +377,13 @@ fn parse_number(state: &mut LexerState) -> Option<Spanned<Token>> {
 fn lex_token(state: &mut LexerState) -> Option<Result<Spanned<Token>, LexerError>> {
     state.skip_whitespace_and_comments();
 
+    lex_next(state)
+}
+
+/// Lexes the token starting exactly at the current position, which must not
+/// be whitespace or a comment. `None` if nothing is left or no token starts
+/// here.
+pub(super) fn lex_next(state: &mut LexerState) -> Option<Result<Spanned<Token>, LexerError>> {
     if state.current().is_empty() {
         return None;
     }
@@ -424,6 +431,23 @@ pub enum LexerError {
     UnterminatedString(Location),
 }
 
+impl LexerError {
+    /// Where in the source the error was found.
+    pub fn location(&self) -> Location {
+        match self {
+            LexerError::UnexpectedCharacter(_, loc) | LexerError::UnterminatedString(loc) => *loc,
+        }
+    }
+
+    /// Stable identifier of the kind of error, for `--error-format=json`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerError::UnexpectedCharacter(..) => "unexpected-character",
+            LexerError::UnterminatedString(_) => "unterminated-string",
+        }
+    }
+}
+
 impl std::fmt::Display for LexerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -8,6 +8,9 @@
 //!
 //! - `lexer` — the main [`Lexer`] type with `lex_tokens` entry point
 //! - `token` — the [`Token`] enum and the [`Tokens`](token::Tokens) wrapper
+//! - `highlight` — classifies source into highlight categories for editors
 
+pub mod highlight;
+#[allow(clippy::module_inception)]
 pub mod lexer;
 pub mod token;
@@ -9,15 +9,38 @@
 //!    and produces an [`ast::Program`] (a vector of [`ast::Stmt`])
 //! 3. **Compiler** — compiles AST into bytecode chunks for the VM
 //! 4. **VM** — executes bytecode with a stack-based virtual machine
+//!
+//! [`Engine`] runs the whole pipeline for programs that embed the language;
+//! [`capi`] offers it to hosts in other languages.
+//!
+//! The crate also builds for `wasm32-unknown-unknown`, without the CLI, the
+//! language server and the stdlib modules that need the file system or the
+//! network; [`playground`] is what the browser calls.
 
+// Some helpers are only used by the CLI, which a wasm32 build leaves out.
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
 
 pub mod ast;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod capi;
+pub mod engine;
 pub mod lexer;
+pub mod lint;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lsp;
+pub mod package;
 pub mod parser;
+pub mod playground;
 pub mod std;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod runners;
+#[cfg(feature = "wasm")]
 pub mod wasm;
 pub mod vm;
 
+pub use engine::{Engine, LangError, NativeType};
+pub use lexer::lexer::LexerError;
+pub use vm::runtime::runtime_errors::{ParserError, RuntimeError};
+
 #[cfg(test)]
 mod tests;
\ No newline at end of file
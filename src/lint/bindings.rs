@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use crate::ast::ast::{Expr, Ident, ImportItems, Infix, Stmt};
+use crate::ast::visitor::{Visitor, walk_block, walk_expr, walk_stmt};
+use crate::lexer::token::Location;
+use crate::lint::{Report, Rule};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Variable,
+    Import,
+    /// Functions, structs, parameters, loop and catch variables: never
+    /// reported as unused.
+    Other,
+}
+
+struct Binding {
+    name: String,
+    kind: Kind,
+    used: bool,
+    /// The struct this variable was last assigned a literal of.
+    struct_name: Option<String>,
+    /// The statement that declared it.
+    location: Option<Location>,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: Vec<Binding>,
+    /// Names read somewhere inside this scope that were not declared yet at
+    /// that point, e.g. a global used by a function declared above it.
+    late_reads: HashSet<String>,
+}
+
+/// Resolves every name to its declaration through nested block scopes, the
+/// way `compute_slots` does, to find unused variables and imports, names
+/// that shadow an outer binding and `==` on struct values.
+///
+/// Names starting with `_` are never reported.
+#[derive(Default)]
+pub(super) struct Bindings {
+    pub(super) report: Report,
+    scopes: Vec<Scope>,
+}
+
+fn literal_struct(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::StructLiteral { name, .. } => Some(name.name.clone()),
+        _ => None,
+    }
+}
+
+impl Bindings {
+    fn pop_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            for binding in scope.bindings {
+                self.report_unused(binding, &scope.late_reads);
+            }
+        }
+    }
+
+    fn report_unused(&mut self, binding: Binding, late_reads: &HashSet<String>) {
+        if binding.used || binding.name.starts_with('_') || late_reads.contains(&binding.name) {
+            return;
+        }
+        match binding.kind {
+            Kind::Variable => {
+                self.report.warn_at(Rule::UnusedVariable, format!("variable `{}` is never used", binding.name), binding.location)
+            }
+            Kind::Import => {
+                self.report.warn_at(Rule::UnusedImport, format!("import `{}` is never used", binding.name), binding.location)
+            }
+            Kind::Other => {}
+        }
+    }
+
+    fn declare(&mut self, name: &str, kind: Kind, struct_name: Option<String>) {
+        let Some((current, enclosing)) = self.scopes.split_last_mut() else {
+            return;
+        };
+        if !name.starts_with('_') && enclosing.iter().any(|scope| scope.bindings.iter().any(|b| b.name == name)) {
+            self.report.warn(
+                Rule::ShadowedName,
+                format!("`{}` shadows a binding of the same name in an enclosing scope", name),
+            );
+        }
+
+        let previous = current.bindings.iter().position(|b| b.name == name).map(|i| current.bindings.remove(i));
+        let location = self.report.location;
+        current.bindings.push(Binding { name: name.to_string(), kind, used: false, struct_name, location });
+        if let Some(previous) = previous {
+            let late_reads = std::mem::take(&mut self.scopes.last_mut().unwrap().late_reads);
+            self.report_unused(previous, &late_reads);
+            self.scopes.last_mut().unwrap().late_reads = late_reads;
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes.iter_mut().rev().find_map(|scope| scope.bindings.iter_mut().rev().find(|b| b.name == name))
+    }
+
+    fn read(&mut self, name: &str) {
+        match self.lookup(name) {
+            Some(binding) => binding.used = true,
+            None => {
+                for scope in &mut self.scopes {
+                    scope.late_reads.insert(name.to_string());
+                }
+            }
+        }
+    }
+
+    fn struct_of(&mut self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::IdentExpr(ident) => self.lookup(&ident.name).and_then(|b| b.struct_name.clone()),
+            _ => literal_struct(expr),
+        }
+    }
+
+    fn visit_function(&mut self, params: &[Ident], body: &[Stmt]) {
+        self.scopes.push(Scope::default());
+        for param in params {
+            self.declare(&param.name, Kind::Other, None);
+        }
+        self.visit_block(body);
+        self.pop_scope();
+    }
+}
+
+impl Visitor for Bindings {
+    fn visit_block(&mut self, block: &[Stmt]) {
+        let location = self.report.location;
+        self.scopes.push(Scope::default());
+        walk_block(self, block);
+        self.pop_scope();
+        self.report.location = location;
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.report.track(stmt);
+        match stmt {
+            // Declared before the body so recursive calls resolve to it.
+            Stmt::LetStmt(ident, value @ (Expr::FnExpr { .. } | Expr::AsyncFnExpr { .. })) => {
+                self.declare(&ident.name, Kind::Other, None);
+                self.report.functions.push(ident.name.clone());
+                self.visit_expr(value);
+                self.report.functions.pop();
+            }
+            Stmt::LetStmt(ident, value) => {
+                self.visit_expr(value);
+                self.declare(&ident.name, Kind::Variable, literal_struct(value));
+            }
+            Stmt::MultiLetStmt { idents, values } => {
+                for value in values {
+                    self.visit_expr(value);
+                }
+                for (i, ident) in idents.iter().enumerate() {
+                    self.declare(&ident.name, Kind::Variable, values.get(i).and_then(literal_struct));
+                }
+            }
+            Stmt::AssignStmt(ident, value) => {
+                self.visit_expr(value);
+                if let Some(binding) = self.lookup(&ident.name) {
+                    binding.struct_name = literal_struct(value);
+                }
+            }
+            Stmt::FnStmt { name, params, body } => {
+                self.declare(&name.name, Kind::Other, None);
+                self.report.functions.push(name.name.clone());
+                self.visit_function(params, body);
+                self.report.functions.pop();
+            }
+            Stmt::StructStmt { name, .. } => {
+                self.declare(&name.name, Kind::Other, None);
+                walk_stmt(self, stmt);
+            }
+            Stmt::ImportStmt { path, items } => match items {
+                ImportItems::All => {
+                    if let Some(module) = path.last() {
+                        self.declare(module, Kind::Import, None);
+                    }
+                }
+                ImportItems::Specific(names) => {
+                    for name in names {
+                        self.declare(name, Kind::Import, None);
+                    }
+                }
+                ImportItems::Single(name) => self.declare(name, Kind::Import, None),
+            },
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::IdentExpr(ident) => self.read(&ident.name),
+            Expr::StructLiteral { name, .. } => {
+                self.read(&name.name);
+                walk_expr(self, expr);
+            }
+            Expr::FnExpr { params, body } | Expr::AsyncFnExpr { params, body } => self.visit_function(params, body),
+            Expr::ForExpr { ident, iterable, body } => {
+                self.visit_expr(iterable);
+                self.scopes.push(Scope::default());
+                for ident in ident {
+                    self.declare(&ident.name, Kind::Other, None);
+                }
+                self.visit_block(body);
+                self.pop_scope();
+            }
+            // `init` declares into a scope around the whole loop.
+            Expr::CStyleForExpr { .. } => {
+                self.scopes.push(Scope::default());
+                walk_expr(self, expr);
+                self.pop_scope();
+            }
+            Expr::TryCatchExpr { try_body, catch_ident, catch_body, finally_body } => {
+                self.visit_block(try_body);
+                if let Some(catch_body) = catch_body {
+                    self.scopes.push(Scope::default());
+                    if let Some(ident) = catch_ident {
+                        self.declare(&ident.name, Kind::Other, None);
+                    }
+                    self.visit_block(catch_body);
+                    self.pop_scope();
+                }
+                if let Some(finally_body) = finally_body {
+                    self.visit_block(finally_body);
+                }
+            }
+            Expr::InfixExpr(op @ (Infix::Equal | Infix::NotEqual), left, right) => {
+                if let Some(name) = self.struct_of(left).or_else(|| self.struct_of(right)) {
+                    let op = if *op == Infix::Equal { "==" } else { "!=" };
+                    self.report.warn(
+                        Rule::StructEquality,
+                        format!("`{}` on struct `{}` compares every field by value; compare the fields that identify it instead", op, name),
+                    );
+                }
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Per-project lint settings.
+//!
+//! `gl lint` reads them from [`CONFIG_FILE`] in the current directory, or
+//! from the file given with `--config`. It is an INI file whose `[rules]`
+//! section turns rules on or off by name:
+//!
+//! ```ini
+//! [rules]
+//! shadowed-name = off
+//! struct-equality = off
+//! ```
+
+use std::path::Path;
+
+use crate::lint::Rule;
+use crate::vm::obj::Object;
+
+/// The file `gl lint` looks for when no `--config` is given.
+pub const CONFIG_FILE: &str = "gllint.ini";
+
+/// Which rules to run. Every rule is enabled by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    disabled: Vec<Rule>,
+}
+
+impl LintConfig {
+    pub fn is_enabled(&self, rule: Rule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    pub fn set_enabled(&mut self, rule: Rule, enabled: bool) {
+        self.disabled.retain(|r| *r != rule);
+        if !enabled {
+            self.disabled.push(rule);
+        }
+    }
+
+    /// Parses the contents of a config file.
+    pub fn parse(text: &str) -> Result<LintConfig, String> {
+        let mut config = LintConfig::default();
+        let root = crate::std::ini::parse(text).map_err(|e| e.to_string())?;
+        let Object::Hash(root) = root else {
+            return Ok(config);
+        };
+        let rules = match root.get(&Object::String("rules".into())) {
+            Some(Object::Hash(rules)) => rules,
+            Some(_) => return Err("'rules' must be a section".to_string()),
+            None => return Ok(config),
+        };
+
+        for (key, value) in rules.iter() {
+            let Object::String(name) = key else {
+                continue;
+            };
+            let rule = Rule::from_name(name).ok_or_else(|| format!("unknown lint rule '{}'", name))?;
+            let enabled = match value {
+                Object::String(s) if matches!(s.as_str(), "on" | "true" | "yes") => true,
+                Object::String(s) if matches!(s.as_str(), "off" | "false" | "no") => false,
+                _ => return Err(format!("rule '{}' must be 'on' or 'off', got '{}'", name, value)),
+            };
+            config.set_enabled(rule, enabled);
+        }
+        Ok(config)
+    }
+
+    /// Reads the config file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        LintConfig::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
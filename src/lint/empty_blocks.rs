@@ -0,0 +1,62 @@
+use crate::ast::ast::{Expr, Stmt};
+use crate::ast::visitor::{Visitor, walk_block, walk_expr, walk_stmt};
+use crate::lint::{Report, Rule, declared_function};
+
+/// Flags control-flow constructs whose body is empty. Function bodies are
+/// left alone: an empty function is usually a deliberate stub.
+#[derive(Default)]
+pub(super) struct EmptyBlocks {
+    pub(super) report: Report,
+}
+
+impl EmptyBlocks {
+    fn check(&mut self, body: &[Stmt], what: &str) {
+        if body.is_empty() {
+            self.report.warn(Rule::EmptyBlock, format!("empty {}", what));
+        }
+    }
+}
+
+impl Visitor for EmptyBlocks {
+    fn visit_block(&mut self, block: &[Stmt]) {
+        let location = self.report.location;
+        walk_block(self, block);
+        self.report.location = location;
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.report.track(stmt);
+        let function = declared_function(stmt);
+        if let Some(name) = function {
+            self.report.functions.push(name.to_string());
+        }
+        walk_stmt(self, stmt);
+        if function.is_some() {
+            self.report.functions.pop();
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::IfExpr { consequence, alternative, .. } => {
+                self.check(consequence, "`if` block");
+                if let Some(alternative) = alternative {
+                    self.check(alternative, "`else` block");
+                }
+            }
+            Expr::WhileExpr { body, .. } => self.check(body, "`while` loop body"),
+            Expr::ForExpr { body, .. } | Expr::CStyleForExpr { body, .. } => self.check(body, "`for` loop body"),
+            Expr::TryCatchExpr { try_body, catch_body, finally_body, .. } => {
+                self.check(try_body, "`try` block");
+                if let Some(catch_body) = catch_body {
+                    self.check(catch_body, "`catch` block, the error is silently ignored");
+                }
+                if let Some(finally_body) = finally_body {
+                    self.check(finally_body, "`finally` block");
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,151 @@
+//! Linter — flags code that runs but is probably a mistake.
+//!
+//! Each group of rules is an AST [`Visitor`](crate::ast::visitor::Visitor)
+//! run over the program as parsed, before the optimizer rewrites it:
+//!
+//! - `bindings` — resolves names through nested scopes: unused variables
+//!   and imports, shadowed names, `==` on structs
+//! - `unreachable` — statements after `return`, `break`, `continue` or `throw`
+//! - `empty_blocks` — control-flow bodies with nothing in them
+//!
+//! Every rule is on by default; [`LintConfig`] turns rules off. Warnings
+//! carry the position of the statement they are about when the program was
+//! parsed with line markers, as `gl lint` does.
+
+mod bindings;
+pub mod config;
+mod empty_blocks;
+mod unreachable;
+
+use std::fmt;
+
+use crate::ast::ast::{Expr, Program, Stmt};
+use crate::ast::visitor::Visitor;
+use crate::lexer::token::Location;
+
+pub use config::LintConfig;
+
+/// A check the linter can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    UnusedVariable,
+    UnusedImport,
+    ShadowedName,
+    UnreachableCode,
+    EmptyBlock,
+    StructEquality,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Rule::UnusedVariable,
+        Rule::UnusedImport,
+        Rule::ShadowedName,
+        Rule::UnreachableCode,
+        Rule::EmptyBlock,
+        Rule::StructEquality,
+    ];
+
+    /// The rule's name in configuration files and lint output.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::UnusedVariable => "unused-variable",
+            Rule::UnusedImport => "unused-import",
+            Rule::ShadowedName => "shadowed-name",
+            Rule::UnreachableCode => "unreachable-code",
+            Rule::EmptyBlock => "empty-block",
+            Rule::StructEquality => "struct-equality",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One problem found by the linter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub rule: Rule,
+    pub message: String,
+    /// Where the statement it is about starts, if the program has markers.
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.rule, self.message)
+    }
+}
+
+/// Runs every rule `config` enables over `program`. Located warnings come in
+/// source order.
+pub fn lint(program: &Program, config: &LintConfig) -> Vec<LintWarning> {
+    let mut bindings = bindings::Bindings::default();
+    bindings.visit_block(program);
+    let mut unreachable = unreachable::Unreachable::default();
+    unreachable.visit_block(program);
+    let mut empty_blocks = empty_blocks::EmptyBlocks::default();
+    empty_blocks.visit_block(program);
+
+    let mut warnings: Vec<LintWarning> = [bindings.report, unreachable.report, empty_blocks.report]
+        .into_iter()
+        .flat_map(|report| report.warnings)
+        .filter(|warning| config.is_enabled(warning.rule))
+        .collect();
+    warnings.sort_by_key(|warning| warning.location.map(|at| (at.line, at.column)));
+    warnings
+}
+
+/// Warnings of one visitor, located by the line marker of the statement
+/// being visited and named after the enclosing function.
+#[derive(Default)]
+struct Report {
+    warnings: Vec<LintWarning>,
+    functions: Vec<String>,
+    location: Option<Location>,
+}
+
+impl Report {
+    fn warn(&mut self, rule: Rule, message: String) {
+        self.warn_at(rule, message, self.location);
+    }
+
+    fn warn_at(&mut self, rule: Rule, message: String, location: Option<Location>) {
+        let message = match self.functions.last() {
+            Some(name) => format!("{} (in fn `{}`)", message, name),
+            None => message,
+        };
+        self.warnings.push(LintWarning { rule, message, location });
+    }
+
+    /// Moves to the statement a line marker announces.
+    fn track(&mut self, stmt: &Stmt) {
+        if let Some(location) = marker_location(stmt) {
+            self.location = Some(location);
+        }
+    }
+}
+
+fn marker_location(stmt: &Stmt) -> Option<Location> {
+    match stmt {
+        Stmt::LineMarker(line, column) => Some(Location::new(*line as usize, *column as usize)),
+        _ => None,
+    }
+}
+
+/// The name of the function `stmt` declares, if it declares one: `fn f()`
+/// or `async fn f()`, which the parser turns into a `let`.
+fn declared_function(stmt: &Stmt) -> Option<&str> {
+    match stmt {
+        Stmt::FnStmt { name, .. } => Some(&name.name),
+        Stmt::LetStmt(name, Expr::FnExpr { .. } | Expr::AsyncFnExpr { .. }) => Some(&name.name),
+        _ => None,
+    }
+}
@@ -0,0 +1,49 @@
+use crate::ast::ast::Stmt;
+use crate::ast::visitor::{Visitor, walk_block, walk_stmt};
+use crate::lint::{Report, Rule, declared_function, marker_location};
+
+/// Flags statements that follow a `return`, `break`, `continue` or `throw`
+/// in the same block.
+#[derive(Default)]
+pub(super) struct Unreachable {
+    pub(super) report: Report,
+}
+
+fn terminator(stmt: &Stmt) -> Option<&'static str> {
+    match stmt {
+        Stmt::ReturnStmt(_) => Some("return"),
+        Stmt::BreakStmt => Some("break"),
+        Stmt::ContinueStmt => Some("continue"),
+        Stmt::ThrowStmt(_) => Some("throw"),
+        _ => None,
+    }
+}
+
+impl Visitor for Unreachable {
+    fn visit_block(&mut self, block: &[Stmt]) {
+        let ends = block[..block.len().saturating_sub(1)]
+            .iter()
+            .enumerate()
+            .find_map(|(index, stmt)| Some((index, terminator(stmt)?)));
+        if let Some((index, keyword)) = ends {
+            // At the first statement that is never run.
+            let location = block[index + 1..].iter().find_map(marker_location);
+            self.report.warn_at(Rule::UnreachableCode, format!("code after `{}` is never run", keyword), location);
+        }
+        let location = self.report.location;
+        walk_block(self, block);
+        self.report.location = location;
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.report.track(stmt);
+        let function = declared_function(stmt);
+        if let Some(name) = function {
+            self.report.functions.push(name.to_string());
+        }
+        walk_stmt(self, stmt);
+        if function.is_some() {
+            self.report.functions.pop();
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! What the language server knows about a document, read off its tokens.
+//!
+//! The AST carries no source positions, so definitions and imports are
+//! found by matching token patterns (`let x`, `fn f(a, b)`, `import a::b;`,
+//! ...). Names are resolved without scopes: a use refers to the closest
+//! definition of the name before it, or failing that the first one after
+//! it, which is what a function called above its declaration needs.
+
+use crate::lexer::lexer::Lexer;
+use crate::lexer::token::{Location, Span, Spanned, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DefinitionKind {
+    Variable,
+    Parameter,
+    Function,
+    Struct,
+    Import,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Definition {
+    pub name: String,
+    pub kind: DefinitionKind,
+    pub span: Span,
+    /// How the definition reads in a hover, e.g. `let x: integer`.
+    pub detail: String,
+    /// Nesting depth in braces; 0 for the top level of the file.
+    pub depth: usize,
+}
+
+/// One `import a::b;` or `import a::b::{x, y};` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Import {
+    /// The module path, e.g. `["std", "math"]`.
+    pub path: Vec<String>,
+    /// The names it binds: the module itself for `import a::b;`, or each
+    /// listed name for `import a::b::{x, y};`.
+    pub names: Vec<String>,
+    pub selective: bool,
+}
+
+impl Import {
+    pub fn module_path(&self) -> String {
+        self.path.join("::")
+    }
+
+    pub fn is_std(&self) -> bool {
+        self.path.first().map(String::as_str) == Some("std")
+    }
+}
+
+pub(crate) struct Analysis {
+    pub tokens: Vec<Spanned<Token>>,
+    pub definitions: Vec<Definition>,
+    pub imports: Vec<Import>,
+}
+
+/// Lexes `text`. A line the lexer rejects (typically a string still being
+/// typed) is blanked out and lexing retried, so one bad line does not hide
+/// every token in the file.
+pub(crate) fn lex(text: &str) -> Vec<Spanned<Token>> {
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    for _ in 0..lines.len() + 1 {
+        match Lexer::lex_tokens(lines.join("\n").as_bytes()) {
+            Ok(tokens) => return tokens,
+            Err(e) => {
+                let Some(line) = lines.get_mut(e.location().line.saturating_sub(1)) else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    break;
+                }
+                *line = " ".repeat(line.chars().count());
+            }
+        }
+    }
+    Vec::new()
+}
+
+fn ident(token: Option<&Spanned<Token>>) -> Option<&str> {
+    match token.map(|t| &t.node) {
+        Some(Token::Ident(name)) => Some(name),
+        _ => None,
+    }
+}
+
+/// Names the type of the value starting at `tokens[0]`, when it is a
+/// literal that nothing else follows.
+fn literal_type(tokens: &[Spanned<Token>]) -> Option<String> {
+    let ends = |i: usize| matches!(tokens.get(i).map(|t| &t.node), Some(Token::SemiColon) | None);
+    let type_name = match &tokens.first()?.node {
+        Token::IntLiteral(_) if ends(1) => "integer",
+        Token::BigIntLiteral(_) if ends(1) => "bigInteger",
+        Token::FloatLiteral(_) if ends(1) => "float",
+        Token::StringLiteral(_) if ends(1) => "string",
+        Token::BoolLiteral(_) if ends(1) => "boolean",
+        Token::NullLiteral if ends(1) => "null",
+        Token::LBracket => "array",
+        Token::LBrace => "hash",
+        Token::Ident(name) if matches!(tokens.get(1).map(|t| &t.node), Some(Token::LBrace)) => {
+            return Some(format!("struct {}", name));
+        }
+        _ => return None,
+    };
+    Some(type_name.to_string())
+}
+
+/// The identifiers between the `(` at `tokens[open]` and its `)`, with
+/// the index of the `)`.
+fn parenthesized_idents(tokens: &[Spanned<Token>], open: usize) -> (Vec<&Spanned<Token>>, usize) {
+    let mut idents = Vec::new();
+    let mut i = open + 1;
+    while let Some(token) = tokens.get(i) {
+        match &token.node {
+            Token::RParen => break,
+            Token::Ident(_) => idents.push(token),
+            _ => {}
+        }
+        i += 1;
+    }
+    (idents, i)
+}
+
+impl Analysis {
+    pub fn new(text: &str) -> Analysis {
+        let tokens = lex(text);
+        let mut analysis = Analysis { tokens, definitions: Vec::new(), imports: Vec::new() };
+        analysis.scan();
+        analysis
+    }
+
+    fn define(&mut self, token: &Spanned<Token>, kind: DefinitionKind, detail: String, depth: usize) {
+        if let Token::Ident(name) = &token.node {
+            self.definitions.push(Definition { name: name.clone(), kind, span: token.span, detail, depth });
+        }
+    }
+
+    fn scan(&mut self) {
+        let tokens = std::mem::take(&mut self.tokens);
+        let mut depth = 0usize;
+        for (i, token) in tokens.iter().enumerate() {
+            match &token.node {
+                Token::LBrace => depth += 1,
+                Token::RBrace => depth = depth.saturating_sub(1),
+                Token::Let => self.scan_let(&tokens, i, depth),
+                Token::Function => self.scan_fn(&tokens, i, depth),
+                Token::Struct => self.scan_struct(&tokens, i, depth),
+                Token::Import => self.scan_import(&tokens, i, depth),
+                Token::For | Token::Catch => self.scan_loop_or_catch(&tokens, i, depth),
+                _ => {}
+            }
+        }
+        self.tokens = tokens;
+    }
+
+    /// `for (x in ...)`, `for ((k, v) in ...)` and `catch (e)` bind names
+    /// inside the block that follows.
+    fn scan_loop_or_catch(&mut self, tokens: &[Spanned<Token>], i: usize, depth: usize) {
+        if !matches!(tokens.get(i + 1).map(|t| &t.node), Some(Token::LParen)) {
+            return;
+        }
+        let header = &tokens[i + 2..];
+        let names: Vec<&Spanned<Token>> = if tokens[i].node == Token::Catch {
+            header.iter().take(1).filter(|t| matches!(t.node, Token::Ident(_))).collect()
+        } else {
+            let end = header.iter().position(|t| matches!(t.node, Token::In | Token::SemiColon | Token::LBrace));
+            match end {
+                Some(end) if header[end].node == Token::In => {
+                    header[..end].iter().filter(|t| matches!(t.node, Token::Ident(_))).collect()
+                }
+                _ => Vec::new(),
+            }
+        };
+        for name in names {
+            let detail = format!("let {}", ident(Some(name)).unwrap_or_default());
+            self.define(name, DefinitionKind::Variable, detail, depth + 1);
+        }
+    }
+
+    fn scan_let(&mut self, tokens: &[Spanned<Token>], i: usize, depth: usize) {
+        match tokens.get(i + 1).map(|t| &t.node) {
+            Some(Token::Ident(name)) => {
+                let value = tokens.get(i + 3..).unwrap_or_default();
+                let detail = match value.first().map(|t| &t.node) {
+                    Some(Token::Function) | Some(Token::Async) => {
+                        let open = i + 3 + value.iter().position(|t| t.node == Token::LParen).unwrap_or(0);
+                        let (params, _) = parenthesized_idents(tokens, open);
+                        let params: Vec<&str> = params.iter().filter_map(|t| ident(Some(t))).collect();
+                        let prefix = if value[0].node == Token::Async { "async fn" } else { "fn" };
+                        format!("{} {}({})", prefix, name, params.join(", "))
+                    }
+                    _ => match literal_type(value) {
+                        Some(type_name) => format!("let {}: {}", name, type_name),
+                        None => format!("let {}", name),
+                    },
+                };
+                let kind = if detail.starts_with("let") { DefinitionKind::Variable } else { DefinitionKind::Function };
+                self.define(&tokens[i + 1], kind, detail, depth);
+            }
+            // `let (a, b) = ...`
+            Some(Token::LParen) => {
+                for name in parenthesized_idents(tokens, i + 1).0 {
+                    let detail = format!("let {}", ident(Some(name)).unwrap_or_default());
+                    self.define(name, DefinitionKind::Variable, detail, depth);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scan_fn(&mut self, tokens: &[Spanned<Token>], i: usize, depth: usize) {
+        let name = ident(tokens.get(i + 1));
+        let open = if name.is_some() { i + 2 } else { i + 1 };
+        if !matches!(tokens.get(open).map(|t| &t.node), Some(Token::LParen)) {
+            return;
+        }
+        let (params, _) = parenthesized_idents(tokens, open);
+        let param_names: Vec<&str> = params.iter().filter_map(|t| ident(Some(t))).collect();
+
+        // `let f = fn(...)` is recorded by `scan_let`.
+        if let Some(name) = name {
+            let is_async = i > 0 && tokens[i - 1].node == Token::Async;
+            let prefix = if is_async { "async fn" } else { "fn" };
+            let detail = format!("{} {}({})", prefix, name, param_names.join(", "));
+            self.define(&tokens[i + 1], DefinitionKind::Function, detail, depth);
+        }
+        for param in params {
+            let detail = format!("parameter {}", ident(Some(param)).unwrap_or_default());
+            self.define(param, DefinitionKind::Parameter, detail, depth + 1);
+        }
+    }
+
+    fn scan_struct(&mut self, tokens: &[Spanned<Token>], i: usize, depth: usize) {
+        if ident(tokens.get(i + 1)).is_none() || !matches!(tokens.get(i + 2).map(|t| &t.node), Some(Token::LBrace)) {
+            return;
+        }
+        // Fields are the `name:` pairs directly inside the struct's braces.
+        let mut fields = Vec::new();
+        let mut inner = 0usize;
+        for (j, token) in tokens.iter().enumerate().skip(i + 3) {
+            match &token.node {
+                Token::LBrace | Token::LParen | Token::LBracket => inner += 1,
+                Token::RBrace if inner == 0 => break,
+                Token::RBrace | Token::RParen | Token::RBracket => inner = inner.saturating_sub(1),
+                Token::Ident(field) if inner == 0 && matches!(tokens.get(j + 1).map(|t| &t.node), Some(Token::Colon)) => {
+                    fields.push(field.as_str());
+                }
+                _ => {}
+            }
+        }
+        let detail = format!("struct {} {{ {} }}", ident(tokens.get(i + 1)).unwrap_or_default(), fields.join(", "));
+        self.define(&tokens[i + 1], DefinitionKind::Struct, detail, depth);
+    }
+
+    fn scan_import(&mut self, tokens: &[Spanned<Token>], i: usize, depth: usize) {
+        let mut path = Vec::new();
+        let mut listed = Vec::new();
+        let mut selective = false;
+        for token in &tokens[i + 1..] {
+            match &token.node {
+                Token::Ident(_) if selective => listed.push(token),
+                Token::Ident(name) => {
+                    path.push(name.clone());
+                    listed = vec![token];
+                }
+                Token::LBrace => {
+                    selective = true;
+                    listed.clear();
+                }
+                Token::DoubleColon | Token::Comma | Token::RBrace => {}
+                _ => break,
+            }
+        }
+        if path.is_empty() {
+            return;
+        }
+
+        let import = Import {
+            names: listed.iter().filter_map(|t| ident(Some(t))).map(str::to_string).collect(),
+            path,
+            selective,
+        };
+        for name in listed {
+            let detail = if selective {
+                format!("import {}::{{{}}}", import.module_path(), ident(Some(name)).unwrap_or_default())
+            } else {
+                format!("import {}", import.module_path())
+            };
+            self.define(name, DefinitionKind::Import, detail, depth);
+        }
+        self.imports.push(import);
+    }
+
+    /// The index of the token under `at`; a cursor just past the end of a
+    /// token counts as on it.
+    pub fn token_at(&self, at: Location) -> Option<usize> {
+        let on_line = |t: &&Spanned<Token>| t.span.start.line == at.line && t.node != Token::EOF;
+        self.tokens
+            .iter()
+            .position(|t| on_line(&t) && t.span.start.column <= at.column && at.column < t.span.end.column)
+            .or_else(|| self.tokens.iter().rposition(|t| on_line(&t) && t.span.end.column == at.column))
+    }
+
+    /// The definition a use of `name` at `at` refers to.
+    pub fn resolve(&self, name: &str, at: Location) -> Option<&Definition> {
+        let before = |d: &&Definition| (d.span.start.line, d.span.start.column) <= (at.line, at.column);
+        let mut candidates = self.definitions.iter().filter(|d| d.name == name);
+        candidates.clone().rfind(before).or_else(|| candidates.find(|d| !before(d)))
+    }
+
+    /// The import that binds `name`, if any.
+    pub fn import_binding(&self, name: &str) -> Option<&Import> {
+        self.imports.iter().find(|import| import.names.iter().any(|n| n == name))
+    }
+
+    /// Definitions at the top level of the file, which is what importing
+    /// it makes available.
+    pub fn top_level(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter().filter(|d| d.depth == 0 && d.kind != DefinitionKind::Import)
+    }
+}
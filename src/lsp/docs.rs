@@ -0,0 +1,51 @@
+//! Hover documentation for keywords and global builtins.
+
+/// Reserved words, offered as completions.
+pub(crate) const KEYWORDS: &[&str] = &[
+    "let", "fn", "if", "else", "return", "struct", "this", "import", "true", "false", "null", "while", "for", "in",
+    "break", "continue", "try", "catch", "finally", "throw", "async", "await",
+];
+
+/// Signature and one-line description of a global builtin, in markdown.
+pub(crate) fn builtin_doc(name: &str) -> Option<&'static str> {
+    let doc = match name {
+        "print" => "print(value, ...)\n\nWrites the values separated by spaces, without a newline.",
+        "println" => "println(value, ...)\n\nWrites the values separated by spaces, followed by a newline.",
+        "printf" => "printf(format, value, ...)\n\nWrites `format` with each `{}` replaced by the next value.",
+        "input" => "input(prompt?)\n\nReads a line from standard input, after printing `prompt`.",
+        "inspect" => "inspect(value)\n\nReturns a debug representation of `value` as a string.",
+        "type" => "type(value)\n\nReturns the name of the type of `value`.",
+        "len" => "len(value)\n\nReturns the length of a string, array, hash or bytes.",
+        "is_empty" => "is_empty(value)\n\nReturns whether a string, array, hash or bytes is empty.",
+        "split" => "split(string, delimiter)\n\nSplits `string` at every `delimiter` into an array.",
+        "replace" => "replace(string, old, new)\n\nReplaces every `old` in `string` with `new`.",
+        "trim" => "trim(string)\n\nRemoves leading and trailing whitespace.",
+        "contains" => "contains(collection, item)\n\nReturns whether a string contains a substring or an array contains a value.",
+        "slice" => "slice(value, start, end?)\n\nReturns part of a string or array; negative indexes count from the end.",
+        "head" => "head(array)\n\nReturns the first element of a non-empty array.",
+        "tail" => "tail(array)\n\nReturns every element of a non-empty array but the first.",
+        "cons" => "cons(value, array)\n\nReturns `array` with `value` prepended.",
+        "push" => "push(array, value)\n\nReturns `array` with `value` appended.",
+        "pow" => "pow(base, exponent)\n\nRaises `base` to the power of `exponent`.",
+        "abs" => "abs(number)\n\nReturns the absolute value of `number`.",
+        "min" => "min(a, b)\n\nReturns the smaller of two numbers.",
+        "max" => "max(a, b)\n\nReturns the larger of two numbers.",
+        "keys" => "keys(hash)\n\nReturns the keys of `hash` as an array.",
+        "values" => "values(hash)\n\nReturns the values of `hash` as an array.",
+        "clear" => "clear(collection)\n\nReturns an empty hash or array.",
+        "deep_copy" => "deep_copy(value)\n\nReturns a copy of `value` that shares nothing with it.",
+        "deep_equals" => "deep_equals(a, b)\n\nReturns whether two values are structurally equal.",
+        "range" => "range(end) / range(start, end, step?)\n\nReturns the integers from `start` (default 0) up to, not including, `end`.",
+        "parse_int" => "parse_int(string, radix?)\n\nParses an integer, or returns `null` if `string` is not one.",
+        "parse_float" => "parse_float(string)\n\nParses a float, or returns `null` if `string` is not one.",
+        "bytes" => "bytes(value)\n\nCreates bytes from a string, an array of integers, or a length of zeros.",
+        "ref" => "ref(value)\n\nWraps `value` in a reference that every copy shares.",
+        "exit" => "exit(code?)\n\nEnds the script with exit status `code` (default 0).",
+        "fields" => "fields(struct)\n\nReturns the field names of a struct.",
+        "name" => "name(struct)\n\nReturns the name of a struct's type.",
+        "get_field" => "get_field(struct, name)\n\nReturns the value of the field called `name`.",
+        "set_field" => "set_field(struct, name, value)\n\nReturns `struct` with the field `name` set to `value`.",
+        _ => return None,
+    };
+    Some(doc)
+}
@@ -0,0 +1,478 @@
+//! # Language Server
+//!
+//! `gl lsp` speaks the Language Server Protocol over stdin/stdout so editors
+//! can show diagnostics, hovers, go-to-definition and completions for `.g`
+//! files.
+//!
+//! ## Components
+//!
+//! - **transport**: `Content-Length` framing of JSON-RPC messages
+//! - **analysis**: definitions, imports and the token under the cursor
+//! - **docs**: hover text for keywords and global builtins
+//!
+//! Diagnostics are the ones `gl check` reports, recomputed whenever a
+//! document is opened or changed. Documents are synchronised in full on
+//! every change.
+
+pub(crate) mod analysis;
+pub(crate) mod docs;
+pub(crate) mod transport;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::lexer::token::{Location, Span, Token};
+use crate::lsp::analysis::{Analysis, Definition, DefinitionKind, Import};
+use crate::lsp::docs::{KEYWORDS, builtin_doc};
+use crate::lsp::transport::{read_message, write_message};
+use crate::package::installed_module_file;
+use crate::runners::run_check::{Severity, check_source};
+use crate::vm::obj::Object;
+use crate::vm::runtime::builtins::functions::BuiltinsFunctions;
+use crate::vm::runtime::module_registry::{stdlib_exports, stdlib_module_paths};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// LSP `CompletionItemKind` values.
+const KIND_FUNCTION: u32 = 3;
+const KIND_VARIABLE: u32 = 6;
+const KIND_MODULE: u32 = 9;
+const KIND_KEYWORD: u32 = 14;
+const KIND_CONSTANT: u32 = 21;
+const KIND_STRUCT: u32 = 22;
+
+/// Runs a language server reading requests from `reader` and writing
+/// responses and notifications to `writer`, until the client sends `exit`
+/// or closes the input.
+pub fn serve<R: BufRead, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<()> {
+    let mut server = Server::default();
+    while let Some(message) = read_message(reader)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        if method == "exit" {
+            break;
+        }
+        let params = &message["params"];
+
+        match message.get("id") {
+            Some(id) => {
+                let response = match server.request(method, params) {
+                    Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    None => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": METHOD_NOT_FOUND, "message": format!("unknown method '{}'", method) },
+                    }),
+                };
+                write_message(writer, &response)?;
+            }
+            None => {
+                for notification in server.notify(method, params) {
+                    write_message(writer, &notification)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    /// Text of every open document, by URI.
+    documents: HashMap<String, String>,
+    /// Directory user modules are resolved against, as `gl` resolves them
+    /// against the directory it runs in.
+    root: Option<PathBuf>,
+}
+
+impl Server {
+    /// Handles a request; `None` if the method is not supported.
+    fn request(&mut self, method: &str, params: &Value) -> Option<Value> {
+        let result = match method {
+            "initialize" => {
+                self.root = params["rootUri"].as_str().and_then(uri_to_path);
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": 1,
+                        "hoverProvider": true,
+                        "definitionProvider": true,
+                        "completionProvider": { "triggerCharacters": [".", ":"] },
+                    },
+                    "serverInfo": { "name": "gl", "version": env!("CARGO_PKG_VERSION") },
+                })
+            }
+            "shutdown" => Value::Null,
+            "textDocument/hover" => self.at_position(params, Server::hover),
+            "textDocument/definition" => self.at_position(params, Server::definition),
+            "textDocument/completion" => self.at_position(params, Server::completion),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Handles a notification, returning the notifications to send back.
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.diagnostics(&uri)]
+            }
+            "textDocument/didChange" => {
+                let Some(text) = params["contentChanges"].as_array().and_then(|c| c.last()).and_then(|c| c["text"].as_str())
+                else {
+                    return Vec::new();
+                };
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.diagnostics(&uri)]
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                vec![publish_diagnostics(&uri, Vec::new())]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn diagnostics(&self, uri: &str) -> Value {
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or_default();
+        let diagnostics = check_source(text)
+            .into_iter()
+            .map(|d| {
+                let at = d.location.unwrap_or(Location::new(1, 1));
+                json!({
+                    "range": range(Span { start: at, end: Location::new(at.line, at.column + 1) }),
+                    "severity": if d.severity == Severity::Error { 1 } else { 2 },
+                    "source": "gl",
+                    "message": format!("{}: {}", d.stage, d.message),
+                })
+            })
+            .collect();
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    /// Runs `handler` on the open document and cursor position named in
+    /// `params`.
+    fn at_position(&self, params: &Value, handler: fn(&Server, &Document) -> Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(text) = self.documents.get(uri) else {
+            return Value::Null;
+        };
+        let position = &params["position"];
+        let at = Location::new(
+            position["line"].as_u64().unwrap_or(0) as usize + 1,
+            position["character"].as_u64().unwrap_or(0) as usize + 1,
+        );
+        handler(self, &Document { uri, text, analysis: Analysis::new(text), at })
+    }
+
+    fn hover(&self, doc: &Document) -> Value {
+        let Some(index) = doc.analysis.token_at(doc.at) else {
+            return Value::Null;
+        };
+        let token = &doc.analysis.tokens[index];
+        let markdown = match &token.node {
+            Token::Ident(name) => match doc.member_of(index) {
+                Some(import) => self.member_hover(doc, import, name),
+                None => self.name_hover(doc, name),
+            },
+            Token::IntLiteral(_) => Some("integer".to_string()),
+            Token::BigIntLiteral(_) => Some("bigInteger".to_string()),
+            Token::FloatLiteral(_) => Some("float".to_string()),
+            Token::StringLiteral(_) => Some("string".to_string()),
+            Token::BoolLiteral(_) => Some("boolean".to_string()),
+            Token::NullLiteral => Some("null".to_string()),
+            _ => None,
+        };
+        match markdown {
+            Some(markdown) => json!({
+                "contents": { "kind": "markdown", "value": markdown },
+                "range": range(token.span),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    fn name_hover(&self, doc: &Document, name: &str) -> Option<String> {
+        if let Some(definition) = doc.analysis.resolve(name, doc.at) {
+            let mut markdown = code_block(&definition.detail);
+            if definition.kind == DefinitionKind::Import
+                && let Some(import) = doc.analysis.import_binding(name)
+                && !import.selective
+            {
+                let origin = if import.is_std() { "standard library module" } else { "module" };
+                markdown.push_str(&format!("\n\n{} `{}`", origin, import.module_path()));
+            }
+            return Some(markdown);
+        }
+        let (signature, description) = builtin_doc(name)?.split_once("\n\n")?;
+        Some(format!("{}\n\n{}", code_block(signature), description))
+    }
+
+    /// Hover for `module.name`.
+    fn member_hover(&self, doc: &Document, import: &Import, name: &str) -> Option<String> {
+        let qualified = format!("{}.{}", import.path.last()?, name);
+        if import.is_std() {
+            let export = stdlib_exports(&import.module_path())?.remove(name)?;
+            let described = match export {
+                Object::BuiltinStd(f) => arity(&qualified, f.min_params, f.max_params),
+                Object::BuiltinStdAsync(f) => format!("async {}", arity(&qualified, f.min_params, f.max_params)),
+                value => format!("{}: {} = {}", qualified, value.type_name(), value),
+            };
+            return Some(code_block(&described));
+        }
+        let (_, module) = self.module(doc, import)?;
+        let definition = module.top_level().find(|d| d.name == name)?;
+        Some(format!("{}\n\nfrom module `{}`", code_block(&definition.detail), import.module_path()))
+    }
+
+    fn definition(&self, doc: &Document) -> Value {
+        let Some(index) = doc.analysis.token_at(doc.at) else {
+            return Value::Null;
+        };
+        let Token::Ident(name) = &doc.analysis.tokens[index].node else {
+            return Value::Null;
+        };
+
+        if let Some(import) = doc.member_of(index) {
+            return self.exported_definition(doc, import, name).unwrap_or(Value::Null);
+        }
+        let Some(definition) = doc.analysis.resolve(name, doc.at) else {
+            return Value::Null;
+        };
+        if definition.kind == DefinitionKind::Import
+            && let Some(import) = doc.analysis.import_binding(name)
+            && !import.is_std()
+        {
+            if import.selective {
+                return self.exported_definition(doc, import, name).unwrap_or(Value::Null);
+            }
+            if let Some((uri, _)) = self.module(doc, import) {
+                let start = Location::new(1, 1);
+                return json!({ "uri": uri, "range": range(Span { start, end: start }) });
+            }
+        }
+        json!({ "uri": doc.uri, "range": range(definition.span) })
+    }
+
+    /// Where the user module `import` defines `name` at its top level.
+    fn exported_definition(&self, doc: &Document, import: &Import, name: &str) -> Option<Value> {
+        let (uri, module) = self.module(doc, import)?;
+        let definition: &Definition = module.top_level().find(|d| d.name == name)?;
+        Some(json!({ "uri": uri, "range": range(definition.span) }))
+    }
+
+    fn completion(&self, doc: &Document) -> Value {
+        let line = doc.text.lines().nth(doc.at.line - 1).unwrap_or_default();
+        let before: String = line.chars().take(doc.at.column - 1).collect();
+        let stem = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+
+        let items = if let Some(path) = stem.trim_start().strip_prefix("import ").and_then(|p| p.strip_suffix("::")) {
+            self.module_completions(doc, path.trim())
+        } else if let Some(object) = stem.strip_suffix('.') {
+            let object = &object[object.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_').len()..];
+            match doc.analysis.import_binding(object).filter(|import| !import.selective) {
+                Some(import) => self.member_completions(doc, import),
+                None => Vec::new(),
+            }
+        } else {
+            let mut items: Vec<Value> = Vec::new();
+            let mut seen = Vec::new();
+            for definition in &doc.analysis.definitions {
+                if !seen.contains(&definition.name) {
+                    seen.push(definition.name.clone());
+                    items.push(completion_item(&definition.name, definition_kind(definition.kind), &definition.detail));
+                }
+            }
+            for name in BuiltinsFunctions::BUILTIN_NAMES.iter().filter(|name| !seen.contains(&name.to_string())) {
+                let detail = builtin_doc(name).and_then(|doc| doc.split_once("\n\n")).map_or("builtin", |(sig, _)| sig);
+                items.push(completion_item(name, KIND_FUNCTION, detail));
+            }
+            items.extend(KEYWORDS.iter().map(|keyword| completion_item(keyword, KIND_KEYWORD, "keyword")));
+            items
+        };
+        Value::Array(items)
+    }
+
+    /// The modules that can follow `import path::`.
+    fn module_completions(&self, doc: &Document, path: &str) -> Vec<Value> {
+        if path == "std" {
+            return stdlib_module_paths()
+                .filter_map(|p| p.strip_prefix("std::"))
+                .map(|name| completion_item(name, KIND_MODULE, "standard library module"))
+                .collect();
+        }
+        let mut dir = self.module_root(doc);
+        dir.extend(path.split("::").map(str::trim));
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.is_dir() {
+                    return path.file_name().map(|n| n.to_string_lossy().into_owned());
+                }
+                (path.extension()? == "g").then(|| path.file_stem().unwrap().to_string_lossy().into_owned())
+            })
+            .collect();
+        names.sort();
+        names.iter().map(|name| completion_item(name, KIND_MODULE, "module")).collect()
+    }
+
+    /// The exports of the module bound by `import`.
+    fn member_completions(&self, doc: &Document, import: &Import) -> Vec<Value> {
+        if import.is_std() {
+            let mut exports: Vec<(String, Object)> = stdlib_exports(&import.module_path()).unwrap_or_default().into_iter().collect();
+            exports.sort_by(|a, b| a.0.cmp(&b.0));
+            return exports
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Object::BuiltinStd(f) => completion_item(&name, KIND_FUNCTION, &arity(&name, f.min_params, f.max_params)),
+                    Object::BuiltinStdAsync(f) => {
+                        completion_item(&name, KIND_FUNCTION, &format!("async {}", arity(&name, f.min_params, f.max_params)))
+                    }
+                    value => completion_item(&name, KIND_CONSTANT, &value.type_name()),
+                })
+                .collect();
+        }
+        let Some((_, module)) = self.module(doc, import) else {
+            return Vec::new();
+        };
+        module.top_level().map(|d| completion_item(&d.name, definition_kind(d.kind), &d.detail)).collect()
+    }
+
+    fn module_root(&self, doc: &Document) -> PathBuf {
+        self.root.clone().or_else(|| Some(uri_to_path(doc.uri)?.parent()?.to_path_buf())).unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// The URI and analysis of the user module `import` refers to,
+    /// preferring the editor's copy when the file is open.
+    fn module(&self, doc: &Document, import: &Import) -> Option<(String, Analysis)> {
+        let mut path = self.module_root(doc);
+        for part in &import.path {
+            if part == "super" {
+                path.pop();
+            } else {
+                path.push(part);
+            }
+        }
+        path.set_extension("g");
+        if !path.exists()
+            && !self.documents.contains_key(&path_to_uri(&path))
+            && let Some(installed) = installed_module_file(&self.module_root(doc), &import.path)
+        {
+            path = installed;
+        }
+        let uri = path_to_uri(&path);
+        let text = match self.documents.get(&uri) {
+            Some(text) => text.clone(),
+            None => std::fs::read_to_string(&path).ok()?,
+        };
+        Some((uri, Analysis::new(&text)))
+    }
+}
+
+/// An open document and the cursor position a request is about.
+struct Document<'a> {
+    uri: &'a str,
+    text: &'a str,
+    analysis: Analysis,
+    at: Location,
+}
+
+impl Document<'_> {
+    /// For the name at `tokens[index]` in `module.name`, the import that
+    /// binds `module`.
+    fn member_of(&self, index: usize) -> Option<&Import> {
+        let tokens = &self.analysis.tokens;
+        if index < 2 || tokens[index - 1].node != Token::Dot {
+            return None;
+        }
+        let Token::Ident(object) = &tokens[index - 2].node else {
+            return None;
+        };
+        // A local binding of the same name hides the module.
+        let definition = self.analysis.resolve(object, tokens[index - 2].span.start)?;
+        if definition.kind != DefinitionKind::Import {
+            return None;
+        }
+        self.analysis.import_binding(object).filter(|import| !import.selective)
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// An LSP range, which counts lines and characters from 0.
+fn range(span: Span) -> Value {
+    let position = |at: Location| json!({ "line": at.line.saturating_sub(1), "character": at.column.saturating_sub(1) });
+    json!({ "start": position(span.start), "end": position(span.end) })
+}
+
+fn code_block(code: &str) -> String {
+    format!("```gl\n{}\n```", code)
+}
+
+fn arity(name: &str, min: usize, max: usize) -> String {
+    let args = match (min, max) {
+        (min, max) if min == max => format!("{} argument{}", min, if min == 1 { "" } else { "s" }),
+        (min, usize::MAX) => format!("{} or more arguments", min),
+        (min, max) => format!("{} to {} arguments", min, max),
+    };
+    format!("{}(...) takes {}", name, args)
+}
+
+fn completion_item(label: &str, kind: u32, detail: &str) -> Value {
+    json!({ "label": label, "kind": kind, "detail": detail })
+}
+
+fn definition_kind(kind: DefinitionKind) -> u32 {
+    match kind {
+        DefinitionKind::Variable | DefinitionKind::Parameter => KIND_VARIABLE,
+        DefinitionKind::Function => KIND_FUNCTION,
+        DefinitionKind::Struct => KIND_STRUCT,
+        DefinitionKind::Import => KIND_MODULE,
+    }
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%').then(|| path.get(i + 1..i + 3)).flatten();
+        match escaped.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&decoded).into_owned()))
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
@@ -1,14 +1,174 @@
 use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
+use g_lang::lint::LintConfig;
+use g_lang::lint::config::CONFIG_FILE;
+use g_lang::package::manifest::{MANIFEST_FILE, Manifest};
+use g_lang::parser::depth_check::DEFAULT_MAX_NESTING_DEPTH;
 use g_lang::runners::print_help::print_help;
-use g_lang::runners::run_check::run_check;
-use g_lang::runners::run_source::run_source;
+use g_lang::runners::run_bench::run_bench;
+use g_lang::runners::run_build::{default_output, embedded_program, run_build, run_embedded};
+use g_lang::runners::run_check::{ErrorFormat, error_format, run_check, set_error_format};
+use g_lang::runners::run_install::run_install;
+use g_lang::runners::run_lint::run_lint;
+use g_lang::runners::run_lsp::run_lsp;
+use g_lang::runners::run_new::run_new;
+use g_lang::runners::run_source::{run_file, run_project, run_source_with_limits};
+use g_lang::runners::run_test::run_tests;
 use g_lang::runners::run_repl_mode::repl;
+use g_lang::std::env::set_script_args;
+use g_lang::std::log::set_level as set_log_level;
+use g_lang::vm::coverage::{self, CoverageFormat};
+use g_lang::vm::limits::ResourceLimits;
+use g_lang::vm::profiler::{self, ProfileFormat};
+use g_lang::vm::sandbox::{set_default_capabilities, Capabilities, Capability};
 
 #[tokio::main]
 async fn main() {
-    let args: Vec<String> = env::args().collect();
+    // An executable made by `gl build` runs its script and nothing else.
+    if let Some(program) = embedded_program() {
+        set_script_args(env::args().skip(1).collect());
+        process::exit(run_embedded(program).await);
+    }
+
+    let mut args: Vec<String> = env::args().collect();
+
+    // Everything after `--` belongs to the script, not to `gl`.
+    let script_args = match args.iter().position(|a| a == "--") {
+        Some(pos) => args.split_off(pos).into_iter().skip(1).collect(),
+        None => Vec::new(),
+    };
+    set_script_args(script_args);
+
+    if let Some(pos) = args.iter().position(|a| a == "--log-level") {
+        let level = args.get(pos + 1).cloned().unwrap_or_default();
+        if let Err(e) = set_log_level(&level) {
+            eprintln!("Error: {}", e);
+            process::exit(2);
+        }
+        args.drain(pos..(pos + 2).min(args.len()));
+    }
+
+    if let Some(pos) = args.iter().position(|a| a.starts_with("--error-format=")) {
+        match ErrorFormat::parse(&args[pos]["--error-format=".len()..]) {
+            Ok(format) => set_error_format(format),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(2);
+            }
+        }
+        args.remove(pos);
+    }
+
+    let mut max_depth = DEFAULT_MAX_NESTING_DEPTH;
+    if let Some(pos) = args.iter().position(|a| a == "--max-depth") {
+        match args.get(pos + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(limit) if limit > 0 => max_depth = limit,
+            _ => {
+                eprintln!("Error: --max-depth expects a positive integer");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..(pos + 2).min(args.len()));
+    }
+
+    let mut limits = ResourceLimits::default();
+    if let Some(pos) = args.iter().position(|a| a == "--max-steps") {
+        match args.get(pos + 1).and_then(|n| n.parse::<u64>().ok()) {
+            Some(steps) if steps > 0 => limits.max_steps = Some(steps),
+            _ => {
+                eprintln!("Error: --max-steps expects a positive integer");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..(pos + 2).min(args.len()));
+    }
+
+    if let Some(pos) = args.iter().position(|a| a == "--timeout") {
+        match args.get(pos + 1).and_then(|n| n.parse::<f64>().ok()) {
+            Some(secs) if secs > 0.0 && secs.is_finite() => limits.timeout = Some(Duration::from_secs_f64(secs)),
+            _ => {
+                eprintln!("Error: --timeout expects a positive number of seconds");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..(pos + 2).min(args.len()));
+    }
+
+    if let Some(pos) = args.iter().position(|a| a == "--deny") {
+        let Some(names) = args.get(pos + 1) else {
+            eprintln!("Error: --deny expects a list of capabilities, e.g. --deny fs,net");
+            process::exit(2);
+        };
+        let mut capabilities = Capabilities::all();
+        for name in names.split(',') {
+            match name {
+                "all" => capabilities = Capabilities::none(),
+                name => match Capability::parse(name) {
+                    Ok(capability) => capabilities = capabilities.deny(capability),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        process::exit(2);
+                    }
+                },
+            }
+        }
+        set_default_capabilities(capabilities);
+        args.drain(pos..pos + 2);
+    }
+
+    let mut profile = None;
+    if let Some(pos) = args.iter().position(|a| a == "--profile" || a.starts_with("--profile=")) {
+        let format = args[pos].strip_prefix("--profile=").unwrap_or("text");
+        match ProfileFormat::parse(format) {
+            Ok(format) => profile = Some(format),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(2);
+            }
+        }
+        args.remove(pos);
+    }
+
+    let mut profile_out = None;
+    if let Some(pos) = args.iter().position(|a| a == "--profile-out") {
+        match args.get(pos + 1) {
+            Some(path) => profile_out = Some(path.clone()),
+            None => {
+                eprintln!("Error: --profile-out expects a file path");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..pos + 2);
+    }
+
+    let mut coverage = None;
+    if let Some(pos) = args.iter().position(|a| a == "--coverage" || a.starts_with("--coverage=")) {
+        let format = args[pos].strip_prefix("--coverage=").unwrap_or("text");
+        match CoverageFormat::parse(format) {
+            Ok(format) => coverage = Some(format),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(2);
+            }
+        }
+        args.remove(pos);
+    }
+
+    let mut coverage_out = None;
+    if let Some(pos) = args.iter().position(|a| a == "--coverage-out") {
+        match args.get(pos + 1) {
+            Some(path) => coverage_out = Some(path.clone()),
+            None => {
+                eprintln!("Error: --coverage-out expects a file path");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..pos + 2);
+    }
 
     match args.get(1) {
         Some(flag) if flag == "--version" || flag == "-v" => {
@@ -20,46 +180,239 @@ async fn main() {
             print_help();
         }
 
+        Some(flag) if flag == "-e" || flag == "--eval" => {
+            let Some(code) = args.get(2) else {
+                eprintln!("Error: {} expects a program, e.g. gl -e 'println(1 + 2)'", flag);
+                process::exit(1);
+            };
+            process::exit(run_source_with_limits(code, limits, max_depth).await);
+        }
+
         Some(flag) if flag == "check" => {
-            if let Some(filename) = args.get(2) {
+            let filenames = &args[2..];
+            let mut passed = true;
+            for filename in filenames {
                 if !filename.ends_with(".g") {
                     eprintln!("Error: File must have .g extension");
-                    return;
+                    process::exit(1);
                 }
                 let source = match fs::read_to_string(filename) {
                     Ok(s) => s,
                     Err(e) => {
                         eprintln!("Could not read file {}: {}", filename, e);
-                        return;
+                        process::exit(1);
                     }
                 };
-                run_check(&source);
+                if filenames.len() > 1 && error_format() == ErrorFormat::Human {
+                    println!("{}", filename);
+                }
+                passed &= run_check(&source, filename, max_depth);
+            }
+            if !passed {
+                process::exit(1);
             }
         }
 
-        Some(flag) if flag == "run" => {
-            if let Some(filename) = args.get(2) {
+        Some(flag) if flag == "lint" => {
+            let mut filenames = args[2..].to_vec();
+            let config = match filenames.iter().position(|a| a == "--config") {
+                Some(pos) => {
+                    let Some(path) = filenames.get(pos + 1).cloned() else {
+                        eprintln!("Error: --config expects a file path");
+                        process::exit(1);
+                    };
+                    filenames.drain(pos..pos + 2);
+                    LintConfig::load(Path::new(&path))
+                }
+                None if Path::new(CONFIG_FILE).exists() => LintConfig::load(Path::new(CONFIG_FILE)),
+                None => Ok(LintConfig::default()),
+            };
+            let config = match config {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+
+            let mut passed = true;
+            for filename in &filenames {
                 if !filename.ends_with(".g") {
                     eprintln!("Error: File must have .g extension");
-                    return;
+                    process::exit(1);
                 }
                 let source = match fs::read_to_string(filename) {
                     Ok(s) => s,
                     Err(e) => {
                         eprintln!("Could not read file {}: {}", filename, e);
-                        return;
+                        process::exit(1);
                     }
                 };
+                if filenames.len() > 1 {
+                    println!("{}", filename);
+                }
+                passed &= run_lint(&source, filename, &config, max_depth);
+            }
+            if !passed {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "test" => {
+            if coverage.is_some() {
+                coverage::enable();
+            }
+            let passed = run_tests(&args[2..], max_depth).await;
+
+            if let Some(format) = coverage {
+                let report = coverage::report(format);
+                match &coverage_out {
+                    Some(path) => {
+                        if let Err(e) = fs::write(path, report) {
+                            eprintln!("Could not write coverage to {}: {}", path, e);
+                        }
+                    }
+                    None => {
+                        println!();
+                        print!("{}", report);
+                    }
+                }
+            }
+            if !passed {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "build" => {
+            let mut rest = args[2..].to_vec();
+            let output = match rest.iter().position(|a| a == "-o" || a == "--output") {
+                Some(pos) => {
+                    let Some(path) = rest.get(pos + 1).cloned() else {
+                        eprintln!("Error: -o expects a file path");
+                        process::exit(1);
+                    };
+                    rest.drain(pos..pos + 2);
+                    Some(path)
+                }
+                None => None,
+            };
+            let Some(filename) = rest.first() else {
+                eprintln!("Error: gl build expects a .g file");
+                process::exit(1);
+            };
+            if !filename.ends_with(".g") {
+                eprintln!("Error: File must have .g extension");
+                process::exit(1);
+            }
+            let source = match fs::read_to_string(filename) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Could not read file {}: {}", filename, e);
+                    process::exit(1);
+                }
+            };
+            let output = output.map(PathBuf::from).unwrap_or_else(|| default_output(Path::new(filename)));
+            if !run_build(&source, &output, max_depth) {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "new" => {
+            if !run_new(&args[2..]) {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "install" => {
+            if !run_install(&args[2..]) {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "lsp" => {
+            if let Err(e) = run_lsp() {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
 
-                run_source(&source).await;
+        Some(flag) if flag == "bench" => {
+            let Some(filename) = args.get(2) else {
+                eprintln!("Error: gl bench expects a .g file");
+                process::exit(2);
+            };
+            if !filename.ends_with(".g") {
+                eprintln!("Error: File must have .g extension");
+                process::exit(1);
+            }
+            let source = match fs::read_to_string(filename) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Could not read file {}: {}", filename, e);
+                    process::exit(1);
+                }
+            };
+            if !run_bench(&source, max_depth).await {
+                process::exit(1);
+            }
+        }
+
+        Some(flag) if flag == "run" => {
+            if profile.is_some() {
+                profiler::enable();
+            }
+            let status = match args.get(2) {
+                Some(filename) => {
+                    if !filename.ends_with(".g") {
+                        eprintln!("Error: File must have .g extension");
+                        process::exit(1);
+                    }
+                    let source = match fs::read_to_string(filename) {
+                        Ok(s) => s,
+                        Err(e) => {
+                            eprintln!("Could not read file {}: {}", filename, e);
+                            process::exit(1);
+                        }
+                    };
+                    run_file(filename, &source, limits, max_depth).await
+                }
+                // Without a file, run the entry point of the enclosing project.
+                None => {
+                    let manifest = match Manifest::find(Path::new(".")) {
+                        Some(Ok(manifest)) => manifest,
+                        Some(Err(e)) => {
+                            eprintln!("Error: {}", e);
+                            process::exit(1);
+                        }
+                        None => {
+                            eprintln!("Error: no file given and no {} found", MANIFEST_FILE);
+                            process::exit(1);
+                        }
+                    };
+                    run_project(&manifest, limits, max_depth).await
+                }
+            };
+
+            if let Some(format) = profile {
+                let report = profiler::report(format);
+                match &profile_out {
+                    Some(path) => {
+                        if let Err(e) = fs::write(path, report) {
+                            eprintln!("Could not write profile to {}: {}", path, e);
+                        }
+                    }
+                    None => eprint!("{}", report),
+                }
             }
+            process::exit(status);
         }
 
         Some(arg) => {
             eprintln!("Unknown argument: {}", arg);
             eprintln!("Use --help for usage.");
+            process::exit(2);
         }
 
-        None => repl().await,
+        None => repl(max_depth).await,
     }
 }
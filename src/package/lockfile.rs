@@ -0,0 +1,94 @@
+//! The lockfile written by `gl install`.
+//!
+//! [`LOCKFILE`] records the exact commit every installed package was
+//! checked out at, so `gl install` with no arguments reproduces the same
+//! `giulio_modules/` on another machine. It is an INI file with one section
+//! per package:
+//!
+//! ```ini
+//! [utils]
+//! source = https://github.com/someone/utils.git
+//! rev = 4f1c2a9e0b7d...
+//! ```
+
+use std::path::Path;
+
+use crate::package::is_valid_name;
+use crate::vm::obj::Object;
+
+/// Name of the lockfile in the project root.
+pub const LOCKFILE: &str = "giulio.lock";
+
+/// One installed package, pinned to a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    /// The name it is imported by, and its directory in `giulio_modules/`.
+    pub name: String,
+    /// Git URL it was cloned from.
+    pub source: String,
+    /// Full hash of the commit it is checked out at.
+    pub rev: String,
+}
+
+/// Every package in a lockfile, sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lockfile {
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    /// Parses the contents of a lockfile.
+    pub fn parse(text: &str) -> Result<Lockfile, String> {
+        let root = crate::std::ini::parse(text).map_err(|e| e.to_string())?;
+        let Object::Hash(root) = root else {
+            return Ok(Lockfile::default());
+        };
+
+        let mut lockfile = Lockfile::default();
+        for (key, value) in root.iter() {
+            let (Object::String(name), Object::Hash(section)) = (key, value) else {
+                return Err(format!("'{}' is not a package section", key));
+            };
+            if !is_valid_name(name) {
+                return Err(format!("'{}' is not a valid package name", name));
+            }
+            let field = |field: &str| match section.get(&Object::String(field.into())) {
+                Some(Object::String(value)) if value.starts_with('-') => {
+                    Err(format!("package '{}' has an invalid {} '{}'", name, field, value))
+                }
+                Some(Object::String(value)) => Ok(value.to_string()),
+                _ => Err(format!("package '{}' has no {}", name, field)),
+            };
+            lockfile.insert(LockedPackage { name: name.to_string(), source: field("source")?, rev: field("rev")? });
+        }
+        Ok(lockfile)
+    }
+
+    /// Reads the lockfile at `path`; a missing file is an empty lockfile.
+    pub fn load(path: &Path) -> Result<Lockfile, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Lockfile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Lockfile::default()),
+            Err(e) => Err(format!("could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.render()).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    /// Adds `package`, replacing any package of the same name.
+    pub fn insert(&mut self, package: LockedPackage) {
+        self.packages.retain(|p| p.name != package.name);
+        let index = self.packages.partition_point(|p| p.name < package.name);
+        self.packages.insert(index, package);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("# Generated by `gl install`. Do not edit by hand.\n");
+        for package in &self.packages {
+            out.push_str(&format!("\n[{}]\nsource = {}\nrev = {}\n", package.name, package.source, package.rev));
+        }
+        out
+    }
+}
@@ -0,0 +1,281 @@
+//! The project manifest, [`MANIFEST_FILE`].
+//!
+//! It names the project, says which file `gl run` starts from, where else
+//! imports are looked up and which packages `gl install` should fetch:
+//!
+//! ```toml
+//! [package]
+//! name = "weather"
+//! version = "0.3.1"
+//! entry = "src/main.g"
+//! paths = ["src", "vendor"]
+//!
+//! [dependencies]
+//! utils = "https://github.com/someone/utils.git"
+//! json5 = { git = "https://github.com/someone/json5.git", rev = "v1.2.0" }
+//! ```
+//!
+//! Only the part of TOML a manifest needs is understood: tables, strings,
+//! arrays of strings and inline tables of strings.
+
+use std::path::{Path, PathBuf};
+
+use crate::package::is_valid_name;
+
+/// Name of the manifest in the project root.
+pub const MANIFEST_FILE: &str = "giulio.toml";
+
+/// Entry point used when the manifest does not set `entry`.
+pub const DEFAULT_ENTRY: &str = "main.g";
+
+/// A package the project depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    /// Git URL to clone it from.
+    pub git: String,
+    /// Branch, tag or commit to check out; the default branch if `None`.
+    pub rev: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The directory the manifest is in. Relative paths in it are relative
+    /// to this, and it is where imports are resolved first.
+    pub root: PathBuf,
+    pub name: String,
+    pub version: String,
+    /// The script `gl run` runs, relative to `root`.
+    pub entry: String,
+    /// More directories to resolve imports in, relative to `root`, tried in
+    /// order after `root` itself.
+    pub paths: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Manifest {
+    /// Parses the contents of a manifest found in `root`.
+    pub fn parse(text: &str, root: &Path) -> Result<Manifest, String> {
+        let mut manifest = Manifest {
+            root: root.to_path_buf(),
+            name: String::new(),
+            version: String::new(),
+            entry: DEFAULT_ENTRY.to_string(),
+            paths: Vec::new(),
+            dependencies: Vec::new(),
+        };
+
+        for entry in parse_toml(text)? {
+            let line = entry.line;
+            let error = |message: String| Err(format!("line {}: {}", line, message));
+            match (entry.table.as_str(), entry.key.as_str(), entry.value) {
+                ("package", "name", Value::String(name)) => manifest.name = name,
+                ("package", "version", Value::String(version)) => manifest.version = version,
+                ("package", "entry", Value::String(entry)) => manifest.entry = entry,
+                ("package", "paths", Value::Array(paths)) => manifest.paths = paths,
+                ("package", key @ ("name" | "version" | "entry" | "paths"), _) => {
+                    let expected = if key == "paths" { "an array of strings" } else { "a string" };
+                    return error(format!("package.{} must be {}", key, expected));
+                }
+                ("package", key, _) => return error(format!("unknown key package.{}", key)),
+                ("dependencies", name, value) => {
+                    if !is_valid_name(name) {
+                        return error(format!("'{}' cannot be imported; dependency names must be identifiers", name));
+                    }
+                    let (git, rev) = match value {
+                        Value::String(git) => (git, None),
+                        Value::Table(fields) => {
+                            let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+                            if let Some((key, _)) = fields.iter().find(|(k, _)| k != "git" && k != "rev") {
+                                return error(format!("unknown key '{}' in dependency '{}'", key, name));
+                            }
+                            match field("git") {
+                                Some(git) => (git, field("rev")),
+                                None => return error(format!("dependency '{}' has no git URL", name)),
+                            }
+                        }
+                        Value::Array(_) => return error(format!("dependency '{}' must be a git URL or a table", name)),
+                    };
+                    manifest.dependencies.push(Dependency { name: name.to_string(), git, rev });
+                }
+                ("", key, _) => return error(format!("'{}' must be in the [package] table", key)),
+                (table, _, _) => return error(format!("unknown table [{}]", table)),
+            }
+        }
+
+        if manifest.name.is_empty() {
+            return Err("package.name is missing".to_string());
+        }
+        if manifest.version.is_empty() {
+            return Err("package.version is missing".to_string());
+        }
+        Ok(manifest)
+    }
+
+    /// Reads the manifest in the directory `root`.
+    pub fn load(root: &Path) -> Result<Manifest, String> {
+        let path = root.join(MANIFEST_FILE);
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        Manifest::parse(&text, root).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// The manifest of the project `dir` is in: the nearest [`MANIFEST_FILE`]
+    /// in `dir` or one of its parents. `None` if there is none.
+    pub fn find(dir: &Path) -> Option<Result<Manifest, String>> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let root = dir.ancestors().find(|d| d.join(MANIFEST_FILE).is_file())?;
+        Some(Manifest::load(root))
+    }
+
+    pub fn entry_path(&self) -> PathBuf {
+        self.root.join(&self.entry)
+    }
+
+    /// [`Manifest::paths`] resolved against the project root.
+    pub fn search_paths(&self) -> Vec<PathBuf> {
+        self.paths.iter().map(|path| self.root.join(path)).collect()
+    }
+}
+
+enum Value {
+    String(String),
+    Array(Vec<String>),
+    /// An inline table of strings, in source order.
+    Table(Vec<(String, String)>),
+}
+
+struct Entry {
+    line: usize,
+    /// The `[table]` the key is in; empty before the first one.
+    table: String,
+    key: String,
+    value: Value,
+}
+
+/// Splits TOML text into its key/value pairs.
+fn parse_toml(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let error = |message: &str| format!("line {}: {}", line, message);
+        let content = strip_comment(raw).trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = content.strip_prefix('[') {
+            let name = header.strip_suffix(']').ok_or_else(|| error("table header is missing ']'"))?.trim();
+            if name.is_empty() {
+                return Err(error("empty table name"));
+            }
+            table = name.to_string();
+            continue;
+        }
+
+        let (key, value) = content.split_once('=').ok_or_else(|| error("expected 'key = value'"))?;
+        let key = unquote_key(key.trim());
+        if key.is_empty() {
+            return Err(error("missing key before '='"));
+        }
+        if entries.iter().any(|e: &Entry| e.table == table && e.key == key) {
+            return Err(error(&format!("'{}' is set twice", key)));
+        }
+        let value = parse_value(value.trim()).map_err(|e| error(&e))?;
+        entries.push(Entry { line, table: table.clone(), key, value });
+    }
+    Ok(entries)
+}
+
+/// `raw` without a `#` comment, ignoring `#`s inside strings.
+fn strip_comment(raw: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in raw.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &raw[..i],
+            _ => {}
+        }
+    }
+    raw
+}
+
+fn unquote_key(key: &str) -> String {
+    key.strip_prefix('"').and_then(|k| k.strip_suffix('"')).unwrap_or(key).to_string()
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').ok_or("array is missing ']'")?;
+        let items = split_items(inner)?;
+        return items.iter().map(|item| parse_string(item)).collect::<Result<_, _>>().map(Value::Array);
+    }
+    if let Some(inner) = text.strip_prefix('{') {
+        let inner = inner.strip_suffix('}').ok_or("inline table is missing '}'")?;
+        let mut fields = Vec::new();
+        for item in split_items(inner)? {
+            let (key, value) = item.split_once('=').ok_or("expected 'key = value' in inline table")?;
+            fields.push((unquote_key(key.trim()), parse_string(value.trim())?));
+        }
+        return Ok(Value::Table(fields));
+    }
+    parse_string(text).map(Value::String)
+}
+
+/// The comma-separated items of an array or inline table, trimmed. A
+/// trailing comma is allowed.
+fn split_items(text: &str) -> Result<Vec<&str>, String> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                items.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = text[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    if items.iter().any(|item| item.is_empty()) {
+        return Err("empty item".to_string());
+    }
+    Ok(items)
+}
+
+/// A basic `"..."` string with `\"`, `\\`, `\n` and `\t` escapes.
+fn parse_string(text: &str) -> Result<String, String> {
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .filter(|_| text.len() >= 2)
+        .ok_or_else(|| format!("expected a quoted string, got '{}'", text))?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            other => return Err(format!("unsupported escape '\\{}'", other.map(String::from).unwrap_or_default())),
+        }
+    }
+    Ok(out)
+}
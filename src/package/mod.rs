@@ -0,0 +1,119 @@
+//! Packages installed with `gl install`.
+//!
+//! A package is a git repository cloned into [`MODULES_DIR`] in the project
+//! root, in a directory named after the package. Its [`ENTRY_FILE`] is what
+//! `import name;` loads, and `import name::a::b;` loads `a/b.g` inside it.
+//! A local module with the same path always wins over a package.
+//!
+//! - `manifest` — the project's `giulio.toml`: entry point, search paths
+//!   and dependencies
+//! - `lockfile` — the commits packages are pinned to, for reproducible
+//!   installs
+//! - `scaffold` — the project skeleton `gl new` creates
+//!
+//! Installing runs the `git` executable, which must be on `PATH`. Only git
+//! sources are supported for now.
+
+pub mod lockfile;
+pub mod manifest;
+pub mod scaffold;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::package::lockfile::LockedPackage;
+
+/// Directory in the project root that packages are installed into.
+pub const MODULES_DIR: &str = "giulio_modules";
+
+/// The file of a package that `import package;` loads.
+pub const ENTRY_FILE: &str = "lib.g";
+
+/// The file an import of `path` loads from an installed package under
+/// `root`, if the first part of `path` names one.
+pub fn installed_module_file(root: &Path, path: &[String]) -> Option<PathBuf> {
+    let (package, rest) = path.split_first()?;
+    let mut file = root.join(MODULES_DIR).join(package);
+    if !file.is_dir() {
+        return None;
+    }
+    if rest.is_empty() {
+        file.push(ENTRY_FILE);
+    } else {
+        file.extend(rest);
+        file.set_extension("g");
+    }
+    Some(file)
+}
+
+/// The name a package from `url` is installed and imported as: the last
+/// part of its path without a `.git` suffix.
+pub fn package_name(url: &str) -> Option<String> {
+    let last = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last).replace('-', "_");
+    is_valid_name(&name).then_some(name)
+}
+
+/// Whether `name` can be imported: an identifier that is not a stdlib root.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(name, "std" | "wasm" | "plugin" | "super")
+}
+
+/// Clones `url` into the package directory `name` under `root`, replacing
+/// whatever was there, and checks out `rev` (a branch, tag or commit) if
+/// given. Returns the package pinned to the commit it ended up at.
+pub fn install(root: &Path, name: &str, url: &str, rev: Option<&str>) -> Result<LockedPackage, String> {
+    // The name becomes a directory that is deleted, and the URL and rev git
+    // arguments, so none of them may escape giulio_modules or pass as an
+    // option.
+    if !is_valid_name(name) {
+        return Err(format!("'{}' is not a valid package name", name));
+    }
+    if url.starts_with('-') {
+        return Err(format!("'{}' is not a valid git URL", url));
+    }
+    if let Some(rev) = rev.filter(|rev| rev.starts_with('-')) {
+        return Err(format!("'{}' is not a valid git revision", rev));
+    }
+    let dir = root.join(MODULES_DIR).join(name);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("could not remove {}: {}", dir.display(), e))?;
+    }
+    std::fs::create_dir_all(root.join(MODULES_DIR))
+        .map_err(|e| format!("could not create {}: {}", MODULES_DIR, e))?;
+
+    git(None, &["clone", "--quiet", "--", url, &dir.to_string_lossy()])?;
+    if let Some(rev) = rev {
+        git(Some(&dir), &["checkout", "--quiet", rev, "--"])?;
+    }
+    let rev = git(Some(&dir), &["rev-parse", "HEAD"])?;
+    Ok(LockedPackage { name: name.to_string(), source: url.to_string(), rev })
+}
+
+/// Makes the package directory match `package`, cloning it only when it is
+/// missing or checked out at another commit. Returns whether it changed.
+pub fn install_locked(root: &Path, package: &LockedPackage) -> Result<bool, String> {
+    let dir = root.join(MODULES_DIR).join(&package.name);
+    if git(Some(&dir), &["rev-parse", "HEAD"]).is_ok_and(|rev| rev == package.rev) {
+        return Ok(false);
+    }
+    install(root, &package.name, &package.source, Some(&package.rev))?;
+    Ok(true)
+}
+
+/// Runs git in `dir` and returns its trimmed standard output.
+fn git(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command.args(args).output().map_err(|e| format!("could not run git: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
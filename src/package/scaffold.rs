@@ -0,0 +1,55 @@
+//! Project skeletons created by `gl new`.
+//!
+//! The layout is the one the rest of the tooling expects: a manifest whose
+//! entry point is `src/main.g`, tests in `*_test.g` files for `gl test`,
+//! and installed packages and caches kept out of version control.
+
+use std::path::{Path, PathBuf};
+
+use crate::package::MODULES_DIR;
+use crate::package::manifest::MANIFEST_FILE;
+use crate::vm::runtime::ast_cache::CACHE_DIR;
+
+const MAIN: &str = r#"fn greet(name) {
+    return "Hello, " + name + "!";
+}
+
+println(greet("world"));
+"#;
+
+const TEST: &str = r#"import std::test;
+
+fn test_addition() {
+    test.assert_eq(1 + 1, 2);
+}
+"#;
+
+/// Creates a project named `name` in `dir`, which must not exist yet or be
+/// empty. Returns the files written, relative to `dir`.
+pub fn create_project(dir: &Path, name: &str) -> Result<Vec<PathBuf>, String> {
+    let occupied = std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied {
+        return Err(format!("{} already exists and is not empty", dir.display()));
+    }
+
+    let manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nentry = \"src/main.g\"\npaths = [\"src\"]\n\n[dependencies]\n",
+        name
+    );
+    let gitignore = format!("/{}/\n{}/\n", MODULES_DIR, CACHE_DIR);
+    let files = [
+        (PathBuf::from(MANIFEST_FILE), manifest),
+        (Path::new("src").join("main.g"), MAIN.to_string()),
+        (Path::new("tests").join("main_test.g"), TEST.to_string()),
+        (PathBuf::from(".gitignore"), gitignore),
+    ];
+
+    for (file, contents) in &files {
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("could not create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&path, contents).map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    }
+    Ok(files.into_iter().map(|(file, _)| file).collect())
+}
@@ -36,7 +36,7 @@ fn verify_await_in_stmt(stmt: &Stmt, in_async: bool) -> Result<(), ParserError>
             }
             Ok(())
         }
-        Stmt::BreakStmt | Stmt::ContinueStmt => Ok(()),
+        Stmt::BreakStmt | Stmt::ContinueStmt | Stmt::LineMarker(..) => Ok(()),
         Stmt::ImportStmt { .. } | Stmt::FieldAssignStmt { .. } | Stmt::IndexAssignStmt { .. } => {
             Ok(())
         }
@@ -53,9 +53,14 @@ fn verify_await_in_expr(expr: &Expr, in_async: bool) -> Result<(), ParserError>
     match expr {
         Expr::IdentExpr(_) | Expr::LitExpr(_) => Ok(()),
         Expr::PrefixExpr(_, e) => verify_await_in_expr(e, in_async),
-        Expr::InfixExpr(_, e1, e2) => {
-            verify_await_in_expr(e1, in_async)?;
-            verify_await_in_expr(e2, in_async)
+        Expr::InfixExpr(..) => {
+            // Operator chains nest on the left; walk down them in a loop.
+            let mut left = expr;
+            while let Expr::InfixExpr(_, e1, e2) = left {
+                verify_await_in_expr(e2, in_async)?;
+                left = &**e1;
+            }
+            verify_await_in_expr(left, in_async)
         }
         Expr::IfExpr {
             cond,
@@ -0,0 +1,194 @@
+//! Guards the parser and the recursive AST passes against pathologically
+//! deep programs.
+//!
+//! Parsing recurses once per nested parenthesis, array or block, and
+//! everything after parsing (await validation, the optimizer, slot
+//! resolution and the compiler) walks the tree recursively, so a generated
+//! script can exhaust the host stack long before it reaches the VM. The
+//! parser counts its own recursion with [`NestingGuard`] and then measures
+//! the finished tree with an explicit worklist; both report
+//! [`ParserError::NestingTooDeep`](crate::vm::runtime::runtime_errors::ParserError::NestingTooDeep).
+//!
+//! Only real nesting counts. Operator chains such as `0 + 1 + 1 + ...` are
+//! parsed in a loop and every later pass walks them in a loop too, so a
+//! generated script may chain as many operators as it likes.
+
+use std::cell::Cell;
+
+use crate::ast::ast::{Expr, Program, Stmt};
+
+/// Default for [`max_nesting_depth`].
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 256;
+
+thread_local! {
+    static PARSE_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static NESTING_LIMIT: Cell<usize> = const { Cell::new(DEFAULT_MAX_NESTING_DEPTH) };
+}
+
+/// How many expressions the parse running on this thread may nest inside
+/// one another. The finished tree may be up to twice as deep, since
+/// statements add levels of their own.
+pub(crate) fn max_nesting_depth() -> usize {
+    NESTING_LIMIT.with(Cell::get)
+}
+
+/// Runs `parse` with `limit` as [`max_nesting_depth`]. The limit belongs to
+/// whoever asked for the parse (an [`Engine`](crate::engine::Engine), a
+/// module registry or a `gl` command), so one caller's setting never leaks
+/// into another's.
+pub(crate) fn with_nesting_limit<T>(limit: usize, parse: impl FnOnce() -> T) -> T {
+    let previous = NESTING_LIMIT.with(|cell| cell.replace(limit.max(1)));
+    let result = parse();
+    NESTING_LIMIT.with(|cell| cell.set(previous));
+    result
+}
+
+/// Limit on the depth of the finished tree.
+pub(crate) fn max_tree_depth() -> usize {
+    max_nesting_depth().saturating_mul(2)
+}
+
+/// Counts one level of parser recursion for as long as it is alive.
+pub(crate) struct NestingGuard;
+
+impl NestingGuard {
+    /// Enters one more level, or returns `None` once the limit is reached.
+    pub(crate) fn enter() -> Option<NestingGuard> {
+        PARSE_DEPTH.with(|depth| {
+            if depth.get() >= max_nesting_depth() {
+                return None;
+            }
+            depth.set(depth.get() + 1);
+            Some(NestingGuard)
+        })
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        PARSE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+enum Node<'a> {
+    Stmt(&'a Stmt),
+    Expr(&'a Expr),
+}
+
+fn block(depth: usize, body: &Program) -> impl Iterator<Item = (Node<'_>, usize)> {
+    body.iter().map(move |s| (Node::Stmt(s), depth))
+}
+
+/// Returns `true` if any path from the top level of `program` down to a leaf
+/// passes through more than `limit` statements and expressions. The left
+/// operand of an operator and the target of a call, index or field access
+/// stay at the level of the expression they belong to, so `a + b + c` and
+/// `x.f().g()` are as deep as their deepest operand, however long they are.
+pub(crate) fn exceeds_depth(program: &Program, limit: usize) -> bool {
+    walk(program, limit, false)
+}
+
+/// Like [`exceeds_depth`], but every link of an operator or call chain
+/// counts as a level, as it does for code that recurses into the tree
+/// without special-casing chains (such as `serde`).
+pub(crate) fn exceeds_depth_with_chains(program: &Program, limit: usize) -> bool {
+    walk(program, limit, true)
+}
+
+fn walk(program: &Program, limit: usize, count_chains: bool) -> bool {
+    let mut pending: Vec<(Node, usize)> = program.iter().map(|s| (Node::Stmt(s), 1)).collect();
+
+    while let Some((node, depth)) = pending.pop() {
+        if depth > limit {
+            return true;
+        }
+        let next = depth + 1;
+        let chained = if count_chains { next } else { depth };
+        match node {
+            Node::Stmt(stmt) => match stmt {
+                Stmt::LetStmt(_, e)
+                | Stmt::AssignStmt(_, e)
+                | Stmt::ReturnStmt(e)
+                | Stmt::ExprStmt(e)
+                | Stmt::ExprValueStmt(e)
+                | Stmt::ThrowStmt(e) => pending.push((Node::Expr(e), next)),
+                Stmt::MultiLetStmt { values, .. } | Stmt::TupleAssignStmt { values, .. } => {
+                    pending.extend(values.iter().map(|e| (Node::Expr(e), next)));
+                }
+                Stmt::FieldAssignStmt { object, value, .. } => {
+                    pending.push((Node::Expr(object), next));
+                    pending.push((Node::Expr(value), next));
+                }
+                Stmt::IndexAssignStmt { target, index, value } => {
+                    pending.push((Node::Expr(target), next));
+                    pending.push((Node::Expr(index), next));
+                    pending.push((Node::Expr(value), next));
+                }
+                Stmt::FnStmt { body, .. } => pending.extend(block(next, body)),
+                Stmt::StructStmt { fields, methods, .. } => {
+                    pending.extend(fields.iter().chain(methods).map(|(_, e)| (Node::Expr(e), next)));
+                }
+                Stmt::ImportStmt { .. } | Stmt::BreakStmt | Stmt::ContinueStmt | Stmt::LineMarker(..) => {}
+            },
+            Node::Expr(expr) => match expr {
+                Expr::IdentExpr(_) | Expr::LitExpr(_) | Expr::ThisExpr => {}
+                Expr::PrefixExpr(_, e) | Expr::AwaitExpr(e) => pending.push((Node::Expr(e), next)),
+                Expr::InfixExpr(_, left, right) => {
+                    pending.push((Node::Expr(left), chained));
+                    pending.push((Node::Expr(right), next));
+                }
+                Expr::IfExpr { cond, consequence, alternative } => {
+                    pending.push((Node::Expr(cond), next));
+                    pending.extend(block(next, consequence));
+                    if let Some(alt) = alternative {
+                        pending.extend(block(next, alt));
+                    }
+                }
+                Expr::FnExpr { body, .. } | Expr::AsyncFnExpr { body, .. } => pending.extend(block(next, body)),
+                Expr::CallExpr { function: object, arguments }
+                | Expr::MethodCallExpr { object, arguments, .. } => {
+                    pending.push((Node::Expr(object), chained));
+                    pending.extend(arguments.iter().map(|e| (Node::Expr(e), next)));
+                }
+                Expr::ArrayExpr(items) => pending.extend(items.iter().map(|e| (Node::Expr(e), next))),
+                Expr::HashExpr(pairs) => {
+                    for (k, v) in pairs {
+                        pending.push((Node::Expr(k), next));
+                        pending.push((Node::Expr(v), next));
+                    }
+                }
+                Expr::IndexExpr { array, index } => {
+                    pending.push((Node::Expr(array), chained));
+                    pending.push((Node::Expr(index), next));
+                }
+                Expr::StructLiteral { fields, .. } => {
+                    pending.extend(fields.iter().map(|(_, e)| (Node::Expr(e), next)));
+                }
+                Expr::FieldAccessExpr { object, .. } => pending.push((Node::Expr(object), chained)),
+                Expr::WhileExpr { cond, body } => {
+                    pending.push((Node::Expr(cond), next));
+                    pending.extend(block(next, body));
+                }
+                Expr::ForExpr { iterable, body, .. } => {
+                    pending.push((Node::Expr(iterable), next));
+                    pending.extend(block(next, body));
+                }
+                Expr::CStyleForExpr { init, cond, update, body } => {
+                    pending.extend(init.iter().chain(update).map(|s| (Node::Stmt(s), next)));
+                    if let Some(cond) = cond {
+                        pending.push((Node::Expr(cond), next));
+                    }
+                    pending.extend(block(next, body));
+                }
+                Expr::TryCatchExpr { try_body, catch_body, finally_body, .. } => {
+                    pending.extend(block(next, try_body));
+                    for body in catch_body.iter().chain(finally_body) {
+                        pending.extend(block(next, body));
+                    }
+                }
+            },
+        }
+    }
+
+    false
+}
@@ -11,8 +11,13 @@
 //! - `parser_helpers` — shared combinators (`parens`, `braced`, `comma_separated`, etc.)
 //! - `parser_errors` — diagnostic-quality error reporting with context-aware messages
 //! - `await_ctx_helpers` — validates that `await` only appears inside `async fn`
+//! - `depth_check` — rejects programs nested too deeply for the recursive AST passes
+//! - `optimize` — AST passes (constant folding, dead code elimination) run before compilation
 
 pub mod await_ctx_helpers;
+pub mod depth_check;
+pub mod optimize;
+#[allow(clippy::module_inception)]
 pub mod parser;
 pub mod parser_errors;
 pub mod parser_helpers;
@@ -0,0 +1,351 @@
+//! AST optimization passes run before compilation.
+//!
+//! Constant folding pre-evaluates operators whose operands are literals
+//! (`60 * 60 * 24`, `"a" + "b"`, `!true`) so the VM doesn't redo the work
+//! every time the expression runs, e.g. on each loop iteration. Folding goes
+//! through the same arithmetic routines the VM uses, so results are identical
+//! to what would have been computed at runtime. Expressions that would fail at
+//! runtime (`1 / 0`, `"a" - 1`) are left untouched so the error still surfaces
+//! when, and only if, the code actually runs.
+//!
+//! Dead code elimination then drops statements after an unconditional
+//! `return`, `break`, `continue` or `throw`, and branches whose condition
+//! folded to a constant that never selects them. Each removal is reported as
+//! a warning, since it usually points at a mistake in the script.
+
+use num_bigint::BigInt;
+
+use crate::ast::ast::{Expr, Infix, Literal, Prefix, Program, Stmt};
+use crate::vm::obj::Object;
+use crate::vm::ops::arithmetic;
+
+/// Runs all optimization passes over `program` in place and returns warnings
+/// about code that was removed because it can never run.
+pub fn optimize(program: &mut Program) -> Vec<String> {
+    let mut optimizer = Optimizer::default();
+    optimizer.program(program);
+    optimizer.warnings
+}
+
+#[derive(Default)]
+struct Optimizer {
+    warnings: Vec<String>,
+    /// Names of the enclosing named functions, used to locate warnings.
+    functions: Vec<String>,
+}
+
+impl Optimizer {
+    fn warn(&mut self, message: &str) {
+        let warning = match self.functions.last() {
+            Some(name) => format!("{} (in fn `{}`)", message, name),
+            None => message.to_string(),
+        };
+        self.warnings.push(warning);
+    }
+
+    /// Optimizes each statement, splices constant-true `if` statements into
+    /// the block and drops everything after a `return`, `break`, `continue`
+    /// or `throw`.
+    fn program(&mut self, program: &mut Program) {
+        let mut stmts = std::mem::take(program).into_iter();
+
+        while let Some(mut stmt) = stmts.next() {
+            self.stmt(&mut stmt);
+
+            let spliced = match &mut stmt {
+                Stmt::ExprStmt(Expr::IfExpr { cond, consequence, alternative: None })
+                | Stmt::ExprValueStmt(Expr::IfExpr { cond, consequence, alternative: None })
+                    if literal_value(cond).is_some_and(|c| arithmetic::is_truthy(&c))
+                        && !consequence.iter().any(declares_name) =>
+                {
+                    Some(std::mem::take(consequence))
+                }
+                _ => None,
+            };
+
+            let pending = match spliced {
+                // An empty block still has to produce the `if`'s null value
+                // when it was the block's last statement.
+                Some(body) if body.is_empty() && stmts.len() == 0 => vec![Stmt::ExprStmt(null())],
+                Some(body) => body,
+                None => vec![stmt],
+            };
+
+            for stmt in pending {
+                let terminator = match &stmt {
+                    Stmt::ReturnStmt(_) => Some("return"),
+                    Stmt::BreakStmt => Some("break"),
+                    Stmt::ContinueStmt => Some("continue"),
+                    Stmt::ThrowStmt(_) => Some("throw"),
+                    _ => None,
+                };
+                program.push(stmt);
+                if let Some(keyword) = terminator {
+                    if stmts.len() > 0 {
+                        self.warn(&format!("unreachable code after `{}` was removed", keyword));
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::LetStmt(_, expr)
+            | Stmt::AssignStmt(_, expr)
+            | Stmt::ReturnStmt(expr)
+            | Stmt::ExprStmt(expr)
+            | Stmt::ExprValueStmt(expr)
+            | Stmt::ThrowStmt(expr) => self.expr(expr),
+            Stmt::MultiLetStmt { values, .. } | Stmt::TupleAssignStmt { values, .. } => {
+                values.iter_mut().for_each(|value| self.expr(value));
+            }
+            Stmt::FieldAssignStmt { object, value, .. } => {
+                self.expr(object);
+                self.expr(value);
+            }
+            Stmt::IndexAssignStmt { target, index, value } => {
+                self.expr(target);
+                self.expr(index);
+                self.expr(value);
+            }
+            Stmt::FnStmt { name, body, .. } => {
+                self.functions.push(name.name.clone());
+                self.program(body);
+                self.functions.pop();
+            }
+            Stmt::StructStmt { fields, methods, .. } => {
+                for (_, expr) in fields.iter_mut() {
+                    self.expr(expr);
+                }
+                for (name, expr) in methods.iter_mut() {
+                    self.functions.push(name.name.clone());
+                    self.expr(expr);
+                    self.functions.pop();
+                }
+            }
+            Stmt::ImportStmt { .. } | Stmt::BreakStmt | Stmt::ContinueStmt | Stmt::LineMarker(..) => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::IdentExpr(_) | Expr::LitExpr(_) | Expr::ThisExpr => {}
+            Expr::PrefixExpr(op, operand) => {
+                self.expr(operand);
+                if let Some(folded) = fold_prefix(op, operand) {
+                    *expr = folded;
+                }
+            }
+            Expr::InfixExpr(..) => {
+                // Operator chains nest on the left: take the chain apart and
+                // fold it back together from the innermost operand out, in a
+                // loop rather than one recursion per operator.
+                let mut operands = Vec::new();
+                let mut left = std::mem::replace(expr, null());
+                while let Expr::InfixExpr(op, inner, right) = &mut left {
+                    let inner = std::mem::replace(&mut **inner, null());
+                    operands.push((op.clone(), std::mem::replace(right, Box::new(null()))));
+                    left = inner;
+                }
+                self.expr(&mut left);
+                while let Some((op, mut right)) = operands.pop() {
+                    self.expr(&mut right);
+                    left = match fold_infix(&op, &mut left, &mut right) {
+                        Some(folded) => folded,
+                        None => Expr::InfixExpr(op, Box::new(left), right),
+                    };
+                }
+                *expr = left;
+            }
+            Expr::IfExpr { cond, consequence, alternative } => {
+                self.expr(cond);
+                self.program(consequence);
+                if let Some(alt) = alternative {
+                    self.program(alt);
+                }
+                if let Some(pruned) = self.prune_if(cond, consequence, alternative) {
+                    *expr = pruned;
+                }
+            }
+            Expr::FnExpr { body, .. } | Expr::AsyncFnExpr { body, .. } => self.program(body),
+            Expr::CallExpr { function, arguments } => {
+                self.expr(function);
+                arguments.iter_mut().for_each(|arg| self.expr(arg));
+            }
+            Expr::ArrayExpr(items) => items.iter_mut().for_each(|item| self.expr(item)),
+            Expr::HashExpr(pairs) => {
+                for (key, value) in pairs.iter_mut() {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            }
+            Expr::IndexExpr { array, index } => {
+                self.expr(array);
+                self.expr(index);
+            }
+            Expr::MethodCallExpr { object, arguments, .. } => {
+                self.expr(object);
+                arguments.iter_mut().for_each(|arg| self.expr(arg));
+            }
+            Expr::StructLiteral { fields, .. } => {
+                for (_, value) in fields.iter_mut() {
+                    self.expr(value);
+                }
+            }
+            Expr::FieldAccessExpr { object, .. } => self.expr(object),
+            Expr::WhileExpr { cond, body } => {
+                self.expr(cond);
+                if literal_value(cond).is_some_and(|c| !arithmetic::is_truthy(&c)) {
+                    if !body.is_empty() {
+                        self.warn("`while` condition is always false, the loop body was removed");
+                    }
+                    *expr = null();
+                    return;
+                }
+                self.program(body);
+            }
+            Expr::ForExpr { iterable, body, .. } => {
+                self.expr(iterable);
+                self.program(body);
+            }
+            Expr::CStyleForExpr { init, cond, update, body } => {
+                if let Some(init) = init {
+                    self.stmt(init);
+                }
+                if let Some(cond) = cond {
+                    self.expr(cond);
+                }
+                if let Some(update) = update {
+                    self.stmt(update);
+                }
+                self.program(body);
+            }
+            Expr::TryCatchExpr { try_body, catch_body, finally_body, .. } => {
+                self.program(try_body);
+                if let Some(body) = catch_body {
+                    self.program(body);
+                }
+                if let Some(body) = finally_body {
+                    self.program(body);
+                }
+            }
+            Expr::AwaitExpr(inner) => self.expr(inner),
+        }
+    }
+
+    /// Removes the branch a constant condition can never take. A surviving
+    /// branch that is a single expression replaces the `if` entirely;
+    /// otherwise the `if` is kept with a `true` condition and no `else`.
+    fn prune_if(&mut self, cond: &Expr, consequence: &mut Program, alternative: &mut Option<Program>) -> Option<Expr> {
+        let taken = arithmetic::is_truthy(&literal_value(cond)?);
+        let mut branch = if taken {
+            if alternative.as_ref().is_some_and(|alt| !alt.is_empty()) {
+                self.warn("`if` condition is always true, the `else` branch was removed");
+            }
+            std::mem::take(consequence)
+        } else {
+            if !consequence.is_empty() {
+                self.warn("`if` condition is always false, the branch was removed");
+            }
+            alternative.take().unwrap_or_default()
+        };
+
+        Some(match branch.as_mut_slice() {
+            [] => null(),
+            [Stmt::ExprStmt(only) | Stmt::ExprValueStmt(only)] => std::mem::replace(only, Expr::ThisExpr),
+            _ => Expr::IfExpr {
+                cond: Box::new(Expr::LitExpr(Literal::BoolLiteral(true))),
+                consequence: branch,
+                alternative: None,
+            },
+        })
+    }
+}
+
+fn null() -> Expr {
+    Expr::LitExpr(Literal::NullLiteral)
+}
+
+/// Statements that introduce a name into their block; blocks containing them
+/// can't be merged into the enclosing block without changing scoping.
+fn declares_name(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::LetStmt(..) | Stmt::MultiLetStmt { .. } | Stmt::FnStmt { .. } | Stmt::StructStmt { .. } | Stmt::ImportStmt { .. }
+    )
+}
+
+fn literal_value(expr: &Expr) -> Option<Object> {
+    match expr {
+        Expr::LitExpr(literal) => Some(match literal {
+            Literal::IntLiteral(i) => Object::Integer(*i),
+            Literal::BigIntLiteral(b) => Object::BigInteger(Box::new(b.clone())),
+            Literal::FloatLiteral(f) => Object::Float(*f),
+            Literal::BoolLiteral(b) => Object::Boolean(*b),
+            Literal::StringLiteral(s) => Object::String(s.clone().into()),
+            Literal::NullLiteral => Object::Null,
+        }),
+        _ => None,
+    }
+}
+
+fn to_literal(value: Object) -> Option<Expr> {
+    let literal = match value {
+        Object::Integer(i) => Literal::IntLiteral(i),
+        Object::BigInteger(b) => Literal::BigIntLiteral(BigInt::clone(&b)),
+        Object::Float(f) => Literal::FloatLiteral(f),
+        Object::Boolean(b) => Literal::BoolLiteral(b),
+        Object::String(s) => Literal::StringLiteral(s.into()),
+        Object::Null => Literal::NullLiteral,
+        _ => return None,
+    };
+    Some(Expr::LitExpr(literal))
+}
+
+fn fold_prefix(op: &Prefix, operand: &mut Expr) -> Option<Expr> {
+    if let Prefix::PrefixPlus = op {
+        // Unary plus compiles to nothing, so it can always be dropped.
+        return Some(std::mem::replace(operand, Expr::ThisExpr));
+    }
+    let value = literal_value(operand)?;
+    match op {
+        Prefix::Not => to_literal(arithmetic::execute_not(value)),
+        Prefix::PrefixMinus => to_literal(arithmetic::execute_negate(value)),
+        Prefix::PrefixPlus => unreachable!("handled above"),
+    }
+}
+
+fn fold_infix(op: &Infix, left: &mut Expr, right: &mut Expr) -> Option<Expr> {
+    let lhs = literal_value(left)?;
+
+    // `&&` and `||` return one of their operands, so only the left side needs
+    // to be constant.
+    match op {
+        Infix::And | Infix::Or => {
+            let pick_left = arithmetic::is_truthy(&lhs) == matches!(op, Infix::Or);
+            let chosen = if pick_left { left } else { right };
+            return Some(std::mem::replace(chosen, Expr::ThisExpr));
+        }
+        _ => {}
+    }
+
+    let rhs = literal_value(right)?;
+
+    let result = match op {
+        Infix::Plus => arithmetic::add(lhs, rhs),
+        Infix::Minus => arithmetic::subtract(lhs, rhs),
+        Infix::Multiply => arithmetic::multiply(lhs, rhs),
+        Infix::Divide => arithmetic::divide(lhs, rhs),
+        Infix::Modulo => arithmetic::modulo(lhs, rhs),
+        Infix::Equal => arithmetic::execute_equal(lhs, rhs),
+        Infix::NotEqual => arithmetic::execute_not_equal(lhs, rhs),
+        Infix::LessThan => arithmetic::less_than(lhs, rhs),
+        Infix::GreaterThan => arithmetic::greater_than(lhs, rhs),
+        Infix::LessThanEqual => arithmetic::less_equal(lhs, rhs),
+        Infix::GreaterThanEqual => arithmetic::greater_equal(lhs, rhs),
+        Infix::And | Infix::Or => unreachable!("handled above"),
+    };
+    to_literal(result)
+}
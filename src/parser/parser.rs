@@ -12,6 +12,7 @@ use nom::multi::many0;
 use nom::sequence::*;
 use nom::Err;
 use nom::{branch::*, IResult};
+use std::cell::RefCell;
 use std::result::Result::*;
 
 use crate::ast::ast::{
@@ -19,6 +20,7 @@ use crate::ast::ast::{
 };
 use crate::lexer::token::{Token, Tokens};
 use crate::parser::await_ctx_helpers::validate_await_usage;
+use crate::parser::depth_check::{exceeds_depth, max_tree_depth, NestingGuard};
 use crate::parser::parser_helpers::*;
 
 /// Generates a parser that consumes exactly one token matching `$tag`.
@@ -146,8 +148,56 @@ fn infix_op(t: &Token) -> (Precedence, Option<Infix>) {
 
 // ─── Program, expression, and statement parsers ─────────────────────
 
+thread_local! {
+    /// Line and column of every token of the input, while parsing with
+    /// markers.
+    static MARKER_POSITIONS: RefCell<Option<Vec<(u16, u16)>>> = const { RefCell::new(None) };
+}
+
 fn parse_program(input: Tokens) -> IResult<Tokens, Program> {
-    terminated(many0(parse_stmt), eof_tag)(input)
+    terminated(parse_stmts, eof_tag)(input)
+}
+
+/// Zero or more statements. When parsing with markers, each one is preceded
+/// by a [`Stmt::LineMarker`] with the line and column it starts on.
+fn parse_stmts(input: Tokens) -> IResult<Tokens, Program> {
+    if MARKER_POSITIONS.with(|positions| positions.borrow().is_none()) {
+        return many0(parse_stmt)(input);
+    }
+
+    let mut program = Vec::new();
+    let mut input = input;
+    loop {
+        let (line, column) = statement_position(&input);
+        match parse_stmt(input) {
+            Ok((rest, stmt)) => {
+                // Same guard as `many0` against parsers that consume nothing.
+                if rest.token.len() == input.token.len() {
+                    return Err(Err::Error(Error::new(input, ErrorKind::Many0)));
+                }
+                program.push(Stmt::LineMarker(line, column));
+                program.push(stmt);
+                input = rest;
+            }
+            Err(Err::Error(_)) => return Ok((input, program)),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Line and column of the first token of `input`, which is a suffix of the
+/// tokens being parsed with markers.
+fn statement_position(input: &Tokens) -> (u16, u16) {
+    MARKER_POSITIONS.with(|positions| {
+        let positions = positions.borrow();
+        let positions = positions.as_deref().unwrap_or_default();
+        positions
+            .len()
+            .checked_sub(input.token.len())
+            .and_then(|index| positions.get(index))
+            .copied()
+            .unwrap_or((0, 0))
+    })
 }
 
 fn parse_expr(input: Tokens) -> IResult<Tokens, Expr> {
@@ -202,6 +252,12 @@ fn parse_fn_declaration(input: Tokens) -> IResult<Tokens, Stmt> {
 
 // ─── Assignment and expression parsing ──────────────────────────────
 
+/// Moves an expression out of a box that belongs to an `Expr`, which cannot
+/// be destructured by value since it implements `Drop`.
+fn take_boxed(expr: &mut Box<Expr>) -> Box<Expr> {
+    std::mem::replace(expr, Box::new(Expr::ThisExpr))
+}
+
 /// Parses an expression, then determines whether it forms a statement.
 ///
 /// Three outcomes are possible:
@@ -212,7 +268,7 @@ fn parse_fn_declaration(input: Tokens) -> IResult<Tokens, Stmt> {
 /// Block expressions (if, fn, while, for, try-catch) do not require a
 /// trailing semicolon when they appear at the end of a block.
 fn parse_expr_or_assign_stmt(input: Tokens) -> IResult<Tokens, Stmt> {
-    let (i1, lhs_expr) = parse_expr(input)?;
+    let (i1, mut lhs_expr) = parse_expr(input)?;
 
     if peek_matches(i1, Token::Assign) {
         // This is an assignment statement
@@ -220,21 +276,21 @@ fn parse_expr_or_assign_stmt(input: Tokens) -> IResult<Tokens, Stmt> {
         let (i3, rhs_expr) = parse_expr(i2)?;
         let (i4, _) = semicolon_tag(i3)?;
 
-        match lhs_expr {
-            Expr::IdentExpr(ident) => Ok((i4, Stmt::AssignStmt(ident, rhs_expr))),
+        match &mut lhs_expr {
+            Expr::IdentExpr(ident) => Ok((i4, Stmt::AssignStmt(ident.clone(), rhs_expr))),
             Expr::FieldAccessExpr { object, field } => Ok((
                 i4,
                 Stmt::FieldAssignStmt {
-                    object,
-                    field,
+                    object: take_boxed(object),
+                    field: field.clone(),
                     value: Box::new(rhs_expr),
                 },
             )),
             Expr::IndexExpr { array, index } => Ok((
                 i4,
                 Stmt::IndexAssignStmt {
-                    target: array,
-                    index,
+                    target: take_boxed(array),
+                    index: take_boxed(index),
                     value: Box::new(rhs_expr),
                 },
             )),
@@ -247,12 +303,12 @@ fn parse_expr_or_assign_stmt(input: Tokens) -> IResult<Tokens, Stmt> {
         let (i3, rhs_expr) = parse_expr(i2)?;
         let (i4, _) = semicolon_tag(i3)?;
 
-        match lhs_expr {
+        match &lhs_expr {
             Expr::IdentExpr(ident) => Ok((
                 i4,
                 Stmt::AssignStmt(
                     ident.clone(),
-                    Expr::InfixExpr(infix, Box::new(Expr::IdentExpr(ident)), Box::new(rhs_expr)),
+                    Expr::InfixExpr(infix, Box::new(Expr::IdentExpr(ident.clone())), Box::new(rhs_expr)),
                 ),
             )),
             _ => Err(Err::Error(Error::new(input, ErrorKind::Verify))),
@@ -277,8 +333,11 @@ fn parse_expr_or_assign_stmt(input: Tokens) -> IResult<Tokens, Stmt> {
                     | Expr::TryCatchExpr { .. }
             );
             let is_trailing_in_block = peek_matches(after_expr, Token::RBrace);
+            // The last statement of a program may leave out its `;` too,
+            // which keeps one-liners such as `gl -e 'println(1 + 2)'` short.
+            let is_last_in_program = peek_matches(after_expr, Token::EOF);
 
-            if is_block_expr || is_trailing_in_block {
+            if is_block_expr || is_trailing_in_block || is_last_in_program {
                 Ok((after_expr, Stmt::ExprValueStmt(expr)))
             } else {
                 // Expect a semicolon for other expression statements
@@ -446,7 +505,7 @@ fn parse_return_stmt(input: Tokens) -> IResult<Tokens, Stmt> {
 }
 
 fn parse_block_stmt(input: Tokens) -> IResult<Tokens, Program> {
-    braced(many0(parse_stmt))(input)
+    braced(parse_stmts)(input)
 }
 
 // EXPRESSION PARSING
@@ -504,12 +563,13 @@ fn parse_atom_expr(input: Tokens) -> IResult<Tokens, Expr> {
 /// This correctly handles precedence and left-associativity without
 /// the grammar ambiguity issues of a naive recursive approach.
 fn parse_pratt_expr(input: Tokens, precedence: Precedence) -> IResult<Tokens, Expr> {
+    let Some(_nesting) = NestingGuard::enter() else {
+        return Err(Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+    };
     let (mut i, mut left) = parse_atom_expr(input)?;
 
-    loop {
-        let Some(curr_token) = peek_token(i) else {
-            break;
-        };
+
+    while let Some(curr_token) = peek_token(i) {
         let (peek_precedence, _) = infix_op(curr_token);
 
         if precedence >= peek_precedence || peek_precedence == Precedence::PLowest {
@@ -876,21 +936,43 @@ pub(crate) struct Parser;
 
 impl Parser {
     pub fn parse_tokens(tokens: Tokens) -> IResult<Tokens, Program> {
-        let (rest, program) = parse_program(tokens)?;
+        let (rest, program) = Parser::parse_syntax(tokens)?;
         if validate_await_usage(&program).is_err() {
             return Err(Err::Error(Error::new(tokens, ErrorKind::Verify)));
         }
+        Ok((rest, program))
+    }
+
+    /// Like [`Parser::parse_tokens`], but puts a [`Stmt::LineMarker`] before
+    /// every statement, for `gl test --coverage` and `gl lint`. `positions`
+    /// holds the source line and column of each of `tokens`.
+    pub(crate) fn parse_with_markers(tokens: Tokens, positions: Vec<(u16, u16)>) -> IResult<Tokens, Program> {
+        MARKER_POSITIONS.with(|cell| *cell.borrow_mut() = Some(positions));
+        let result = Parser::parse_tokens(tokens);
+        MARKER_POSITIONS.with(|cell| *cell.borrow_mut() = None);
+        result
+    }
+
+    /// Like [`Parser::parse_tokens`], but does not check that `await` is only
+    /// used inside async functions, so the caller can report that separately.
+    pub(crate) fn parse_syntax(tokens: Tokens) -> IResult<Tokens, Program> {
+        let (rest, program) = parse_program(tokens)?;
+        if exceeds_depth(&program, max_tree_depth()) {
+            return Err(Err::Failure(Error::new(tokens, ErrorKind::TooLarge)));
+        }
 
         // This is for checking wether there are more statements after a top level return and if they are valid
-        if program.len() > 1 {
-            for (i, stmt) in program.iter().enumerate() {
+        // Coverage line markers do not count as statements here.
+        let statements: Vec<&Stmt> = program.iter().filter(|stmt| !matches!(stmt, Stmt::LineMarker(..))).collect();
+        if statements.len() > 1 {
+            for (i, stmt) in statements.iter().enumerate() {
                 if i == 0 {
                     continue;
                 }
                 // Check if current is ExprValueStmt (implicit return, no semicolon)
                 // and previous is ReturnStmt
                 if matches!(stmt, Stmt::ExprValueStmt(_)) {
-                    let prev_stmt = &program[i - 1];
+                    let prev_stmt = statements[i - 1];
                     if matches!(prev_stmt, Stmt::ReturnStmt(_)) {
                         return Err(Err::Error(Error::new(tokens, ErrorKind::Verify)));
                     }
@@ -16,6 +16,7 @@
 //! 5. [`create_contextual_error`] — maps context strings to specific messages
 
 use crate::lexer::token::{Location, Spanned, Token, Tokens};
+use crate::parser::depth_check::max_nesting_depth;
 use crate::vm::runtime::runtime_errors::ParserError;
 use nom::Err;
 use nom::error::{Error, ErrorKind};
@@ -44,6 +45,13 @@ pub(crate) fn convert_nom_error<'a>(
                 };
             }
 
+            if e.code == ErrorKind::TooLarge {
+                return ParserError::NestingTooDeep {
+                    limit: max_nesting_depth(),
+                    location,
+                };
+            }
+
             let current_token = &tokens.token[0];
             let token_description = describe_token(current_token);
 
@@ -420,6 +428,15 @@ fn has_matching_brace(tokens: &Tokens) -> bool {
     depth == 0
 }
 
+/// Whether `tokens` end inside an unclosed `(`, `{` or `[`, so that more
+/// input could still complete the statement. The REPL keeps reading lines
+/// while this holds.
+pub(crate) fn is_unterminated(tokens: &Tokens) -> bool {
+    [(Token::LParen, Token::RParen), (Token::LBrace, Token::RBrace), (Token::LBracket, Token::RBracket)]
+        .into_iter()
+        .any(|(open, close)| count_unmatched(tokens, open, close) > 0)
+}
+
 fn count_unmatched(tokens: &Tokens, open: Token, close: Token) -> i32 {
     let mut depth = 0;
     for token in tokens.token.iter() {
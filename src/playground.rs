@@ -0,0 +1,72 @@
+//! The entry point of the browser playground.
+//!
+//! In a `wasm32-unknown-unknown` build, [`eval_str`] is exported to
+//! JavaScript with wasm-bindgen; `playground/index.html` is the page that
+//! calls it. It works on other targets too, like any library function.
+
+use std::sync::{Arc, Mutex};
+
+use crate::engine::Engine;
+use crate::vm::obj::Object;
+use crate::vm::runtime::builtins::impls::output::{format_placeholders, join_args};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Runs `source` in a new engine and returns what it printed, followed by
+/// the value of its last expression unless that is `null`, or by the error
+/// that stopped it, like the REPL shows them.
+///
+/// The script runs to completion before this returns, so it must not be
+/// called from inside an async runtime.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn eval_str(source: &str) -> String {
+    let output = Arc::new(Mutex::new(String::new()));
+    let mut engine = Engine::new();
+    capture_output(&mut engine, &output);
+
+    let mut builder = tokio::runtime::Builder::new_current_thread();
+    // The timer reads the clock, which wasm32 does not have.
+    #[cfg(not(target_arch = "wasm32"))]
+    builder.enable_all();
+    let result = match builder.build() {
+        Ok(runtime) => runtime.block_on(engine.eval(source)),
+        Err(e) => return format!("Could not start the runtime: {}\n", e),
+    };
+
+    let mut output = std::mem::take(&mut *output.lock().unwrap());
+    match result {
+        Ok(Object::Null) => {}
+        Ok(value) => output.push_str(&format!("{}\n", value)),
+        Err(e) => output.push_str(&format!("{}\n", e)),
+    }
+    output
+}
+
+/// Replaces the printing builtins with ones that append to `output`, since
+/// a wasm32 build has no standard output.
+fn capture_output(engine: &mut Engine, output: &Arc<Mutex<String>>) {
+    let out = Arc::clone(output);
+    engine.register_fn("print", move |args| {
+        out.lock().unwrap().push_str(&join_args(&args));
+        Ok(Object::Null)
+    });
+    let out = Arc::clone(output);
+    engine.register_fn("println", move |args| {
+        let mut out = out.lock().unwrap();
+        out.push_str(&join_args(&args));
+        out.push('\n');
+        Ok(Object::Null)
+    });
+    let out = Arc::clone(output);
+    engine.register_fn("printf", move |args| match args.split_first() {
+        Some((Object::String(template), rest)) => {
+            let text = format_placeholders("printf", template, rest).map_err(RuntimeError::InvalidArguments)?;
+            out.lock().unwrap().push_str(&text);
+            Ok(Object::Null)
+        }
+        Some((other, _)) => Err(RuntimeError::TypeMismatch { expected: "format string".to_string(), got: other.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: usize::MAX, got: 0 }),
+    });
+}
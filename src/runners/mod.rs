@@ -3,11 +3,25 @@
 //! Each submodule implements one way to run G-lang code:
 //!
 //! - `run_source` — lex, parse, and execute a `.g` file
-//! - `run_check` — lex and parse only (syntax validation)
+//! - `run_check` — lex, parse and compile without running (static checks)
+//! - `run_lint` — report likely mistakes found by the linter
+//! - `run_lsp` — language server for editors
+//! - `run_test` — run the tests in `*_test.g` files
+//! - `run_bench` — time the `bench_*` functions of a `.g` file
+//! - `run_install` — install packages into `giulio_modules/`
+//! - `run_new` — create a project skeleton
+//! - `run_build` — bundle a script into a standalone executable
 //! - `run_repl_mode` — interactive read-eval-print loop
 //! - `print_help` — CLI usage information
 
 pub mod print_help;
 pub mod run_repl_mode;
 pub mod run_source;
-pub mod run_check;
\ No newline at end of file
+pub mod run_check;
+pub mod run_bench;
+pub mod run_lint;
+pub mod run_lsp;
+pub mod run_test;
+pub mod run_install;
+pub mod run_new;
+pub mod run_build;
\ No newline at end of file
@@ -8,21 +8,50 @@ pub fn print_help() {
     println!("A dynamically-typed interpreted programming language\n");
     
     println!("USAGE:");
-    println!("    gl [COMMAND] [OPTIONS]\n");
+    println!("    gl [COMMAND] [OPTIONS] [-- SCRIPT ARGS]\n");
     
     println!("COMMANDS:");
     println!("    (no command)       Start the REPL (Read-Eval-Print Loop)");
-    println!("    run <file>         Execute a .g file");
-    println!("    check <file>       Lex and Parse to check a .g file for syntax errors\n");
+    println!("    run [file]         Execute a .g file, or the project's entry point");
+    println!("    check <file>...    Check .g files for errors without running them");
+    println!("    lint <file>...     Report likely mistakes in .g files");
+    println!("    test [path]...     Run the tests in *_test.g files (default: current directory)");
+    println!("    build <file>       Make a standalone executable (name it with -o <out>)");
+    println!("    new <dir>          Create a project with a manifest, main.g and a test");
+    println!("    install [url]      Install a package from git, or those in giulio.lock");
+    println!("    lsp                Start a language server on stdin/stdout");
+    println!("    bench <file>       Time the bench_* functions of a .g file\n");
     
     println!("OPTIONS:");
     println!("    -h, --help         Print this help message");
-    println!("    -v, --version      Print version information\n");
+    println!("    -v, --version      Print version information");
+    println!("    -e, --eval <code>  Run <code> instead of a file");
+    println!("    --log-level <lvl>  Minimum std::log level: debug, info, warn, error, off");
+    println!("                       (defaults to $G_LOG_LEVEL, then info)");
+    println!("    --error-format=<f> Print errors of `run` and `check` as human (default)");
+    println!("                       or json, one object per line on stderr");
+    println!("    --max-depth <n>    Maximum expression nesting depth (default 256)");
+    println!("    --max-steps <n>    Stop `run` after n loop iterations and calls");
+    println!("    --timeout <secs>   Stop `run` after secs seconds");
+    println!("    --deny <caps>      Deny scripts fs, net, process and/or env access");
+    println!("                       (comma-separated, or all)");
+    println!("    --profile[=<fmt>]  Profile `run`: text (default), json or flamegraph");
+    println!("    --profile-out <f>  Write the profile to <f> instead of stderr");
+    println!("    --coverage[=<fmt>] Report line coverage of `test`: text (default) or lcov");
+    println!("    --coverage-out <f> Write the coverage report to <f> instead of stdout");
+    println!("    --config <file>    Lint settings for `lint` (default ./gllint.ini)\n");
     
     println!("EXAMPLES:");
     println!("    gl                    # Start REPL mode");
     println!("    gl run script.g     # Run a script");
+    println!("    gl -e 'println(1 + 2)'  # Run a one-liner");
     println!("    gl check script.g   # Check a file");
+    println!("    gl lint script.g    # Lint a file");
+    println!("    gl test               # Run every *_test.g file under .");
+    println!("    gl bench script.g   # Run a script's benchmarks");
+    println!("    gl install https://github.com/user/utils.git  # Install a package");
+    println!("    gl run --profile script.g  # Run a script and print a profile");
+    println!("    gl test --coverage    # Run the tests and show which lines ran");
     println!("    gl --version          # Show version");
     println!("    gl --help             # Show this help\n");
     
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::ast::ast::Stmt;
+use crate::parser::optimize::optimize;
+use crate::runners::run_source::{parse_or_report, print_warnings, registry_with_depth};
+use crate::vm::compiler::Compiler;
+use crate::vm::obj::Object;
+use crate::vm::ops::calls::call_function_object;
+use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::module_registry::ModuleRegistry;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+use crate::vm::vm::VirtualMachine;
+
+/// Untimed calls made before measuring, to warm caches (and the JIT).
+const WARMUP_RUNS: usize = 3;
+/// Every benchmark is measured at least this many times...
+const MIN_RUNS: usize = 10;
+/// ...and for at least this long, unless it hits `MAX_RUNS` first.
+const MIN_TIME: Duration = Duration::from_secs(1);
+const MAX_RUNS: usize = 100_000;
+
+/// Timing statistics of one benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub runs: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+impl Summary {
+    /// Summarizes `samples`; the standard deviation is the sample one.
+    pub fn from_samples(samples: &[Duration]) -> Summary {
+        let runs = samples.len();
+        if runs == 0 {
+            return Summary { runs, mean: Duration::ZERO, median: Duration::ZERO, stddev: Duration::ZERO };
+        }
+        let secs: Vec<f64> = samples.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let median = if runs % 2 == 1 {
+            sorted[runs / 2]
+        } else {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        };
+
+        Summary {
+            runs,
+            mean: Duration::from_secs_f64(mean),
+            median,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2} µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2} ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2} s", nanos as f64 / 1e9)
+    }
+}
+
+/// Runs every top-level `fn bench_*()` in `input` repeatedly and prints
+/// mean, median and standard deviation of its run time.
+///
+/// The script's top level runs once first, so benchmarks can use globals
+/// it sets up. `max_depth` is the nesting limit for the script and its
+/// imports. Returns `false` if the script could not run, had no
+/// benchmarks, or a benchmark failed; the problem has been printed.
+pub async fn run_bench(input: &str, max_depth: usize) -> bool {
+    let Some(mut program) = parse_or_report(input, max_depth) else {
+        return false;
+    };
+    print_warnings(&optimize(&mut program), None);
+
+    let names: Vec<String> = program
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::FnStmt { name, params, .. } if name.name.starts_with("bench_") && params.is_empty() => {
+                Some(name.name.clone())
+            }
+            _ => None,
+        })
+        .collect();
+    if names.is_empty() {
+        eprintln!("No benchmarks found: define functions named bench_* that take no arguments");
+        return false;
+    }
+
+    let chunk = match Compiler::compile_program(&mut program) {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            eprintln!("╭─ Compiler Error ───────────────────────────");
+            eprintln!("│");
+            eprintln!("│ {}", e);
+            eprintln!("│");
+            eprintln!("╰────────────────────────────────────────────");
+            return false;
+        }
+    };
+    let globals = Arc::new(Mutex::new(Environment::new_root()));
+    let module_registry = Arc::new(Mutex::new(registry_with_depth(PathBuf::from("."), max_depth)));
+    let mut vm = VirtualMachine::new(Arc::clone(&globals), Arc::clone(&module_registry));
+    if let Err(e) = vm.run(Arc::new(chunk)).await {
+        print_runtime_error("setup", &e.to_string());
+        return false;
+    }
+
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    println!("{:<width$}  {:>12}  {:>12}  {:>12}  {:>8}", "benchmark", "mean", "median", "stddev", "runs");
+    let mut passed = true;
+    for name in names {
+        let Some(function) = globals.lock().unwrap().get_by_name(&name) else {
+            continue;
+        };
+        match measure(&function, &module_registry, &globals).await {
+            Ok(summary) => println!(
+                "{:<width$}  {:>12}  {:>12}  {:>12}  {:>8}",
+                name,
+                format_duration(summary.mean),
+                format_duration(summary.median),
+                format_duration(summary.stddev),
+                summary.runs
+            ),
+            Err(message) => {
+                print_runtime_error(&name, &message);
+                passed = false;
+            }
+        }
+    }
+    passed
+}
+
+async fn measure(
+    function: &Object,
+    module_registry: &Arc<Mutex<ModuleRegistry>>,
+    globals: &Arc<Mutex<Environment>>,
+) -> Result<Summary, String> {
+    let call = || call_function_object(function.clone(), Vec::new(), Arc::clone(module_registry), Arc::clone(globals));
+
+    for _ in 0..WARMUP_RUNS {
+        check_result(call().await)?;
+    }
+
+    let mut samples = Vec::new();
+    let started = Instant::now();
+    while samples.len() < MAX_RUNS && (samples.len() < MIN_RUNS || started.elapsed() < MIN_TIME) {
+        let run_started = Instant::now();
+        let result = call().await;
+        samples.push(run_started.elapsed());
+        check_result(result)?;
+    }
+    Ok(Summary::from_samples(&samples))
+}
+
+pub(crate) fn check_result(result: Result<Object, RuntimeError>) -> Result<(), String> {
+    match result {
+        Ok(Object::Error(e)) => Err(e.to_string()),
+        Err(e) => Err(e.to_string()),
+        Ok(_) => Ok(()),
+    }
+}
+
+fn print_runtime_error(name: &str, message: &str) {
+    eprintln!("╭─ Runtime Error ────────────────────────────");
+    eprintln!("│");
+    eprintln!("│ {}: {}", name, message);
+    eprintln!("│");
+    eprintln!("╰────────────────────────────────────────────");
+}
@@ -0,0 +1,130 @@
+//! Standalone executables made by `gl build`.
+//!
+//! A built executable is a copy of the running `gl` binary with the
+//! script's parsed AST appended, followed by a trailer: the length of the
+//! AST as a little-endian `u64` and [`MAGIC`]. On startup `gl` looks for the
+//! trailer at the end of its own file and, if it is there, runs the
+//! embedded script instead of reading its arguments as a command.
+//!
+//! Only the script itself is embedded; the standard library is part of the
+//! interpreter, but user modules it imports are still read from disk.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::ast::Program;
+use crate::runners::run_source::{parse_or_report, run_program};
+use crate::vm::limits::ResourceLimits;
+use crate::vm::runtime::module_registry::ModuleRegistry;
+
+/// Last bytes of a built executable.
+const MAGIC: &[u8; 8] = b"GLBUNDL1";
+
+const TRAILER_LEN: u64 = 16;
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    /// Version of the `gl` that built it; the AST format is only stable
+    /// within a version, and the interpreter is always the same one.
+    version: String,
+    program: Program,
+}
+
+/// Builds the script `input`, parsed with `max_depth` as the nesting
+/// limit, into a standalone executable at `output`. Returns `false` if it
+/// could not; the problem has been printed.
+pub fn run_build(input: &str, output: &Path, max_depth: usize) -> bool {
+    let Some(program) = parse_or_report(input, max_depth) else {
+        return false;
+    };
+    let built = std::env::current_exe()
+        .map_err(|e| format!("could not find the gl executable: {}", e))
+        .and_then(|interpreter| build(&interpreter, program, output));
+    match built {
+        Ok(()) => {
+            println!("Built {}", output.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Could not build {}: {}", output.display(), e);
+            false
+        }
+    }
+}
+
+/// Writes a copy of the executable `interpreter` with `program` embedded
+/// to `output`.
+pub(crate) fn build(interpreter: &Path, program: Program, output: &Path) -> Result<(), String> {
+    if output.canonicalize().is_ok_and(|output| interpreter.canonicalize().is_ok_and(|exe| exe == output)) {
+        return Err("the output would overwrite the gl executable".to_string());
+    }
+    let metadata = std::fs::metadata(interpreter).map_err(|e| e.to_string())?;
+    // When building from a built executable, copy only the interpreter.
+    let interpreter_len = metadata.len() - embedded_len(interpreter).map_or(0, |len| len + TRAILER_LEN);
+    let bundle = Bundle { version: env!("CARGO_PKG_VERSION").to_string(), program };
+    let payload = bincode::serialize(&bundle).map_err(|e| e.to_string())?;
+
+    let mut source = File::open(interpreter).map_err(|e| e.to_string())?;
+    let mut target = File::create(output).map_err(|e| e.to_string())?;
+    std::io::copy(&mut (&mut source).take(interpreter_len), &mut target).map_err(|e| e.to_string())?;
+    target.write_all(&payload).map_err(|e| e.to_string())?;
+    target.write_all(&(payload.len() as u64).to_le_bytes()).map_err(|e| e.to_string())?;
+    target.write_all(MAGIC).map_err(|e| e.to_string())?;
+    drop(target);
+
+    std::fs::set_permissions(output, metadata.permissions()).map_err(|e| e.to_string())
+}
+
+/// Length of the bundle embedded in `exe`, if it has one.
+fn embedded_len(exe: &Path) -> Option<u64> {
+    let mut file = File::open(exe).ok()?;
+    let size = file.metadata().ok()?.len();
+    if size < TRAILER_LEN {
+        return None;
+    }
+    file.seek(SeekFrom::End(-(TRAILER_LEN as i64))).ok()?;
+    let mut trailer = [0u8; TRAILER_LEN as usize];
+    file.read_exact(&mut trailer).ok()?;
+    if &trailer[8..] != MAGIC {
+        return None;
+    }
+    let len = u64::from_le_bytes(trailer[..8].try_into().ok()?);
+    (len <= size - TRAILER_LEN).then_some(len)
+}
+
+/// The script embedded in the running executable, if it was made by
+/// `gl build`.
+pub fn embedded_program() -> Option<Program> {
+    let exe = std::env::current_exe().ok()?;
+    read_bundle(&exe)
+}
+
+pub(crate) fn read_bundle(exe: &Path) -> Option<Program> {
+    let len = embedded_len(exe)?;
+    let mut file = File::open(exe).ok()?;
+    file.seek(SeekFrom::End(-((len + TRAILER_LEN) as i64))).ok()?;
+    let mut payload = vec![0u8; len as usize];
+    file.read_exact(&mut payload).ok()?;
+    let bundle: Bundle = bincode::deserialize(&payload).ok()?;
+    (bundle.version == env!("CARGO_PKG_VERSION")).then_some(bundle.program)
+}
+
+/// Runs a script returned by [`embedded_program`], resolving imports from
+/// the current directory like `gl run`. Returns the script's exit status.
+pub async fn run_embedded(program: Program) -> i32 {
+    run_program(program, None, ResourceLimits::default(), ModuleRegistry::new(PathBuf::from("."))).await
+}
+
+/// Where `gl build script.g` writes the executable when `-o` is not given:
+/// the script's name without its extension, in the current directory.
+pub fn default_output(script: &Path) -> PathBuf {
+    let stem = script.file_stem().map(|s| s.to_os_string()).unwrap_or_else(|| "app".into());
+    let mut output = PathBuf::from(stem);
+    if cfg!(windows) {
+        output.set_extension("exe");
+    }
+    output
+}
@@ -1,56 +1,225 @@
-use crate::lexer::token::SpannedTokens;
-use crate::parser::parser_errors::{convert_nom_error, show_error_context};
-use crate::vm::compiler::compute_slots::compute_slots;
-use crate::parser::parser::Parser;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::json;
+
 use crate::lexer::lexer::Lexer;
+use crate::lexer::token::{Location, Token, Tokens};
+use crate::parser::await_ctx_helpers::validate_await_usage;
+use crate::parser::depth_check::with_nesting_limit;
+use crate::parser::optimize::optimize;
+use crate::parser::parser::Parser;
+use crate::parser::parser_errors::convert_nom_error;
+use crate::vm::compiler::Compiler;
+
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// How `gl` reports errors, chosen with `--error-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Boxes meant to be read in a terminal.
+    Human,
+    /// One JSON object per diagnostic and line on stderr, see
+    /// [`Diagnostic::to_json`].
+    Json,
+}
+
+impl ErrorFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("unknown error format '{}', expected human or json", name)),
+        }
+    }
+}
+
+/// Sets how errors are reported for the rest of the process.
+pub fn set_error_format(format: ErrorFormat) {
+    JSON_ERRORS.store(format == ErrorFormat::Json, Ordering::Relaxed);
+}
+
+pub fn error_format() -> ErrorFormat {
+    if JSON_ERRORS.load(Ordering::Relaxed) { ErrorFormat::Json } else { ErrorFormat::Human }
+}
+
+/// The pass that produced a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Lexer,
+    Parser,
+    Compiler,
+    Optimizer,
+    Runtime,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stage::Lexer => write!(f, "Lexer"),
+            Stage::Parser => write!(f, "Parser"),
+            Stage::Compiler => write!(f, "Compiler"),
+            Stage::Optimizer => write!(f, "Optimizer"),
+            Stage::Runtime => write!(f, "Runtime"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a script, by `gl check` or while running it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub stage: Stage,
+    pub severity: Severity,
+    /// Stable identifier of the kind of problem, e.g. `unexpected-token`.
+    pub code: &'static str,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(stage: Stage, code: &'static str, message: String, location: Option<Location>) -> Self {
+        Diagnostic { stage, severity: Severity::Error, code, message, location }
+    }
+
+    /// An optimizer warning; they are all about code that was removed.
+    pub(crate) fn warning(message: String) -> Self {
+        Diagnostic { stage: Stage::Optimizer, severity: Severity::Warning, code: "dead-code", message, location: None }
+    }
 
-pub fn run_check(input: &str) {
+    /// The diagnostic as a single line of JSON, e.g.
+    ///
+    /// ```json
+    /// {"severity":"error","code":"unexpected-token","stage":"parser","message":"...","file":"main.g","span":{"line":3,"column":7}}
+    /// ```
+    ///
+    /// `file` and `span` are `null` when not known.
+    pub fn to_json(&self, file: Option<&str>) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let span = self.location.map(|loc| json!({ "line": loc.line, "column": loc.column }));
+        json!({
+            "severity": severity,
+            "code": self.code,
+            "stage": self.stage.to_string().to_lowercase(),
+            "message": self.message,
+            "file": file,
+            "span": span,
+        })
+        .to_string()
+    }
+}
+
+/// Lexes, parses and compiles `input` without evaluating it, and returns
+/// everything that was found wrong with it.
+///
+/// Lexer and parser errors stop the check, since nothing after them can be
+/// trusted; the passes over a parsed program (`await` placement, the
+/// optimizer's warnings and compilation) all report.
+pub fn check_source(input: &str) -> Vec<Diagnostic> {
     let spanned_tokens = match Lexer::lex_tokens(input.as_bytes()) {
         Ok(t) => t,
-        Err(e) => {
-            eprintln!("╭─ Check Failed ─────────────────────────────");
-            eprintln!("│");
-            eprintln!("│ Lexer Error:");
-            eprintln!("│   {}", e);
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
-            return;
-        }
+        Err(e) => return vec![Diagnostic::error(Stage::Lexer, e.code(), e.to_string(), Some(e.location()))],
     };
 
-    let spanned = SpannedTokens::new(&spanned_tokens);
-    let (tokens, _) = spanned.to_tokens_with_offset();
+    // Not `SpannedTokens::to_tokens`, which leaks the token vector: the
+    // language server checks every edit of a document.
+    let token_vec: Vec<Token> = spanned_tokens.iter().map(|s| s.node.clone()).collect();
+    let tokens = Tokens::new(&token_vec);
 
-    let mut program = match Parser::parse_tokens(tokens) {
+    let mut program = match Parser::parse_syntax(tokens) {
         Ok((_, program)) => program,
         Err(e) => {
-            eprintln!("╭─ Check Failed ─────────────────────────────");
-            eprintln!("│");
-            eprintln!("│ Parser Error:");
-
-            if let nom::Err::Error(err) | nom::Err::Failure(err) = &e {
-                let remaining_count = err.input.token.len();
-                let total_count = tokens.token.len();
-                let error_index = total_count - remaining_count;
-                let parser_error = convert_nom_error(&e, "", &spanned_tokens, error_index);
-                eprintln!("│   {}", parser_error);
-                eprintln!("│");
-                eprintln!("│ {}", show_error_context(&err.input, 3));
-            } else {
-                eprintln!("│   Unexpected end of input");
-            }
-
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
-            return;
+            let error = match &e {
+                nom::Err::Error(err) | nom::Err::Failure(err) => {
+                    let error_index = tokens.token.len() - err.input.token.len();
+                    let parser_error = convert_nom_error(&e, "", &spanned_tokens, error_index);
+                    Diagnostic::error(Stage::Parser, parser_error.code(), parser_error.to_string(), parser_error.location())
+                }
+                nom::Err::Incomplete(_) => {
+                    Diagnostic::error(Stage::Parser, "unexpected-eof", "Unexpected end of input".to_string(), None)
+                }
+            };
+            return vec![error];
         }
     };
 
-    compute_slots(&mut program);
+    let mut diagnostics = Vec::new();
+    if let Err(e) = validate_await_usage(&program) {
+        diagnostics.push(Diagnostic::error(Stage::Parser, e.code(), e.to_string(), e.location()));
+    }
+
+    diagnostics.extend(optimize(&mut program).into_iter().map(Diagnostic::warning));
+    if let Err(e) = Compiler::compile_program(&mut program) {
+        diagnostics.push(Diagnostic::error(Stage::Compiler, e.code(), e.to_string(), None));
+    }
+
+    diagnostics
+}
+
+/// Checks `input`, read from `file`, with `max_depth` as the nesting limit
+/// and prints what was found. Returns `false` if the script has errors.
+pub fn run_check(input: &str, file: &str, max_depth: usize) -> bool {
+    let diagnostics = with_nesting_limit(max_depth, || check_source(input));
+    if error_format() == ErrorFormat::Json {
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic.to_json(Some(file)));
+        }
+        return diagnostics.iter().all(|d| d.severity != Severity::Error);
+    }
+
+    let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.severity == Severity::Error).collect();
+    let warnings: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.severity == Severity::Warning).collect();
+
+    if !warnings.is_empty() {
+        eprintln!("╭─ Warning ──────────────────────────────────");
+        eprintln!("│");
+        for warning in &warnings {
+            eprintln!("│ {}", warning.message);
+        }
+        eprintln!("│");
+        eprintln!("╰────────────────────────────────────────────");
+    }
+
+    if !errors.is_empty() {
+        eprintln!("╭─ Check Failed ─────────────────────────────");
+        for error in &errors {
+            eprintln!("│");
+            eprintln!("│ {} Error:", error.stage);
+            eprintln!("│   {}", error.message);
+            if let Some(location) = error.location {
+                print_source_line(input, location);
+            }
+        }
+        eprintln!("│");
+        eprintln!("╰────────────────────────────────────────────");
+        return false;
+    }
 
     println!("╭─ Check Passed ─────────────────────────────");
     println!("│");
-    println!("│ ✓ No syntax errors found");
+    println!("│ ✓ No errors found");
     println!("│");
     println!("╰────────────────────────────────────────────");
+    true
+}
+
+/// Prints the line of `input` that `location` points into, with a caret
+/// under the column.
+fn print_source_line(input: &str, location: Location) {
+    let Some(line) = input.lines().nth(location.line.saturating_sub(1)) else {
+        return;
+    };
+    let number = location.line.to_string();
+    let caret_at = line.chars().take(location.column.saturating_sub(1)).count();
+    eprintln!("│");
+    eprintln!("│   {} | {}", number, line);
+    eprintln!("│   {} | {}^", " ".repeat(number.len()), " ".repeat(caret_at));
 }
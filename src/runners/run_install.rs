@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use crate::package::lockfile::{LOCKFILE, Lockfile};
+use crate::package::manifest::{Dependency, MANIFEST_FILE, Manifest};
+use crate::package::{MODULES_DIR, install, install_locked, is_valid_name, package_name};
+
+/// `gl install <git-url>[#<rev>] [name]` installs one package into
+/// `giulio_modules/` and pins it in the lockfile; `gl install` alone
+/// installs every package the lockfile lists at its pinned commit, plus the
+/// dependencies in `giulio.toml` it does not list yet.
+/// Returns `false` if anything failed; the problem has been printed.
+pub fn run_install(args: &[String]) -> bool {
+    let root = Path::new(".");
+    let lockfile_path = root.join(LOCKFILE);
+    let mut lockfile = match Lockfile::load(&lockfile_path) {
+        Ok(lockfile) => lockfile,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return false;
+        }
+    };
+
+    let Some(spec) = args.first() else {
+        let dependencies = if root.join(MANIFEST_FILE).is_file() {
+            match Manifest::load(root) {
+                Ok(manifest) => manifest.dependencies,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return false;
+                }
+            }
+        } else {
+            Vec::new()
+        };
+        if lockfile.packages.is_empty() && dependencies.is_empty() {
+            println!("Nothing to install: no dependencies in {} or {}", MANIFEST_FILE, LOCKFILE);
+            return true;
+        }
+
+        let mut ok = true;
+        let unlocked: Vec<Dependency> = dependencies
+            .into_iter()
+            .filter(|dep| !lockfile.packages.iter().any(|p| p.name == dep.name && p.source == dep.git))
+            .collect();
+        let mut installed = Vec::new();
+        for dependency in unlocked {
+            match install(root, &dependency.name, &dependency.git, dependency.rev.as_deref()) {
+                Ok(package) => {
+                    println!("  installed {} at {}", package.name, short(&package.rev));
+                    installed.push(package.name.clone());
+                    lockfile.insert(package);
+                }
+                Err(e) => {
+                    eprintln!("  could not install {}: {}", dependency.name, e);
+                    ok = false;
+                }
+            }
+        }
+        if let Err(e) = lockfile.save(&lockfile_path) {
+            eprintln!("Error: {}", e);
+            return false;
+        }
+
+        for package in lockfile.packages.iter().filter(|p| !installed.contains(&p.name)) {
+            match install_locked(root, package) {
+                Ok(true) => println!("  installed {} at {}", package.name, short(&package.rev)),
+                Ok(false) => println!("  {} is up to date", package.name),
+                Err(e) => {
+                    eprintln!("  could not install {}: {}", package.name, e);
+                    ok = false;
+                }
+            }
+        }
+        return ok;
+    };
+
+    let (url, rev) = match spec.split_once('#') {
+        Some((url, rev)) => (url, Some(rev)),
+        None => (spec.as_str(), None),
+    };
+    let name = match args.get(1) {
+        Some(name) if is_valid_name(name) => name.clone(),
+        Some(name) => {
+            eprintln!("Error: '{}' cannot be imported; package names must be identifiers", name);
+            return false;
+        }
+        None => match package_name(url) {
+            Some(name) => name,
+            None => {
+                eprintln!("Error: cannot name a package after '{}'; pass a name: gl install <url> <name>", url);
+                return false;
+            }
+        },
+    };
+
+    let package = match install(root, &name, url, rev) {
+        Ok(package) => package,
+        Err(e) => {
+            eprintln!("Could not install {}: {}", name, e);
+            return false;
+        }
+    };
+    println!("Installed {} at {} into {}/{}", name, short(&package.rev), MODULES_DIR, name);
+    lockfile.insert(package);
+    if let Err(e) = lockfile.save(&lockfile_path) {
+        eprintln!("Error: {}", e);
+        return false;
+    }
+    true
+}
+
+/// The abbreviated form of a commit hash.
+fn short(rev: &str) -> &str {
+    rev.get(..7).unwrap_or(rev)
+}
@@ -0,0 +1,34 @@
+use crate::lint::{LintConfig, lint};
+use crate::runners::run_source::parse_with_markers_or_report;
+
+/// Lints `input`, the contents of `file`, with the rules `config` enables and
+/// prints what was found, each at `file:line:column`. Returns `false` if the
+/// script does not parse, with `max_depth` as the nesting limit, or has lint
+/// warnings.
+pub fn run_lint(input: &str, file: &str, config: &LintConfig, max_depth: usize) -> bool {
+    let Some(program) = parse_with_markers_or_report(input, max_depth) else {
+        return false;
+    };
+
+    let warnings = lint(&program, config);
+    if warnings.is_empty() {
+        println!("╭─ Lint Passed ──────────────────────────────");
+        println!("│");
+        println!("│ ✓ No problems found");
+        println!("│");
+        println!("╰────────────────────────────────────────────");
+        return true;
+    }
+
+    eprintln!("╭─ Lint ─────────────────────────────────────");
+    eprintln!("│");
+    for warning in &warnings {
+        match warning.location {
+            Some(at) => eprintln!("│ {}:{}:{}: {}", file, at.line, at.column, warning),
+            None => eprintln!("│ {}", warning),
+        }
+    }
+    eprintln!("│");
+    eprintln!("╰────────────────────────────────────────────");
+    false
+}
@@ -0,0 +1,11 @@
+use std::io;
+
+use crate::lsp::serve;
+
+/// Serves the Language Server Protocol on stdin/stdout until the editor
+/// disconnects.
+pub fn run_lsp() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    serve(&mut stdin.lock(), &mut stdout.lock())
+}
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use crate::package::is_valid_name;
+use crate::package::scaffold::create_project;
+
+/// `gl new <path>` creates a project skeleton in `path`, named after its
+/// last component. Returns `false` if it could not; the problem has been
+/// printed.
+pub fn run_new(args: &[String]) -> bool {
+    let Some(target) = args.first() else {
+        eprintln!("Error: gl new expects the directory to create, e.g. gl new my_app");
+        return false;
+    };
+    let dir = Path::new(target);
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().replace('-', "_"))
+        .unwrap_or_default();
+    if !is_valid_name(&name) {
+        eprintln!("Error: '{}' is not a valid project name; use letters, digits and '_'", name);
+        return false;
+    }
+
+    match create_project(dir, &name) {
+        Ok(files) => {
+            println!("Created project {} in {}", name, dir.display());
+            for file in files {
+                println!("  {}", file.display());
+            }
+            println!();
+            println!("Run it with `cd {} && gl run`, and its tests with `gl test`.", dir.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            false
+        }
+    }
+}
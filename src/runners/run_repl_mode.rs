@@ -1,38 +1,148 @@
+use std::borrow::Cow;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::ast::ast::Program;
+use crate::parser::depth_check::with_nesting_limit;
 use crate::parser::parser::Parser;
 use crate::lexer::lexer::Lexer;
 use crate::vm::obj::Object;
-use crate::lexer::token::SpannedTokens;
-use crate::parser::parser_errors::{convert_nom_error, show_error_context};
+use crate::lexer::token::{Spanned, SpannedTokens, Token, Tokens};
+use crate::parser::parser_errors::{convert_nom_error, is_unterminated, show_error_context};
 use crate::vm::runtime::env::Environment;
-use crate::vm::runtime::module_registry::ModuleRegistry;
+use crate::runners::run_source::registry_with_depth;
 use crate::vm::compiler::Compiler;
 use crate::vm::vm::VirtualMachine;
+use crate::lexer::highlight::{HighlightKind, highlight};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
 
-pub async fn repl() {
+const HISTORY_FILE: &str = ".giulio_history";
+
+/// Colours the line being typed. Cursor movement, kill/yank and bracketed
+/// paste come with the editor itself.
+struct ReplHelper;
+
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let highlights = highlight(line);
+        if highlights.is_empty() {
+            return Cow::Borrowed(line);
+        }
+
+        // The editor hands over one line at a time, so only columns matter.
+        let mut colored = String::with_capacity(line.len() * 2);
+        let mut pending = highlights.iter().peekable();
+        let mut open = None;
+        for (i, c) in line.chars().enumerate() {
+            let column = i + 1;
+            if open.is_some_and(|end| end == column) {
+                colored.push_str(RESET);
+                open = None;
+            }
+            while pending.peek().is_some_and(|h| h.span.start.column < column) {
+                pending.next();
+            }
+            if let Some(h) = pending.next_if(|h| h.span.start.column == column)
+                && let Some(color) = color(h.kind)
+            {
+                colored.push_str(color);
+                open = Some(h.span.end.column);
+            }
+            colored.push(c);
+        }
+        if open.is_some() {
+            colored.push_str(RESET);
+        }
+        Cow::Owned(colored)
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, kind: CmdKind) -> bool {
+        kind != CmdKind::MoveCursor
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+const RESET: &str = "\x1b[0m";
+
+/// ANSI colour for a kind of token; identifiers and punctuation stay plain.
+fn color(kind: HighlightKind) -> Option<&'static str> {
+    match kind {
+        HighlightKind::Keyword => Some("\x1b[35m"),
+        HighlightKind::String => Some("\x1b[32m"),
+        HighlightKind::Number => Some("\x1b[33m"),
+        HighlightKind::Comment => Some("\x1b[90m"),
+        HighlightKind::Operator => Some("\x1b[36m"),
+        HighlightKind::Identifier | HighlightKind::Punctuation => None,
+    }
+}
+
+pub async fn repl(max_depth: usize) {
     const VERSION: &str = env!("CARGO_PKG_VERSION");
     println!("g-lang v{}", VERSION);
     println!("Type 'exit' or 'quit' to quit\n");
 
     let globals = Arc::new(Mutex::new(Environment::new_root()));
-    let module_registry = Arc::new(Mutex::new(ModuleRegistry::new(PathBuf::from("."))));
+    let module_registry = Arc::new(Mutex::new(registry_with_depth(PathBuf::from("."), max_depth)));
+    let gc = Arc::clone(&module_registry.lock().unwrap().gc);
     let mut vm = VirtualMachine::new(globals, module_registry);
 
-    loop {
-        print!(">> ");
-        io::stdout().flush().unwrap();
+    let mut editor = match Editor::<ReplHelper, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("Failed to start the REPL: {}", e);
+            return;
+        }
+    };
+    editor.set_helper(Some(ReplHelper));
+    let history = history_path();
+    if let Some(path) = &history {
+        // Missing on first start.
+        let _ = editor.load_history(path);
+    }
 
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            eprintln!("Failed to read input");
-            continue;
+    // Lines read so far for the statement being entered.
+    let mut input = String::new();
+
+    loop {
+        let prompt = if input.is_empty() { ">> " } else { ".. " };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                input.push_str(&line);
+                input.push('\n');
+            }
+            // Ctrl-C drops the statement being entered.
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Failed to read input: {}", e);
+                break;
+            }
         }
 
         let trimmed = input.trim();
         if trimmed.is_empty() {
+            input.clear();
             continue;
         }
         if trimmed == "exit" || trimmed == "quit" {
@@ -44,28 +154,25 @@ pub async fn repl() {
             Ok(t) => t,
             Err(e) => {
                 eprintln!("Lexer Error: {}", e);
+                let _ = editor.add_history_entry(input.trim_end());
+                input.clear();
                 continue;
             }
         };
 
+        // An unclosed `(`, `{` or `[` continues on the next line.
+        let token_vec: Vec<Token> = spanned_tokens.iter().map(|t| t.node.clone()).collect();
+        if is_unterminated(&Tokens::new(&token_vec)) {
+            continue;
+        }
+        let _ = editor.add_history_entry(input.trim_end());
+        input.clear();
+
         let spanned = SpannedTokens::new(&spanned_tokens);
         let (tokens, _) = spanned.to_tokens_with_offset();
 
-        let mut program = match Parser::parse_tokens(tokens) {
-            Ok((_, program)) => program,
-            Err(e) => {
-                if let nom::Err::Error(err) | nom::Err::Failure(err) = &e {
-                    let remaining_count = err.input.token.len();
-                    let total_count = tokens.token.len();
-                    let error_index = total_count - remaining_count;
-                    let parser_error = convert_nom_error(&e, "", &spanned_tokens, error_index);
-                    eprintln!("Parser Error: {}", parser_error);
-                    eprintln!("{}", show_error_context(&err.input, 3));
-                } else {
-                    eprintln!("Parser Error: Unexpected end of input");
-                }
-                continue;
-            }
+        let Some(mut program) = with_nesting_limit(max_depth, || parse_entry(tokens, &spanned_tokens)) else {
+            continue;
         };
 
         let chunk = match Compiler::compile_program(&mut program) {
@@ -85,7 +192,42 @@ pub async fn repl() {
             Err(e) => eprintln!("{}", e),
         }
 
+        // Closures defined on this line may have formed environment cycles;
+        // free them now instead of waiting for the next automatic collection.
+        gc.collect_cycles();
+
         println!();
         io::stdout().flush().unwrap();
     }
+
+    if let Some(path) = &history
+        && let Err(e) = editor.save_history(path)
+    {
+        eprintln!("Failed to save history to {}: {}", path.display(), e);
+    }
+}
+
+/// Parses one REPL entry, printing any error.
+fn parse_entry(tokens: Tokens, spanned_tokens: &[Spanned<Token>]) -> Option<Program> {
+    match Parser::parse_tokens(tokens) {
+        Ok((_, program)) => Some(program),
+        Err(e) => {
+            if let nom::Err::Error(err) | nom::Err::Failure(err) = &e {
+                let remaining_count = err.input.token.len();
+                let total_count = tokens.token.len();
+                let error_index = total_count - remaining_count;
+                let parser_error = convert_nom_error(&e, "", spanned_tokens, error_index);
+                eprintln!("Parser Error: {}", parser_error);
+                eprintln!("{}", show_error_context(&err.input, 3));
+            } else {
+                eprintln!("Parser Error: Unexpected end of input");
+            }
+            None
+        }
+    }
+}
+
+/// Where REPL history is kept between sessions: `~/.giulio_history`.
+fn history_path() -> Option<PathBuf> {
+    Some(std::env::home_dir()?.join(HISTORY_FILE))
 }
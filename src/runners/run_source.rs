@@ -1,88 +1,217 @@
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::ast::ast::Program;
 use crate::lexer::lexer::Lexer;
 use crate::lexer::token::SpannedTokens;
+use crate::package::manifest::Manifest;
+use crate::parser::depth_check::{with_nesting_limit, DEFAULT_MAX_NESTING_DEPTH};
+use crate::parser::optimize::optimize;
 use crate::parser::parser::Parser;
 use crate::parser::parser_errors::{convert_nom_error, show_error_context};
+use crate::runners::run_check::{Diagnostic, ErrorFormat, Stage, error_format};
+use crate::vm::limits::ResourceLimits;
 use crate::vm::obj::Object;
 use crate::vm::runtime::env::Environment;
 use crate::vm::runtime::module_registry::ModuleRegistry;
+use crate::vm::runtime::runtime_errors::RuntimeError;
 use crate::vm::compiler::Compiler;
 use crate::vm::vm::VirtualMachine;
 
-pub async fn run_source(input: &str) {
-    let spanned_tokens = match Lexer::lex_tokens(input.as_bytes()) {
-        Ok(t) => t,
-        Err(e) => {
-            eprintln!("╭─ Lexer Error ──────────────────────────────");
-            eprintln!("│");
-            eprintln!("│ {}", e);
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
-            return;
-        }
-    };
+/// Runs a script and returns its exit status: what it passed to `exit`, 0
+/// if it finished, or 1 if it could not be parsed or failed with an error.
+pub async fn run_source(input: &str) -> i32 {
+    run_source_with_limits(input, ResourceLimits::default(), DEFAULT_MAX_NESTING_DEPTH).await
+}
 
-    let spanned = SpannedTokens::new(&spanned_tokens);
-    let (tokens, _) = spanned.to_tokens_with_offset();
+/// Like [`run_source`], but aborts scripts that break `limits` and rejects
+/// ones, or modules they import, that nest expressions more than
+/// `max_depth` deep.
+pub async fn run_source_with_limits(input: &str, limits: ResourceLimits, max_depth: usize) -> i32 {
+    run_with_registry(input, None, limits, registry_with_depth(PathBuf::from("."), max_depth)).await
+}
 
-    let mut program = match Parser::parse_tokens(tokens) {
-        Ok((_, program)) => program,
+/// Like [`run_source_with_limits`] for a script read from `file`, which
+/// errors are reported against.
+pub async fn run_file(file: &str, input: &str, limits: ResourceLimits, max_depth: usize) -> i32 {
+    run_with_registry(input, Some(file), limits, registry_with_depth(PathBuf::from("."), max_depth)).await
+}
+
+/// Runs the entry point of the project `manifest` describes, resolving
+/// imports from the project root and then its search paths. Returns the
+/// exit status like [`run_source`]; 1 if the entry point could not be read.
+pub async fn run_project(manifest: &Manifest, limits: ResourceLimits, max_depth: usize) -> i32 {
+    let entry = manifest.entry_path();
+    let source = match std::fs::read_to_string(&entry) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("╭─ Parser Error ─────────────────────────────");
-            eprintln!("│");
-
-            if let nom::Err::Error(err) | nom::Err::Failure(err) = &e {
-                let remaining_count = err.input.token.len();
-                let total_count = tokens.token.len();
-                let error_index = total_count - remaining_count;
-                let parser_error = convert_nom_error(&e, "", &spanned_tokens, error_index);
-                eprintln!("│ {}", parser_error);
-                eprintln!("│");
-                eprintln!("│ {}", show_error_context(&err.input, 3));
-            } else {
-                eprintln!("│ Unexpected end of input");
-            }
-
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
-            return;
+            eprintln!("Could not read entry point {}: {}", entry.display(), e);
+            return 1;
         }
     };
+    let mut registry = registry_with_depth(manifest.root.clone(), max_depth);
+    registry.search_paths = manifest.search_paths();
+    run_with_registry(&source, Some(&entry.to_string_lossy()), limits, registry).await
+}
+
+async fn run_with_registry(
+    input: &str,
+    file: Option<&str>,
+    limits: ResourceLimits,
+    module_registry: ModuleRegistry,
+) -> i32 {
+    let max_depth = module_registry.max_nesting_depth;
+    let Some(program) = with_nesting_limit(max_depth, || parse_with_report(input, file, false)) else {
+        return 1;
+    };
+    run_program(program, file, limits, module_registry).await
+}
+
+/// Optimizes, compiles and runs a parsed script, printing any error.
+/// Returns the exit status like [`run_source`].
+pub(crate) async fn run_program(
+    mut program: Program,
+    file: Option<&str>,
+    limits: ResourceLimits,
+    module_registry: ModuleRegistry,
+) -> i32 {
+    print_warnings(&optimize(&mut program), file);
 
     let chunk = match Compiler::compile_program(&mut program) {
         Ok(chunk) => chunk,
         Err(e) => {
-            eprintln!("╭─ Compiler Error ───────────────────────────");
-            eprintln!("│");
-            eprintln!("│ {}", e);
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
-            return;
+            report(&Diagnostic::error(Stage::Compiler, e.code(), e.to_string(), None), file, None);
+            return 1;
         }
     };
     let globals = Arc::new(Mutex::new(Environment::new_root()));
-    let module_registry = Arc::new(Mutex::new(ModuleRegistry::new(PathBuf::from("."))));
+    let module_registry = Arc::new(Mutex::new(module_registry));
     let mut vm = VirtualMachine::new(globals, module_registry);
+    if limits != ResourceLimits::default() {
+        vm.set_limits(limits);
+    }
+
+    let error = match vm.run(Arc::new(chunk)).await {
+        Err(RuntimeError::Exit(code)) => return code,
+        Ok(Object::Error(e)) => *e,
+        Ok(Object::ThrownValue(value)) => RuntimeError::UncaughtException(value.to_string()),
+        Err(e) => e,
+        Ok(_) => return 0,
+    };
+    // A module that failed to load is where the problem is, not the script.
+    let (file, location) = match &error {
+        RuntimeError::ModuleError { file, location, .. } => (Some(file.as_str()), *location),
+        _ => (file, None),
+    };
+    report(&Diagnostic::error(Stage::Runtime, error.code(), error.to_string(), location), file, None);
+    1
+}
 
-    let result = vm.run(Arc::new(chunk)).await;
+/// A registry resolving imports from `base_path` that parses them with
+/// `max_depth` as the nesting limit.
+pub(crate) fn registry_with_depth(base_path: PathBuf, max_depth: usize) -> ModuleRegistry {
+    let mut registry = ModuleRegistry::new(base_path);
+    registry.max_nesting_depth = max_depth;
+    registry
+}
 
-    match result {
-        Ok(Object::Error(e)) => {
-            eprintln!("╭─ Runtime Error ────────────────────────────");
-            eprintln!("│");
-            eprintln!("│ {}", e);
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
+/// Lexes and parses `input` with `max_depth` as the nesting limit, printing
+/// any error the way `gl run` reports it.
+pub(crate) fn parse_or_report(input: &str, max_depth: usize) -> Option<Program> {
+    with_nesting_limit(max_depth, || parse_with_report(input, None, false))
+}
+
+/// Like [`parse_or_report`], but with a line marker before every statement
+/// for `gl test --coverage` and `gl lint`.
+pub(crate) fn parse_with_markers_or_report(input: &str, max_depth: usize) -> Option<Program> {
+    with_nesting_limit(max_depth, || parse_with_report(input, None, true))
+}
+
+fn parse_with_report(input: &str, file: Option<&str>, with_markers: bool) -> Option<Program> {
+    let spanned_tokens = match Lexer::lex_tokens(input.as_bytes()) {
+        Ok(t) => t,
+        Err(e) => {
+            report(&Diagnostic::error(Stage::Lexer, e.code(), e.to_string(), Some(e.location())), file, None);
+            return None;
         }
+    };
+
+    let spanned = SpannedTokens::new(&spanned_tokens);
+    let (tokens, _) = spanned.to_tokens_with_offset();
+
+    let result = if with_markers {
+        let clamp = |n: usize| u16::try_from(n).unwrap_or(u16::MAX);
+        let positions = spanned_tokens
+            .iter()
+            .map(|t| (clamp(t.span.start.line), clamp(t.span.start.column)))
+            .collect();
+        Parser::parse_with_markers(tokens, positions)
+    } else {
+        Parser::parse_tokens(tokens)
+    };
+    match result {
+        Ok((_, program)) => Some(program),
         Err(e) => {
-            eprintln!("╭─ Runtime Error ────────────────────────────");
-            eprintln!("│");
-            eprintln!("│ {}", e);
-            eprintln!("│");
-            eprintln!("╰────────────────────────────────────────────");
+            let (diagnostic, context) = match &e {
+                nom::Err::Error(err) | nom::Err::Failure(err) => {
+                    let remaining_count = err.input.token.len();
+                    let total_count = tokens.token.len();
+                    let error_index = total_count - remaining_count;
+                    let parser_error = convert_nom_error(&e, "", &spanned_tokens, error_index);
+                    let diagnostic = Diagnostic::error(
+                        Stage::Parser,
+                        parser_error.code(),
+                        parser_error.to_string(),
+                        parser_error.location(),
+                    );
+                    (diagnostic, Some(show_error_context(&err.input, 3)))
+                }
+                nom::Err::Incomplete(_) => {
+                    (Diagnostic::error(Stage::Parser, "unexpected-eof", "Unexpected end of input".to_string(), None), None)
+                }
+            };
+            report(&diagnostic, file, context);
+            None
         }
-        _ => {}
     }
 }
+
+/// Prints `diagnostic` about `file` in the chosen error format. `context`
+/// is shown under the message, in the human format only.
+fn report(diagnostic: &Diagnostic, file: Option<&str>, context: Option<String>) {
+    if error_format() == ErrorFormat::Json {
+        eprintln!("{}", diagnostic.to_json(file));
+        return;
+    }
+    let title = format!("╭─ {} Error ", diagnostic.stage);
+    eprintln!("{}{}", title, "─".repeat(45 - title.chars().count()));
+    eprintln!("│");
+    eprintln!("│ {}", diagnostic.message);
+    if let Some(context) = context {
+        eprintln!("│");
+        eprintln!("│ {}", context);
+    }
+    eprintln!("│");
+    eprintln!("╰────────────────────────────────────────────");
+}
+
+/// Prints warnings produced by the AST optimizer (e.g. unreachable code)
+/// about `file`.
+pub fn print_warnings(warnings: &[String], file: Option<&str>) {
+    if warnings.is_empty() {
+        return;
+    }
+    if error_format() == ErrorFormat::Json {
+        for warning in warnings {
+            eprintln!("{}", Diagnostic::warning(warning.clone()).to_json(file));
+        }
+        return;
+    }
+    eprintln!("╭─ Warning ──────────────────────────────────");
+    eprintln!("│");
+    for warning in warnings {
+        eprintln!("│ {}", warning);
+    }
+    eprintln!("│");
+    eprintln!("╰────────────────────────────────────────────");
+}
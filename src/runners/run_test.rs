@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::ast::ast::Stmt;
+use crate::parser::depth_check::DEFAULT_MAX_NESTING_DEPTH;
+use crate::parser::optimize::optimize;
+use crate::runners::run_bench::check_result;
+use crate::runners::run_source::{parse_with_markers_or_report, parse_or_report, registry_with_depth};
+use crate::vm::chunk::Chunk;
+use crate::vm::compiler::Compiler;
+use crate::vm::coverage;
+use crate::vm::obj::Object;
+use crate::vm::ops::calls::call_function_object;
+use crate::vm::runtime::env::Environment;
+use crate::vm::vm::VirtualMachine;
+
+/// Test files are the `.g` files whose name ends with this.
+pub const TEST_FILE_SUFFIX: &str = "_test.g";
+
+/// Outcome of one test: `error` is why it failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The test files under `paths`: directories are searched recursively for
+/// `*_test.g` files, skipping hidden ones, and files are taken as given.
+/// No paths means the current directory.
+pub fn discover_tests(paths: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if paths.is_empty() {
+        collect_test_files(Path::new("."), &mut files);
+    }
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            collect_test_files(path, &mut files);
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    files
+}
+
+fn collect_test_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_test_files(&path, files);
+        } else if name.ends_with(TEST_FILE_SUFFIX) {
+            files.push(path);
+        }
+    }
+}
+
+/// Runs the tests in `input`: every top-level `fn test_*()` that takes no
+/// arguments, or the whole script as one test named `name` if it has none.
+///
+/// Each test gets a fresh VM that runs the script's top level first, so
+/// tests cannot see each other's changes to globals. `None` if the script
+/// does not parse or compile; the problem has been printed.
+pub async fn run_test_source(input: &str, name: &str) -> Option<Vec<TestResult>> {
+    run_file_tests(input, name, None, DEFAULT_MAX_NESTING_DEPTH).await
+}
+
+/// [`run_test_source`], recording which lines ran as the coverage of
+/// `coverage_path` if that is given, with `max_depth` as the nesting limit.
+async fn run_file_tests(
+    input: &str,
+    name: &str,
+    coverage_path: Option<&str>,
+    max_depth: usize,
+) -> Option<Vec<TestResult>> {
+    let mut program = match coverage_path {
+        Some(_) => parse_with_markers_or_report(input, max_depth)?,
+        None => parse_or_report(input, max_depth)?,
+    };
+    // Taken before optimizing so code the optimizer drops counts as not run.
+    let executable = coverage::executable_lines(&program);
+    optimize(&mut program);
+
+    let names: Vec<String> = program
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::FnStmt { name, params, .. } if name.name.starts_with("test_") && params.is_empty() => {
+                Some(name.name.clone())
+            }
+            _ => None,
+        })
+        .collect();
+
+    let chunk = match Compiler::compile_program(&mut program) {
+        Ok(chunk) => Arc::new(chunk),
+        Err(e) => {
+            eprintln!("╭─ Compiler Error ───────────────────────────");
+            eprintln!("│");
+            eprintln!("│ {}", e);
+            eprintln!("│");
+            eprintln!("╰────────────────────────────────────────────");
+            return None;
+        }
+    };
+
+    coverage::reset();
+    let mut results = Vec::new();
+    if names.is_empty() {
+        let error = run_test(&chunk, None, max_depth).await.err();
+        results.push(TestResult { name: name.to_string(), error });
+    }
+    for name in names {
+        let error = run_test(&chunk, Some(&name), max_depth).await.err();
+        results.push(TestResult { name, error });
+    }
+    if let Some(path) = coverage_path {
+        coverage::record_file(path, &executable);
+    }
+    Some(results)
+}
+
+/// Runs the script's top level in a new VM, then calls `function` if given.
+async fn run_test(chunk: &Arc<Chunk>, function: Option<&str>, max_depth: usize) -> Result<(), String> {
+    let globals = Arc::new(Mutex::new(Environment::new_root()));
+    let module_registry = Arc::new(Mutex::new(registry_with_depth(PathBuf::from("."), max_depth)));
+    let mut vm = VirtualMachine::new(Arc::clone(&globals), Arc::clone(&module_registry));
+    check_result(vm.run(Arc::clone(chunk)).await)?;
+
+    let Some(name) = function else {
+        return Ok(());
+    };
+    let function = globals.lock().unwrap().get_by_name(name).unwrap_or(Object::Null);
+    check_result(call_function_object(function, Vec::new(), module_registry, globals).await)
+}
+
+/// Runs the test files under `paths`, with `max_depth` as the nesting
+/// limit, and prints each result and a summary. Returns `false` if a test
+/// failed, a file could not be run or there were no tests.
+pub async fn run_tests(paths: &[String], max_depth: usize) -> bool {
+    let files = discover_tests(paths);
+    if files.is_empty() {
+        eprintln!("No tests found: name test files *{}", TEST_FILE_SUFFIX);
+        return false;
+    }
+
+    let (mut passed, mut failed) = (0, 0);
+    let mut files_ok = true;
+    for file in &files {
+        let display = file.strip_prefix(".").unwrap_or(file).display().to_string();
+        println!("{}", display);
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("  could not read {}: {}", display, e);
+                files_ok = false;
+                continue;
+            }
+        };
+        let name = file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or(display.clone());
+        let coverage_path = coverage::is_enabled().then_some(display.as_str());
+        let Some(results) = run_file_tests(&source, &name, coverage_path, max_depth).await else {
+            files_ok = false;
+            continue;
+        };
+        for result in results {
+            match &result.error {
+                None => {
+                    println!("  ✓ {}", result.name);
+                    passed += 1;
+                }
+                Some(error) => {
+                    println!("  ✗ {}", result.name);
+                    println!("      {}", error);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} passed, {} failed", passed, failed);
+    files_ok && failed == 0
+}
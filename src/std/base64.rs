@@ -0,0 +1,75 @@
+use ::base64::Engine;
+use ::base64::alphabet;
+use ::base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+// Encoders emit canonical output; decoders accept input with or without padding.
+const STANDARD: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+fn input_bytes(args: &[Object]) -> Result<&[u8], RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s.as_bytes()),
+        Some(Object::Bytes(b)) => Ok(b.as_slice()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string or bytes".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+fn decode_with(engine: &GeneralPurpose, args: &[Object]) -> Result<Vec<u8>, RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => engine
+            .decode(s.trim())
+            .map_err(|e| RuntimeError::InvalidArguments(format!("Invalid base64: {}", e))),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+fn bytes_to_string(bytes: Vec<u8>) -> Result<Object, RuntimeError> {
+    String::from_utf8(bytes).map(|s| Object::String(s.into())).map_err(|_| {
+        RuntimeError::InvalidOperation(
+            "Decoded data is not valid UTF-8, use decode_bytes instead".to_string(),
+        )
+    })
+}
+
+pub(crate) fn base64_encode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::String(STANDARD.encode(input_bytes(&args)?).into()))
+}
+
+pub(crate) fn base64_decode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    bytes_to_string(decode_with(&STANDARD, &args)?)
+}
+
+pub(crate) fn base64_decode_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Bytes(Box::new(decode_with(&STANDARD, &args)?)))
+}
+
+pub(crate) fn base64_encode_url(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::String(URL_SAFE.encode(input_bytes(&args)?).into()))
+}
+
+pub(crate) fn base64_decode_url(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    bytes_to_string(decode_with(&URL_SAFE, &args)?)
+}
+
+pub(crate) fn base64_decode_url_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Bytes(Box::new(decode_with(&URL_SAFE, &args)?)))
+}
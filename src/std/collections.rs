@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use indexmap::IndexMap;
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollectionKind {
+    Queue,
+    Stack,
+    Deque,
+}
+
+impl CollectionKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            CollectionKind::Queue => "queue",
+            CollectionKind::Stack => "stack",
+            CollectionKind::Deque => "deque",
+        }
+    }
+}
+
+/// Backing storage for queues, stacks and deques. Copies of the value share
+/// the same buffer, so pushes and pops are visible through every reference.
+pub struct CollectionData {
+    pub kind: CollectionKind,
+    pub items: VecDeque<Object>,
+}
+
+fn new_collection(kind: CollectionKind, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let items = match args.into_iter().next() {
+        Some(Object::Array(arr)) => VecDeque::from(*arr),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "array".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => VecDeque::new(),
+    };
+
+    Ok(Object::Collection(Arc::new(Mutex::new(CollectionData { kind, items }))))
+}
+
+pub(crate) fn collections_queue(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    new_collection(CollectionKind::Queue, args)
+}
+
+pub(crate) fn collections_stack(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    new_collection(CollectionKind::Stack, args)
+}
+
+pub(crate) fn collections_deque(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    new_collection(CollectionKind::Deque, args)
+}
+
+/// Method calls on queues, stacks and deques. `push`/`pop`/`peek` follow the
+/// collection's discipline (FIFO for queues, LIFO for stacks); deques expose
+/// explicit `_front`/`_back` variants instead. Popping or peeking an empty
+/// collection returns null.
+pub(crate) fn call_collection_method(
+    collection: &Arc<Mutex<CollectionData>>,
+    method_name: &str,
+    args: Vec<Object>,
+) -> Result<Object, RuntimeError> {
+    let mut data = collection.lock().unwrap();
+    let kind = data.kind;
+
+    let expect_args = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(RuntimeError::WrongNumberOfArguments { min: n, max: n, got: args.len() })
+        }
+    };
+
+    let result = match (kind, method_name) {
+        (_, "len") => {
+            expect_args(0)?;
+            Object::Integer(data.items.len() as i64)
+        }
+        (_, "is_empty") => {
+            expect_args(0)?;
+            Object::Boolean(data.items.is_empty())
+        }
+        (_, "clear") => {
+            expect_args(0)?;
+            data.items.clear();
+            Object::Null
+        }
+        (_, "to_array") => {
+            expect_args(0)?;
+            Object::Array(Box::new(data.items.iter().cloned().collect()))
+        }
+
+        (CollectionKind::Queue | CollectionKind::Stack, "push") | (CollectionKind::Deque, "push_back") => {
+            expect_args(1)?;
+            data.items.push_back(args.into_iter().next().unwrap_or(Object::Null));
+            Object::Null
+        }
+        (CollectionKind::Deque, "push_front") => {
+            expect_args(1)?;
+            data.items.push_front(args.into_iter().next().unwrap_or(Object::Null));
+            Object::Null
+        }
+
+        (CollectionKind::Queue, "pop") | (CollectionKind::Deque, "pop_front") => {
+            expect_args(0)?;
+            data.items.pop_front().unwrap_or(Object::Null)
+        }
+        (CollectionKind::Stack, "pop") | (CollectionKind::Deque, "pop_back") => {
+            expect_args(0)?;
+            data.items.pop_back().unwrap_or(Object::Null)
+        }
+        (CollectionKind::Queue, "peek") | (CollectionKind::Deque, "peek_front") => {
+            expect_args(0)?;
+            data.items.front().cloned().unwrap_or(Object::Null)
+        }
+        (CollectionKind::Stack, "peek") | (CollectionKind::Deque, "peek_back") => {
+            expect_args(0)?;
+            data.items.back().cloned().unwrap_or(Object::Null)
+        }
+
+        _ => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "{} has no method '{}'",
+                kind.name(),
+                method_name
+            )));
+        }
+    };
+
+    Ok(result)
+}
+
+/// Insertion-ordered map behind `collections.ordered_map`. Shared between
+/// copies like the other collections.
+pub type OrderedMapData = IndexMap<Object, Object, ahash::RandomState>;
+
+fn check_key(key: &Object) -> Result<(), RuntimeError> {
+    if key.is_hashable() {
+        Ok(())
+    } else {
+        Err(RuntimeError::NotHashable(key.type_name()))
+    }
+}
+
+fn sort_key(key: &Object) -> (u8, String, i64) {
+    match key {
+        Object::Boolean(b) => (0, String::new(), *b as i64),
+        Object::Integer(i) => (1, String::new(), *i),
+        other => (2, other.to_string(), 0),
+    }
+}
+
+/// Builds an ordered map from an array of `[key, value]` pairs, keeping
+/// their order, or from a hash, whose keys are sorted since hashes have no
+/// meaningful order.
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn collections_ordered_map(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut map = OrderedMapData::default();
+
+    match args.into_iter().next() {
+        Some(Object::Array(pairs)) => {
+            for pair in pairs.into_iter() {
+                match pair {
+                    Object::Array(kv) if kv.len() == 2 => {
+                        let mut kv = kv.into_iter();
+                        let (key, value) = (kv.next().unwrap(), kv.next().unwrap());
+                        check_key(&key)?;
+                        map.insert(key, value);
+                    }
+                    other => {
+                        return Err(RuntimeError::InvalidArguments(format!(
+                            "ordered_map() expects [key, value] pairs, got {}",
+                            other
+                        )));
+                    }
+                }
+            }
+        }
+        Some(Object::Hash(hash)) => {
+            map.extend(*hash);
+            map.sort_by(|a, _, b, _| sort_key(a).cmp(&sort_key(b)));
+        }
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "array or hash".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => {}
+    }
+
+    Ok(Object::OrderedMap(Arc::new(Mutex::new(map))))
+}
+
+/// Method calls on ordered maps. Iteration helpers (`keys`, `values`,
+/// `entries`) return arrays in insertion order; `remove` keeps the order of
+/// the remaining entries.
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn call_ordered_map_method(
+    map: &Arc<Mutex<OrderedMapData>>,
+    method_name: &str,
+    args: Vec<Object>,
+) -> Result<Object, RuntimeError> {
+    let mut map = map.lock().unwrap();
+    let argc = args.len();
+    let mut args = args.into_iter();
+
+    let arity = |min: usize, max: usize| {
+        if (min..=max).contains(&argc) {
+            Ok(())
+        } else {
+            Err(RuntimeError::WrongNumberOfArguments { min, max, got: argc })
+        }
+    };
+
+    let result = match method_name {
+        "set" => {
+            arity(2, 2)?;
+            let (key, value) = (args.next().unwrap(), args.next().unwrap());
+            check_key(&key)?;
+            map.insert(key, value);
+            Object::Null
+        }
+        "get" => {
+            arity(1, 2)?;
+            let key = args.next().unwrap();
+            match map.get(&key) {
+                Some(value) => value.clone(),
+                None => args.next().unwrap_or(Object::Null),
+            }
+        }
+        "has" => {
+            arity(1, 1)?;
+            Object::Boolean(map.contains_key(&args.next().unwrap()))
+        }
+        "remove" => {
+            arity(1, 1)?;
+            map.shift_remove(&args.next().unwrap()).unwrap_or(Object::Null)
+        }
+        "keys" => {
+            arity(0, 0)?;
+            Object::Array(Box::new(map.keys().cloned().collect()))
+        }
+        "values" => {
+            arity(0, 0)?;
+            Object::Array(Box::new(map.values().cloned().collect()))
+        }
+        "entries" => {
+            arity(0, 0)?;
+            let entries = map
+                .iter()
+                .map(|(k, v)| Object::Array(Box::new(vec![k.clone(), v.clone()])))
+                .collect();
+            Object::Array(Box::new(entries))
+        }
+        "sort_keys" => {
+            arity(0, 0)?;
+            map.sort_by(|a, _, b, _| sort_key(a).cmp(&sort_key(b)));
+            Object::Null
+        }
+        "to_hash" => {
+            arity(0, 0)?;
+            Object::Hash(Box::new(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()))
+        }
+        "len" => {
+            arity(0, 0)?;
+            Object::Integer(map.len() as i64)
+        }
+        "is_empty" => {
+            arity(0, 0)?;
+            Object::Boolean(map.is_empty())
+        }
+        "clear" => {
+            arity(0, 0)?;
+            map.clear();
+            Object::Null
+        }
+        _ => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "ordered map has no method '{}'",
+                method_name
+            )));
+        }
+    };
+
+    Ok(result)
+}
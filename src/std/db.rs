@@ -0,0 +1,196 @@
+use std::sync::{Arc, Mutex};
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+type DbFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>;
+
+/// Connection pool returned by `db.connect`. Only the URL scheme is kept
+/// for display so credentials never end up in printed values.
+pub struct DbHandle {
+    pub scheme: String,
+    #[cfg(any(feature = "postgres", feature = "mysql"))]
+    pub pool: sqlx::AnyPool,
+}
+
+pub(crate) fn db_connect(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_db_connect(args)) as DbFuture)))))
+}
+
+async fn async_db_connect(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let url = match args.first() {
+        Some(Object::String(url)) => url.clone(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+
+    let scheme = url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase()).unwrap_or_default();
+    let (enabled, feature) = match scheme.as_str() {
+        "postgres" | "postgresql" => (cfg!(feature = "postgres"), "postgres"),
+        "mysql" | "mariadb" => (cfg!(feature = "mysql"), "mysql"),
+        _ => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "unsupported database URL '{}://...', expected postgres:// or mysql://",
+                scheme
+            )));
+        }
+    };
+    if !enabled {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "{}:// databases need g-lang built with the '{}' feature",
+            scheme, feature
+        )));
+    }
+
+    connect_pool(scheme, &url).await
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+async fn connect_pool(scheme: String, url: &str) -> Result<Object, RuntimeError> {
+    static DRIVERS: std::sync::Once = std::sync::Once::new();
+    DRIVERS.call_once(sqlx::any::install_default_drivers);
+
+    let pool = sqlx::any::AnyPoolOptions::new()
+        .max_connections(5)
+        .acquire_timeout(std::time::Duration::from_secs(10))
+        .connect(url)
+        .await
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not connect to {} database: {}", scheme, e)))?;
+
+    Ok(Object::Db(Arc::new(DbHandle { scheme, pool })))
+}
+
+#[cfg(not(any(feature = "postgres", feature = "mysql")))]
+async fn connect_pool(scheme: String, _url: &str) -> Result<Object, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(format!("no driver available for {}://", scheme)))
+}
+
+/// Method calls on a pool returned by `db.connect`. `query` and `execute`
+/// return futures; parameters are passed as an array and use the driver's
+/// placeholder syntax (`$1` for Postgres, `?` for MySQL).
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn call_db_method(handle: &Arc<DbHandle>, method_name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let pool = handle.pool.clone();
+    let mut args = args.into_iter();
+    let sql = match args.next() {
+        Some(Object::String(sql)) => Some(sql),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => None,
+    };
+    let params = match args.next() {
+        Some(Object::Array(params)) => *params,
+        Some(Object::Null) | None => Vec::new(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "array".to_string(),
+                got: o.type_name(),
+            });
+        }
+    };
+
+    let future: DbFuture = match (method_name, sql) {
+        ("query", Some(sql)) => Box::pin(async move {
+            let rows = bind_params(sqlx::query(&sql), params)?
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| RuntimeError::InvalidOperation(format!("Database error: {}", e)))?;
+            rows.iter().map(row_to_hash).collect::<Result<Vec<_>, _>>().map(|rows| Object::Array(Box::new(rows)))
+        }),
+        ("execute", Some(sql)) => Box::pin(async move {
+            let result = bind_params(sqlx::query(&sql), params)?
+                .execute(&pool)
+                .await
+                .map_err(|e| RuntimeError::InvalidOperation(format!("Database error: {}", e)))?;
+            Ok(Object::Integer(result.rows_affected() as i64))
+        }),
+        ("query" | "execute", None) => {
+            return Err(RuntimeError::InvalidArguments(format!("{}() expects a SQL string", method_name)));
+        }
+        ("close", _) => Box::pin(async move {
+            pool.close().await;
+            Ok(Object::Null)
+        }),
+        _ => {
+            return Err(RuntimeError::InvalidOperation(format!("db connection has no method '{}'", method_name)));
+        }
+    };
+
+    Ok(Object::Future(Arc::new(Mutex::new(Some(future)))))
+}
+
+#[cfg(not(any(feature = "postgres", feature = "mysql")))]
+pub(crate) fn call_db_method(handle: &Arc<DbHandle>, method_name: &str, _args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(format!(
+        "cannot call '{}' on {} connection: no database driver compiled in",
+        method_name, handle.scheme
+    )))
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+fn bind_params<'q>(
+    mut query: sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>,
+    params: Vec<Object>,
+) -> Result<sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>, RuntimeError> {
+    for param in params {
+        query = match param {
+            Object::Null => query.bind(None::<String>),
+            Object::Integer(i) => query.bind(i),
+            Object::Float(f) => query.bind(f),
+            Object::Boolean(b) => query.bind(b),
+            Object::String(s) => query.bind(s.to_string()),
+            Object::Bytes(b) => query.bind(*b),
+            o => {
+                return Err(RuntimeError::TypeMismatch {
+                    expected: "null, integer, float, boolean, string or bytes".to_string(),
+                    got: o.type_name(),
+                });
+            }
+        };
+    }
+    Ok(query)
+}
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+#[allow(clippy::mutable_key_type)]
+fn row_to_hash(row: &sqlx::any::AnyRow) -> Result<Object, RuntimeError> {
+    use ahash::HashMapExt;
+    use sqlx::any::AnyTypeInfoKind as Kind;
+    use sqlx::{Column, Row};
+
+    fn decode_err(e: sqlx::Error) -> RuntimeError {
+        RuntimeError::InvalidOperation(format!("Could not decode column: {}", e))
+    }
+
+    let mut hash = crate::vm::obj::HashMap::with_capacity(row.columns().len());
+    for column in row.columns() {
+        let i = column.ordinal();
+        let value = match column.type_info().kind() {
+            Kind::Null => Object::Null,
+            Kind::Bool => row.try_get_unchecked::<Option<bool>, _>(i).map_err(decode_err)?.map(Object::Boolean).unwrap_or(Object::Null),
+            Kind::SmallInt | Kind::Integer | Kind::BigInt => {
+                row.try_get_unchecked::<Option<i64>, _>(i).map_err(decode_err)?.map(Object::Integer).unwrap_or(Object::Null)
+            }
+            Kind::Real | Kind::Double => {
+                row.try_get_unchecked::<Option<f64>, _>(i).map_err(decode_err)?.map(Object::Float).unwrap_or(Object::Null)
+            }
+            Kind::Text => row.try_get_unchecked::<Option<String>, _>(i).map_err(decode_err)?.map(|s| Object::String(s.into())).unwrap_or(Object::Null),
+            Kind::Blob => row
+                .try_get_unchecked::<Option<Vec<u8>>, _>(i)
+                .map_err(decode_err)?
+                .map(|b| Object::Bytes(Box::new(b)))
+                .unwrap_or(Object::Null),
+        };
+        hash.insert(Object::String(column.name().to_string().into()), value);
+    }
+    Ok(Object::Hash(Box::new(hash)))
+}
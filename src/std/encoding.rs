@@ -0,0 +1,187 @@
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+fn string_arg(args: &[Object]) -> Result<&str, RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+/// Decodes `%XX` escapes and `+` (as space) in a URL component.
+pub(crate) fn percent_decode(input: &str) -> String {
+    decode_component(input, true)
+}
+
+/// Decodes `%XX` escapes in a URL path segment, where `+` stands for itself.
+pub(crate) fn percent_decode_path(input: &str) -> String {
+    decode_component(input, false)
+}
+
+fn decode_component(input: &str, plus_is_space: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' if plus_is_space => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Percent-encodes everything except the RFC 3986 unreserved characters, so
+/// the result is safe as a query value or path segment.
+pub(crate) fn encoding_url_encode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let s = string_arg(&args)?;
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Ok(Object::String(out.into()))
+}
+
+pub(crate) fn encoding_url_decode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::String(percent_decode(string_arg(&args)?).into()))
+}
+
+pub(crate) fn encoding_hex_encode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let data = match args.first() {
+        Some(Object::String(s)) => s.as_bytes(),
+        Some(Object::Bytes(b)) => b.as_slice(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string or bytes".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+    Ok(Object::String(data.iter().map(|b| format!("{:02x}", b)).collect::<String>().into()))
+}
+
+fn hex_decode(s: &str) -> Result<Vec<u8>, RuntimeError> {
+    let s = s.trim();
+    if !s.len().is_multiple_of(2) {
+        return Err(RuntimeError::InvalidArguments("hex string must have an even length".to_string()));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| RuntimeError::InvalidArguments(format!("invalid hex digits at position {}", i)))
+        })
+        .collect()
+}
+
+pub(crate) fn encoding_hex_decode(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let bytes = hex_decode(string_arg(&args)?)?;
+    String::from_utf8(bytes).map(|s| Object::String(s.into())).map_err(|_| {
+        RuntimeError::InvalidOperation("Decoded data is not valid UTF-8, use hex_decode_bytes instead".to_string())
+    })
+}
+
+pub(crate) fn encoding_hex_decode_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Bytes(Box::new(hex_decode(string_arg(&args)?)?)))
+}
+
+pub(crate) fn encoding_html_escape(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let s = string_arg(&args)?;
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    Ok(Object::String(out.into()))
+}
+
+/// Reverses `html_escape` and also decodes numeric references such as
+/// `&#233;` and `&#xE9;`. Unknown entities are left untouched.
+pub(crate) fn encoding_html_unescape(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let s = string_arg(&args)?;
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    Ok(Object::String(out.into()))
+}
+
+/// `encoding.serialize(value)` encodes a value as bytes with
+/// [`Object::to_bytes`], for saving it or sending it elsewhere.
+pub(crate) fn encoding_serialize(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(value) => Ok(Object::Bytes(Box::new(value.to_bytes()?))),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+/// `encoding.deserialize(bytes)` reads back what `serialize` wrote.
+pub(crate) fn encoding_deserialize(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::Bytes(bytes)) => Object::from_bytes(bytes),
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "bytes".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
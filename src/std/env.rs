@@ -1,8 +1,140 @@
-use crate::vm::obj::Object;
+use crate::vm::obj::{HashMap, Object};
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use ahash::HashMapExt;
 use std::env::args;
+use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+static SCRIPT_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Held for writing while `env.set` and `env.remove` change the process
+/// environment and for reading while the other `env` functions look at it,
+/// so scripts running on different threads never change it under each
+/// other.
+static ENV_LOCK: RwLock<()> = RwLock::new(());
+
+fn read_env() -> RwLockReadGuard<'static, ()> {
+    ENV_LOCK.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write_env() -> RwLockWriteGuard<'static, ()> {
+    ENV_LOCK.write().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sets what `env.args()` returns: the arguments meant for the script, such
+/// as those after `--` in `gl run script.g -- a b`. Only the first call has
+/// an effect.
+pub fn set_script_args(script_args: Vec<String>) {
+    let _ = SCRIPT_ARGS.set(script_args);
+}
 
 pub(crate) fn env_args(_args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let args: Vec<Object> = args().skip(1).map(Object::String).collect();
+    let args: Vec<Object> = match SCRIPT_ARGS.get() {
+        Some(script_args) => script_args.iter().map(|s| Object::String(s.as_str().into())).collect(),
+        // Embedded use without a command line of its own.
+        None => args().skip(1).map(|s| Object::String(s.into())).collect(),
+    };
     Ok(Object::Array(Box::new(args)))
 }
+
+pub(crate) fn env_get(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(name)) => {
+            let _env = read_env();
+            match std::env::var(name) {
+                Ok(value) => Ok(Object::String(value.into())),
+                Err(_) => Ok(Object::Null),
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn env_set(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next()) {
+        (Some(Object::String(name)), Some(Object::String(value))) => {
+            if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+                return Err(RuntimeError::InvalidArguments(format!("Invalid environment variable '{}'", name)));
+            }
+            let _env = write_env();
+            // SAFETY: set_var is unsafe because another thread may read the
+            // environment at the same time. Readers that go through std, and
+            // the other `env` functions, are excluded by std's lock and
+            // ENV_LOCK. Native code that calls getenv directly (C libraries
+            // such as the system resolver, or a host's own threads) is not;
+            // hosts that run such code next to scripts should deny the `env`
+            // capability.
+            unsafe { std::env::set_var(name, value) };
+            Ok(Object::Null)
+        }
+        (Some(Object::String(_)), Some(o)) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        (Some(o), _) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 0 }),
+    }
+}
+
+pub(crate) fn env_remove(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(name)) => {
+            if name.is_empty() || name.contains(['=', '\0']) {
+                return Err(RuntimeError::InvalidArguments(format!("Invalid environment variable '{}'", name)));
+            }
+            let _env = write_env();
+            // SAFETY: see env_set.
+            unsafe { std::env::remove_var(name) };
+            Ok(Object::Null)
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+// Safe: only String keys are inserted.
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn env_vars(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    #[allow(clippy::mutable_key_type)]
+    let mut hash = HashMap::new();
+
+    // Variables that are not valid unicode are skipped rather than mangled.
+    let vars: Vec<_> = {
+        let _env = read_env();
+        std::env::vars_os().collect()
+    };
+    for (name, value) in vars {
+        if let (Ok(name), Ok(value)) = (name.into_string(), value.into_string()) {
+            hash.insert(Object::String(name.into()), Object::String(value.into()));
+        }
+    }
+    Ok(Object::Hash(Box::new(hash)))
+}
+
+pub(crate) fn env_cwd(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match std::env::current_dir() {
+        Ok(path) => Ok(Object::String(path.to_string_lossy().to_string().into())),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not get current directory: {}", e)))
+    }
+}
+
+pub(crate) fn env_set_cwd(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => match std::env::set_current_dir(path) {
+            Ok(_) => Ok(Object::Null),
+            Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not change directory: {}", e)))
+        },
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn env_home_dir(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let home = {
+        let _env = read_env();
+        std::env::home_dir()
+    };
+    match home {
+        Some(path) => Ok(Object::String(path.to_string_lossy().to_string().into())),
+        None => Ok(Object::Null),
+    }
+}
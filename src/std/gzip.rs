@@ -0,0 +1,148 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::std::io::path_string;
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+fn gzip_error(context: &str, e: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("gzip error for {}: {}", context, e))
+}
+
+fn data_arg(args: &[Object]) -> Result<&[u8], RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s.as_bytes()),
+        Some(Object::Bytes(b)) => Ok(b.as_slice()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string or bytes".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    }
+}
+
+fn string_arg(args: &[Object], index: usize) -> Result<String, RuntimeError> {
+    match args.get(index) {
+        Some(Object::String(s)) => Ok(s.to_string()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+/// `gzip.compress(data, level?)` with `level` from 0 (store) to 9 (best),
+/// defaulting to 6. Always returns bytes.
+pub(crate) fn gzip_compress(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let level = match args.get(1) {
+        Some(Object::Integer(n)) if (0..=9).contains(n) => Compression::new(*n as u32),
+        Some(Object::Integer(n)) => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "compression level must be between 0 and 9, got {}",
+                n
+            )));
+        }
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "integer".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => Compression::default(),
+    };
+
+    let mut encoder = GzEncoder::new(Vec::new(), level);
+    encoder.write_all(data_arg(&args)?).map_err(|e| gzip_error("data", e))?;
+    let compressed = encoder.finish().map_err(|e| gzip_error("data", e))?;
+    Ok(Object::Bytes(Box::new(compressed)))
+}
+
+/// `gzip.decompress(data)` returns bytes; call `.to_string()` on the result
+/// for text payloads.
+pub(crate) fn gzip_decompress(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut decoder = GzDecoder::new(data_arg(&args)?);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).map_err(|e| gzip_error("data", e))?;
+    Ok(Object::Bytes(Box::new(out)))
+}
+
+/// `gzip.tar_create(archive, paths)` packs files and directories
+/// (recursively) into a `.tar.gz`, storing each under its own name.
+pub(crate) fn gzip_tar_create(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let archive = string_arg(&args, 0)?;
+    let sources: Vec<PathBuf> = match args.get(1) {
+        Some(Object::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Object::String(s) => Ok(PathBuf::from(s)),
+                o => Err(RuntimeError::TypeMismatch {
+                    expected: "string".to_string(),
+                    got: o.type_name(),
+                }),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(Object::String(s)) => vec![PathBuf::from(s)],
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "array or string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
+    };
+
+    let file = File::create(&archive).map_err(|e| gzip_error(&archive, e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    for source in &sources {
+        let name = source.file_name().map(Path::new).unwrap_or(source.as_path());
+        let result = if source.is_dir() {
+            builder.append_dir_all(name, source)
+        } else {
+            builder.append_path_with_name(source, name)
+        };
+        result.map_err(|e| gzip_error(&path_string(source), e))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| gzip_error(&archive, e))?;
+    Ok(Object::Null)
+}
+
+/// `gzip.tar_extract(archive, dest)` unpacks a `.tar.gz` below `dest` and
+/// returns the extracted file paths. Entries that would escape `dest` are
+/// skipped.
+pub(crate) fn gzip_tar_extract(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let archive_path = string_arg(&args, 0)?;
+    let dest = PathBuf::from(string_arg(&args, 1)?);
+    std::fs::create_dir_all(&dest).map_err(|e| gzip_error(&path_string(&dest), e))?;
+
+    let file = File::open(&archive_path).map_err(|e| gzip_error(&archive_path, e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut extracted = Vec::new();
+
+    for entry in archive.entries().map_err(|e| gzip_error(&archive_path, e))? {
+        let mut entry = entry.map_err(|e| gzip_error(&archive_path, e))?;
+        let relative = entry.path().map_err(|e| gzip_error(&archive_path, e))?.into_owned();
+        let is_file = entry.header().entry_type().is_file();
+
+        let unpacked = entry.unpack_in(&dest).map_err(|e| gzip_error(&archive_path, e))?;
+        if unpacked && is_file {
+            extracted.push(Object::String(path_string(&dest.join(relative)).into()));
+        }
+    }
+
+    Ok(Object::Array(Box::new(extracted)))
+}
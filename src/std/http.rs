@@ -2,6 +2,8 @@ use crate::vm::obj::{Object, HashMap};
 use crate::vm::runtime::runtime_errors::RuntimeError;
 use std::sync::{Arc, Mutex};
 use ahash::HashMapExt;
+use reqwest::Method;
+use std::time::Duration;
 
 pub fn http_get(args: Vec<Object>) -> Result<Object, RuntimeError> {
     let args = args;
@@ -9,21 +11,16 @@ pub fn http_get(args: Vec<Object>) -> Result<Object, RuntimeError> {
 }
 
 async fn async_http_get(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let url = match args.first() {
-        Some(Object::String(url)) => url.clone(),
+    let mut args = args.into_iter();
+
+    let url = match args.next() {
+        Some(Object::String(url)) => url.to_string(),
         Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
     };
+    let options = RequestOptions::from_arg(args.next())?;
 
-    let client = reqwest::Client::new();
-    match client.get(&url).send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            Ok(Object::Hash(Box::new(create_response_hash(status, body))))
-        }
-        Err(e) => Err(RuntimeError::InvalidOperation(format!("HTTP GET failed: {}", e)))
-    }
+    send_request(Method::GET, "GET", url, None, options).await
 }
 
 pub fn http_post(args: Vec<Object>) -> Result<Object, RuntimeError> {
@@ -32,29 +29,8 @@ pub fn http_post(args: Vec<Object>) -> Result<Object, RuntimeError> {
 }
 
 async fn async_http_post(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let mut args = args.into_iter();
-    
-    let url = match args.next() {
-        Some(Object::String(url)) => url.clone(),
-        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 0 }),
-    };
-    
-    let body = match args.next() {
-        Some(Object::String(body)) => body.clone(),
-        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
-    };
-
-    let client = reqwest::Client::new();
-    match client.post(&url).body(body).send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let response_body = response.text().await.unwrap_or_default();
-            Ok(Object::Hash(Box::new(create_response_hash(status, response_body))))
-        }
-        Err(e) => Err(RuntimeError::InvalidOperation(format!("HTTP POST failed: {}", e)))
-    }
+    let (url, body, options) = url_body_args(args)?;
+    send_request(Method::POST, "POST", url, Some(body), options).await
 }
 
 pub fn http_put(args: Vec<Object>) -> Result<Object, RuntimeError> {
@@ -63,52 +39,247 @@ pub fn http_put(args: Vec<Object>) -> Result<Object, RuntimeError> {
 }
 
 async fn async_http_put(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (url, body, options) = url_body_args(args)?;
+    send_request(Method::PUT, "PUT", url, Some(body), options).await
+}
+
+pub fn http_delete(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_http_delete(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+async fn async_http_delete(args: Vec<Object>) -> Result<Object, RuntimeError> {
     let mut args = args.into_iter();
-    
+
     let url = match args.next() {
-        Some(Object::String(url)) => url.clone(),
+        Some(Object::String(url)) => url.to_string(),
         Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 0 }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
     };
-    
+    let options = RequestOptions::from_arg(args.next())?;
+
+    send_request(Method::DELETE, "DELETE", url, None, options).await
+}
+
+/// Parses the `(url, body, opts?)` arguments shared by post and put.
+fn url_body_args(args: Vec<Object>) -> Result<(String, String, RequestOptions), RuntimeError> {
+    let mut args = args.into_iter();
+
+    let url = match args.next() {
+        Some(Object::String(url)) => url.to_string(),
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 3, got: 0 }),
+    };
+
     let body = match args.next() {
-        Some(Object::String(body)) => body.clone(),
+        Some(Object::String(body)) => body.to_string(),
         Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 3, got: 1 }),
     };
 
-    let client = reqwest::Client::new();
-    match client.put(&url).body(body).send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let response_body = response.text().await.unwrap_or_default();
-            Ok(Object::Hash(Box::new(create_response_hash(status, response_body))))
+    Ok((url, body, RequestOptions::from_arg(args.next())?))
+}
+
+/// Per-request settings taken from the optional trailing hash argument:
+///
+/// - `headers`: hash of header name to string value
+/// - `query`: hash of parameter name to value, appended to the URL
+/// - `basic_auth`: `[user]` or `[user, password]`
+/// - `timeout`: milliseconds before a single attempt is abandoned
+/// - `retries`: extra attempts after a transport error or 5xx response
+/// - `backoff`: milliseconds before the first retry, doubled for each one after
+/// - `follow_redirects`: whether 3xx responses are followed (default true)
+struct RequestOptions {
+    headers: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    basic_auth: Option<(String, Option<String>)>,
+    timeout: Option<Duration>,
+    retries: u32,
+    backoff: Duration,
+    follow_redirects: bool,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        RequestOptions {
+            headers: Vec::new(),
+            query: Vec::new(),
+            basic_auth: None,
+            timeout: None,
+            retries: 0,
+            backoff: Duration::from_millis(100),
+            follow_redirects: true,
         }
-        Err(e) => Err(RuntimeError::InvalidOperation(format!("HTTP PUT failed: {}", e)))
     }
 }
 
-pub fn http_delete(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let args = args;
-    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_http_delete(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+impl RequestOptions {
+    fn from_arg(arg: Option<Object>) -> Result<Self, RuntimeError> {
+        let opts = match arg {
+            None | Some(Object::Null) => return Ok(Self::default()),
+            Some(Object::Hash(opts)) => opts,
+            Some(o) => return Err(RuntimeError::TypeMismatch { expected: "hash".to_string(), got: o.type_name() }),
+        };
+
+        let mut options = Self::default();
+        for (key, value) in opts.iter() {
+            match (key, value) {
+                (Object::String(k), Object::Hash(headers)) if k == "headers" => {
+                    options.headers = string_pairs("headers", headers, false)?;
+                }
+                (Object::String(k), Object::Hash(query)) if k == "query" => {
+                    options.query = string_pairs("query", query, true)?;
+                }
+                (Object::String(k), Object::Array(creds)) if k == "basic_auth" => {
+                    options.basic_auth = Some(match creds.as_slice() {
+                        [Object::String(user)] => (user.to_string(), None),
+                        [Object::String(user), Object::String(password)] => (user.to_string(), Some(password.to_string())),
+                        _ => return Err(RuntimeError::InvalidArguments(
+                            "basic_auth must be [user] or [user, password]".to_string(),
+                        )),
+                    });
+                }
+                (Object::String(k), Object::Integer(ms)) if k == "timeout" && *ms > 0 => {
+                    options.timeout = Some(Duration::from_millis(*ms as u64));
+                }
+                (Object::String(k), Object::Integer(n)) if k == "retries" && (0..=u32::MAX as i64).contains(n) => {
+                    options.retries = *n as u32;
+                }
+                (Object::String(k), Object::Integer(ms)) if k == "backoff" && *ms >= 0 => {
+                    options.backoff = Duration::from_millis(*ms as u64);
+                }
+                (Object::String(k), Object::Boolean(follow)) if k == "follow_redirects" => {
+                    options.follow_redirects = *follow;
+                }
+                (Object::String(k), o) if k == "timeout" => {
+                    return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a positive integer, got {}", k, o)));
+                }
+                (Object::String(k), o) if k == "retries" || k == "backoff" => {
+                    return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a non-negative integer, got {}", k, o)));
+                }
+                (Object::String(k), o) if k == "follow_redirects" => {
+                    return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a boolean, got {}", k, o.type_name())));
+                }
+                (Object::String(k), o) if k == "headers" || k == "query" => {
+                    return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a hash, got {}", k, o.type_name())));
+                }
+                (Object::String(k), o) if k == "basic_auth" => {
+                    return Err(RuntimeError::InvalidArguments(format!("'{}' option must be an array, got {}", k, o.type_name())));
+                }
+                (k, _) => return Err(RuntimeError::InvalidArguments(format!("Unknown HTTP option '{}'", k))),
+            }
+        }
+        Ok(options)
+    }
+
+    fn client(&self) -> Result<reqwest::Client, RuntimeError> {
+        let policy = if self.follow_redirects {
+            reqwest::redirect::Policy::default()
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        reqwest::Client::builder()
+            .redirect(policy)
+            .build()
+            .map_err(|e| RuntimeError::InvalidOperation(format!("Could not create HTTP client: {}", e)))
+    }
+
+    fn apply(&self, mut builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some((user, password)) = &self.basic_auth {
+            builder = builder.basic_auth(user, password.as_ref());
+        }
+        builder
+    }
 }
 
-async fn async_http_delete(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let url = match args.first() {
-        Some(Object::String(url)) => url.clone(),
-        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
-        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
-    };
+/// Flattens a hash into name/value string pairs sorted by name. Query values
+/// may be any scalar and are stringified; header values must be strings.
+#[allow(clippy::mutable_key_type)]
+fn string_pairs(option: &str, hash: &HashMap<Object, Object>, stringify: bool) -> Result<Vec<(String, String)>, RuntimeError> {
+    let mut pairs = Vec::with_capacity(hash.len());
+    for (key, value) in hash.iter() {
+        let value = match value {
+            Object::String(s) => s.to_string(),
+            Object::Integer(_) | Object::BigInteger(_) | Object::Float(_) | Object::Boolean(_) if stringify => value.to_string(),
+            o => return Err(RuntimeError::InvalidArguments(format!("{} value for '{}' must be a string, got {}", option, key, o.type_name()))),
+        };
+        pairs.push((key.to_string(), value));
+    }
+    pairs.sort();
+    Ok(pairs)
+}
 
-    let client = reqwest::Client::new();
-    match client.delete(&url).send().await {
-        Ok(response) => {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            Ok(Object::Hash(Box::new(create_response_hash(status, body))))
+// Safe: response hashes only use String keys.
+#[allow(clippy::mutable_key_type)]
+async fn send_request(method: Method, label: &str, url: String, body: Option<String>, options: RequestOptions) -> Result<Object, RuntimeError> {
+    let mut url = reqwest::Url::parse(&url)
+        .map_err(|e| RuntimeError::InvalidArguments(format!("Invalid URL '{}': {}", url, e)))?;
+    if !options.query.is_empty() {
+        url.query_pairs_mut().extend_pairs(&options.query);
+    }
+
+    let client = options.client()?;
+    let mut delay = options.backoff;
+    let mut attempt = 0;
+
+    loop {
+        let mut builder = options.apply(client.request(method.clone(), url.clone()));
+        if let Some(body) = &body {
+            builder = builder.body(body.clone());
+        }
+
+        let result = builder.send().await;
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => !e.is_builder(),
+        };
+        if retryable && attempt < options.retries {
+            attempt += 1;
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+            continue;
+        }
+
+        return match result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let headers = header_hash(response.headers());
+                let body = response.text().await.unwrap_or_default();
+                let mut hash = create_response_hash(status, body);
+                hash.insert(Object::String("headers".into()), Object::Hash(Box::new(headers)));
+                Ok(Object::Hash(Box::new(hash)))
+            }
+            Err(e) if e.is_timeout() => Err(RuntimeError::InvalidOperation(format!("HTTP {} timed out: {}", label, e))),
+            Err(e) => Err(RuntimeError::InvalidOperation(format!("HTTP {} failed: {}", label, e)))
+        };
+    }
+}
+
+/// Response headers keyed by lowercase name. Repeated headers are joined
+/// with ", " as allowed by RFC 9110.
+#[allow(clippy::mutable_key_type)]
+fn header_hash(headers: &reqwest::header::HeaderMap) -> HashMap<Object, Object> {
+    let mut hash: HashMap<Object, Object> = HashMap::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        let key = Object::String(name.as_str().to_string().into());
+        match hash.get_mut(&key) {
+            Some(Object::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            }
+            _ => {
+                hash.insert(key, Object::String(value.into()));
+            }
         }
-        Err(e) => Err(RuntimeError::InvalidOperation(format!("HTTP DELETE failed: {}", e)))
     }
+    hash
 }
 
 // Safe: only Integer, Boolean, String (immutable types) are allowed as keys,
@@ -120,7 +291,7 @@ fn create_response_hash(status: u16, body: String) -> HashMap<Object, Object> {
     #[allow(clippy::mutable_key_type)]
     let mut hash = HashMap::new();
 
-    hash.insert(Object::String("status".to_string()), Object::Integer(status as i64));
-    hash.insert(Object::String("body".to_string()), Object::String(body));
+    hash.insert(Object::String("status".into()), Object::Integer(status as i64));
+    hash.insert(Object::String("body".into()), Object::String(body.into()));
     hash
 }
@@ -0,0 +1,209 @@
+use ahash::HashMapExt;
+
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+fn string_arg(args: &[Object], index: usize) -> Result<&str, RuntimeError> {
+    match args.get(index) {
+        Some(Object::String(s)) => Ok(s),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn ini_error(line: usize, message: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::InvalidArguments(format!("INI error on line {}: {}", line, message))
+}
+
+/// Strips a trailing `;` or `#` comment (only when preceded by whitespace, so
+/// `url = http://host/#anchor` survives) and surrounding quotes.
+fn parse_value(raw: &str) -> String {
+    let mut value = raw.trim();
+    if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'')
+        && let Some(end) = value[1..].find(quote)
+    {
+        return value[1..end + 1].to_string();
+    }
+    let bytes = value.as_bytes();
+    if let Some(i) = (1..bytes.len()).find(|&i| (bytes[i] == b';' || bytes[i] == b'#') && bytes[i - 1].is_ascii_whitespace()) {
+        value = value[..i].trim_end();
+    }
+    value.to_string()
+}
+
+#[allow(clippy::mutable_key_type)]
+fn section_mut<'a>(
+    root: &'a mut HashMap<Object, Object>,
+    path: &[String],
+    line: usize,
+) -> Result<&'a mut HashMap<Object, Object>, RuntimeError> {
+    let mut current = root;
+    for name in path {
+        let entry = current
+            .entry(Object::String(name.clone().into()))
+            .or_insert_with(|| Object::Hash(Box::new(HashMap::new())));
+        current = match entry {
+            Object::Hash(hash) => hash,
+            _ => return Err(ini_error(line, format!("section '{}' clashes with a key of the same name", path.join(".")))),
+        };
+    }
+    Ok(current)
+}
+
+/// Parses INI text into a hash. Keys before the first section are stored at
+/// the top level, `[section]` opens a nested hash and `[a.b]` nests further.
+/// Values are always strings.
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn parse(text: &str) -> Result<Object, RuntimeError> {
+    let mut root = HashMap::new();
+    let mut section: Vec<String> = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| ini_error(line, "section header is missing ']'"))?
+                .trim();
+            if name.is_empty() || name.split('.').any(|part| part.trim().is_empty()) {
+                return Err(ini_error(line, format!("invalid section name '[{}]'", name)));
+            }
+            section = name.split('.').map(|part| part.trim().to_string()).collect();
+            section_mut(&mut root, &section, line)?;
+            continue;
+        }
+
+        let (key, value) = trimmed
+            .find(['=', ':'])
+            .map(|i| (&trimmed[..i], &trimmed[i + 1..]))
+            .ok_or_else(|| ini_error(line, format!("expected 'key = value', got '{}'", trimmed)))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(ini_error(line, "missing key before '='"));
+        }
+
+        let target = section_mut(&mut root, &section, line)?;
+        if matches!(target.get(&Object::String(key.to_string().into())), Some(Object::Hash(_))) {
+            return Err(ini_error(line, format!("key '{}' clashes with a section of the same name", key)));
+        }
+        target.insert(Object::String(key.to_string().into()), Object::String(parse_value(value).into()));
+    }
+
+    Ok(Object::Hash(Box::new(root)))
+}
+
+fn format_value(value: &Object, key: &str) -> Result<String, RuntimeError> {
+    let text = match value {
+        Object::String(s) => s.to_string(),
+        Object::Integer(_) | Object::BigInteger(_) | Object::Float(_) | Object::Boolean(_) => value.to_string(),
+        Object::Null => String::new(),
+        o => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "INI value for '{}' must be a scalar, got {}",
+                key,
+                o.type_name()
+            )));
+        }
+    };
+    let needs_quotes = text != text.trim() || text.contains([';', '#', '"', '\n']);
+    let quote = if text.contains('\'') { '"' } else { '\'' };
+    Ok(if needs_quotes { format!("{quote}{text}{quote}") } else { text })
+}
+
+fn key_name(key: &Object) -> String {
+    match key {
+        Object::String(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Writes scalar entries of `hash` and then one `[section]` per nested hash,
+/// with keys sorted so the output is stable.
+#[allow(clippy::mutable_key_type)]
+fn emit_section(out: &mut String, path: &mut Vec<String>, hash: &HashMap<Object, Object>) -> Result<(), RuntimeError> {
+    let mut entries: Vec<(String, &Object)> = hash.iter().map(|(k, v)| (key_name(k), v)).collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut wrote_keys = false;
+    for (key, value) in &entries {
+        if matches!(value, Object::Hash(_)) {
+            continue;
+        }
+        if !wrote_keys && !path.is_empty() {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", path.join(".")));
+        }
+        wrote_keys = true;
+        out.push_str(&format!("{} = {}\n", key, format_value(value, key)?));
+    }
+
+    for (key, value) in entries {
+        if let Object::Hash(nested) = value {
+            path.push(key);
+            if nested.is_empty() {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&format!("[{}]\n", path.join(".")));
+            }
+            emit_section(out, path, nested)?;
+            path.pop();
+        }
+    }
+
+    Ok(())
+}
+
+fn emit(value: &Object) -> Result<String, RuntimeError> {
+    match value {
+        Object::Hash(hash) => {
+            let mut out = String::new();
+            emit_section(&mut out, &mut Vec::new(), hash)?;
+            Ok(out)
+        }
+        o => Err(RuntimeError::TypeMismatch {
+            expected: "hash".to_string(),
+            got: o.type_name(),
+        }),
+    }
+}
+
+pub(crate) fn ini_parse(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    parse(string_arg(&args, 0)?)
+}
+
+pub(crate) fn ini_emit(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(value) => emit(value).map(|s| Object::String(s.into())),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn ini_read_file(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let path = string_arg(&args, 0)?;
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not read {}: {}", path, e)))?;
+    parse(&text)
+}
+
+pub(crate) fn ini_write_file(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let path = string_arg(&args, 0)?;
+    let value = args.get(1).ok_or(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 })?;
+    std::fs::write(path, emit(value)?)
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not write {}: {}", path, e)))?;
+    Ok(Object::Null)
+}
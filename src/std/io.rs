@@ -1,8 +1,11 @@
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use std::io::ErrorKind;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ahash::HashMapExt;
 
-use crate::vm::obj::Object;
+use crate::vm::obj::{FileHandle, HashMap, Object};
 use crate::vm::runtime::runtime_errors::RuntimeError;
 use std::sync::{Arc, Mutex};
 
@@ -10,7 +13,7 @@ pub(crate) fn io_read_file(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match args.first() {
         Some(Object::String(path)) => {
             match std::fs::read_to_string(path) {
-                Ok(text) => Ok(Object::String(text)),
+                Ok(text) => Ok(Object::String(text.into())),
                 Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read from file: {}", e)))
             }
         }
@@ -23,7 +26,7 @@ pub async fn async_io_read_file(args: Vec<Object>) -> Result<Object, RuntimeErro
     match args.first() {
         Some(Object::String(path)) => {
             match fs::read_to_string(path).await {
-                Ok(text) => Ok(Object::String(text)),
+                Ok(text) => Ok(Object::String(text.into())),
                 Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read from file: {}", e)))
             }
         }
@@ -264,7 +267,7 @@ pub(crate) fn io_list_dir(args: Vec<Object>) -> Result<Object, RuntimeError> {
             for entry in std::fs::read_dir(path).map_err(|e| RuntimeError::InvalidOperation(e.to_string()))? {
                 let entry = entry.map_err(|e| RuntimeError::InvalidOperation(e.to_string()))?;
                 if let Some(name) = entry.file_name().to_str() {
-                    items.push(Object::String(name.to_string()));
+                    items.push(Object::String(name.to_string().into()));
                 }
             }
 
@@ -292,7 +295,7 @@ pub async fn async_io_list_dir(args: Vec<Object>) -> Result<Object, RuntimeError
             while let Some(entry) = dir.next_entry().await
                 .map_err(|e| RuntimeError::InvalidOperation(e.to_string()))? {
                 if let Some(name) = entry.file_name().to_str() {
-                    items.push(Object::String(name.to_string()));
+                    items.push(Object::String(name.to_string().into()));
                 }
             }
             
@@ -307,3 +310,516 @@ pub(crate) fn io_list_dir_wrapper(args: Vec<Object>) -> Result<Object, RuntimeEr
     let args = args;
     Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_list_dir(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
 }
+
+/// Milliseconds since the Unix epoch, or `Null` when the platform does not
+/// record the timestamp.
+fn timestamp_millis(time: std::io::Result<SystemTime>) -> Object {
+    match time.ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()) {
+        Some(dur) => Object::Integer(dur.as_millis() as i64),
+        None => Object::Null,
+    }
+}
+
+// Safe: only String keys are inserted.
+#[allow(clippy::mutable_key_type)]
+fn metadata_hash(meta: &std::fs::Metadata) -> HashMap<Object, Object> {
+    #[allow(clippy::mutable_key_type)]
+    let mut hash = HashMap::new();
+
+    hash.insert(Object::String("size".into()), Object::Integer(meta.len() as i64));
+    hash.insert(Object::String("is_file".into()), Object::Boolean(meta.is_file()));
+    hash.insert(Object::String("is_dir".into()), Object::Boolean(meta.is_dir()));
+    hash.insert(Object::String("modified".into()), timestamp_millis(meta.modified()));
+    hash.insert(Object::String("created".into()), timestamp_millis(meta.created()));
+    hash.insert(Object::String("readonly".into()), Object::Boolean(meta.permissions().readonly()));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = meta.permissions().mode() & 0o777;
+        hash.insert(Object::String("permissions".into()), Object::String(format!("{:o}", mode).into()));
+    }
+    #[cfg(not(unix))]
+    {
+        let perms = if meta.permissions().readonly() { "r" } else { "rw" };
+        hash.insert(Object::String("permissions".into()), Object::String(perms.to_string().into()));
+    }
+
+    hash
+}
+
+pub(crate) fn io_metadata(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            match std::fs::metadata(path) {
+                Ok(meta) => Ok(Object::Hash(Box::new(metadata_hash(&meta)))),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read metadata: {}", e)))
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub async fn async_io_metadata(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            match fs::metadata(path).await {
+                Ok(meta) => Ok(Object::Hash(Box::new(metadata_hash(&meta)))),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read metadata: {}", e)))
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn io_metadata_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_metadata(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub(crate) fn io_file_size(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            match std::fs::metadata(path) {
+                Ok(meta) => Ok(Object::Integer(meta.len() as i64)),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read file size: {}", e)))
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+/// Extracts the `(source, destination)` path pair shared by copy/move/rename.
+fn path_pair(args: Vec<Object>) -> Result<(String, String), RuntimeError> {
+    let got = args.len();
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next()) {
+        (Some(Object::String(src)), Some(Object::String(dst))) => Ok((src.to_string(), dst.to_string())),
+        (Some(Object::String(_)), Some(o)) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        (Some(o), Some(_)) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got }),
+    }
+}
+
+pub(crate) fn io_copy(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (src, dst) = path_pair(args)?;
+    match std::fs::copy(src, dst) {
+        Ok(_) => Ok(Object::Null),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not copy file: {}", e)))
+    }
+}
+
+pub async fn async_io_copy(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (src, dst) = path_pair(args)?;
+    match fs::copy(src, dst).await {
+        Ok(_) => Ok(Object::Null),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not copy file: {}", e)))
+    }
+}
+
+pub(crate) fn io_copy_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_copy(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub(crate) fn io_rename(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (old, new) = path_pair(args)?;
+    match std::fs::rename(old, new) {
+        Ok(_) => Ok(Object::Null),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not rename: {}", e)))
+    }
+}
+
+pub async fn async_io_rename(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (old, new) = path_pair(args)?;
+    match fs::rename(old, new).await {
+        Ok(_) => Ok(Object::Null),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not rename: {}", e)))
+    }
+}
+
+pub(crate) fn io_rename_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_rename(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+// A plain rename fails when source and destination live on different
+// filesystems, so move falls back to copying the file and removing the source.
+fn move_error(e: std::io::Error) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("Could not move file: {}", e))
+}
+
+pub(crate) fn io_move(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (src, dst) = path_pair(args)?;
+    match std::fs::rename(&src, &dst) {
+        Ok(()) => return Ok(Object::Null),
+        // A rename cannot cross filesystems; copy and delete instead.
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(move_error(e)),
+    }
+
+    std::fs::copy(&src, &dst)
+        .and_then(|_| std::fs::remove_file(&src))
+        .map(|_| Object::Null)
+        .map_err(move_error)
+}
+
+pub async fn async_io_move(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (src, dst) = path_pair(args)?;
+    match fs::rename(&src, &dst).await {
+        Ok(()) => return Ok(Object::Null),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(move_error(e)),
+    }
+
+    fs::copy(&src, &dst).await.map_err(move_error)?;
+    fs::remove_file(&src).await.map_err(move_error)?;
+
+    Ok(Object::Null)
+}
+
+pub(crate) fn io_move_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_move(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+/// Recursively collects every entry below `dir`, depth-first and sorted by
+/// name so the result is stable across platforms. Symbolic links are listed
+/// but not followed, so a link back up the tree cannot loop forever.
+pub(crate) fn walk_paths(dir: &Path, out: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.and_then(|e| Ok((e.path(), e.file_type()?.is_dir()))))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for (path, is_dir) in entries {
+        out.push(path.clone());
+        if is_dir {
+            walk_paths(&path, out)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+pub(crate) fn io_walk(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            let path = Path::new(path);
+
+            if !path.is_dir() {
+                return Err(RuntimeError::InvalidOperation(format!("'{}' is not a directory", path.display())));
+            }
+
+            let mut paths = Vec::new();
+            walk_paths(path, &mut paths).map_err(|e| RuntimeError::InvalidOperation(e.to_string()))?;
+
+            let items = paths.iter().map(|p| Object::String(path_string(p).into())).collect();
+            Ok(Object::Array(Box::new(items)))
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+/// Translates a glob pattern into an anchored regex. `**` crosses directory
+/// boundaries, `*` and `?` stay within one path component, and `[...]` classes
+/// are passed through.
+fn glob_to_regex(pattern: &str) -> Result<regex::Regex, RuntimeError> {
+    let mut re = String::from("^");
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+
+    regex::Regex::new(&re).map_err(|e| RuntimeError::InvalidArguments(format!("Invalid glob pattern: {}", e)))
+}
+
+pub(crate) fn io_glob(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(pattern)) => {
+            let pattern = pattern.replace('\\', "/");
+            let matcher = glob_to_regex(&pattern)?;
+
+            // Only walk from the longest wildcard-free directory prefix.
+            let components: Vec<&str> = pattern.split('/').collect();
+            let literal = components
+                .iter()
+                .take_while(|c| !c.contains(['*', '?', '[']))
+                .count()
+                .min(components.len().saturating_sub(1));
+            let root = components[..literal].join("/");
+            let root = match root.as_str() {
+                "" if pattern.starts_with('/') => "/".to_string(),
+                "" => ".".to_string(),
+                _ => root,
+            };
+
+            let mut paths = Vec::new();
+            if Path::new(&root).is_dir() {
+                walk_paths(Path::new(&root), &mut paths).map_err(|e| RuntimeError::InvalidOperation(e.to_string()))?;
+            }
+
+            let items = paths
+                .iter()
+                .map(|p| {
+                    let s = path_string(p);
+                    // Paths under the implicit "." root are reported without the prefix.
+                    match (root.as_str(), s.strip_prefix("./")) {
+                        (".", Some(rest)) => rest.to_string(),
+                        _ => s,
+                    }
+                })
+                .filter(|s| matcher.is_match(s))
+                .map(|s| Object::String(s.into()))
+                .collect();
+            Ok(Object::Array(Box::new(items)))
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn io_read_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            match std::fs::read(path) {
+                Ok(data) => Ok(Object::Bytes(Box::new(data))),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read from file: {}", e)))
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub async fn async_io_read_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(path)) => {
+            match fs::read(path).await {
+                Ok(data) => Ok(Object::Bytes(Box::new(data))),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not read from file: {}", e)))
+            }
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn io_read_bytes_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_read_bytes(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub(crate) fn io_write_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next()) {
+        (Some(Object::String(path)), Some(Object::Bytes(data))) => {
+            match std::fs::write(path, *data) {
+                Ok(_) => Ok(Object::Null),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not write to file: {}", e)))
+            }
+        }
+        (Some(Object::String(_)), Some(o)) => Err(RuntimeError::TypeMismatch { expected: "bytes".to_string(), got: o.type_name() }),
+        (Some(o), _) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 0 }),
+    }
+}
+
+pub async fn async_io_write_bytes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next()) {
+        (Some(Object::String(path)), Some(Object::Bytes(data))) => {
+            match fs::write(path, *data).await {
+                Ok(_) => Ok(Object::Null),
+                Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not write to file: {}", e)))
+            }
+        }
+        (Some(Object::String(_)), Some(o)) => Err(RuntimeError::TypeMismatch { expected: "bytes".to_string(), got: o.type_name() }),
+        (Some(o), _) => Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 0 }),
+    }
+}
+
+pub(crate) fn io_write_bytes_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_write_bytes(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+// Stdin is read through the process-wide std handle, which keeps its own
+// buffer, so sync, async and `input()` reads can be mixed without losing data.
+// The async variants run the blocking read on tokio's blocking pool. The
+// `*_from` helpers take any reader so the line handling can be tested.
+
+fn stdin_error(e: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("Could not read from stdin: {}", e))
+}
+
+/// Reads one line without its `\n` or `\r\n`, or `null` at end of input.
+pub(crate) fn read_line_from(reader: &mut impl std::io::BufRead) -> Result<Object, RuntimeError> {
+    let mut line = String::new();
+    match reader.read_line(&mut line) {
+        Ok(0) => Ok(Object::Null),
+        Ok(_) => {
+            let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+            let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+            Ok(Object::String(trimmed.to_string().into()))
+        }
+        Err(e) => Err(stdin_error(e)),
+    }
+}
+
+pub(crate) fn read_all_from(reader: &mut impl std::io::Read) -> Result<Object, RuntimeError> {
+    let mut text = String::new();
+    match reader.read_to_string(&mut text) {
+        Ok(_) => Ok(Object::String(text.into())),
+        Err(e) => Err(stdin_error(e)),
+    }
+}
+
+pub(crate) fn read_lines_from(reader: impl std::io::BufRead) -> Result<Object, RuntimeError> {
+    let lines: Vec<Object> = reader
+        .lines()
+        .map(|line| line.map(|s| Object::String(s.into())))
+        .collect::<std::io::Result<_>>()
+        .map_err(stdin_error)?;
+    Ok(Object::Array(Box::new(lines)))
+}
+
+fn read_stdin_line() -> Result<Object, RuntimeError> {
+    read_line_from(&mut std::io::stdin().lock())
+}
+
+fn read_stdin_all() -> Result<Object, RuntimeError> {
+    read_all_from(&mut std::io::stdin().lock())
+}
+
+fn read_stdin_lines() -> Result<Object, RuntimeError> {
+    read_lines_from(std::io::stdin().lock())
+}
+
+async fn run_blocking_stdin(read: fn() -> Result<Object, RuntimeError>) -> Result<Object, RuntimeError> {
+    tokio::task::spawn_blocking(read)
+        .await
+        .map_err(stdin_error)?
+}
+
+pub(crate) fn io_read_line(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    read_stdin_line()
+}
+
+pub async fn async_io_read_line(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    run_blocking_stdin(read_stdin_line).await
+}
+
+pub(crate) fn io_read_line_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_read_line(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub(crate) fn io_read_all_stdin(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    read_stdin_all()
+}
+
+pub async fn async_io_read_all_stdin(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    run_blocking_stdin(read_stdin_all).await
+}
+
+pub(crate) fn io_read_all_stdin_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_read_all_stdin(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub(crate) fn io_lines(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    read_stdin_lines()
+}
+
+pub async fn async_io_lines(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    run_blocking_stdin(read_stdin_lines).await
+}
+
+pub(crate) fn io_lines_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_io_lines(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+/// Maps a C-style mode string ("r", "w", "a", optionally with "+") onto
+/// open options.
+fn open_options(mode: &str) -> Option<std::fs::OpenOptions> {
+    let mut options = std::fs::OpenOptions::new();
+    match mode {
+        "r" => options.read(true),
+        "r+" => options.read(true).write(true),
+        "w" => options.write(true).create(true).truncate(true),
+        "w+" => options.read(true).write(true).create(true).truncate(true),
+        "a" => options.append(true).create(true),
+        "a+" => options.read(true).append(true).create(true),
+        _ => return None,
+    };
+    Some(options)
+}
+
+pub(crate) fn io_open(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut args = args.into_iter();
+
+    let (path, mode) = match (args.next(), args.next()) {
+        (Some(Object::String(path)), None) => (path, "r".to_string()),
+        (Some(Object::String(path)), Some(Object::String(mode))) => (path, mode.to_string()),
+        (Some(Object::String(_)), Some(o)) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        (Some(o), _) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        (None, _) => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+
+    let options = open_options(&mode).ok_or_else(|| {
+        RuntimeError::InvalidArguments(format!("Invalid file mode '{}', expected r, w, a, r+, w+ or a+", mode))
+    })?;
+
+    match options.open(&path) {
+        Ok(file) => Ok(Object::File(Arc::new(Mutex::new(FileHandle {
+            path: path.into(),
+            reader: Some(std::io::BufReader::new(file)),
+        })))),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not open file: {}", e)))
+    }
+}
@@ -5,7 +5,20 @@ use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 use serde_json::{self, Number, Value};
 
-fn object_to_json(obj: &Object) -> Result<Value, RuntimeError> {
+fn json_key(k: &Object) -> Result<String, RuntimeError> {
+    match k {
+        Object::String(s) => Ok(s.to_string()),
+        Object::Integer(i) => Ok(i.to_string()),
+        Object::BigInteger(b) => Ok(b.to_string()),
+        Object::Boolean(b) => Ok(b.to_string()),
+        _ => Err(RuntimeError::InvalidOperation(format!(
+            "Hash key of type '{}' cannot be converted to JSON string key",
+            k.type_name()
+        ))),
+    }
+}
+
+pub(crate) fn object_to_json(obj: &Object) -> Result<Value, RuntimeError> {
     match obj {
         Object::Integer(i) => Ok(Value::Number(Number::from(*i))),
 
@@ -53,7 +66,7 @@ fn object_to_json(obj: &Object) -> Result<Value, RuntimeError> {
         }
 
         Object::Boolean(b) => Ok(Value::Bool(*b)),
-        Object::String(s) => Ok(Value::String(s.clone())),
+        Object::String(s) => Ok(Value::String(s.to_string())),
 
         Object::Array(arr) => {
             let mut json_arr = Vec::with_capacity(arr.len());
@@ -67,28 +80,25 @@ fn object_to_json(obj: &Object) -> Result<Value, RuntimeError> {
 
         Object::Hash(map) => {
             let mut json_map = serde_json::Map::new();
-            for (k, v) in map.iter() {
-                let key_str = match k {
-                    Object::String(s) => s.clone(),
-                    Object::Integer(i) => i.to_string(),
-                    Object::BigInteger(b) => b.to_string(),
-                    Object::Boolean(b) => b.to_string(),
-                    _ => {
-                        return Err(RuntimeError::InvalidOperation(format!(
-                            "Hash key of type '{}' cannot be converted to JSON string key",
-                            k.type_name()
-                        )));
-                    }
-                };
+            for (k, v) in sorted_entries(map.iter()) {
+                json_map.insert(json_key(k)?, object_to_json(v)?);
+            }
+            Ok(Value::Object(json_map))
+        }
 
-                json_map.insert(key_str.clone(), object_to_json(v)?);
+        Object::OrderedMap(map) => {
+            let mut json_map = serde_json::Map::new();
+            for (k, v) in map.lock().unwrap().iter() {
+                json_map.insert(json_key(k)?, object_to_json(v)?);
             }
             Ok(Value::Object(json_map))
         }
 
         Object::Struct(s) => {
             let mut json_map = serde_json::Map::new();
-            for (k, v) in s.fields.iter() {
+            let mut fields: Vec<_> = s.fields.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            for (k, v) in fields {
                 json_map.insert(k.clone(), object_to_json(v)?);
             }
             Ok(Value::Object(json_map))
@@ -103,7 +113,18 @@ fn object_to_json(obj: &Object) -> Result<Value, RuntimeError> {
     }
 }
 
-fn json_to_object(val: Value) -> Object {
+/// Orders hash entries by their JSON key so serialized output is stable
+/// across runs (the backing HashMap iterates in random order).
+fn sorted_entries<'a>(entries: impl Iterator<Item = (&'a Object, &'a Object)>) -> Vec<(&'a Object, &'a Object)> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by_cached_key(|(k, _)| match k {
+        Object::String(s) => s.to_string(),
+        other => other.to_string(),
+    });
+    entries
+}
+
+pub(crate) fn json_to_object(val: Value) -> Object {
     match val {
         Value::Null => Object::Null,
         Value::Bool(b) => Object::Boolean(b),
@@ -130,7 +151,7 @@ fn json_to_object(val: Value) -> Object {
             }
         }
 
-        Value::String(s) => Object::String(s),
+        Value::String(s) => Object::String(s.into()),
 
         Value::Array(arr) => {
             let objects: Vec<Object> = arr.into_iter().map(json_to_object).collect();
@@ -143,7 +164,7 @@ fn json_to_object(val: Value) -> Object {
             #[allow(clippy::mutable_key_type)]
             let mut hash = HashMap::with_capacity(map.len());
             for (k, v) in map {
-                hash.insert(Object::String(k), json_to_object(v));
+                hash.insert(Object::String(k.into()), json_to_object(v));
             }
             Object::Hash(Box::new(hash))
         }
@@ -160,11 +181,88 @@ pub fn json_serialize(args: Vec<Object>) -> Result<Object, RuntimeError> {
     }
 
     match object_to_json(&args[0]) {
-        Ok(val) => Ok(Object::String(val.to_string())),
+        Ok(val) => Ok(Object::String(val.to_string().into())),
         Err(e) => Err(e),
     }
 }
 
+pub fn json_serialize_pretty(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongNumberOfArguments {
+            min: 1,
+            max: 2,
+            got: args.len(),
+        });
+    }
+
+    let indent = match args.get(1) {
+        None | Some(Object::Null) => "  ".to_string(),
+        Some(Object::Integer(n)) if (0..=16).contains(n) => " ".repeat(*n as usize),
+        Some(Object::Integer(n)) => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "indent must be between 0 and 16, got {}",
+                n
+            )));
+        }
+        Some(Object::String(s)) if s.chars().all(|c| c == ' ' || c == '\t') => s.to_string(),
+        Some(Object::String(_)) => {
+            return Err(RuntimeError::InvalidArguments(
+                "indent string may only contain spaces and tabs".to_string(),
+            ));
+        }
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "integer or string".to_string(),
+                got: o.type_name(),
+            });
+        }
+    };
+
+    let val = object_to_json(&args[0])?;
+    let mut out = String::new();
+    write_pretty(&val, &indent, 0, &mut out);
+    Ok(Object::String(out.into()))
+}
+
+fn write_pretty(val: &Value, indent: &str, depth: usize, out: &mut String) {
+    let newline = |out: &mut String, depth: usize| {
+        if !indent.is_empty() {
+            out.push('\n');
+            out.push_str(&indent.repeat(depth));
+        }
+    };
+
+    match val {
+        Value::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_pretty(item, indent, depth + 1, out);
+            }
+            newline(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push('{');
+            for (i, (k, v)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                out.push_str(&Value::String(k.clone()).to_string());
+                out.push_str(if indent.is_empty() { ":" } else { ": " });
+                write_pretty(v, indent, depth + 1, out);
+            }
+            newline(out, depth);
+            out.push('}');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
 pub fn json_deserialize(args: Vec<Object>) -> Result<Object, RuntimeError> {
     if args.len() != 1 {
         return Err(RuntimeError::WrongNumberOfArguments {
@@ -201,7 +299,7 @@ pub fn json_prettify(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match &args[0] {
         Object::String(s) => match serde_json::from_str::<Value>(s) {
             Ok(val) => match serde_json::to_string_pretty(&val) {
-                Ok(pretty_s) => Ok(Object::String(pretty_s)),
+                Ok(pretty_s) => Ok(Object::String(pretty_s.into())),
                 Err(e) => Err(RuntimeError::InvalidOperation(format!(
                     "JSON prettify error: {}",
                     e
@@ -236,3 +334,161 @@ pub fn json_validate(args: Vec<Object>) -> Result<Object, RuntimeError> {
         }),
     }
 }
+
+/// Strips `//` and `/* */` comments and trailing commas from JSON text,
+/// leaving string literals untouched.
+fn strip_lenient(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+                out.push(' ');
+            }
+            ']' | '}' => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+pub fn json_deserialize_lenient(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongNumberOfArguments {
+            min: 1,
+            max: 1,
+            got: args.len(),
+        });
+    }
+
+    match &args[0] {
+        Object::String(s) => match serde_json::from_str::<Value>(&strip_lenient(s)) {
+            Ok(val) => Ok(json_to_object(val)),
+            Err(e) => Err(RuntimeError::InvalidArguments(format!(
+                "JSON parse error: {}",
+                e
+            ))),
+        },
+        o => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+    }
+}
+
+pub fn json_read_lines(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongNumberOfArguments {
+            min: 1,
+            max: 1,
+            got: args.len(),
+        });
+    }
+
+    let path = match &args[0] {
+        Object::String(s) => s,
+        o => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+    };
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not read {}: {}", path, e)))?;
+
+    let mut records = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(val) => records.push(json_to_object(val)),
+            Err(e) => {
+                return Err(RuntimeError::InvalidArguments(format!(
+                    "JSON parse error on line {}: {}",
+                    i + 1,
+                    e
+                )));
+            }
+        }
+    }
+
+    Ok(Object::Array(Box::new(records)))
+}
+
+pub fn json_write_lines(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongNumberOfArguments {
+            min: 2,
+            max: 2,
+            got: args.len(),
+        });
+    }
+
+    match (&args[0], &args[1]) {
+        (Object::String(path), Object::Array(records)) => {
+            let mut out = String::new();
+            for record in records.iter() {
+                out.push_str(&object_to_json(record)?.to_string());
+                out.push('\n');
+            }
+
+            std::fs::write(path, out)
+                .map_err(|e| RuntimeError::InvalidOperation(format!("Could not write {}: {}", path, e)))?;
+            Ok(Object::Null)
+        }
+        (Object::String(_), o) => Err(RuntimeError::TypeMismatch {
+            expected: "array".to_string(),
+            got: o.type_name(),
+        }),
+        (o, _) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+    }
+}
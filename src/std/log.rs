@@ -0,0 +1,181 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// Environment variable read once at startup to pick the initial level.
+pub const LOG_LEVEL_ENV: &str = "G_LOG_LEVEL";
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Off,
+}
+
+impl Level {
+    fn parse(name: &str) -> Option<Level> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            "off" | "none" => Some(Level::Off),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Off => "off",
+        }
+    }
+}
+
+struct Logger {
+    level: Level,
+    /// When set, records are appended here instead of written to stderr.
+    file: Option<File>,
+}
+
+static LOGGER: LazyLock<Mutex<Logger>> = LazyLock::new(|| {
+    let level = std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|name| Level::parse(&name))
+        .unwrap_or(Level::Info);
+    Mutex::new(Logger { level, file: None })
+});
+
+/// Sets the minimum level that gets written. Used by `--log-level` on the
+/// command line and by `log.set_level` in scripts.
+pub fn set_level(name: &str) -> Result<(), String> {
+    let level = Level::parse(name)
+        .ok_or_else(|| format!("unknown log level '{}', expected debug, info, warn, error or off", name))?;
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner()).level = level;
+    Ok(())
+}
+
+/// Formats the current UTC time as `YYYY-MM-DDTHH:MM:SS.mmmZ`.
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Days since the epoch to a proleptic Gregorian date (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        now.subsec_millis()
+    )
+}
+
+fn write_record(level: Level, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    if level < logger.level {
+        return Ok(Object::Null);
+    }
+
+    let message = match args.first() {
+        Some(Object::String(s)) => s.to_string(),
+        Some(o) => o.to_string(),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+    let prefix = match args.get(1) {
+        Some(Object::String(module)) => format!("[{}] ", module),
+        Some(Object::Null) | None => String::new(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+    };
+
+    let line = format!("{} {:<5} {}{}", timestamp(), level.name().to_ascii_uppercase(), prefix, message);
+    match logger.file.as_mut() {
+        Some(file) => writeln!(file, "{}", line)
+            .map_err(|e| RuntimeError::InvalidOperation(format!("Could not write log file: {}", e)))?,
+        None => eprintln!("{}", line),
+    }
+    Ok(Object::Null)
+}
+
+pub(crate) fn log_debug(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    write_record(Level::Debug, args)
+}
+
+pub(crate) fn log_info(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    write_record(Level::Info, args)
+}
+
+pub(crate) fn log_warn(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    write_record(Level::Warn, args)
+}
+
+pub(crate) fn log_error(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    write_record(Level::Error, args)
+}
+
+pub(crate) fn log_set_level(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(name)) => set_level(name).map(|_| Object::Null).map_err(RuntimeError::InvalidArguments),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn log_level(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(Object::String(logger.level.name().to_string().into()))
+}
+
+/// Appends records to `path` instead of stderr; `null` switches back.
+pub(crate) fn log_to_file(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let file = match args.first() {
+        Some(Object::String(path)) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| RuntimeError::InvalidOperation(format!("Could not open log file {}: {}", path, e)))?,
+        ),
+        Some(Object::Null) => None,
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string or null".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner()).file = file;
+    Ok(Object::Null)
+}
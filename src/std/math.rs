@@ -1,6 +1,12 @@
 use crate::vm::obj::Object;
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use crate::vm::runtime::type_converters::{normalize_int, to_bigint};
+#[cfg(feature = "random")]
 use rand::Rng;
+#[cfg(feature = "random")]
+use crate::std::random::with_rng;
 
 pub(crate) fn math_clamp(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match (&args[0], &args[1], &args[2]) {
@@ -20,18 +26,17 @@ pub(crate) fn math_clamp(args: Vec<Object>) -> Result<Object, RuntimeError> {
     }
 }
 
+#[cfg(feature = "random")]
 pub(crate) fn math_random(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let mut rng = rand::rng();
-
     match args.as_slice() {
-        [] => Ok(Object::Integer(rng.random_range(0..=10))),
+        [] => Ok(Object::Integer(with_rng(|rng| rng.random_range(0..=10)))),
         [Object::Integer(max)] => {
             if *max < 0 {
                 return Err(RuntimeError::InvalidArguments(
                     "max must be non negative".to_string(),
                 ));
             }
-            Ok(Object::Integer(rng.random_range(0..*max)))
+            Ok(Object::Integer(with_rng(|rng| rng.random_range(0..*max))))
         }
         [Object::Integer(min), Object::Integer(max)] => {
             if *max < *min {
@@ -39,7 +44,7 @@ pub(crate) fn math_random(args: Vec<Object>) -> Result<Object, RuntimeError> {
                     "min must be lower than or equal to max".to_string(),
                 ));
             }
-            Ok(Object::Integer(rng.random_range(*min..=*max)))
+            Ok(Object::Integer(with_rng(|rng| rng.random_range(*min..=*max))))
         }
         _ => Err(RuntimeError::InvalidArguments(
             "random() expects 0, 1, or 2 integer arguments".to_string(),
@@ -281,3 +286,249 @@ pub(crate) fn math_pi() -> Object {
 pub(crate) fn math_e() -> Object {
     Object::Float(std::f64::consts::E)
 }
+
+fn float_arg(args: &[Object], index: usize) -> Result<f64, RuntimeError> {
+    match args.get(index) {
+        Some(Object::Float(n)) => Ok(*n),
+        Some(Object::Integer(n)) => Ok(*n as f64),
+        Some(Object::BigInteger(n)) => Ok(n.to_f64().unwrap_or(f64::NAN)),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "float or integer".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn unary_float(args: &[Object], f: fn(f64) -> f64) -> Result<Object, RuntimeError> {
+    Ok(Object::Float(f(float_arg(args, 0)?)))
+}
+
+pub(crate) fn math_pow(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match (&args[0], &args[1]) {
+        (Object::Integer(base), Object::Integer(exp)) if *exp >= 0 => {
+            let exp = u32::try_from(*exp).map_err(|_| {
+                RuntimeError::InvalidArguments("pow exponent is too large".to_string())
+            })?;
+            match base.checked_pow(exp) {
+                Some(n) => Ok(Object::Integer(n)),
+                None => Ok(Object::BigInteger(Box::new(BigInt::from(*base).pow(exp)))),
+            }
+        }
+        (Object::BigInteger(base), Object::Integer(exp)) if *exp >= 0 => {
+            let exp = u32::try_from(*exp).map_err(|_| {
+                RuntimeError::InvalidArguments("pow exponent is too large".to_string())
+            })?;
+            Ok(Object::BigInteger(Box::new(base.pow(exp))))
+        }
+        _ => Ok(Object::Float(float_arg(&args, 0)?.powf(float_arg(&args, 1)?))),
+    }
+}
+
+pub(crate) fn math_exp(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::exp)
+}
+
+pub(crate) fn math_atan2(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Float(float_arg(&args, 0)?.atan2(float_arg(&args, 1)?)))
+}
+
+pub(crate) fn math_hypot(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Float(float_arg(&args, 0)?.hypot(float_arg(&args, 1)?)))
+}
+
+pub(crate) fn math_asin(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let n = float_arg(&args, 0)?;
+    if !(-1.0..=1.0).contains(&n) {
+        return Err(RuntimeError::InvalidArguments(
+            "asin argument must be between -1 and 1".to_string(),
+        ));
+    }
+    Ok(Object::Float(n.asin()))
+}
+
+pub(crate) fn math_acos(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let n = float_arg(&args, 0)?;
+    if !(-1.0..=1.0).contains(&n) {
+        return Err(RuntimeError::InvalidArguments(
+            "acos argument must be between -1 and 1".to_string(),
+        ));
+    }
+    Ok(Object::Float(n.acos()))
+}
+
+pub(crate) fn math_atan(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::atan)
+}
+
+pub(crate) fn math_sinh(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::sinh)
+}
+
+pub(crate) fn math_cosh(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::cosh)
+}
+
+pub(crate) fn math_tanh(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::tanh)
+}
+
+pub(crate) fn math_degrees(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::to_degrees)
+}
+
+pub(crate) fn math_radians(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    unary_float(&args, f64::to_radians)
+}
+
+pub(crate) fn math_trunc(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::Integer(n)) => Ok(Object::Integer(*n)),
+        Some(Object::BigInteger(n)) => Ok(Object::BigInteger(n.clone())),
+        _ => unary_float(&args, f64::trunc),
+    }
+}
+
+pub(crate) fn math_sign(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::Integer(n)) => Ok(Object::Integer(n.signum())),
+        Some(Object::BigInteger(n)) => Ok(Object::Integer(match n.sign() {
+            num_bigint::Sign::Minus => -1,
+            num_bigint::Sign::NoSign => 0,
+            num_bigint::Sign::Plus => 1,
+        })),
+        Some(Object::Float(n)) if *n == 0.0 || n.is_nan() => Ok(Object::Float(*n)),
+        _ => unary_float(&args, f64::signum),
+    }
+}
+
+fn int_arg(args: &[Object], index: usize) -> Result<BigInt, RuntimeError> {
+    match args.get(index) {
+        Some(o) => to_bigint(o).ok_or_else(|| RuntimeError::TypeMismatch {
+            expected: "integer".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn non_negative_arg(args: &[Object], name: &str, limit: u64) -> Result<u64, RuntimeError> {
+    match int_arg(args, 0)?.to_u64() {
+        Some(n) if n <= limit => Ok(n),
+        _ => Err(RuntimeError::InvalidArguments(format!(
+            "{} argument must be between 0 and {}",
+            name, limit
+        ))),
+    }
+}
+
+fn gcd_big(mut a: BigInt, mut b: BigInt) -> BigInt {
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a.abs()
+}
+
+pub(crate) fn math_gcd(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(normalize_int(gcd_big(int_arg(&args, 0)?, int_arg(&args, 1)?)))
+}
+
+pub(crate) fn math_lcm(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (a, b) = (int_arg(&args, 0)?, int_arg(&args, 1)?);
+    if a.is_zero() || b.is_zero() {
+        return Ok(Object::Integer(0));
+    }
+    let gcd = gcd_big(a.clone(), b.clone());
+    Ok(normalize_int((a / gcd * b).abs()))
+}
+
+pub(crate) fn math_factorial(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let n = non_negative_arg(&args, "factorial", 20_000)?;
+    let result = (2..=n).fold(BigInt::one(), |acc, k| acc * k);
+    Ok(normalize_int(result))
+}
+
+/// Miller-Rabin with the first twelve primes as witnesses, which is
+/// deterministic below 3.3 * 10^24 and a strong probable-prime test above.
+fn is_prime_big(n: &BigInt) -> bool {
+    const WITNESSES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if *n < BigInt::from(2) {
+        return false;
+    }
+    for p in WITNESSES {
+        let p = BigInt::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+
+    let one = BigInt::one();
+    let n_minus_one = n - &one;
+    let mut d = n_minus_one.clone();
+    let mut s = 0u32;
+    while (&d % 2u32).is_zero() {
+        d /= 2u32;
+        s += 1;
+    }
+
+    'witness: for a in WITNESSES {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&BigInt::from(2), n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+pub(crate) fn math_is_prime(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Boolean(is_prime_big(&int_arg(&args, 0)?)))
+}
+
+pub(crate) fn math_fib(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let n = non_negative_arg(&args, "fib", 1_000_000)?;
+
+    // Fast doubling: F(2k) = F(k) * (2F(k+1) - F(k)), F(2k+1) = F(k)^2 + F(k+1)^2.
+    let (mut a, mut b) = (BigInt::zero(), BigInt::one());
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = &a * (&b * 2u32 - &a);
+        let d = &a * &a + &b * &b;
+        if (n >> bit) & 1 == 0 {
+            (a, b) = (c, d);
+        } else {
+            b = &c + &d;
+            a = d;
+        }
+    }
+    Ok(normalize_int(a))
+}
+
+pub(crate) fn math_isqrt(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let n = int_arg(&args, 0)?;
+    if n.is_negative() {
+        return Err(RuntimeError::InvalidArguments(
+            "isqrt argument must be non-negative".to_string(),
+        ));
+    }
+    Ok(normalize_int(n.sqrt()))
+}
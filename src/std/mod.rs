@@ -1,7 +1,34 @@
 pub(crate) mod string;
 pub(crate) mod math;
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod io;
 pub(crate) mod time;
 pub(crate) mod json;
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
 pub(crate) mod http;
-pub(crate) mod env;
\ No newline at end of file
+pub mod env;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod net;
+pub(crate) mod base64;
+#[cfg(feature = "random")]
+pub(crate) mod random;
+pub(crate) mod collections;
+pub(crate) mod set;
+pub mod log;
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+pub(crate) mod sqlite;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod db;
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+pub(crate) mod zip;
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+pub(crate) mod gzip;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod python;
+pub(crate) mod encoding;
+pub(crate) mod template;
+pub(crate) mod semver;
+pub(crate) mod ini;
+pub(crate) mod test;
@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex};
+
+use ahash::HashMapExt;
+use tokio::net::UdpSocket;
+
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+type NetFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>;
+
+/// Largest payload a single UDP datagram can carry.
+const MAX_DATAGRAM: usize = 65_507;
+
+pub(crate) fn net_udp_bind(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_net_udp_bind(args)) as NetFuture)))))
+}
+
+async fn async_net_udp_bind(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let addr = match args.first() {
+        Some(Object::String(addr)) => addr.to_string(),
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+
+    match UdpSocket::bind(&addr).await {
+        Ok(socket) => Ok(Object::UdpSocket(Arc::new(socket))),
+        Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not bind UDP socket to {}: {}", addr, e)))
+    }
+}
+
+/// Method calls on a socket returned by `net.udp_bind`. `send_to` and
+/// `recv_from` return futures; `local_addr` is synchronous.
+pub(crate) fn call_udp_method(socket: Arc<UdpSocket>, method_name: &str, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match method_name {
+        "send_to" => {
+            let mut args = args.into_iter();
+            let (data, addr) = match (args.next(), args.next()) {
+                (Some(Object::String(s)), Some(Object::String(addr))) => (s.as_bytes().to_vec(), addr),
+                (Some(Object::Bytes(b)), Some(Object::String(addr))) => (*b, addr),
+                (Some(Object::String(_) | Object::Bytes(_)), Some(o)) => {
+                    return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() })
+                }
+                (Some(o), Some(_)) => {
+                    return Err(RuntimeError::TypeMismatch { expected: "string or bytes".to_string(), got: o.type_name() })
+                }
+                (first, _) => {
+                    return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: first.map_or(0, |_| 1) })
+                }
+            };
+            Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async move {
+                match socket.send_to(&data, addr.as_str()).await {
+                    Ok(sent) => Ok(Object::Integer(sent as i64)),
+                    Err(e) => Err(RuntimeError::InvalidOperation(format!("UDP send to {} failed: {}", addr, e)))
+                }
+            }) as NetFuture)))))
+        }
+        "recv_from" => {
+            let size = match args.first() {
+                Some(Object::Integer(n)) if *n > 0 => (*n as usize).min(MAX_DATAGRAM),
+                Some(Object::Integer(n)) => return Err(RuntimeError::InvalidArguments(format!("recv_from() size must be positive, got {}", n))),
+                Some(o) => return Err(RuntimeError::TypeMismatch { expected: "integer".to_string(), got: o.type_name() }),
+                None => MAX_DATAGRAM,
+            };
+            Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async move {
+                let mut buf = vec![0u8; size];
+                match socket.recv_from(&mut buf).await {
+                    Ok((n, from)) => {
+                        buf.truncate(n);
+                        Ok(datagram_hash(buf, from.to_string()))
+                    }
+                    Err(e) => Err(RuntimeError::InvalidOperation(format!("UDP receive failed: {}", e)))
+                }
+            }) as NetFuture)))))
+        }
+        "local_addr" => match socket.local_addr() {
+            Ok(addr) => Ok(Object::String(addr.to_string().into())),
+            Err(e) => Err(RuntimeError::InvalidOperation(format!("Could not get local address: {}", e)))
+        },
+        _ => Err(RuntimeError::InvalidOperation(format!("udp socket has no method '{}'", method_name))),
+    }
+}
+
+fn datagram_hash(data: Vec<u8>, addr: String) -> Object {
+    #[allow(clippy::mutable_key_type)]
+    let mut hash = HashMap::new();
+
+    hash.insert(Object::String("data".into()), Object::Bytes(Box::new(data)));
+    hash.insert(Object::String("addr".into()), Object::String(addr.into()));
+    Object::Hash(Box::new(hash))
+}
@@ -0,0 +1,229 @@
+//! `std::python`: calling Python from scripts.
+//!
+//! Needs g-lang built with the `python` feature, which embeds the Python
+//! interpreter found at build time; without it every function fails with
+//! an error saying so.
+//!
+//! Values cross over by conversion: `null`, booleans, integers, floats,
+//! strings, bytes, arrays and hashes become their Python counterparts and
+//! back (tuples come back as arrays). Any other Python value comes back as a
+//! `python object` handle, whose methods are `call(name, ...args)`,
+//! `get(name)` and `to_string()`, and which can be passed back to Python.
+
+#[cfg(feature = "python")]
+pub(crate) use interop::{python_call, python_eval, python_exec, python_module};
+#[cfg(not(feature = "python"))]
+pub(crate) use python_unavailable as python_call;
+#[cfg(not(feature = "python"))]
+pub(crate) use python_unavailable as python_eval;
+#[cfg(not(feature = "python"))]
+pub(crate) use python_unavailable as python_exec;
+#[cfg(not(feature = "python"))]
+pub(crate) use python_unavailable as python_module;
+
+#[cfg(not(feature = "python"))]
+use crate::vm::{obj::Object, runtime::runtime_errors::RuntimeError};
+
+#[cfg(not(feature = "python"))]
+pub(crate) fn python_unavailable(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Err(RuntimeError::InvalidOperation("std::python needs g-lang built with the 'python' feature".to_string()))
+}
+
+#[cfg(feature = "python")]
+mod interop {
+    use std::ffi::CString;
+    use std::sync::LazyLock;
+
+    use ahash::HashMapExt;
+    use num_bigint::BigInt;
+    use pyo3::prelude::*;
+    use pyo3::sync::PyOnceLock;
+    use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+    use pyo3::IntoPyObjectExt;
+
+    use crate::engine::NativeType;
+    use crate::vm::obj::{self, Object};
+    use crate::vm::runtime::runtime_errors::RuntimeError;
+
+    /// Globals that `exec` and `eval` share, so what one defines the next
+    /// can use.
+    static GLOBALS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
+
+    static PYTHON_OBJECT: LazyLock<NativeType<Py<PyAny>>> = LazyLock::new(|| {
+        NativeType::new("python object")
+            .method("call", |object: &Py<PyAny>, args: Vec<Object>| {
+                let name = string_arg("call", &args)?;
+                Python::attach(|py| {
+                    let args = to_python_tuple(py, &args[1..])?;
+                    let result = object.bind(py).call_method1(name.as_str(), args).map_err(python_error)?;
+                    Ok(from_python(&result))
+                })
+            })
+            .method("get", |object: &Py<PyAny>, args: Vec<Object>| {
+                let name = string_arg("get", &args)?;
+                Python::attach(|py| {
+                    let value = object.bind(py).getattr(name.as_str()).map_err(python_error)?;
+                    Ok(from_python(&value))
+                })
+            })
+            .method("to_string", |object: &Py<PyAny>, _| {
+                Python::attach(|py| {
+                    let text = object.bind(py).str().map_err(python_error)?;
+                    Ok(Object::String(text.to_string().into()))
+                })
+            })
+    });
+
+    fn string_arg(name: &str, args: &[Object]) -> Result<String, RuntimeError> {
+        match args.first() {
+            Some(Object::String(s)) => Ok(s.to_string()),
+            Some(other) => Err(RuntimeError::TypeMismatch {
+                expected: format!("string for python.{}", name),
+                got: other.type_name(),
+            }),
+            None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+        }
+    }
+
+    fn python_error(e: PyErr) -> RuntimeError {
+        RuntimeError::InvalidOperation(format!("Python error: {}", e))
+    }
+
+    fn code(source: String) -> Result<CString, RuntimeError> {
+        CString::new(source).map_err(|_| RuntimeError::InvalidArguments("Python code cannot contain NUL bytes".to_string()))
+    }
+
+    /// `python.exec(code)` runs Python statements.
+    pub(crate) fn python_exec(args: Vec<Object>) -> Result<Object, RuntimeError> {
+        let source = code(string_arg("exec", &args)?)?;
+        Python::attach(|py| {
+            let globals = GLOBALS.get_or_init(py, || PyDict::new(py).unbind()).bind(py);
+            py.run(&source, Some(globals), None).map_err(python_error)?;
+            Ok(Object::Null)
+        })
+    }
+
+    /// `python.eval(expression)` evaluates a Python expression.
+    pub(crate) fn python_eval(args: Vec<Object>) -> Result<Object, RuntimeError> {
+        let source = code(string_arg("eval", &args)?)?;
+        Python::attach(|py| {
+            let globals = GLOBALS.get_or_init(py, || PyDict::new(py).unbind()).bind(py);
+            let value = py.eval(&source, Some(globals), None).map_err(python_error)?;
+            Ok(from_python(&value))
+        })
+    }
+
+    /// `python.module(name)` imports a Python module as a handle.
+    pub(crate) fn python_module(args: Vec<Object>) -> Result<Object, RuntimeError> {
+        let name = string_arg("module", &args)?;
+        Python::attach(|py| {
+            let module = PyModule::import(py, name.as_str()).map_err(python_error)?;
+            Ok(PYTHON_OBJECT.wrap(module.into_any().unbind()))
+        })
+    }
+
+    /// `python.call(function, ...args)` calls a Python callable: a handle,
+    /// or a dotted path such as `"math.sqrt"` to import it from.
+    pub(crate) fn python_call(args: Vec<Object>) -> Result<Object, RuntimeError> {
+        let Some((target, rest)) = args.split_first() else {
+            return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: usize::MAX, got: 0 });
+        };
+        Python::attach(|py| {
+            let function = match target {
+                Object::String(path) => {
+                    let (module, name) = path.rsplit_once('.').ok_or_else(|| {
+                        RuntimeError::InvalidArguments(format!("python.call expects 'module.function', got '{}'", path))
+                    })?;
+                    PyModule::import(py, module).and_then(|m| m.getattr(name)).map_err(python_error)?
+                }
+                other => to_python(py, other)?,
+            };
+            let args = to_python_tuple(py, rest)?;
+            let result = function.call1(args).map_err(python_error)?;
+            Ok(from_python(&result))
+        })
+    }
+
+    fn to_python_tuple<'py>(py: Python<'py>, args: &[Object]) -> Result<Bound<'py, PyTuple>, RuntimeError> {
+        let items = args.iter().map(|arg| to_python(py, arg)).collect::<Result<Vec<_>, _>>()?;
+        PyTuple::new(py, items).map_err(python_error)
+    }
+
+    fn to_python<'py>(py: Python<'py>, object: &Object) -> Result<Bound<'py, PyAny>, RuntimeError> {
+        let value = match object {
+            Object::Null => py.None().into_bound(py),
+            Object::Boolean(b) => b.into_bound_py_any(py).map_err(python_error)?,
+            Object::Integer(i) => i.into_bound_py_any(py).map_err(python_error)?,
+            Object::BigInteger(big) => big.as_ref().into_bound_py_any(py).map_err(python_error)?,
+            Object::Float(f) => f.into_bound_py_any(py).map_err(python_error)?,
+            Object::String(s) => PyString::new(py, s).into_any(),
+            Object::Bytes(bytes) => PyBytes::new(py, bytes).into_any(),
+            Object::Array(items) => {
+                let items = items.iter().map(|item| to_python(py, item)).collect::<Result<Vec<_>, _>>()?;
+                PyList::new(py, items).map_err(python_error)?.into_any()
+            }
+            Object::Hash(hash) => dict(py, hash.iter())?,
+            Object::OrderedMap(map) => dict(py, map.lock().unwrap().iter())?,
+            other => match other.downcast_native::<Py<PyAny>>() {
+                Some(handle) => handle.clone_ref(py).into_bound(py),
+                None => {
+                    return Err(RuntimeError::TypeMismatch {
+                        expected: "value that converts to Python".to_string(),
+                        got: other.type_name(),
+                    });
+                }
+            },
+        };
+        Ok(value)
+    }
+
+    fn dict<'a, 'py>(
+        py: Python<'py>,
+        entries: impl Iterator<Item = (&'a Object, &'a Object)>,
+    ) -> Result<Bound<'py, PyAny>, RuntimeError> {
+        let dict = PyDict::new(py);
+        for (key, value) in entries {
+            dict.set_item(to_python(py, key)?, to_python(py, value)?).map_err(python_error)?;
+        }
+        Ok(dict.into_any())
+    }
+
+    /// Converts `value` to the matching object, or wraps it in a handle.
+    fn from_python(value: &Bound<'_, PyAny>) -> Object {
+        convert(value).unwrap_or_else(|| PYTHON_OBJECT.wrap(value.clone().unbind()))
+    }
+
+    fn convert(value: &Bound<'_, PyAny>) -> Option<Object> {
+        if value.is_none() {
+            Some(Object::Null)
+        } else if let Ok(b) = value.cast::<PyBool>() {
+            Some(Object::Boolean(b.is_true()))
+        } else if let Ok(i) = value.cast::<PyInt>() {
+            match i.extract::<i64>() {
+                Ok(i) => Some(Object::Integer(i)),
+                Err(_) => i.extract::<BigInt>().ok().map(|big| Object::BigInteger(Box::new(big))),
+            }
+        } else if let Ok(f) = value.cast::<PyFloat>() {
+            Some(Object::Float(f.value()))
+        } else if let Ok(s) = value.cast::<PyString>() {
+            Some(Object::String(s.to_string().into()))
+        } else if let Ok(bytes) = value.cast::<PyBytes>() {
+            Some(Object::Bytes(Box::new(bytes.as_bytes().to_vec())))
+        } else if let Ok(list) = value.cast::<PyList>() {
+            Some(Object::Array(Box::new(list.iter().map(|item| from_python(&item)).collect())))
+        } else if let Ok(tuple) = value.cast::<PyTuple>() {
+            Some(Object::Array(Box::new(tuple.iter().map(|item| from_python(&item)).collect())))
+        } else if let Ok(dict) = value.cast::<PyDict>() {
+            // Keys that are not hashable here leave the dict a handle.
+            #[allow(clippy::mutable_key_type)]
+            let mut hash = obj::HashMap::with_capacity(dict.len());
+            for (key, value) in dict.iter() {
+                let key = convert(&key).filter(Object::is_hashable)?;
+                hash.insert(key, from_python(&value));
+            }
+            Some(Object::Hash(Box::new(hash)))
+        } else {
+            None
+        }
+    }
+}
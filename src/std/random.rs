@@ -0,0 +1,166 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, RngCore, SeedableRng};
+
+use crate::vm::obj::{HashMap, NativeCallable, NativeFunctionData, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// The generator `random.seed(n)` installed in one module registry, shared by
+/// everything that engine runs. While unset, calls draw from the thread-local
+/// OS-seeded generator.
+pub(crate) type RngState = Arc<Mutex<Option<StdRng>>>;
+
+thread_local! {
+    /// State of the registry whose random function is running.
+    static CURRENT: RefCell<Option<RngState>> = const { RefCell::new(None) };
+}
+
+/// Makes the functions of `exports` that draw random numbers, all of
+/// `std::random` and `math.random`, use `state`, the generator of the
+/// registry importing them.
+pub(crate) fn bind_exports(module_path: &str, exports: &mut HashMap<String, Object>, state: &RngState) {
+    for (name, export) in exports.iter_mut() {
+        if !(module_path == "std::random" || (module_path == "std::math" && name == "random")) {
+            continue;
+        }
+        let Object::BuiltinStd(data) = export else {
+            continue;
+        };
+        let (min, max, builtin) = (data.min_params, data.max_params, data.func);
+        let state = Arc::clone(state);
+        let func = NativeCallable::Sync(Arc::new(move |args: Vec<Object>| {
+            if args.len() < min || args.len() > max {
+                return Err(RuntimeError::WrongNumberOfArguments { min, max, got: args.len() });
+            }
+            with_current(&state, || builtin(args))
+        }));
+        *export = Object::NativeFunction(Box::new(NativeFunctionData { name: name.clone(), func }));
+    }
+}
+
+/// Runs `f`, a call into builtins, with `state` as the generator that
+/// [`with_rng`] draws from.
+pub(crate) fn with_current<T>(state: &RngState, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.replace(Some(Arc::clone(state)));
+    let result = f();
+    CURRENT.set(previous);
+    result
+}
+
+/// Runs `f` with the generator state of the running function's registry.
+fn with_state<T>(f: impl FnOnce(&mut Option<StdRng>) -> T) -> T {
+    match CURRENT.with_borrow(Clone::clone) {
+        Some(state) => f(&mut state.lock().unwrap_or_else(|e| e.into_inner())),
+        // Not bound to a registry, e.g. exports built for the language server.
+        None => f(&mut None),
+    }
+}
+
+/// Runs `f` with the seeded generator when one is installed, so `std::random`,
+/// `math.random` and `array.shuffle` stay reproducible after `random.seed(n)`.
+pub(crate) fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    with_state(|seeded| match seeded.as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut rand::rng()),
+    })
+}
+
+fn as_float(obj: &Object) -> Result<f64, RuntimeError> {
+    match obj {
+        Object::Integer(i) => Ok(*i as f64),
+        Object::Float(f) => Ok(*f),
+        o => Err(RuntimeError::TypeMismatch {
+            expected: "integer or float".to_string(),
+            got: o.type_name(),
+        }),
+    }
+}
+
+fn as_array(obj: Option<&Object>) -> Result<&[Object], RuntimeError> {
+    match obj {
+        Some(Object::Array(arr)) => Ok(arr.as_slice()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "array".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub(crate) fn random_seed(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let rng = match args.first() {
+        Some(Object::Integer(n)) => Some(StdRng::seed_from_u64(*n as u64)),
+        Some(Object::Null) | None => None,
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "integer".to_string(),
+                got: o.type_name(),
+            });
+        }
+    };
+    with_state(|seeded| *seeded = rng);
+    Ok(Object::Null)
+}
+
+pub(crate) fn random_float(_args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Float(with_rng(|rng| rng.random::<f64>())))
+}
+
+pub(crate) fn random_gaussian(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mean = args.first().map(as_float).transpose()?.unwrap_or(0.0);
+    let std_dev = args.get(1).map(as_float).transpose()?.unwrap_or(1.0);
+    if !std_dev.is_finite() || std_dev < 0.0 {
+        return Err(RuntimeError::InvalidArguments(format!(
+            "standard deviation must be a non negative number, got {}",
+            std_dev
+        )));
+    }
+
+    // Box-Muller transform; 1 - u keeps the log argument in (0, 1].
+    let (u1, u2) = with_rng(|rng| (1.0 - rng.random::<f64>(), rng.random::<f64>()));
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    Ok(Object::Float(mean + std_dev * z))
+}
+
+pub(crate) fn random_choice(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let arr = as_array(args.first())?;
+    match with_rng(|rng| arr.choose(rng).cloned()) {
+        Some(item) => Ok(item),
+        None => Err(RuntimeError::InvalidArguments(
+            "choice() requires a non empty array".to_string(),
+        )),
+    }
+}
+
+pub(crate) fn random_sample(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let arr = as_array(args.first())?;
+    let n = match args.get(1) {
+        Some(Object::Integer(n)) if *n >= 0 && (*n as usize) <= arr.len() => *n as usize,
+        Some(Object::Integer(n)) => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "sample size must be between 0 and {}, got {}",
+                arr.len(),
+                n
+            )));
+        }
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "integer".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
+    };
+
+    let picked: Vec<Object> = with_rng(|rng| arr.choose_multiple(rng, n).cloned().collect());
+    Ok(Object::Array(Box::new(picked)))
+}
+
+pub(crate) fn random_shuffle(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut items = as_array(args.first())?.to_vec();
+    with_rng(|rng| items.shuffle(rng));
+    Ok(Object::Array(Box::new(items)))
+}
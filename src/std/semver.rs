@@ -0,0 +1,507 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use ahash::HashMapExt;
+
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::Alpha(_)) => Ordering::Less,
+            (Identifier::Alpha(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alpha(a), Identifier::Alpha(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::Alpha(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// A semantic version (`MAJOR.MINOR.PATCH[-PRE][+BUILD]`). Ordering follows
+/// the semver spec: build metadata is ignored and a pre-release sorts before
+/// its release.
+#[derive(Debug, Clone)]
+pub(crate) struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: String,
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            let pre: Vec<String> = self.pre.iter().map(|id| id.to_string()).collect();
+            write!(f, "-{}", pre.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_number(part: &str) -> Option<u64> {
+    if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) || (part.len() > 1 && part.starts_with('0')) {
+        return None;
+    }
+    part.parse().ok()
+}
+
+fn parse_pre(pre: &str) -> Option<Vec<Identifier>> {
+    pre.split('.')
+        .map(|id| {
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                None
+            } else if id.chars().all(|c| c.is_ascii_digit()) {
+                parse_number(id).map(Identifier::Numeric)
+            } else {
+                Some(Identifier::Alpha(id.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// A version whose minor and patch may be missing or wildcards (`1`, `1.2`,
+/// `1.x`, `*`), as used on the right-hand side of range comparators.
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Partial {
+    fn parse(input: &str) -> Option<Partial> {
+        let input = input.trim();
+        let input = input.strip_prefix('v').unwrap_or(input);
+        let input = input.split_once('+').map(|(v, _)| v).unwrap_or(input);
+        let (core, pre) = match input.split_once('-') {
+            Some((core, pre)) => (core, parse_pre(pre)?),
+            None => (input, Vec::new()),
+        };
+
+        let mut parts = [None; 3];
+        let mut count = 0;
+        for (i, part) in core.split('.').enumerate() {
+            if i >= 3 {
+                return None;
+            }
+            parts[i] = match part {
+                "x" | "X" | "*" => None,
+                _ => Some(parse_number(part)?),
+            };
+            count = i + 1;
+        }
+        // A wildcard can't be followed by a concrete number (`1.x.3`).
+        if parts.iter().take(count).skip_while(|p| p.is_some()).any(|p| p.is_some()) {
+            return None;
+        }
+        Some(Partial { major: parts[0], minor: parts[1], patch: parts[2], pre })
+    }
+
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: if self.patch.is_some() { self.pre.clone() } else { Vec::new() },
+            build: String::new(),
+        }
+    }
+
+    /// The first version above everything this partial matches, or `None`
+    /// when it is unbounded (`*`). Pre-release `-0` keeps pre-releases of the
+    /// next version out of the range.
+    fn ceiling(&self) -> Option<Version> {
+        let (major, minor, patch) = match (self.major, self.minor, self.patch) {
+            (None, _, _) => return None,
+            (Some(major), None, _) => (major + 1, 0, 0),
+            (Some(major), Some(minor), None) => (major, minor + 1, 0),
+            (Some(_), Some(_), Some(_)) => return None,
+        };
+        Some(Version { major, minor, patch, pre: vec![Identifier::Numeric(0)], build: String::new() })
+    }
+
+    fn is_exact(&self) -> bool {
+        self.patch.is_some()
+    }
+}
+
+pub(crate) fn parse_version(input: &str) -> Option<Version> {
+    let input = input.trim();
+    let input = input.strip_prefix(['v', '=']).unwrap_or(input);
+    let (rest, build) = match input.split_once('+') {
+        Some((rest, build)) => {
+            if build.is_empty() || !build.split('.').all(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')) {
+                return None;
+            }
+            (rest, build.to_string())
+        }
+        None => (input, String::new()),
+    };
+    let (core, pre) = match rest.split_once('-') {
+        Some((core, pre)) => (core, parse_pre(pre)?),
+        None => (rest, Vec::new()),
+    };
+    let mut parts = core.split('.');
+    let version = Version {
+        major: parse_number(parts.next()?)?,
+        minor: parse_number(parts.next()?)?,
+        patch: parse_number(parts.next()?)?,
+        pre,
+        build,
+    };
+    parts.next().is_none().then_some(version)
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, v: &Version) -> bool {
+        match self.op {
+            Op::Lt => v < &self.version,
+            Op::Le => v <= &self.version,
+            Op::Gt => v > &self.version,
+            Op::Ge => v >= &self.version,
+            Op::Eq => v == &self.version,
+        }
+    }
+}
+
+/// A parsed range such as `^1.2 || >=2.1.0 <3`: a list of alternatives, each
+/// a list of comparators that must all match.
+pub(crate) struct Range {
+    sets: Vec<Vec<Comparator>>,
+}
+
+fn between(out: &mut Vec<Comparator>, floor: Version, ceiling: Option<Version>) {
+    out.push(Comparator { op: Op::Ge, version: floor });
+    if let Some(ceiling) = ceiling {
+        out.push(Comparator { op: Op::Lt, version: ceiling });
+    }
+}
+
+fn parse_comparator(token: &str, out: &mut Vec<Comparator>) -> Option<()> {
+    let (op, rest) = ["<=", ">=", "<", ">", "=", "^", "~"]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    let partial = Partial::parse(rest)?;
+    let floor = partial.floor();
+    let (major, minor) = (floor.major, floor.minor);
+
+    match op {
+        "^" => {
+            let ceiling = match (partial.major, partial.minor, partial.patch) {
+                (Some(0), Some(0), Some(patch)) => (0, 0, patch + 1),
+                (Some(0), Some(minor), _) => (0, minor + 1, 0),
+                (Some(0), None, _) => (1, 0, 0),
+                (Some(major), _, _) => (major + 1, 0, 0),
+                (None, _, _) => {
+                    between(out, floor, None);
+                    return Some(());
+                }
+            };
+            let (major, minor, patch) = ceiling;
+            let ceiling = Version { major, minor, patch, pre: vec![Identifier::Numeric(0)], build: String::new() };
+            between(out, floor, Some(ceiling));
+        }
+        "~" => {
+            let ceiling = match partial.minor {
+                Some(_) => Version { major, minor: minor + 1, patch: 0, pre: vec![Identifier::Numeric(0)], build: String::new() },
+                None => Version { major: major + 1, minor: 0, patch: 0, pre: vec![Identifier::Numeric(0)], build: String::new() },
+            };
+            between(out, floor, partial.major.map(|_| ceiling));
+        }
+        ">=" => out.push(Comparator { op: Op::Ge, version: floor }),
+        "<" => out.push(Comparator { op: Op::Lt, version: floor.clone() }),
+        ">" => match partial.ceiling() {
+            Some(ceiling) => out.push(Comparator { op: Op::Ge, version: ceiling }),
+            None if partial.is_exact() => out.push(Comparator { op: Op::Gt, version: floor }),
+            None => out.push(Comparator { op: Op::Lt, version: Version { major: 0, minor: 0, patch: 0, pre: vec![Identifier::Numeric(0)], build: String::new() } }),
+        },
+        "<=" => match partial.ceiling() {
+            Some(ceiling) => out.push(Comparator { op: Op::Lt, version: ceiling }),
+            None if partial.is_exact() => out.push(Comparator { op: Op::Le, version: floor }),
+            None => between(out, floor, None),
+        },
+        _ if partial.is_exact() => out.push(Comparator { op: Op::Eq, version: floor }),
+        _ => between(out, floor, partial.ceiling()),
+    }
+    Some(())
+}
+
+impl Range {
+    pub(crate) fn parse(input: &str) -> Option<Range> {
+        let mut sets = Vec::new();
+
+        for alternative in input.split("||") {
+            let mut comparators = Vec::new();
+            let alternative = alternative.trim();
+
+            if let Some((low, high)) = alternative.split_once(" - ") {
+                let low = Partial::parse(low)?;
+                let high = Partial::parse(high)?;
+                let upper = match high.ceiling() {
+                    Some(ceiling) => Comparator { op: Op::Lt, version: ceiling },
+                    None if high.is_exact() => Comparator { op: Op::Le, version: high.floor() },
+                    None => Comparator { op: Op::Ge, version: low.floor() },
+                };
+                comparators.push(Comparator { op: Op::Ge, version: low.floor() });
+                comparators.push(upper);
+            } else {
+                // Allow a space between an operator and its version (`>= 1.2`).
+                let mut pending = String::new();
+                for token in alternative.split_whitespace() {
+                    pending.push_str(token);
+                    if token.chars().all(|c| "<>=^~".contains(c)) {
+                        continue;
+                    }
+                    parse_comparator(&pending, &mut comparators)?;
+                    pending.clear();
+                }
+                if !pending.is_empty() {
+                    return None;
+                }
+            }
+
+            sets.push(comparators);
+        }
+
+        Some(Range { sets })
+    }
+
+    /// A pre-release only satisfies a range when some comparator names a
+    /// pre-release of the same `MAJOR.MINOR.PATCH`, so `^1.2.0` never
+    /// silently picks up `1.3.0-beta`.
+    pub(crate) fn satisfies(&self, v: &Version) -> bool {
+        self.sets.iter().any(|set| {
+            set.iter().all(|c| c.matches(v))
+                && (v.pre.is_empty()
+                    || set.iter().any(|c| {
+                        !c.version.pre.is_empty()
+                            && c.version.pre != [Identifier::Numeric(0)]
+                            && (c.version.major, c.version.minor, c.version.patch) == (v.major, v.minor, v.patch)
+                    }))
+        })
+    }
+}
+
+fn version_arg(args: &[Object], index: usize) -> Result<Version, RuntimeError> {
+    match args.get(index) {
+        Some(Object::String(s)) => {
+            parse_version(s).ok_or_else(|| RuntimeError::InvalidArguments(format!("invalid semantic version '{}'", s)))
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn range_arg(args: &[Object], index: usize) -> Result<Range, RuntimeError> {
+    match args.get(index) {
+        Some(Object::String(s)) => {
+            Range::parse(s).ok_or_else(|| RuntimeError::InvalidArguments(format!("invalid version range '{}'", s)))
+        }
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn versions_arg(args: &[Object]) -> Result<Vec<Version>, RuntimeError> {
+    match args.first() {
+        Some(Object::Array(items)) => items.iter().map(|item| version_arg(std::slice::from_ref(item), 0)).collect(),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "array".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    }
+}
+
+/// `semver.parse(v)` returns `{major, minor, patch, pre, build}` with `pre`
+/// and `build` as strings (empty when absent).
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn semver_parse(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let version = version_arg(&args, 0)?;
+    let pre: Vec<String> = version.pre.iter().map(|id| id.to_string()).collect();
+
+    let mut hash = HashMap::with_capacity(5);
+    hash.insert(Object::String("major".into()), Object::Integer(version.major as i64));
+    hash.insert(Object::String("minor".into()), Object::Integer(version.minor as i64));
+    hash.insert(Object::String("patch".into()), Object::Integer(version.patch as i64));
+    hash.insert(Object::String("pre".into()), Object::String(pre.join(".").into()));
+    hash.insert(Object::String("build".into()), Object::String(version.build.into()));
+    Ok(Object::Hash(Box::new(hash)))
+}
+
+pub(crate) fn semver_valid(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(Object::Boolean(parse_version(s).is_some())),
+        _ => Ok(Object::Boolean(false)),
+    }
+}
+
+/// `semver.compare(a, b)` returns -1, 0 or 1.
+pub(crate) fn semver_compare(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let ordering = version_arg(&args, 0)?.cmp(&version_arg(&args, 1)?);
+    Ok(Object::Integer(ordering as i64))
+}
+
+/// `semver.satisfies(v, range)` supports comparators (`>=1.2.0 <2`), caret
+/// and tilde ranges, wildcards (`1.x`, `*`), hyphen ranges (`1.2 - 1.4`) and
+/// alternatives joined with `||`.
+pub(crate) fn semver_satisfies(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let version = version_arg(&args, 0)?;
+    Ok(Object::Boolean(range_arg(&args, 1)?.satisfies(&version)))
+}
+
+/// `semver.max_satisfying(versions, range)` returns the highest matching
+/// version string, or null when none match.
+pub(crate) fn semver_max_satisfying(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let range = range_arg(&args, 1)?;
+    Ok(versions_arg(&args)?
+        .into_iter()
+        .filter(|v| range.satisfies(v))
+        .max()
+        .map(|v| Object::String(v.to_string().into()))
+        .unwrap_or(Object::Null))
+}
+
+pub(crate) fn semver_sort(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut versions = versions_arg(&args)?;
+    versions.sort();
+    Ok(Object::Array(Box::new(versions.into_iter().map(|v| Object::String(v.to_string().into())).collect())))
+}
+
+/// `semver.increment(v, part)` with `part` one of `major`, `minor`, `patch`
+/// or `prerelease`. Releasing a pre-release keeps its numbers, so
+/// `increment("2.0.0-rc.1", "major")` is `2.0.0`.
+pub(crate) fn semver_increment(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut v = version_arg(&args, 0)?;
+    let part = match args.get(1) {
+        Some(Object::String(s)) => s.as_str(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
+    };
+    let was_pre = !v.pre.is_empty();
+    v.build.clear();
+
+    match part {
+        "major" => {
+            if !(was_pre && v.minor == 0 && v.patch == 0) {
+                v.major += 1;
+            }
+            (v.minor, v.patch, v.pre) = (0, 0, Vec::new());
+        }
+        "minor" => {
+            if !(was_pre && v.patch == 0) {
+                v.minor += 1;
+            }
+            (v.patch, v.pre) = (0, Vec::new());
+        }
+        "patch" => {
+            if !was_pre {
+                v.patch += 1;
+            }
+            v.pre.clear();
+        }
+        "prerelease" => match v.pre.last_mut() {
+            Some(Identifier::Numeric(n)) => *n += 1,
+            Some(Identifier::Alpha(_)) => v.pre.push(Identifier::Numeric(0)),
+            None => {
+                v.patch += 1;
+                v.pre.push(Identifier::Numeric(0));
+            }
+        },
+        _ => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "unknown version part '{}', expected major, minor, patch or prerelease",
+                part
+            )));
+        }
+    }
+
+    Ok(Object::String(v.to_string().into()))
+}
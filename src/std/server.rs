@@ -0,0 +1,449 @@
+//! Minimal async HTTP/1.1 server for `std::server`.
+//!
+//! Scripts build a server with `server.new(options?)`, register handlers with
+//! `.route(method, pattern, handler)` and start it with `await .listen(port)`.
+//! Each request is passed to the handler as a hash and the handler returns
+//! either a string (sent as a 200 body) or a response hash with `status`,
+//! `body` and `headers`.
+//!
+//! Request bodies larger than the `max_body_size` option (1 MiB by default)
+//! are refused with `413 Payload Too Large` without being read. A client
+//! that has not sent its whole request within the `read_timeout` option
+//! (30 seconds by default) gets `408 Request Timeout`. Bodies need a
+//! `Content-Length`: chunked requests get `411 Length Required`, and a body
+//! cut short gets `400 Bad Request`.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ahash::HashMapExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::std::encoding::{percent_decode, percent_decode_path};
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::ops::calls::call_function_object;
+use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::module_registry::ModuleRegistry;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+const MAX_HEADERS: usize = 64;
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A registered route. `segments` holds the pattern split on `/`, where a
+/// segment starting with `:` captures that part of the path.
+struct Route {
+    method: String,
+    segments: Vec<String>,
+    handler: Object,
+}
+
+/// Routes and settings of a server created by `server.new()`.
+pub struct ServerData {
+    routes: Vec<Route>,
+    max_body_bytes: usize,
+    /// How long a client may take to send its request.
+    read_timeout: Duration,
+}
+
+impl Default for ServerData {
+    fn default() -> Self {
+        ServerData { routes: Vec::new(), max_body_bytes: DEFAULT_MAX_BODY_BYTES, read_timeout: DEFAULT_READ_TIMEOUT }
+    }
+}
+
+pub(crate) fn server_new(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut data = ServerData::default();
+    match args.first() {
+        Some(Object::Hash(opts)) => {
+            for (key, value) in opts.iter() {
+                match (key, value) {
+                    (Object::String(k), Object::Integer(n)) if k == "max_body_size" && *n >= 0 => {
+                        data.max_body_bytes = usize::try_from(*n).unwrap_or(usize::MAX);
+                    }
+                    (Object::String(k), Object::Integer(ms)) if k == "read_timeout" && *ms > 0 => {
+                        data.read_timeout = Duration::from_millis(*ms as u64);
+                    }
+                    (Object::String(k), o) if k == "max_body_size" => {
+                        return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a non-negative integer, got {}", k, o)));
+                    }
+                    (Object::String(k), o) if k == "read_timeout" => {
+                        return Err(RuntimeError::InvalidArguments(format!("'{}' option must be a positive number of milliseconds, got {}", k, o)));
+                    }
+                    (k, _) => return Err(RuntimeError::InvalidArguments(format!("Unknown server option '{}'", k))),
+                }
+            }
+        }
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "hash".to_string(), got: o.type_name() }),
+        None => {}
+    }
+    Ok(Object::Server(Arc::new(Mutex::new(data))))
+}
+
+/// Dispatches a method call on a server object. `listen` needs the caller's
+/// globals and module registry so handlers can run like ordinary calls.
+pub(crate) fn call_server_method(
+    server: Arc<Mutex<ServerData>>,
+    method_name: &str,
+    args: Vec<Object>,
+    module_registry: &Arc<Mutex<ModuleRegistry>>,
+    globals: &Arc<Mutex<Environment>>,
+) -> Result<Object, RuntimeError> {
+    match method_name {
+        "route" => server_route(server, args),
+        "listen" => {
+            let (host, port) = listen_args(args)?;
+            let module_registry = Arc::clone(module_registry);
+            let globals = Arc::clone(globals);
+            Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(
+                serve(server, host, port, module_registry, globals),
+            ) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+        }
+        _ => Err(RuntimeError::InvalidOperation(format!("server has no method '{}'", method_name))),
+    }
+}
+
+fn server_route(server: Arc<Mutex<ServerData>>, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let got = args.len();
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next(), args.next()) {
+        (Some(Object::String(method)), Some(Object::String(pattern)), Some(handler)) => {
+            if !matches!(handler, Object::Function(_) | Object::AsyncFunction(_)) {
+                return Err(RuntimeError::TypeMismatch { expected: "function".to_string(), got: handler.type_name() });
+            }
+            server.lock().unwrap().routes.push(Route {
+                method: method.to_uppercase(),
+                segments: split_path(&pattern),
+                handler,
+            });
+            Ok(Object::Server(server))
+        }
+        (Some(Object::String(_)), Some(o), Some(_)) | (Some(o), Some(_), Some(_)) => {
+            Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() })
+        }
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 3, max: 3, got }),
+    }
+}
+
+fn listen_args(args: Vec<Object>) -> Result<(String, u16), RuntimeError> {
+    let port = match args.first() {
+        Some(Object::Integer(port)) => u16::try_from(*port)
+            .map_err(|_| RuntimeError::InvalidArguments(format!("Invalid port {}", port)))?,
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "integer".to_string(), got: o.type_name() }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+    let host = match args.get(1) {
+        Some(Object::String(host)) => host.to_string(),
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+        None => "127.0.0.1".to_string(),
+    };
+    Ok((host, port))
+}
+
+async fn serve(
+    server: Arc<Mutex<ServerData>>,
+    host: String,
+    port: u16,
+    module_registry: Arc<Mutex<ModuleRegistry>>,
+    globals: Arc<Mutex<Environment>>,
+) -> Result<Object, RuntimeError> {
+    let listener = TcpListener::bind((host.as_str(), port))
+        .await
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not listen on {}:{}: {}", host, port, e)))?;
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| RuntimeError::InvalidOperation(format!("Could not accept connection: {}", e)))?;
+        let server = Arc::clone(&server);
+        let module_registry = Arc::clone(&module_registry);
+        let globals = Arc::clone(&globals);
+        tokio::spawn(async move {
+            // A failed connection only affects that client.
+            let _ = handle_connection(stream, server, module_registry, globals).await;
+        });
+    }
+}
+
+/// A parsed request: method, raw target, lowercase headers and body.
+struct RawRequest {
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    server: Arc<Mutex<ServerData>>,
+    module_registry: Arc<Mutex<ModuleRegistry>>,
+    globals: Arc<Mutex<Environment>>,
+) -> std::io::Result<()> {
+    let (max_body_bytes, read_timeout) = {
+        let server = server.lock().unwrap();
+        (server.max_body_bytes, server.read_timeout)
+    };
+    let response = match tokio::time::timeout(read_timeout, read_request(&mut stream, max_body_bytes)).await {
+        Ok(read) => match read? {
+            Ok(request) => dispatch(request, server, module_registry, globals).await,
+            Err(response) => response,
+        },
+        Err(_) => Response::text(408, "Request Timeout"),
+    };
+    stream.write_all(&response.to_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Reads one request, or returns the error response for a malformed one or
+/// one whose body is larger than `max_body_bytes`.
+async fn read_request(stream: &mut TcpStream, max_body_bytes: usize) -> std::io::Result<Result<RawRequest, Response>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+
+    let (head_len, mut request) = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(Err(Response::text(400, "Bad Request")));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut headers);
+        match parsed.parse(&buf) {
+            Ok(httparse::Status::Complete(head_len)) => {
+                let request = RawRequest {
+                    method: parsed.method.unwrap_or("GET").to_string(),
+                    target: parsed.path.unwrap_or("/").to_string(),
+                    headers: parsed
+                        .headers
+                        .iter()
+                        .map(|h| (h.name.to_lowercase(), String::from_utf8_lossy(h.value).to_string()))
+                        .collect(),
+                    body: Vec::new(),
+                };
+                break (head_len, request);
+            }
+            Ok(httparse::Status::Partial) if buf.len() < MAX_HEAD_BYTES => continue,
+            _ => return Ok(Err(Response::text(400, "Bad Request"))),
+        }
+    };
+
+    // Chunked bodies are not decoded, so a body must come with its length.
+    if request.headers.iter().any(|(name, _)| name == "transfer-encoding") {
+        return Ok(Err(Response::text(411, "Length Required")));
+    }
+    let content_length = match request.headers.iter().find(|(name, _)| name == "content-length") {
+        Some((_, value)) => match value.trim().parse::<usize>() {
+            Ok(length) => length,
+            Err(_) => return Ok(Err(Response::text(400, "Bad Request"))),
+        },
+        None => 0,
+    };
+    if content_length > max_body_bytes {
+        return Ok(Err(Response::text(413, "Payload Too Large")));
+    }
+
+    let mut body = buf.split_off(head_len);
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            // The client hung up before sending the body it announced.
+            return Ok(Err(Response::text(400, "Bad Request")));
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+    request.body = body;
+    Ok(Ok(request))
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split('/').filter(|s| !s.is_empty()).map(str::to_string).collect()
+}
+
+/// Matches `path` against a route pattern, returning the captured params.
+fn match_route(segments: &[String], path: &[String]) -> Option<Vec<(String, String)>> {
+    if segments.len() != path.len() {
+        return None;
+    }
+    let mut params = Vec::new();
+    for (pattern, actual) in segments.iter().zip(path) {
+        match pattern.strip_prefix(':') {
+            Some(name) => params.push((name.to_string(), actual.clone())),
+            None if pattern == actual => {}
+            None => return None,
+        }
+    }
+    Some(params)
+}
+
+async fn dispatch(
+    request: RawRequest,
+    server: Arc<Mutex<ServerData>>,
+    module_registry: Arc<Mutex<ModuleRegistry>>,
+    globals: Arc<Mutex<Environment>>,
+) -> Response {
+    let (path, query) = match request.target.split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (request.target.clone(), String::new()),
+    };
+    let path_segments: Vec<String> = split_path(&path).iter().map(|s| percent_decode_path(s)).collect();
+
+    let (handler, params) = {
+        let server = server.lock().unwrap();
+        let mut path_matched = false;
+        let mut found = None;
+        for route in &server.routes {
+            if let Some(params) = match_route(&route.segments, &path_segments) {
+                path_matched = true;
+                if route.method == request.method || route.method == "*" {
+                    found = Some((route.handler.clone(), params));
+                    break;
+                }
+            }
+        }
+        match found {
+            Some(found) => found,
+            None if path_matched => return Response::text(405, "Method Not Allowed"),
+            None => return Response::text(404, "Not Found"),
+        }
+    };
+
+    let request_hash = request_to_hash(&request, &path, &query, params);
+    match call_function_object(handler, vec![request_hash], module_registry, globals).await {
+        Ok(result) => Response::from_object(result),
+        Err(e) => Response::text(500, &format!("Internal Server Error: {}", e)),
+    }
+}
+
+fn string_hash(pairs: Vec<(String, String)>) -> Object {
+    // Safe: only String keys are inserted.
+    #[allow(clippy::mutable_key_type)]
+    let mut hash = HashMap::new();
+    for (key, value) in pairs {
+        hash.insert(Object::String(key.into()), Object::String(value.into()));
+    }
+    Object::Hash(Box::new(hash))
+}
+
+fn request_to_hash(request: &RawRequest, path: &str, query: &str, params: Vec<(String, String)>) -> Object {
+    let query_pairs = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) => (percent_decode(k), percent_decode(v)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect();
+
+    // Safe: only String keys are inserted.
+    #[allow(clippy::mutable_key_type)]
+    let mut hash = HashMap::new();
+    hash.insert(Object::String("method".into()), Object::String(request.method.clone().into()));
+    hash.insert(Object::String("path".into()), Object::String(path.to_string().into()));
+    hash.insert(Object::String("params".into()), string_hash(params));
+    hash.insert(Object::String("query".into()), string_hash(query_pairs));
+    hash.insert(Object::String("headers".into()), string_hash(request.headers.clone()));
+    hash.insert(
+        Object::String("body".into()),
+        Object::String(String::from_utf8_lossy(&request.body).to_string().into()),
+    );
+    Object::Hash(Box::new(hash))
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Self {
+        Response {
+            status,
+            headers: vec![("content-type".to_string(), "text/plain; charset=utf-8".to_string())],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// Builds a response from a handler's return value: a string becomes a
+    /// 200 text body, null an empty 204, and a hash supplies `status`,
+    /// `body` and `headers` explicitly.
+    fn from_object(value: Object) -> Self {
+        match value {
+            Object::String(body) => Response::text(200, &body),
+            Object::Null => Response { status: 204, headers: Vec::new(), body: Vec::new() },
+            Object::Hash(hash) => {
+                let status = match hash.get(&Object::String("status".into())) {
+                    Some(Object::Integer(code)) if (100..=999).contains(code) => *code as u16,
+                    Some(_) => return Response::text(500, "Internal Server Error: invalid response status"),
+                    None => 200,
+                };
+                let body = match hash.get(&Object::String("body".into())) {
+                    Some(Object::String(s)) => s.as_bytes().to_vec(),
+                    Some(Object::Bytes(b)) => b.to_vec(),
+                    Some(Object::Null) | None => Vec::new(),
+                    Some(other) => other.to_string().into_bytes(),
+                };
+                let mut headers = Vec::new();
+                if let Some(Object::Hash(h)) = hash.get(&Object::String("headers".into())) {
+                    for (name, value) in h.iter() {
+                        let (name, value) = (name.to_string().to_lowercase(), value.to_string());
+                        // A line break would let the value add headers or a body of its own.
+                        if name.is_empty() || name.contains([':', '\r', '\n']) || value.contains(['\r', '\n']) {
+                            return Response::text(500, "Internal Server Error: invalid response header");
+                        }
+                        headers.push((name, value));
+                    }
+                }
+                if !headers.iter().any(|(name, _)| name == "content-type") {
+                    headers.push(("content-type".to_string(), "text/plain; charset=utf-8".to_string()));
+                }
+                Response { status, headers, body }
+            }
+            Object::Error(e) => Response::text(500, &format!("Internal Server Error: {}", e)),
+            other => Response::text(200, &other.to_string()),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = format!("HTTP/1.1 {} {}\r\n", self.status, reason_phrase(self.status));
+        for (name, value) in &self.headers {
+            if name != "content-length" && name != "connection" {
+                out.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+        out.push_str(&format!("content-length: {}\r\nconnection: close\r\n\r\n", self.body.len()));
+        let mut bytes = out.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+// Sets are plain arrays without duplicates. Every operation keeps the order in
+// which elements first appear, so results are deterministic.
+
+fn array_arg(args: &[Object], index: usize) -> Result<&[Object], RuntimeError> {
+    match args.get(index) {
+        Some(Object::Array(arr)) => Ok(arr.as_slice()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "array".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+#[allow(clippy::mutable_key_type)]
+fn unique<'a>(items: impl Iterator<Item = &'a Object>) -> Vec<Object> {
+    let mut seen = HashSet::new();
+    items.filter(|item| seen.insert(*item)).cloned().collect()
+}
+
+#[allow(clippy::mutable_key_type)]
+fn members(items: &[Object]) -> HashSet<&Object> {
+    items.iter().collect()
+}
+
+pub(crate) fn set_from(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Array(Box::new(unique(array_arg(&args, 0)?.iter()))))
+}
+
+pub(crate) fn set_union(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (a, b) = (array_arg(&args, 0)?, array_arg(&args, 1)?);
+    Ok(Object::Array(Box::new(unique(a.iter().chain(b.iter())))))
+}
+
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn set_intersection(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (a, b) = (array_arg(&args, 0)?, array_arg(&args, 1)?);
+    let b = members(b);
+    Ok(Object::Array(Box::new(unique(a.iter().filter(|item| b.contains(item))))))
+}
+
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn set_difference(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (a, b) = (array_arg(&args, 0)?, array_arg(&args, 1)?);
+    let b = members(b);
+    Ok(Object::Array(Box::new(unique(a.iter().filter(|item| !b.contains(item))))))
+}
+
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn set_is_subset(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let (a, b) = (array_arg(&args, 0)?, array_arg(&args, 1)?);
+    let b = members(b);
+    Ok(Object::Boolean(a.iter().all(|item| b.contains(item))))
+}
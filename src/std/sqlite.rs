@@ -0,0 +1,215 @@
+use std::sync::{Arc, Mutex};
+
+use ahash::HashMapExt;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::Connection;
+
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// Open database returned by `sqlite.open`. Copies of the value share the
+/// connection; `conn` is `None` once the database has been closed.
+pub struct SqliteHandle {
+    pub path: String,
+    pub conn: Option<Connection>,
+}
+
+fn sql_error(e: rusqlite::Error) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("SQLite error: {}", e))
+}
+
+pub(crate) fn sqlite_open(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let path = match args.first() {
+        Some(Object::String(path)) => path.to_string(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+
+    let conn = if path == ":memory:" {
+        Connection::open_in_memory()
+    } else {
+        Connection::open(&path)
+    }
+    .map_err(sql_error)?;
+
+    Ok(Object::Sqlite(Arc::new(Mutex::new(SqliteHandle { path, conn: Some(conn) }))))
+}
+
+fn to_sql_value(obj: &Object) -> Result<Value, RuntimeError> {
+    match obj {
+        Object::Null => Ok(Value::Null),
+        Object::Integer(i) => Ok(Value::Integer(*i)),
+        Object::Boolean(b) => Ok(Value::Integer(*b as i64)),
+        Object::Float(f) => Ok(Value::Real(*f)),
+        Object::String(s) => Ok(Value::Text(s.to_string())),
+        Object::Bytes(b) => Ok(Value::Blob(b.to_vec())),
+        o => Err(RuntimeError::TypeMismatch {
+            expected: "null, integer, boolean, float, string or bytes".to_string(),
+            got: o.type_name(),
+        }),
+    }
+}
+
+fn from_sql_value(value: ValueRef) -> Object {
+    match value {
+        ValueRef::Null => Object::Null,
+        ValueRef::Integer(i) => Object::Integer(i),
+        ValueRef::Real(f) => Object::Float(f),
+        ValueRef::Text(t) => Object::String(String::from_utf8_lossy(t).into_owned().into()),
+        ValueRef::Blob(b) => Object::Bytes(Box::new(b.to_vec())),
+    }
+}
+
+/// Statement parameters: an array binds positionally (`?`), a hash binds by
+/// name (`:name`, `@name` or `$name`, written with the prefix in the key).
+enum Params {
+    Positional(Vec<Value>),
+    Named(Vec<(String, Value)>),
+}
+
+impl Params {
+    fn from_arg(arg: Option<&Object>) -> Result<Params, RuntimeError> {
+        match arg {
+            None | Some(Object::Null) => Ok(Params::Positional(Vec::new())),
+            Some(Object::Array(values)) => values
+                .iter()
+                .map(to_sql_value)
+                .collect::<Result<_, _>>()
+                .map(Params::Positional),
+            Some(Object::Hash(map)) => {
+                let mut named = Vec::with_capacity(map.len());
+                for (k, v) in map.iter() {
+                    let name = match k {
+                        Object::String(s) if s.starts_with([':', '@', '$']) => s.to_string(),
+                        Object::String(s) => format!(":{}", s),
+                        o => {
+                            return Err(RuntimeError::TypeMismatch {
+                                expected: "string".to_string(),
+                                got: o.type_name(),
+                            });
+                        }
+                    };
+                    named.push((name, to_sql_value(v)?));
+                }
+                Ok(Params::Named(named))
+            }
+            Some(o) => Err(RuntimeError::TypeMismatch {
+                expected: "array or hash".to_string(),
+                got: o.type_name(),
+            }),
+        }
+    }
+
+    fn bind(&self, stmt: &mut rusqlite::Statement) -> Result<(), RuntimeError> {
+        match self {
+            Params::Positional(values) => {
+                if values.len() != stmt.parameter_count() {
+                    return Err(RuntimeError::InvalidArguments(format!(
+                        "statement expects {} parameters, got {}",
+                        stmt.parameter_count(),
+                        values.len()
+                    )));
+                }
+                for (i, value) in values.iter().enumerate() {
+                    stmt.raw_bind_parameter(i + 1, value).map_err(sql_error)?;
+                }
+            }
+            Params::Named(values) => {
+                for (name, value) in values {
+                    let index = stmt.parameter_index(name).map_err(sql_error)?.ok_or_else(|| {
+                        RuntimeError::InvalidArguments(format!("statement has no parameter named {}", name))
+                    })?;
+                    stmt.raw_bind_parameter(index, value).map_err(sql_error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::mutable_key_type)]
+fn query(conn: &Connection, sql: &str, params: Params) -> Result<Object, RuntimeError> {
+    let mut stmt = conn.prepare(sql).map_err(sql_error)?;
+    params.bind(&mut stmt)?;
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(str::to_string).collect();
+    let mut rows = stmt.raw_query();
+    let mut result = Vec::new();
+
+    while let Some(row) = rows.next().map_err(sql_error)? {
+        let mut hash = HashMap::with_capacity(columns.len());
+        for (i, name) in columns.iter().enumerate() {
+            let value = row.get_ref(i).map_err(sql_error)?;
+            hash.insert(Object::String(name.clone().into()), from_sql_value(value));
+        }
+        result.push(Object::Hash(Box::new(hash)));
+    }
+
+    Ok(Object::Array(Box::new(result)))
+}
+
+/// Runs `sql`. Without parameters the string may hold several statements;
+/// with parameters it must be a single statement. Returns the number of rows
+/// changed by the last statement.
+fn exec(conn: &Connection, sql: &str, params: Option<&Object>) -> Result<Object, RuntimeError> {
+    match params {
+        None => conn.execute_batch(sql).map_err(sql_error)?,
+        Some(params) => {
+            let params = Params::from_arg(Some(params))?;
+            let mut stmt = conn.prepare(sql).map_err(sql_error)?;
+            params.bind(&mut stmt)?;
+            stmt.raw_execute().map_err(sql_error)?;
+        }
+    }
+    Ok(Object::Integer(conn.changes() as i64))
+}
+
+fn sql_arg(args: &[Object], method_name: &str) -> Result<String, RuntimeError> {
+    match args.first() {
+        Some(Object::String(sql)) => Ok(sql.to_string()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::InvalidArguments(format!("{}() expects a SQL string", method_name))),
+    }
+}
+
+/// Method calls on a database returned by `sqlite.open`.
+pub(crate) fn call_sqlite_method(
+    handle: &Arc<Mutex<SqliteHandle>>,
+    method_name: &str,
+    args: Vec<Object>,
+) -> Result<Object, RuntimeError> {
+    let mut handle = handle.lock().unwrap();
+
+    if method_name == "close" {
+        handle.conn = None;
+        return Ok(Object::Null);
+    }
+    if method_name == "is_closed" {
+        return Ok(Object::Boolean(handle.conn.is_none()));
+    }
+
+    let path = handle.path.clone();
+    let conn = handle
+        .conn
+        .as_ref()
+        .ok_or_else(|| RuntimeError::InvalidOperation(format!("database {} is closed", path)))?;
+
+    match method_name {
+        "exec" => exec(conn, &sql_arg(&args, method_name)?, args.get(1)),
+        "query" => query(conn, &sql_arg(&args, method_name)?, Params::from_arg(args.get(1))?),
+        "begin" => exec(conn, "BEGIN", None),
+        "commit" => exec(conn, "COMMIT", None),
+        "rollback" => exec(conn, "ROLLBACK", None),
+        "in_transaction" => Ok(Object::Boolean(!conn.is_autocommit())),
+        "last_insert_id" => Ok(Object::Integer(conn.last_insert_rowid())),
+        _ => Err(RuntimeError::InvalidOperation(format!("database has no method '{}'", method_name))),
+    }
+}
@@ -1,5 +1,9 @@
+use crate::vm::limits::{self, Alloc};
 use crate::vm::obj::Object;
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub(crate) fn string_join(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match (&args[0], &args[1]) {
@@ -7,7 +11,7 @@ pub(crate) fn string_join(args: Vec<Object>) -> Result<Object, RuntimeError> {
             let strings: Result<Vec<String>, RuntimeError> = arr
                 .iter()
                 .map(|obj| match obj {
-                    Object::String(s) => Ok(s.clone()),
+                    Object::String(s) => Ok(s.to_string()),
                     o => Err(RuntimeError::TypeMismatch {
                         expected: "string".to_string(),
                         got: o.type_name(),
@@ -16,7 +20,7 @@ pub(crate) fn string_join(args: Vec<Object>) -> Result<Object, RuntimeError> {
                 .collect();
 
             match strings {
-                Ok(strs) => Ok(Object::String(strs.join(separator))),
+                Ok(strs) => Ok(Object::String(strs.join(separator).into())),
                 Err(e) => Err(e),
             }
         }
@@ -32,7 +36,7 @@ pub(crate) fn string_reverse(args: Vec<Object>) -> Result<Object, RuntimeError>
         Some(Object::String(s)) => {
             let mut chars: Vec<char> = s.chars().collect();
             chars.reverse();
-            Ok(Object::String(chars.into_iter().collect()))
+            Ok(Object::String(chars.into_iter().collect::<String>().into()))
         }
         Some(o) => Err(RuntimeError::TypeMismatch {
             expected: "string".to_string(),
@@ -54,7 +58,9 @@ pub(crate) fn string_repeat(args: Vec<Object>) -> Result<Object, RuntimeError> {
                     "repeat count must be non-negative".to_string(),
                 ));
             }
-            Ok(Object::String(s.repeat(*n as usize)))
+            limits::reserve(Alloc::String(s.len().saturating_mul(*n as usize)))
+                .map_err(RuntimeError::ResourceLimitExceeded)?;
+            Ok(Object::String(s.repeat(*n as usize).into()))
         }
         _ => Err(RuntimeError::TypeMismatch {
             expected: "string, integer".to_string(),
@@ -66,7 +72,7 @@ pub(crate) fn string_repeat(args: Vec<Object>) -> Result<Object, RuntimeError> {
 pub(crate) fn string_chars(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match args.first() {
         Some(Object::String(s)) => {
-            let chars: Vec<Object> = s.chars().map(|c| Object::String(c.to_string())).collect();
+            let chars: Vec<Object> = s.chars().map(|c| Object::String(c.to_string().into())).collect();
             Ok(Object::Array(Box::new(chars)))
         }
         Some(o) => Err(RuntimeError::TypeMismatch {
@@ -79,4 +85,138 @@ pub(crate) fn string_chars(args: Vec<Object>) -> Result<Object, RuntimeError> {
             got: 0,
         }),
     }
-}
\ No newline at end of file
+}
+fn string_arg(args: &[Object]) -> Result<&str, RuntimeError> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: 1,
+            max: 1,
+            got: 0,
+        }),
+    }
+}
+
+/// Splits an identifier-like string into lowercase words. Separators are any
+/// non-alphanumeric characters plus camelCase humps, so `parseHTTPResponse2`
+/// becomes `parse`, `http`, `response2`.
+fn split_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+pub(crate) fn string_to_snake_case(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::String(split_words(string_arg(&args)?).join("_").into()))
+}
+
+pub(crate) fn string_to_kebab_case(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::String(split_words(string_arg(&args)?).join("-").into()))
+}
+
+pub(crate) fn string_to_camel_case(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let words = split_words(string_arg(&args)?);
+    let mut out = String::new();
+
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 {
+            out.push_str(word);
+            continue;
+        }
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    Ok(Object::String(out.into()))
+}
+
+pub(crate) fn string_slugify(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let s = string_arg(&args)?;
+    let mut slug = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if c != '\'' && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    Ok(Object::String(slug.trim_end_matches('-').to_string().into()))
+}
+
+pub(crate) fn string_graphemes(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let graphemes: Vec<Object> = string_arg(&args)?
+        .graphemes(true)
+        .map(|g| Object::String(g.to_string().into()))
+        .collect();
+    Ok(Object::Array(Box::new(graphemes)))
+}
+
+/// Number of Unicode scalar values, unlike `len()` which counts bytes.
+pub(crate) fn string_char_count(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Integer(string_arg(&args)?.chars().count() as i64))
+}
+
+pub(crate) fn string_normalize(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let s = string_arg(&args)?;
+    let form = match args.get(1) {
+        Some(Object::String(form)) => form.to_ascii_uppercase(),
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => "NFC".to_string(),
+    };
+
+    let normalized: String = match form.as_str() {
+        "NFC" => s.nfc().collect(),
+        "NFD" => s.nfd().collect(),
+        "NFKC" => s.nfkc().collect(),
+        "NFKD" => s.nfkd().collect(),
+        _ => {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "unknown normalization form '{}', expected NFC, NFD, NFKC or NFKD",
+                form
+            )));
+        }
+    };
+    Ok(Object::String(normalized.into()))
+}
+
+/// Display width in terminal columns; wide CJK characters and most emoji
+/// count as two.
+pub(crate) fn string_width(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Integer(string_arg(&args)?.width() as i64))
+}
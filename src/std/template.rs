@@ -0,0 +1,439 @@
+use crate::vm::obj::Object;
+use crate::vm::ops::arithmetic::is_truthy;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+// Templates use `{{ path | filter }}` for output, `{% if %}`/`{% elif %}`/
+// `{% else %}`/`{% endif %}` and `{% for x in items %}`/`{% endfor %}` for
+// control flow, and `{# ... #}` for comments. A block tag that sits alone on
+// its line removes the whole line, so templates can be indented freely.
+
+enum Node {
+    Text(String),
+    Output { expr: Expr, filters: Vec<String>, line: usize },
+    If { branches: Vec<(Condition, Vec<Node>)>, otherwise: Vec<Node> },
+    For { key: Option<String>, value: String, iterable: Expr, body: Vec<Node>, otherwise: Vec<Node>, line: usize },
+}
+
+enum Expr {
+    Path(Vec<String>),
+    Literal(Object),
+}
+
+enum Condition {
+    Truthy(Expr),
+    Not(Box<Condition>),
+    Equals(Expr, Expr, bool),
+}
+
+fn template_error(line: usize, message: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::InvalidArguments(format!("template error on line {}: {}", line, message))
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Output(&'a str, usize),
+    Block(&'a str, usize),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, RuntimeError> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+    // Whether `rest` starts at the beginning of a line.
+    let mut at_line_start = true;
+
+    while let Some(start) = rest.find('{') {
+        let close = match rest[start..].get(..2) {
+            Some("{{") => "}}",
+            Some("{%") => "%}",
+            Some("{#") => "#}",
+            _ => {
+                tokens.push(Token::Text(&rest[..start + 1]));
+                line += rest[..start + 1].matches('\n').count();
+                rest = &rest[start + 1..];
+                at_line_start = false;
+                continue;
+            }
+        };
+
+        let mut text = &rest[..start];
+        line += text.matches('\n').count();
+        let end = rest[start + 2..]
+            .find(close)
+            .ok_or_else(|| template_error(line, format!("unclosed '{}'", &rest[start..start + 2])))?
+            + start
+            + 2;
+        let inner = &rest[start + 2..end];
+        let mut after = &rest[end + 2..];
+        let tag_line = line;
+        line += inner.matches('\n').count();
+
+        let indent_start = text.rfind('\n').map(|i| i + 1);
+        let standalone = close != "}}"
+            && (indent_start.is_some() || at_line_start)
+            && text[indent_start.unwrap_or(0)..].trim().is_empty()
+            && after[..after.find('\n').unwrap_or(after.len())].trim().is_empty();
+        at_line_start = false;
+
+        if standalone {
+            // Drop the indentation before and the newline after the tag.
+            text = &text[..indent_start.unwrap_or(0)];
+            if let Some(newline) = after.find('\n') {
+                after = &after[newline + 1..];
+                line += 1;
+                at_line_start = true;
+            } else {
+                after = "";
+            }
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+        match close {
+            "}}" => tokens.push(Token::Output(inner.trim(), tag_line)),
+            "%}" => tokens.push(Token::Block(inner.trim(), tag_line)),
+            _ => {}
+        }
+        rest = after;
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+    Ok(tokens)
+}
+
+fn parse_expr(source: &str, line: usize) -> Result<Expr, RuntimeError> {
+    let source = source.trim();
+    if let Some(s) = source.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Ok(Expr::Literal(Object::String(s.to_string().into())));
+    }
+    match source {
+        "true" => return Ok(Expr::Literal(Object::Boolean(true))),
+        "false" => return Ok(Expr::Literal(Object::Boolean(false))),
+        "null" => return Ok(Expr::Literal(Object::Null)),
+        _ => {}
+    }
+    if let Ok(i) = source.parse::<i64>() {
+        return Ok(Expr::Literal(Object::Integer(i)));
+    }
+
+    let segments: Vec<String> = source.split('.').map(str::to_string).collect();
+    let valid = segments
+        .iter()
+        .all(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_'));
+    if !valid {
+        return Err(template_error(line, format!("invalid expression '{}'", source)));
+    }
+    Ok(Expr::Path(segments))
+}
+
+fn parse_condition(source: &str, line: usize) -> Result<Condition, RuntimeError> {
+    let source = source.trim();
+    if let Some(rest) = source.strip_prefix("not ") {
+        return Ok(Condition::Not(Box::new(parse_condition(rest, line)?)));
+    }
+    for (op, equal) in [("==", true), ("!=", false)] {
+        if let Some((left, right)) = source.split_once(op) {
+            return Ok(Condition::Equals(parse_expr(left, line)?, parse_expr(right, line)?, equal));
+        }
+    }
+    Ok(Condition::Truthy(parse_expr(source, line)?))
+}
+
+/// Nodes parsed so far plus the tag that ended them, if any.
+type Parsed<'a> = (Vec<Node>, Option<(&'a str, usize)>);
+
+struct Parser<'a> {
+    tokens: std::vec::IntoIter<Token<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses nodes until one of `terminators` is reached and returns the
+    /// terminating tag (or `None` at end of input).
+    fn parse_nodes(&mut self, terminators: &[&str]) -> Result<Parsed<'a>, RuntimeError> {
+        let mut nodes = Vec::new();
+
+        while let Some(token) = self.tokens.next() {
+            match token {
+                Token::Text(text) => nodes.push(Node::Text(text.to_string())),
+                Token::Output(inner, line) => {
+                    let mut parts = inner.split('|');
+                    let expr = parse_expr(parts.next().unwrap_or(""), line)?;
+                    let filters = parts.map(|f| f.trim().to_string()).collect();
+                    nodes.push(Node::Output { expr, filters, line });
+                }
+                Token::Block(tag, line) => {
+                    let keyword = tag.split_whitespace().next().unwrap_or("");
+                    if terminators.contains(&keyword) {
+                        return Ok((nodes, Some((tag, line))));
+                    }
+                    match keyword {
+                        "if" => nodes.push(self.parse_if(&tag[2..], line)?),
+                        "for" => nodes.push(self.parse_for(&tag[3..], line)?),
+                        _ => return Err(template_error(line, format!("unexpected '{{% {} %}}'", tag))),
+                    }
+                }
+            }
+        }
+
+        Ok((nodes, None))
+    }
+
+    fn parse_if(&mut self, condition: &str, line: usize) -> Result<Node, RuntimeError> {
+        let mut branches = Vec::new();
+        let mut condition = parse_condition(condition, line)?;
+
+        loop {
+            let (body, end) = self.parse_nodes(&["elif", "else", "endif"])?;
+            branches.push((condition, body));
+            match end {
+                Some((tag, line)) if tag.starts_with("elif") => condition = parse_condition(&tag[4..], line)?,
+                Some((tag, _)) if tag.starts_with("else") => {
+                    let (otherwise, end) = self.parse_nodes(&["endif"])?;
+                    if end.is_none() {
+                        return Err(template_error(line, "missing '{% endif %}'"));
+                    }
+                    return Ok(Node::If { branches, otherwise });
+                }
+                Some(_) => return Ok(Node::If { branches, otherwise: Vec::new() }),
+                None => return Err(template_error(line, "missing '{% endif %}'")),
+            }
+        }
+    }
+
+    fn parse_for(&mut self, header: &str, line: usize) -> Result<Node, RuntimeError> {
+        let (vars, iterable) = header
+            .split_once(" in ")
+            .ok_or_else(|| template_error(line, "expected '{% for item in items %}'"))?;
+        let mut vars = vars.split(',').map(|v| v.trim().to_string());
+        let (key, value) = match (vars.next(), vars.next(), vars.next()) {
+            (Some(value), None, None) => (None, value),
+            (Some(key), Some(value), None) => (Some(key), value),
+            _ => return Err(template_error(line, "for loops take one or two loop variables")),
+        };
+        let iterable = parse_expr(iterable, line)?;
+
+        let (body, end) = self.parse_nodes(&["else", "endfor"])?;
+        let otherwise = match end {
+            Some((tag, _)) if tag.starts_with("else") => match self.parse_nodes(&["endfor"])? {
+                (otherwise, Some(_)) => otherwise,
+                (_, None) => return Err(template_error(line, "missing '{% endfor %}'")),
+            },
+            Some(_) => Vec::new(),
+            None => return Err(template_error(line, "missing '{% endfor %}'")),
+        };
+
+        Ok(Node::For { key, value, iterable, body, otherwise, line })
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Node>, RuntimeError> {
+    let mut parser = Parser { tokens: tokenize(source)?.into_iter() };
+    match parser.parse_nodes(&[])? {
+        (nodes, None) => Ok(nodes),
+        (_, Some((tag, line))) => Err(template_error(line, format!("unexpected '{{% {} %}}'", tag))),
+    }
+}
+
+fn get_field(value: &Object, name: &str) -> Option<Object> {
+    match value {
+        Object::Hash(hash) => hash.get(&Object::String(name.to_string().into())).cloned(),
+        Object::Struct(s) => s.fields.get(name).cloned(),
+        Object::OrderedMap(map) => map.lock().unwrap().get(&Object::String(name.to_string().into())).cloned(),
+        Object::Array(items) => name.parse::<usize>().ok().and_then(|i| items.get(i).cloned()),
+        _ => None,
+    }
+}
+
+struct Renderer<'a> {
+    data: &'a Object,
+    scopes: Vec<(String, Object)>,
+}
+
+impl Renderer<'_> {
+    fn lookup(&self, expr: &Expr) -> Option<Object> {
+        let path = match expr {
+            Expr::Literal(value) => return Some(value.clone()),
+            Expr::Path(path) => path,
+        };
+        let root = match self.scopes.iter().rev().find(|(name, _)| *name == path[0]) {
+            Some((_, value)) => value.clone(),
+            None => get_field(self.data, &path[0])?,
+        };
+        path[1..].iter().try_fold(root, |value, segment| get_field(&value, segment))
+    }
+
+    fn test(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Truthy(expr) => self.lookup(expr).is_some_and(|v| is_truthy(&v)),
+            Condition::Not(inner) => !self.test(inner),
+            Condition::Equals(left, right, equal) => {
+                let left = self.lookup(left).unwrap_or(Object::Null);
+                let right = self.lookup(right).unwrap_or(Object::Null);
+                (left == right) == *equal
+            }
+        }
+    }
+
+    fn render(&mut self, nodes: &[Node], out: &mut String) -> Result<(), RuntimeError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Output { expr, filters, line } => {
+                    let value = self.lookup(expr).ok_or_else(|| {
+                        let Expr::Path(path) = expr else { unreachable!() };
+                        template_error(*line, format!("'{}' is not defined", path.join(".")))
+                    })?;
+                    let mut text = match &value {
+                        Object::Null => String::new(),
+                        Object::String(s) => s.to_string(),
+                        other => other.to_string(),
+                    };
+                    for filter in filters {
+                        text = apply_filter(filter, text, &value, *line)?;
+                    }
+                    out.push_str(&text);
+                }
+                Node::If { branches, otherwise } => {
+                    let body = branches
+                        .iter()
+                        .find(|(condition, _)| self.test(condition))
+                        .map(|(_, body)| body)
+                        .unwrap_or(otherwise);
+                    self.render(body, out)?;
+                }
+                Node::For { key, value, iterable, body, otherwise, line } => {
+                    let entries = self.loop_entries(iterable, *line)?;
+                    if entries.is_empty() {
+                        self.render(otherwise, out)?;
+                        continue;
+                    }
+                    let length = entries.len();
+                    for (index, (k, v)) in entries.into_iter().enumerate() {
+                        let depth = self.scopes.len();
+                        self.scopes.push(("loop".to_string(), loop_info(index, length)));
+                        match key {
+                            Some(key) => {
+                                self.scopes.push((key.clone(), k));
+                                self.scopes.push((value.clone(), v));
+                            }
+                            None => self.scopes.push((value.clone(), v)),
+                        }
+                        let result = self.render(body, out);
+                        self.scopes.truncate(depth);
+                        result?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Arrays yield `(index, item)`; hashes yield `(key, value)` in key order
+    /// and ordered maps in insertion order. With a single loop variable the
+    /// second element is bound (items for arrays, keys for maps).
+    fn loop_entries(&self, iterable: &Expr, line: usize) -> Result<Vec<(Object, Object)>, RuntimeError> {
+        match self.lookup(iterable) {
+            None | Some(Object::Null) => Ok(Vec::new()),
+            Some(Object::Array(items)) => {
+                Ok(items.into_iter().enumerate().map(|(i, item)| (Object::Integer(i as i64), item)).collect())
+            }
+            Some(Object::Hash(hash)) => {
+                let mut entries: Vec<(Object, Object)> = hash.into_iter().collect();
+                entries.sort_by_cached_key(|(k, _)| k.to_string());
+                Ok(entries)
+            }
+            Some(Object::OrderedMap(map)) => Ok(map.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()),
+            Some(other) => Err(template_error(line, format!("cannot loop over {}", other.type_name()))),
+        }
+    }
+}
+
+#[allow(clippy::mutable_key_type)]
+fn loop_info(index: usize, length: usize) -> Object {
+    use ahash::HashMapExt;
+
+    let mut info = crate::vm::obj::HashMap::with_capacity(5);
+    for (name, value) in [
+        ("index", Object::Integer(index as i64 + 1)),
+        ("index0", Object::Integer(index as i64)),
+        ("first", Object::Boolean(index == 0)),
+        ("last", Object::Boolean(index + 1 == length)),
+        ("length", Object::Integer(length as i64)),
+    ] {
+        info.insert(Object::String(name.to_string().into()), value);
+    }
+    Object::Hash(Box::new(info))
+}
+
+fn apply_filter(filter: &str, text: String, value: &Object, line: usize) -> Result<String, RuntimeError> {
+    match filter {
+        "upper" => Ok(text.to_uppercase()),
+        "lower" => Ok(text.to_lowercase()),
+        "trim" => Ok(text.trim().to_string()),
+        "escape" => match crate::std::encoding::encoding_html_escape(vec![Object::String(text.into())])? {
+            Object::String(s) => Ok(s.into()),
+            _ => unreachable!(),
+        },
+        "len" => Ok(match value {
+            Object::Array(items) => items.len(),
+            Object::Hash(hash) => hash.len(),
+            _ => text.chars().count(),
+        }
+        .to_string()),
+        _ => Err(template_error(line, format!("unknown filter '{}'", filter))),
+    }
+}
+
+/// `template.render(tmpl, data)` renders `tmpl` with names looked up in the
+/// `data` hash (or struct). Outputting an undefined name is an error, while
+/// conditions simply treat it as false.
+pub(crate) fn template_render(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let source = match args.first() {
+        Some(Object::String(s)) => s,
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+    render(source, args.get(1).unwrap_or(&Object::Null))
+}
+
+/// `template.render_file(path, data)` reads the template from `path`.
+pub(crate) fn template_render_file(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let path = match args.first() {
+        Some(Object::String(s)) => s,
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| RuntimeError::InvalidOperation(format!("Could not read template {}: {}", path, e)))?;
+    render(&source, args.get(1).unwrap_or(&Object::Null))
+}
+
+fn render(source: &str, data: &Object) -> Result<Object, RuntimeError> {
+    match data {
+        Object::Null | Object::Hash(_) | Object::Struct(_) | Object::OrderedMap(_) => {}
+        o => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "hash or struct".to_string(),
+                got: o.type_name(),
+            });
+        }
+    }
+
+    let nodes = parse(source)?;
+    let mut out = String::with_capacity(source.len());
+    Renderer { data, scopes: Vec::new() }.render(&nodes, &mut out)?;
+    Ok(Object::String(out.into()))
+}
@@ -0,0 +1,45 @@
+//! Assertions for `gl test`. A failed assertion stops the running test with
+//! [`RuntimeError::AssertionFailed`].
+
+use crate::vm::obj::Object;
+use crate::vm::ops::arithmetic::is_truthy;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// The optional trailing message argument, prefixed to the failure.
+fn with_message(args: &[Object], index: usize, failure: String) -> RuntimeError {
+    match args.get(index) {
+        Some(Object::String(message)) => RuntimeError::AssertionFailed(format!("{}: {}", message, failure)),
+        Some(other) => RuntimeError::AssertionFailed(format!("{}: {}", other, failure)),
+        None => RuntimeError::AssertionFailed(failure),
+    }
+}
+
+pub(crate) fn test_assert(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if is_truthy(&args[0]) {
+        return Ok(Object::Null);
+    }
+    Err(with_message(&args, 1, format!("expected a true value, got {}", args[0].inspect())))
+}
+
+pub(crate) fn test_assert_eq(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if args[0].deep_equals(&args[1]) {
+        return Ok(Object::Null);
+    }
+    Err(with_message(&args, 2, format!("expected {}, got {}", args[1].inspect(), args[0].inspect())))
+}
+
+pub(crate) fn test_assert_ne(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    if !args[0].deep_equals(&args[1]) {
+        return Ok(Object::Null);
+    }
+    Err(with_message(&args, 2, format!("expected a value other than {}", args[1].inspect())))
+}
+
+pub(crate) fn test_fail(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let message = match args.first() {
+        Some(Object::String(message)) => message.to_string(),
+        Some(other) => other.to_string(),
+        None => "test failed".to_string(),
+    };
+    Err(RuntimeError::AssertionFailed(message))
+}
@@ -1,11 +1,12 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 use num_bigint::BigInt;
 use num_traits::{FromPrimitive, ToPrimitive};
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::time::{sleep, sleep_until};
 
-use crate::vm::obj::Object;
+use crate::vm::obj::{HashMap, Object};
 use crate::vm::runtime::runtime_errors::RuntimeError;
 
 pub fn time_now(_: Vec<Object>) -> Result<Object, RuntimeError> {
@@ -18,21 +19,133 @@ pub fn time_now(_: Vec<Object>) -> Result<Object, RuntimeError> {
 }
 
 pub async fn async_time_sleep(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let ms = millis_arg("sleep", args.first())?;
+    sleep(Duration::from_millis(ms)).await;
+    Ok(Object::Null)
+}
+
+pub fn time_sleep_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args = args;
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_time_sleep(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+/// Reference point for stopwatches. Stopwatch values are nanoseconds since
+/// this instant, which keeps them monotonic and plain integers.
+static MONOTONIC_BASE: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+struct IntervalState {
+    period: Duration,
+    next: Instant,
+    ticks: i64,
+}
+
+static INTERVALS: LazyLock<Mutex<HashMap<i64, IntervalState>>> =
+    LazyLock::new(|| Mutex::new(HashMap::default()));
+static NEXT_INTERVAL_ID: AtomicI64 = AtomicI64::new(1);
+
+fn millis_arg(name: &str, obj: Option<&Object>) -> Result<u64, RuntimeError> {
+    match obj {
+        Some(Object::Integer(i)) if *i >= 0 => Ok(*i as u64),
+        Some(Object::BigInteger(bi)) => Ok(bi.to_u64().unwrap_or(u64::MAX)),
+        Some(Object::Float(f)) if *f >= 0.0 => Ok(f.round() as u64),
+        Some(Object::Integer(_)) | Some(Object::Float(_)) => Err(RuntimeError::InvalidArguments(
+            format!("{}() expects a non-negative number of milliseconds", name),
+        )),
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "integer".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
+
+pub fn time_stopwatch(_: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Integer(MONOTONIC_BASE.elapsed().as_nanos() as i64))
+}
+
+/// Milliseconds (as a float) since the given `stopwatch()` value.
+pub fn time_elapsed(args: Vec<Object>) -> Result<Object, RuntimeError> {
     match args.first() {
-        Some(Object::Integer(i)) => {
-            sleep(Duration::from_millis(*i as u64)).await;
-            Ok(Object::Null)
-        }
-        Some(Object::BigInteger(bi)) => {
-            sleep(Duration::from_millis(bi.to_u64().unwrap_or(u64::MAX))).await;
-            Ok(Object::Null)
+        Some(Object::Integer(start)) => {
+            let now = MONOTONIC_BASE.elapsed().as_nanos() as i64;
+            Ok(Object::Float((now - start) as f64 / 1_000_000.0))
         }
-        Some(o) => Err(RuntimeError::TypeMismatch { expected: "integer".to_string(), got: o.type_name() }),
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "stopwatch".to_string(), got: o.type_name() }),
         None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
     }
 }
 
-pub fn time_sleep_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
-    let args = args;
-    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_time_sleep(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
-}
\ No newline at end of file
+/// Converts an amount in `unit` ("ms", "s", "m" or "h") to milliseconds,
+/// the unit `sleep` and `interval` take.
+pub fn time_duration(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let amount = match args.first() {
+        Some(Object::Integer(i)) => *i as f64,
+        Some(Object::Float(f)) => *f,
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "number".to_string(), got: o.type_name() }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 2, got: 0 }),
+    };
+    let factor = match args.get(1) {
+        None => 1.0,
+        Some(Object::String(unit)) => match unit.as_str() {
+            "ms" => 1.0,
+            "s" => 1_000.0,
+            "m" => 60_000.0,
+            "h" => 3_600_000.0,
+            other => {
+                return Err(RuntimeError::InvalidArguments(format!(
+                    "duration() unknown unit '{}', expected ms, s, m or h",
+                    other
+                )))
+            }
+        },
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "string".to_string(), got: o.type_name() }),
+    };
+    Ok(Object::Integer((amount * factor).round() as i64))
+}
+
+/// Registers a ticker firing every `ms` milliseconds and returns its id.
+/// Await `tick(id)` to wait for the next tick.
+pub fn time_interval(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let ms = millis_arg("interval", args.first())?;
+    if ms == 0 {
+        return Err(RuntimeError::InvalidArguments("interval() period must be positive".to_string()));
+    }
+    let period = Duration::from_millis(ms);
+    let id = NEXT_INTERVAL_ID.fetch_add(1, Ordering::Relaxed);
+    INTERVALS.lock().unwrap().insert(id, IntervalState { period, next: Instant::now() + period, ticks: 0 });
+    Ok(Object::Integer(id))
+}
+
+pub async fn async_time_tick(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let id = match args.first() {
+        Some(Object::Integer(id)) => *id,
+        Some(o) => return Err(RuntimeError::TypeMismatch { expected: "interval".to_string(), got: o.type_name() }),
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    };
+
+    // Reserve the deadline under the lock, then sleep without holding it.
+    let (deadline, tick) = {
+        let mut intervals = INTERVALS.lock().unwrap();
+        let state = intervals.get_mut(&id).ok_or_else(|| {
+            RuntimeError::InvalidArguments(format!("tick() unknown or stopped interval {}", id))
+        })?;
+        let deadline = state.next;
+        let now = Instant::now();
+        // Skip missed ticks instead of firing them in a burst
+        state.next = if deadline + state.period <= now { now + state.period } else { deadline + state.period };
+        state.ticks += 1;
+        (deadline, state.ticks)
+    };
+
+    sleep_until(deadline.into()).await;
+    Ok(Object::Integer(tick))
+}
+
+pub fn time_tick_wrapper(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    Ok(Object::Future(Arc::new(Mutex::new(Some(Box::pin(async_time_tick(args)) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>>)))))
+}
+
+pub fn time_stop_interval(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        Some(Object::Integer(id)) => Ok(Object::Boolean(INTERVALS.lock().unwrap().remove(id).is_some())),
+        Some(o) => Err(RuntimeError::TypeMismatch { expected: "interval".to_string(), got: o.type_name() }),
+        None => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got: 0 }),
+    }
+}
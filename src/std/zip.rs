@@ -0,0 +1,160 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ::zip::write::SimpleFileOptions;
+use ::zip::{CompressionMethod, ZipArchive, ZipWriter};
+use ahash::HashMapExt;
+
+use crate::std::io::{path_string, walk_paths};
+use crate::vm::obj::{HashMap, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+fn zip_error(path: &str, e: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("zip error for {}: {}", path, e))
+}
+
+fn string_arg(args: &[Object], index: usize) -> Result<String, RuntimeError> {
+    match args.get(index) {
+        Some(Object::String(s)) => Ok(s.to_string()),
+        Some(o) => Err(RuntimeError::TypeMismatch {
+            expected: "string".to_string(),
+            got: o.type_name(),
+        }),
+        None => Err(RuntimeError::WrongNumberOfArguments {
+            min: index + 1,
+            max: index + 1,
+            got: args.len(),
+        }),
+    }
+}
+
+fn open_archive(path: &str) -> Result<ZipArchive<File>, RuntimeError> {
+    let file = File::open(path).map_err(|e| zip_error(path, e))?;
+    ZipArchive::new(file).map_err(|e| zip_error(path, e))
+}
+
+/// Collects `(source, entry name)` pairs. Files are stored under their file
+/// name and directories under their own name, so `create("a.zip", ["src"])`
+/// produces `src/main.g`, `src/lib/util.g`, ...
+fn collect_entries(sources: &[Object]) -> Result<Vec<(PathBuf, String)>, RuntimeError> {
+    let mut entries = Vec::new();
+
+    for source in sources {
+        let source = match source {
+            Object::String(s) => Path::new(s),
+            o => {
+                return Err(RuntimeError::TypeMismatch {
+                    expected: "string".to_string(),
+                    got: o.type_name(),
+                });
+            }
+        };
+        let base = source.parent().unwrap_or(Path::new(""));
+        let entry_name = |path: &Path| path_string(path.strip_prefix(base).unwrap_or(path));
+
+        if source.is_dir() {
+            entries.push((source.to_path_buf(), format!("{}/", entry_name(source))));
+            let mut paths = Vec::new();
+            walk_paths(source, &mut paths).map_err(|e| zip_error(&path_string(source), e))?;
+            for path in paths {
+                let name = if path.is_dir() { format!("{}/", entry_name(&path)) } else { entry_name(&path) };
+                entries.push((path, name));
+            }
+        } else if source.is_file() {
+            entries.push((source.to_path_buf(), entry_name(source)));
+        } else {
+            return Err(RuntimeError::InvalidOperation(format!("'{}' does not exist", source.display())));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// `zip.create(archive, paths)` writes the given files and directories
+/// (recursively) into a deflate-compressed archive and returns the number of
+/// entries written.
+pub(crate) fn zip_create(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let archive = string_arg(&args, 0)?;
+    let entries = match args.get(1) {
+        Some(Object::Array(sources)) => collect_entries(sources)?,
+        Some(Object::String(_)) => collect_entries(&args[1..2])?,
+        Some(o) => {
+            return Err(RuntimeError::TypeMismatch {
+                expected: "array or string".to_string(),
+                got: o.type_name(),
+            });
+        }
+        None => return Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }),
+    };
+
+    let file = File::create(&archive).map_err(|e| zip_error(&archive, e))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (path, name) in &entries {
+        if name.ends_with('/') {
+            writer.add_directory(name.as_str(), options).map_err(|e| zip_error(&archive, e))?;
+            continue;
+        }
+        let data = std::fs::read(path).map_err(|e| zip_error(&path_string(path), e))?;
+        writer.start_file(name.as_str(), options).map_err(|e| zip_error(&archive, e))?;
+        writer.write_all(&data).map_err(|e| zip_error(&archive, e))?;
+    }
+
+    writer.finish().map_err(|e| zip_error(&archive, e))?;
+    Ok(Object::Integer(entries.len() as i64))
+}
+
+/// `zip.extract(archive, dest)` unpacks every entry below `dest` and returns
+/// the extracted file paths. Entries that would escape `dest` are rejected.
+pub(crate) fn zip_extract(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let archive_path = string_arg(&args, 0)?;
+    let dest = PathBuf::from(string_arg(&args, 1)?);
+    let mut archive = open_archive(&archive_path)?;
+    let mut extracted = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| zip_error(&archive_path, e))?;
+        let relative = entry.enclosed_name().ok_or_else(|| {
+            RuntimeError::InvalidOperation(format!("zip entry '{}' has an unsafe path", entry.name()))
+        })?;
+        let target = dest.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| zip_error(&path_string(&target), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| zip_error(&path_string(parent), e))?;
+        }
+
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data).map_err(|e| zip_error(&archive_path, e))?;
+        std::fs::write(&target, data).map_err(|e| zip_error(&path_string(&target), e))?;
+        extracted.push(Object::String(path_string(&target).into()));
+    }
+
+    Ok(Object::Array(Box::new(extracted)))
+}
+
+/// `zip.list(archive)` returns one hash per entry with `name`, `size`,
+/// `compressed_size` and `is_dir`.
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn zip_list(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let archive_path = string_arg(&args, 0)?;
+    let mut archive = open_archive(&archive_path)?;
+    let mut items = Vec::with_capacity(archive.len());
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(|e| zip_error(&archive_path, e))?;
+        let mut hash = HashMap::with_capacity(4);
+        hash.insert(Object::String("name".into()), Object::String(entry.name().to_string().into()));
+        hash.insert(Object::String("size".into()), Object::Integer(entry.size() as i64));
+        hash.insert(Object::String("compressed_size".into()), Object::Integer(entry.compressed_size() as i64));
+        hash.insert(Object::String("is_dir".into()), Object::Boolean(entry.is_dir()));
+        items.push(Object::Hash(Box::new(hash)));
+    }
+
+    Ok(Object::Array(Box::new(items)))
+}
@@ -0,0 +1,464 @@
+use crate::vm::limits::ResourceLimits;
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+use crate::{Engine, LangError, NativeType};
+
+#[tokio::test]
+async fn test_engine_keeps_state_between_calls() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("let total = 40;").await, Ok(Object::Null));
+    assert_eq!(engine.eval("fn add(a, b) { return a + b; }").await, Ok(Object::Null));
+    assert_eq!(engine.eval("add(total, 2)").await, Ok(Object::Integer(42)));
+
+    // A failed call leaves what earlier ones defined in place.
+    assert!(engine.eval("let x = 0; 1 / x;").await.is_err());
+    assert_eq!(engine.eval("total = total + 1; total").await, Ok(Object::Integer(41)));
+}
+
+#[tokio::test]
+async fn test_engine_errors() {
+    let mut engine = Engine::new();
+
+    let lexer = engine.eval("let s = \"abc;").await.unwrap_err();
+    assert!(matches!(lexer, LangError::Lexer(_)));
+    assert_eq!(lexer.location().map(|l| l.line), Some(1));
+
+    assert!(matches!(engine.eval("let = ;").await, Err(LangError::Parser(_))));
+    assert!(matches!(engine.eval("fn f() { return await g(); }").await, Err(LangError::Parser(_))));
+    assert!(matches!(engine.eval("missing + 1").await, Err(LangError::Runtime(_))));
+    assert_eq!(
+        engine.eval("throw \"boom\";").await,
+        Err(LangError::Runtime(RuntimeError::UncaughtException("boom".to_string())))
+    );
+    assert_eq!(engine.eval("exit(3);").await, Err(LangError::Runtime(RuntimeError::Exit(3))));
+}
+
+#[tokio::test]
+async fn test_engine_limits() {
+    let mut engine = Engine::new();
+    engine.set_limits(ResourceLimits { max_steps: Some(1_000), ..Default::default() });
+    let result = engine.eval("while (true) { }").await;
+    assert!(matches!(result, Err(LangError::Runtime(RuntimeError::ExecutionLimitExceeded(_)))), "got: {:?}", result);
+
+    // Each call gets the whole budget.
+    assert_eq!(engine.eval("let n = 0; for (let i = 0; i < 100; i = i + 1) { n = n + 1; } n").await, Ok(Object::Integer(100)));
+}
+
+#[tokio::test]
+async fn test_engine_nesting_limit() {
+    use crate::vm::runtime::runtime_errors::ParserError;
+
+    let nested = format!("let x = {}1{};", "(".repeat(50), ")".repeat(50));
+    let mut strict = Engine::new();
+    strict.set_max_nesting_depth(20);
+    let result = strict.eval(&nested).await;
+    assert!(
+        matches!(result, Err(LangError::Parser(ParserError::NestingTooDeep { limit: 20, .. }))),
+        "got: {:?}",
+        result
+    );
+
+    // The limit belongs to the engine it was set on.
+    assert_eq!(Engine::new().eval(&nested).await, Ok(Object::Null));
+}
+
+#[tokio::test]
+async fn test_engine_base_path() {
+    let dir = std::env::temp_dir().join(format!("gl_engine_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("helpers.g"), "let x = 1;\n").unwrap();
+
+    let mut engine = Engine::new();
+    let before = engine.eval("import helpers;").await;
+    engine.set_base_path(&dir);
+    let after = engine.eval("import helpers;").await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(before.is_err());
+    assert_eq!(after, Ok(Object::Null));
+}
+
+#[tokio::test]
+async fn test_engine_module_cache() {
+    use crate::vm::sandbox::{Capabilities, Capability};
+
+    let dir = std::env::temp_dir().join(format!("gl_engine_cache_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("deep.g"), format!("let x = {}1{};\n", "(".repeat(50), ")".repeat(50))).unwrap();
+
+    // Without the file system no cache entry is written.
+    let mut sandboxed = Engine::new();
+    sandboxed.set_base_path(&dir);
+    sandboxed.set_capabilities(Capabilities::all().deny(Capability::FileSystem));
+    let denied = sandboxed.eval("import deep;").await;
+    let wrote_denied = dir.join(".giulio-cache").exists();
+
+    let mut engine = Engine::new();
+    engine.set_base_path(&dir);
+    let cached = engine.eval("import deep;").await;
+    let wrote = dir.join(".giulio-cache").exists();
+
+    // An entry parsed under the default limit does not skip a stricter one.
+    let mut strict = Engine::new();
+    strict.set_base_path(&dir);
+    strict.set_max_nesting_depth(20);
+    let rejected = strict.eval("import deep;").await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(denied, Ok(Object::Null));
+    assert!(!wrote_denied);
+    assert_eq!(cached, Ok(Object::Null));
+    assert!(wrote);
+    assert!(matches!(rejected, Err(LangError::Runtime(RuntimeError::ModuleError { .. }))), "{:?}", rejected);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_engine_plugins() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Answers `initialize`, echoes the arguments of `echo` and fails the rest.
+    let plugin = r#"#!/bin/sh
+while read -r line; do
+  id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"method":"initialize"'*) echo '{"method":"log","params":["ready"]}'
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"functions\":[\"echo\",\"fail\"]}}" ;;
+    *'"method":"echo"'*) params=$(echo "$line" | sed 's/.*"params":\(.*\)}$/\1/')
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":$params}" ;;
+    *) echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"error\":{\"code\":-32000,\"message\":\"no such thing\"}}" ;;
+  esac
+done
+"#;
+    let dir = std::env::temp_dir().join(format!("gl_plugins_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("plugins")).unwrap();
+    let file = dir.join("plugins").join("tool");
+    std::fs::write(&file, plugin).unwrap();
+    std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut engine = Engine::new();
+    engine.set_base_path(&dir);
+    let echoed = engine.eval(r#"import plugin::tool; tool.echo(1, "two", [3.5, null], {"k": true})"#).await;
+    let failed = engine.eval("tool.fail()").await;
+    let missing = engine.eval("import plugin::nonexistent;").await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(echoed.map(|v| v.to_string()), Ok("[1, two, [3.5, null], {k : true}]".to_string()));
+    assert_eq!(
+        failed,
+        Err(LangError::Runtime(RuntimeError::InvalidOperation("plugin 'tool': no such thing".to_string())))
+    );
+    assert!(matches!(missing, Err(LangError::Runtime(RuntimeError::InvalidOperation(ref e))) if e.contains("gl-plugin-nonexistent")), "{:?}", missing);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_engine_plugin_timeout() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Answers `initialize` and then never answers again.
+    let plugin = r#"#!/bin/sh
+read -r line
+id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"functions\":[\"hang\"]}}"
+exec sleep 30
+"#;
+    let dir = std::env::temp_dir().join(format!("gl_plugin_timeout_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("plugins")).unwrap();
+    let file = dir.join("plugins").join("slow");
+    std::fs::write(&file, plugin).unwrap();
+    std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut engine = Engine::new();
+    engine.set_base_path(&dir);
+    engine.set_plugin_timeout(std::time::Duration::from_millis(200));
+    let started = std::time::Instant::now();
+    let hung = engine.eval("import plugin::slow; slow.hang()").await;
+    let elapsed = started.elapsed();
+    // The plugin was killed, so the next call finds it gone.
+    let again = engine.eval("slow.hang()").await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        hung,
+        Err(LangError::Runtime(RuntimeError::InvalidOperation("plugin 'slow': no answer to 'hang' within 200 ms, stopped it".to_string())))
+    );
+    assert!(elapsed < std::time::Duration::from_secs(10), "{:?}", elapsed);
+    assert!(matches!(again, Err(LangError::Runtime(RuntimeError::InvalidOperation(ref e))) if e.contains("exited")), "{:?}", again);
+}
+
+#[cfg(feature = "random")]
+#[tokio::test]
+async fn test_engine_random_seed_is_per_engine() {
+    let draws = "[random.float(), math.random(1000), random.choice([1, 2, 3, 4, 5])]";
+    let mut engine = Engine::new();
+    engine.eval("import std::random; import std::math; random.seed(7);").await.unwrap();
+    let first = engine.eval(draws).await.unwrap();
+
+    // Seeding and drawing in another engine leaves this one's sequence alone.
+    engine.eval("random.seed(7);").await.unwrap();
+    let mut other = Engine::new();
+    other.eval("import std::random; random.seed(99); random.float(); random.seed();").await.unwrap();
+    let again = engine.eval(draws).await.unwrap();
+
+    assert_eq!(first, again);
+}
+
+#[cfg(feature = "random")]
+#[tokio::test]
+async fn test_engine_shuffle_follows_seed() {
+    let shuffles = "import std::random; random.seed(42); [range(20).shuffle(), range(20).shuffle()]";
+    let first = Engine::new().eval(shuffles).await.unwrap();
+    let second = Engine::new().eval(shuffles).await.unwrap();
+    assert_eq!(first, second);
+
+    let Object::Array(runs) = first else { panic!("expected an array, got {}", first) };
+    assert_ne!(runs[0], runs[1], "the second shuffle should go on from the first");
+}
+
+#[tokio::test]
+async fn test_engine_capabilities() {
+    use crate::vm::sandbox::{Capabilities, Capability};
+
+    let mut engine = Engine::new();
+    engine.set_capabilities(Capabilities::all().deny(Capability::FileSystem).deny(Capability::Process));
+    let denied = |message: &str| Err(LangError::Runtime(RuntimeError::PermissionDenied(message.to_string())));
+
+    assert_eq!(engine.eval("import std::io;").await, denied("'std::io' needs file system access, which is disabled"));
+    assert_eq!(engine.eval("import plugin::tool;").await, denied("'plugin::tool' needs process access, which is disabled"));
+    assert_eq!(
+        engine.eval(r#"import std::json; json.read_lines("data.jsonl")"#).await,
+        denied("json.read_lines needs file system access, which is disabled")
+    );
+    assert_eq!(engine.eval("json.serialize([1, 2])").await, Ok(Object::from("[1,2]")));
+    assert!(engine.eval("import std::env; env.args()").await.is_ok());
+
+    engine.set_capabilities(Capabilities::all());
+    let missing = engine.eval(r#"import std::json; json.read_lines("data.jsonl")"#).await;
+    assert!(matches!(missing, Err(LangError::Runtime(ref e)) if !matches!(e, RuntimeError::PermissionDenied(_))), "{:?}", missing);
+
+    assert_eq!(Capability::parse("net"), Ok(Capability::Network));
+    assert!(Capability::parse("disk").is_err());
+    assert!(!Capabilities::none().allows(Capability::Env));
+    assert!(Capabilities::none().allow(Capability::Env).allows(Capability::Env));
+}
+
+#[tokio::test]
+async fn test_object_conversions() {
+    use std::collections::HashMap;
+
+    let mut engine = Engine::new();
+    let result = engine.eval(r#"{"name": "gl", "tags": ["fast", "small"]}"#).await.unwrap();
+    let Object::Hash(hash) = result else { panic!("expected a hash") };
+    let tags = hash.get(&Object::from("tags")).cloned().unwrap();
+    assert_eq!(Vec::<String>::try_from(tags).unwrap(), vec!["fast", "small"]);
+
+    let ints = engine.eval("[1, 2, 3]").await.unwrap();
+    assert_eq!(Vec::<i64>::try_from(ints.clone()).unwrap(), vec![1, 2, 3]);
+    assert_eq!(Vec::<f64>::try_from(ints.clone()).unwrap(), vec![1.0, 2.0, 3.0]);
+    assert_eq!(
+        Vec::<bool>::try_from(ints),
+        Err(RuntimeError::TypeMismatch { expected: "boolean".to_string(), got: "integer".to_string() })
+    );
+
+    let limits = HashMap::from([("low".to_string(), Some(1_i64)), ("high".to_string(), None)]);
+    let round_trip = HashMap::<String, Option<i64>>::try_from(Object::from(limits.clone())).unwrap();
+    assert_eq!(round_trip, limits);
+    assert!(HashMap::<String, i64>::try_from(engine.eval("{1: 2}").await.unwrap()).is_err());
+
+    assert_eq!(Object::from(vec!["a", "b"]), engine.eval(r#"["a", "b"]"#).await.unwrap());
+    assert_eq!(bool::try_from(Object::from(true)), Ok(true));
+    assert_eq!(String::try_from(Object::from("x".to_string())), Ok("x".to_string()));
+}
+
+#[tokio::test]
+async fn test_object_serde() {
+    let mut engine = Engine::new();
+    let value = engine.eval(r#"{"b": [1, 2.5, null], "a": true}"#).await.unwrap();
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"{"a":true,"b":[1,2.5,null]}"#);
+    assert_eq!(serde_json::from_str::<Object>(&json).unwrap(), value);
+
+    let function = engine.eval("fn f() { return 1; }; f").await.unwrap();
+    assert!(serde_json::to_string(&function).is_err());
+}
+
+#[tokio::test]
+async fn test_engine_native_functions() {
+    use std::sync::{Arc, Mutex};
+
+    let mut engine = Engine::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&log);
+    engine.register_fn("record", move |args| {
+        sink.lock().unwrap().extend(args.into_iter().map(|arg| arg.to_string()));
+        Ok(Object::Null)
+    });
+    engine.register_fn("area", |args| match args.as_slice() {
+        [w, h] => Ok(Object::from(i64::try_from(w.clone())? * i64::try_from(h.clone())?)),
+        _ => Err(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: args.len() }),
+    });
+    engine.register_async_fn("fetch", |args| async move {
+        tokio::task::yield_now().await;
+        Ok(Object::from(format!("data for {}", String::try_from(args[0].clone())?)))
+    });
+
+    assert_eq!(engine.eval("record(1, \"two\"); area(6, 7)").await, Ok(Object::Integer(42)));
+    assert_eq!(*log.lock().unwrap(), vec!["1", "two"]);
+    assert_eq!(engine.eval("type(area)").await, Ok(Object::from("builtin function")));
+
+    let result = engine.eval("async fn main() { return await fetch(\"x\"); }; main()").await;
+    assert_eq!(result, Ok(Object::from("data for x")));
+
+    // Errors from native functions fail the script like a builtin's.
+    assert_eq!(
+        engine.eval("area(1)").await,
+        Err(LangError::Runtime(RuntimeError::WrongNumberOfArguments { min: 2, max: 2, got: 1 }))
+    );
+    assert!(matches!(engine.eval("area(\"a\", 2)").await, Err(LangError::Runtime(RuntimeError::TypeMismatch { .. }))));
+}
+
+#[tokio::test]
+async fn test_engine_native_objects() {
+    use std::sync::Mutex;
+
+    struct Account {
+        owner: String,
+        balance: Mutex<i64>,
+    }
+
+    let account = NativeType::<Account>::new("Account")
+        .method("owner", |account, _| Ok(Object::from(account.owner.as_str())))
+        .method("deposit", |account, args| {
+            let amount = i64::try_from(args.into_iter().next().unwrap_or(Object::Null))?;
+            let mut balance = account.balance.lock().unwrap();
+            *balance += amount;
+            Ok(Object::Integer(*balance))
+        });
+    let mut engine = Engine::new();
+    let opener = account.clone();
+    engine.register_fn("open_account", move |args| {
+        let owner = String::try_from(args.into_iter().next().unwrap_or(Object::Null))?;
+        Ok(opener.wrap(Account { owner, balance: Mutex::new(0) }))
+    });
+
+    let result = engine.eval("let a = open_account(\"ann\"); let b = a; a.deposit(5); b.deposit(10)").await;
+    assert_eq!(result, Ok(Object::Integer(15)));
+    let checks = engine.eval("[type(a), a.owner(), a == b, a == open_account(\"ann\")]").await.unwrap();
+    assert_eq!(checks.to_string(), "[Account, ann, true, false]");
+    assert!(matches!(engine.eval("a.withdraw(1)").await, Err(LangError::Runtime(RuntimeError::InvalidOperation(_)))));
+
+    let handle = engine.eval("a").await.unwrap();
+    let account = handle.downcast_native::<Account>().unwrap();
+    assert_eq!(*account.balance.lock().unwrap(), 15);
+    assert!(handle.downcast_native::<String>().is_none());
+}
+
+#[tokio::test]
+async fn test_engine_call() {
+    let mut engine = Engine::new();
+    let source = "let calls = 0;
+        fn on_event(name, n) { calls = calls + 1; return name + \":\" + n.to_string(); }
+        async fn load(n) { return n * 2; }
+        fn fail() { throw \"bad\"; }
+        let answer = 42;";
+    engine.eval(source).await.unwrap();
+
+    let result = engine.call("on_event", vec![Object::from("click"), Object::from(3)]).await;
+    assert_eq!(result, Ok(Object::from("click:3")));
+    assert_eq!(engine.call("load", vec![Object::from(21)]).await, Ok(Object::Integer(42)));
+    assert_eq!(engine.eval("calls").await, Ok(Object::Integer(1)));
+
+    assert!(matches!(engine.call("fail", vec![]).await, Err(LangError::Runtime(RuntimeError::UncaughtException(_)))));
+    assert!(matches!(engine.call("answer", vec![]).await, Err(LangError::Runtime(RuntimeError::TypeMismatch { .. }))));
+    assert_eq!(
+        engine.call("missing", vec![]).await,
+        Err(LangError::Runtime(RuntimeError::UndefinedVariable("missing".to_string())))
+    );
+
+    engine.eval("fn spin() { while (true) {} }").await.unwrap();
+    engine.set_limits(ResourceLimits { max_steps: Some(1000), ..Default::default() });
+    assert!(matches!(engine.call("spin", vec![]).await, Err(LangError::Runtime(RuntimeError::ExecutionLimitExceeded(_)))));
+}
+
+#[test]
+fn test_engine_call_sync() {
+    let mut engine = Engine::new();
+    let source = "import std::time;
+        fn add(a, b) { return a + b; }
+        async fn later(n) { await time.sleep(5); return n * 2; }";
+    tokio::runtime::Runtime::new().unwrap().block_on(engine.eval(source)).unwrap();
+
+    // No runtime is running here; `call_sync` brings its own.
+    assert_eq!(engine.call_sync("add", vec![Object::from(1), Object::from(2)]), Ok(Object::Integer(3)));
+    assert_eq!(engine.call_sync("later", vec![Object::from(21)]), Ok(Object::Integer(42)));
+    // Later calls reuse the runtime the first one started.
+    for i in 0..1000 {
+        assert_eq!(engine.call_sync("add", vec![Object::from(i), Object::from(1)]), Ok(Object::Integer(i + 1)));
+    }
+    assert_eq!(
+        engine.call_sync("missing", vec![]),
+        Err(LangError::Runtime(RuntimeError::UndefinedVariable("missing".to_string())))
+    );
+}
+
+#[test]
+fn test_playground_eval_str() {
+    use crate::playground::eval_str;
+
+    assert_eq!(eval_str("println(\"hi\", 1); print(\"a\"); printf(\"{}!\", 2); 3 * 4"), "hi 1\na2!12\n");
+    assert_eq!(eval_str("let x = 1;"), "");
+    assert_eq!(eval_str("println(\"before\"); 1 / 0;"), "before\nInvalid operation, Division by zero\n");
+    assert!(eval_str("let = ;").contains("line 1, column 4"));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_engine_c_api() {
+    use crate::capi::*;
+    use std::ffi::{CStr, CString, c_void};
+
+    extern "C" fn scale(user_data: *mut c_void, args: *const *const GlValue, argc: usize) -> *mut GlValue {
+        let factor = unsafe { *(user_data as *const f64) };
+        let args = unsafe { std::slice::from_raw_parts(args, argc) };
+        let mut number = 0.0;
+        if argc != 1 || !unsafe { gl_value_as_number(args[0], &mut number) } {
+            return unsafe { gl_value_error(c"scale expects a number".as_ptr()) };
+        }
+        gl_value_from_number(number * factor)
+    }
+
+    fn eval(engine: *mut GlEngine, source: &str) -> (bool, String) {
+        let source = CString::new(source).unwrap();
+        unsafe {
+            let value = gl_engine_eval(engine, source.as_ptr());
+            let text = gl_value_to_string(value);
+            let result = (gl_value_is_error(value), CStr::from_ptr(text).to_string_lossy().into_owned());
+            gl_string_free(text);
+            gl_value_free(value);
+            result
+        }
+    }
+
+    let engine = gl_engine_new();
+    assert!(!engine.is_null());
+    let mut factor = 2.5;
+    unsafe { gl_engine_register_fn(engine, c"scale".as_ptr(), scale, &mut factor as *mut f64 as *mut c_void) };
+
+    assert_eq!(eval(engine, "fn double(x) { return x * 2; }"), (false, "null".to_string()));
+    assert_eq!(eval(engine, "double(21)"), (false, "42".to_string()));
+    assert_eq!(eval(engine, "\"a\" + \"b\""), (false, "ab".to_string()));
+    assert_eq!(eval(engine, "scale(4)"), (false, "10".to_string()));
+
+    let (failed, message) = eval(engine, "scale(\"x\")");
+    assert!(failed && message.contains("scale expects a number"), "{}", message);
+    let (failed, message) = eval(engine, "missing + 1");
+    assert!(failed && !message.is_empty());
+
+    unsafe {
+        let value = gl_engine_eval(engine, c"let x = 1;".as_ptr());
+        assert!(gl_value_is_null(value));
+        gl_value_free(value);
+        gl_engine_free(engine);
+    }
+}
@@ -1,5 +1,6 @@
+use crate::lexer::highlight::{HighlightKind, highlight};
 use crate::lexer::lexer::Lexer;
-use crate::lexer::token::Token;
+use crate::lexer::token::{Location, Token};
 use num_bigint::BigInt;
 
 fn extract_tokens(spanned_tokens: Vec<crate::lexer::token::Spanned<Token>>) -> Vec<Token> {
@@ -203,3 +204,33 @@ fn test_comments() {
     let tokens = extract_tokens(spanned_tokens);
     assert_eq!(tokens, expected_tokens);
 }
+
+#[test]
+fn test_highlight_categories() {
+    let input = "let name = \"gl\"; // greet\nif (x >= 1.5) { print(name); }";
+    let highlights = highlight(input);
+    let kinds: Vec<&str> = highlights.iter().map(|h| h.kind.name()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "keyword", "identifier", "operator", "string", "punctuation", "comment", "keyword", "punctuation",
+            "identifier", "operator", "number", "punctuation", "punctuation", "identifier", "punctuation",
+            "identifier", "punctuation", "punctuation", "punctuation",
+        ]
+    );
+
+    // Strings keep their quotes and comments run to the end of the line.
+    assert_eq!((highlights[3].span.start, highlights[3].span.end), (Location::new(1, 12), Location::new(1, 16)));
+    assert_eq!(highlights[5].kind, HighlightKind::Comment);
+    assert_eq!((highlights[5].span.start, highlights[5].span.end), (Location::new(1, 18), Location::new(1, 26)));
+    assert_eq!((highlights[10].span.start, highlights[10].span.end), (Location::new(2, 10), Location::new(2, 13)));
+    assert_eq!(highlights[11].span.start, Location::new(2, 13));
+}
+
+#[test]
+fn test_highlight_incomplete_input() {
+    let kinds: Vec<HighlightKind> = highlight("let s = \"open\nlet t = 1 @ 2;").iter().map(|h| h.kind).collect();
+    assert_eq!(kinds[3], HighlightKind::String);
+    assert_eq!(kinds[4], HighlightKind::Keyword);
+    assert_eq!(kinds.len(), 10);
+}
@@ -0,0 +1,92 @@
+use crate::lexer::lexer::Lexer;
+use crate::lexer::token::SpannedTokens;
+use crate::lint::{LintConfig, Rule, lint};
+use crate::parser::parser::Parser;
+
+fn lint_rules(input: &str, config: &LintConfig) -> Vec<Rule> {
+    let spanned_tokens = Lexer::lex_tokens(input.as_bytes()).unwrap();
+    let spanned = SpannedTokens::new(&spanned_tokens);
+    let (_, program) = Parser::parse_tokens(spanned.to_tokens()).expect("parse failed");
+    lint(&program, config).into_iter().map(|warning| warning.rule).collect()
+}
+
+fn rules(input: &str) -> Vec<Rule> {
+    lint_rules(input, &LintConfig::default())
+}
+
+#[test]
+fn test_lint_clean_program() {
+    let input = "
+        import std::math;
+        fn area(r) { return math.PI * r * r; }
+        fn total() { return counter + 1; }
+        let counter = area(2);
+        let _unused = 1;
+        for (let i = 0; i < 3; i = i + 1) { println(total(), i); }
+        try { println(1); } catch (e) { println(e); }
+    ";
+    assert_eq!(rules(input), Vec::<Rule>::new());
+}
+
+#[test]
+fn test_lint_unused_bindings() {
+    assert_eq!(rules("let x = 1;"), vec![Rule::UnusedVariable]);
+    assert_eq!(rules("import std::json;"), vec![Rule::UnusedImport]);
+    assert_eq!(rules("fn f() { let y = 2; return 1; } f();"), vec![Rule::UnusedVariable]);
+    // Re-declaring reads the old value first, so only the last `x` is unused.
+    assert_eq!(rules("let x = 1; let x = x + 1;"), vec![Rule::UnusedVariable]);
+    // Functions, including `async fn`, are not variables.
+    assert_eq!(rules("fn f() { return 1; } async fn g() { return 2; }"), Vec::<Rule>::new());
+}
+
+#[test]
+fn test_lint_shadowing_and_struct_equality() {
+    assert_eq!(rules("let x = 1; fn f(x) { return x; } f(x);"), vec![Rule::ShadowedName]);
+    assert_eq!(rules("let a = [1]; for (v in a) { let a = v; println(a); }"), vec![Rule::ShadowedName]);
+
+    let structs = "struct P { x: 0 } let p = P { x: 1 }; println(p == P { x: 1 });";
+    assert_eq!(rules(structs), vec![Rule::StructEquality]);
+    assert_eq!(rules("struct P { x: 0 } let p = P { x: 1 }; p = 5; println(p == 5);"), Vec::<Rule>::new());
+}
+
+#[test]
+fn test_lint_unreachable_and_empty_blocks() {
+    assert_eq!(rules("fn f() { return 1; println(2); } f();"), vec![Rule::UnreachableCode]);
+    assert_eq!(rules("while (true) { break; }"), Vec::<Rule>::new());
+    assert_eq!(
+        rules("if (true) {} else { println(1); } try { println(1); } catch (e) {}"),
+        vec![Rule::EmptyBlock, Rule::EmptyBlock]
+    );
+}
+
+#[test]
+fn test_lint_config() {
+    let config = LintConfig::parse("[rules]\nunused-variable = off\nempty-block = on\n").unwrap();
+    assert!(!config.is_enabled(Rule::UnusedVariable));
+    assert!(config.is_enabled(Rule::EmptyBlock));
+    assert_eq!(lint_rules("let x = 1; if (x) {}", &config), vec![Rule::EmptyBlock]);
+
+    assert!(LintConfig::parse("[rules]\nno-such-rule = off\n").is_err());
+    assert!(LintConfig::parse("[rules]\nempty-block = maybe\n").is_err());
+}
+
+#[test]
+fn test_lint_locations() {
+    use crate::lexer::token::Location;
+    use crate::parser::depth_check::DEFAULT_MAX_NESTING_DEPTH;
+    use crate::runners::run_source::parse_with_markers_or_report;
+
+    let input = "import std::json;\nfn f(a) {\n    let unused = 1;\n    if (a) {\n    }\n    return a;\n    println(a);\n}\nf(1);";
+    let program = parse_with_markers_or_report(input, DEFAULT_MAX_NESTING_DEPTH).unwrap();
+    let located: Vec<(Rule, Option<Location>)> =
+        lint(&program, &LintConfig::default()).into_iter().map(|warning| (warning.rule, warning.location)).collect();
+    assert_eq!(
+        located,
+        vec![
+            (Rule::UnusedImport, Some(Location::new(1, 1))),
+            (Rule::UnusedVariable, Some(Location::new(3, 5))),
+            (Rule::EmptyBlock, Some(Location::new(4, 5))),
+            (Rule::UnreachableCode, Some(Location::new(7, 5))),
+        ]
+    );
+}
@@ -0,0 +1,146 @@
+use std::io::Cursor;
+
+use serde_json::{Value, json};
+
+use crate::lsp::serve;
+
+/// Sends `messages` to a server and returns everything it wrote back.
+fn exchange(messages: &[Value]) -> Vec<Value> {
+    let mut input = Vec::new();
+    for message in messages {
+        let body = message.to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes());
+    }
+    let mut output = Vec::new();
+    serve(&mut Cursor::new(input), &mut output).unwrap();
+
+    let mut reader = Cursor::new(output);
+    let mut replies = Vec::new();
+    while let Some(reply) = crate::lsp::transport::read_message(&mut reader).unwrap() {
+        replies.push(reply);
+    }
+    replies
+}
+
+fn open(text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": "file:///tmp/main.g", "languageId": "g", "version": 1, "text": text } },
+    })
+}
+
+fn request(id: u64, method: &str, line: u64, character: u64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": { "textDocument": { "uri": "file:///tmp/main.g" }, "position": { "line": line, "character": character } },
+    })
+}
+
+fn result(replies: &[Value], id: u64) -> &Value {
+    &replies.iter().find(|reply| reply["id"] == id).expect("no reply")["result"]
+}
+
+#[test]
+fn test_lsp_initialize_and_diagnostics() {
+    let replies = exchange(&[
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        open("let x = 1;\nlet y = ;\n"),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ]);
+
+    assert_eq!(result(&replies, 1)["capabilities"]["hoverProvider"], true);
+    let published = replies.iter().find(|r| r["method"] == "textDocument/publishDiagnostics").unwrap();
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["source"], "gl");
+    assert!(result(&replies, 2).is_null());
+}
+
+#[test]
+fn test_lsp_hover() {
+    let replies = exchange(&[
+        open("import std::math;\nlet count = 10;\nprintln(count, math.sqrt(2), math.PI);\n"),
+        request(1, "textDocument/hover", 2, 2),
+        request(2, "textDocument/hover", 2, 10),
+        request(3, "textDocument/hover", 2, 21),
+        request(4, "textDocument/hover", 2, 34),
+        request(5, "textDocument/hover", 0, 14),
+    ]);
+
+    let hover = |id| result(&replies, id)["contents"]["value"].as_str().unwrap_or_default().to_string();
+    assert!(hover(1).contains("println(value, ...)"), "{}", hover(1));
+    assert!(hover(2).contains("let count: integer"), "{}", hover(2));
+    assert!(hover(3).contains("math.sqrt(...) takes 1 argument"), "{}", hover(3));
+    assert!(hover(4).contains("math.PI: float"), "{}", hover(4));
+    assert!(hover(5).contains("standard library module `std::math`"), "{}", hover(5));
+}
+
+#[test]
+fn test_lsp_definition() {
+    let replies = exchange(&[
+        open("fn add(a, b) {\n    return a + b;\n}\nlet total = add(1, 2);\nprintln(total);\n"),
+        request(1, "textDocument/definition", 3, 13),
+        request(2, "textDocument/definition", 1, 11),
+        request(3, "textDocument/definition", 4, 10),
+        request(4, "textDocument/definition", 4, 2),
+    ]);
+
+    let start = |id| result(&replies, id)["range"]["start"].clone();
+    assert_eq!(start(1), json!({ "line": 0, "character": 3 }));
+    assert_eq!(start(2), json!({ "line": 0, "character": 7 }));
+    assert_eq!(start(3), json!({ "line": 3, "character": 4 }));
+    assert!(result(&replies, 4).is_null());
+}
+
+#[test]
+fn test_lsp_definition_in_imported_module() {
+    let dir = std::env::temp_dir().join(format!("gl_lsp_test_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("utils")).unwrap();
+    std::fs::write(dir.join("utils").join("shapes.g"), "let unit = 1;\nfn area(w, h) { return w * h; }\n").unwrap();
+    let root = format!("file://{}", dir.display());
+
+    let replies = exchange(&[
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "rootUri": root } }),
+        open("import utils::shapes;\nimport utils::shapes::{area};\nshapes.area(1, 2);\narea(3, 4);\n"),
+        request(2, "textDocument/definition", 2, 9),
+        request(3, "textDocument/definition", 3, 1),
+        request(4, "textDocument/hover", 2, 9),
+        request(5, "textDocument/completion", 2, 7),
+    ]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    for id in [2, 3] {
+        let location = result(&replies, id);
+        assert!(location["uri"].as_str().unwrap().ends_with("/utils/shapes.g"), "{}", location);
+        assert_eq!(location["range"]["start"], json!({ "line": 1, "character": 3 }));
+    }
+    assert!(result(&replies, 4)["contents"]["value"].as_str().unwrap().contains("fn area(w, h)"));
+    let labels: Vec<&str> = result(&replies, 5).as_array().unwrap().iter().map(|i| i["label"].as_str().unwrap()).collect();
+    assert_eq!(labels, vec!["unit", "area"]);
+}
+
+#[test]
+fn test_lsp_completion() {
+    let replies = exchange(&[
+        open("import std::math;\nlet total = 1;\nmath.\nimport std::\nto\n"),
+        request(1, "textDocument/completion", 2, 5),
+        request(2, "textDocument/completion", 3, 12),
+        request(3, "textDocument/completion", 4, 2),
+    ]);
+
+    let labels = |id| -> Vec<String> {
+        result(&replies, id).as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect()
+    };
+    assert!(labels(1).contains(&"sqrt".to_string()));
+    assert!(labels(1).contains(&"PI".to_string()));
+    assert!(labels(2).contains(&"json".to_string()));
+    let names = labels(3);
+    for expected in ["total", "math", "println", "while"] {
+        assert!(names.contains(&expected.to_string()), "missing {}", expected);
+    }
+}
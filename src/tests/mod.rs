@@ -2,13 +2,25 @@
 //!
 //! All language features are tested against the stack-based VM execution engine.
 
+#[cfg(test)]
+mod engine_tests;
+
 #[cfg(test)]
 mod lexer_tests;
 
 #[cfg(test)]
-mod parser_tests;
+mod lint_tests;
+
+#[cfg(test)]
+mod lsp_tests;
 
 #[cfg(test)]
+mod package_tests;
+
+#[cfg(test)]
+mod parser_tests;
+
+#[cfg(all(test, feature = "wasm"))]
 mod wasm_tests;
 
 #[cfg(test)]
@@ -0,0 +1,163 @@
+//! Tests for packages: naming, the manifest, the lockfile and import
+//! resolution.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::package::lockfile::{LockedPackage, Lockfile};
+use crate::package::manifest::{DEFAULT_ENTRY, Dependency, Manifest};
+use crate::package::scaffold::create_project;
+use crate::package::{MODULES_DIR, install, installed_module_file, package_name};
+use crate::vm::runtime::module_registry::ModuleRegistry;
+
+#[test]
+fn test_package_names() {
+    let tests = vec![
+        ("https://github.com/someone/utils.git", Some("utils")),
+        ("https://github.com/someone/text-utils/", Some("text_utils")),
+        ("git@github.com:someone/json5.git", Some("json5")),
+        ("https://example.com/3d.git", None),
+        ("https://example.com/std.git", None),
+    ];
+    for (url, expected) in tests {
+        assert_eq!(package_name(url).as_deref(), expected, "{}", url);
+    }
+}
+
+#[test]
+fn test_lockfile_round_trip() {
+    let mut lockfile = Lockfile::default();
+    let package = |name: &str, rev: &str| LockedPackage {
+        name: name.to_string(),
+        source: format!("https://example.com/{}.git", name),
+        rev: rev.to_string(),
+    };
+    lockfile.insert(package("zeta", "1111"));
+    lockfile.insert(package("alpha", "2222"));
+    lockfile.insert(package("zeta", "3333"));
+
+    let names: Vec<&str> = lockfile.packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["alpha", "zeta"]);
+    assert_eq!(Lockfile::parse(&lockfile.render()), Ok(lockfile));
+    assert!(Lockfile::parse("[broken]\nsource = x\n").unwrap_err().contains("rev"));
+    assert!(Lockfile::parse("[/home/someone]\nsource = x\nrev = 1\n").unwrap_err().contains("not a valid package name"));
+    assert!(Lockfile::parse("[std]\nsource = x\nrev = 1\n").is_err());
+    assert!(Lockfile::parse("[utils]\nsource = --upload-pack=touch pwned\nrev = 1\n").unwrap_err().contains("invalid source"));
+    assert!(Lockfile::parse("[utils]\nsource = x\nrev = --orphan\n").unwrap_err().contains("invalid rev"));
+}
+
+#[test]
+fn test_install_rejects_unsafe_arguments() {
+    let root = std::env::temp_dir().join(format!("gl_install_args_{}", std::process::id()));
+    let outside = root.join("keep");
+    std::fs::create_dir_all(&outside).unwrap();
+
+    assert!(install(&root.join("project"), &outside.to_string_lossy(), "https://example.com/x.git", None).is_err());
+    assert!(install(&root, "utils", "--upload-pack=touch pwned", None).unwrap_err().contains("not a valid git URL"));
+    assert!(install(&root, "utils", "https://example.com/x.git", Some("-b")).unwrap_err().contains("not a valid git revision"));
+    assert!(outside.exists());
+    assert!(!root.join(MODULES_DIR).exists());
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_manifest_parsing() {
+    let text = r#"
+        # The weather app
+        [package]
+        name = "weather"
+        version = "0.3.1"
+        entry = "src/main.g"
+        paths = ["src", "vendor",]
+
+        [dependencies]
+        utils = "https://example.com/utils.git"  # latest
+        json5 = { git = "https://example.com/json5.git", rev = "v1.2.0" }
+    "#;
+    let manifest = Manifest::parse(text, Path::new("/project")).unwrap();
+    assert_eq!(manifest.name, "weather");
+    assert_eq!(manifest.version, "0.3.1");
+    assert_eq!(manifest.entry_path(), Path::new("/project/src/main.g"));
+    assert_eq!(manifest.search_paths(), vec![Path::new("/project/src"), Path::new("/project/vendor")]);
+    assert_eq!(
+        manifest.dependencies,
+        vec![
+            Dependency { name: "utils".into(), git: "https://example.com/utils.git".into(), rev: None },
+            Dependency { name: "json5".into(), git: "https://example.com/json5.git".into(), rev: Some("v1.2.0".into()) },
+        ]
+    );
+
+    let minimal = Manifest::parse("[package]\nname = \"a\"\nversion = \"1.0.0\"\n", Path::new(".")).unwrap();
+    assert_eq!(minimal.entry, DEFAULT_ENTRY);
+
+    let errors = vec![
+        ("[package]\nname = \"a\"\n", "package.version is missing"),
+        ("[package]\nname = a\n", "line 2: expected a quoted string, got 'a'"),
+        ("[package]\npaths = \"src\"\n", "line 2: package.paths must be an array of strings"),
+        ("[package]\nname = \"a\"\nname = \"b\"\n", "line 3: 'name' is set twice"),
+        ("[dependencies]\nmy-lib = \"x\"\n", "line 2: 'my-lib' cannot be imported; dependency names must be identifiers"),
+        ("[dependencies]\nlib = { rev = \"v1\" }\n", "line 2: dependency 'lib' has no git URL"),
+        ("[build]\nx = \"y\"\n", "line 2: unknown table [build]"),
+    ];
+    for (text, expected) in errors {
+        assert_eq!(Manifest::parse(text, Path::new(".")).unwrap_err(), expected, "{}", text);
+    }
+}
+
+#[tokio::test]
+async fn test_imports_resolve_search_paths() {
+    let dir = std::env::temp_dir().join(format!("gl_search_paths_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    std::fs::write(dir.join("vendor").join("helper.g"), "let x = 1;\n").unwrap();
+
+    let path = vec!["helper".to_string()];
+    let registry = Arc::new(Mutex::new(ModuleRegistry::new(dir.clone())));
+    let without = ModuleRegistry::load_module(Arc::clone(&registry), &path).await;
+    registry.lock().unwrap().search_paths = vec![dir.join("vendor")];
+    let with = ModuleRegistry::load_module(Arc::clone(&registry), &path).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(without.is_err());
+    assert!(with.is_ok(), "{:?}", with.err());
+}
+
+#[tokio::test]
+async fn test_imports_resolve_installed_packages() {
+    let dir = std::env::temp_dir().join(format!("gl_packages_{}", std::process::id()));
+    let package = dir.join(MODULES_DIR).join("greeter");
+    std::fs::create_dir_all(&package).unwrap();
+    std::fs::write(package.join("lib.g"), "fn greet() { return 1; }\n").unwrap();
+    std::fs::write(package.join("extra.g"), "let x = 1;\n").unwrap();
+
+    let path = |parts: &[&str]| parts.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    assert_eq!(installed_module_file(&dir, &path(&["greeter"])), Some(package.join("lib.g")));
+    assert_eq!(installed_module_file(&dir, &path(&["greeter", "extra"])), Some(package.join("extra.g")));
+    assert_eq!(installed_module_file(&dir, &path(&["missing"])), None);
+
+    let registry = Arc::new(Mutex::new(ModuleRegistry::new(dir.clone())));
+    let entry = ModuleRegistry::load_module(Arc::clone(&registry), &path(&["greeter"])).await;
+    let submodule = ModuleRegistry::load_module(Arc::clone(&registry), &path(&["greeter", "extra"])).await;
+    let missing = ModuleRegistry::load_module(Arc::clone(&registry), &path(&["missing"])).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(entry.is_ok(), "{:?}", entry.err());
+    assert!(submodule.is_ok(), "{:?}", submodule.err());
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_new_project_skeleton() {
+    let dir = std::env::temp_dir().join(format!("gl_new_project_{}", std::process::id()));
+    let files = create_project(&dir, "my_app").unwrap();
+    let manifest = Manifest::load(&dir);
+    let entry_exists = dir.join("src").join("main.g").is_file();
+    let again = create_project(&dir, "my_app");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(files.len(), 4);
+    let manifest = manifest.unwrap();
+    assert_eq!(manifest.name, "my_app");
+    assert_eq!(manifest.entry, "src/main.g");
+    assert!(entry_exists);
+    assert!(again.unwrap_err().contains("not empty"));
+}
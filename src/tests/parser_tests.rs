@@ -1,6 +1,7 @@
 use crate::ast::ast::{Expr, Ident, Infix, Literal, Prefix, Program, Stmt};
 use crate::lexer::lexer::Lexer;
 use crate::lexer::token::SpannedTokens;
+use crate::parser::optimize::optimize;
 use crate::parser::parser::Parser;
 
 fn mk_ident(name: &str) -> Ident {
@@ -36,7 +37,7 @@ fn test_let_statements() {
 
     let program = parse_test_helper(input);
 
-    let expected = vec![
+    let expected = [
         Stmt::LetStmt(mk_ident("x"), Expr::LitExpr(Literal::IntLiteral(5))),
         Stmt::LetStmt(mk_ident("y"), Expr::LitExpr(Literal::IntLiteral(10))),
         Stmt::LetStmt(
@@ -61,7 +62,7 @@ fn test_return_statements() {
 
     let program = parse_test_helper(input);
 
-    let expected = vec![
+    let expected = [
         Stmt::ReturnStmt(Expr::LitExpr(Literal::IntLiteral(5))),
         Stmt::ReturnStmt(Expr::LitExpr(Literal::IntLiteral(10))),
         Stmt::ReturnStmt(Expr::LitExpr(Literal::IntLiteral(993322))),
@@ -390,6 +391,17 @@ fn test_if_in_function_allows_implicit_return() {
     }
 }
 
+#[test]
+fn test_last_statement_may_omit_semicolon() {
+    let program = parse_test_helper("let y = 1; println(y)");
+    assert_eq!(program.len(), 2);
+    assert!(matches!(&program[1], Stmt::ExprValueStmt(Expr::CallExpr { .. })), "{:?}", program[1]);
+
+    let spanned_tokens = Lexer::lex_tokens("println(1) println(2)".as_bytes()).unwrap();
+    let tokens = SpannedTokens::new(&spanned_tokens).to_tokens();
+    assert!(Parser::parse_tokens(tokens).is_err());
+}
+
 #[test]
 fn test_while_loop() {
     let input = "while (x < 5) { x = x + 1; }";
@@ -505,3 +517,204 @@ fn test_for_in_loop() {
         panic!("Expected Stmt::ExprStmt(Expr::ForExpr), got {:?}", stmt);
     }
 }
+
+#[test]
+fn test_optimize_constant_folding() {
+    let input = r#"
+        let day = 60 * 60 * 24;
+        let greeting = "hello, " + "world" + "!";
+        let flags = [!true, -(2 + 3), 7 % 4 == 3, 1.5 * 2.0, 10 > 3 && "yes", null || x];
+        while (i < 2 * 5) { i = i + (1 + 1); }
+    "#;
+    let mut program = parse_test_helper(input);
+    optimize(&mut program);
+
+    let int = |i| Expr::LitExpr(Literal::IntLiteral(i));
+    let expected = vec![
+        Stmt::LetStmt(mk_ident("day"), int(86400)),
+        Stmt::LetStmt(mk_ident("greeting"), Expr::LitExpr(Literal::StringLiteral("hello, world!".to_string()))),
+        Stmt::LetStmt(
+            mk_ident("flags"),
+            Expr::ArrayExpr(vec![
+                Expr::LitExpr(Literal::BoolLiteral(false)),
+                int(-5),
+                Expr::LitExpr(Literal::BoolLiteral(true)),
+                Expr::LitExpr(Literal::FloatLiteral(3.0)),
+                Expr::LitExpr(Literal::StringLiteral("yes".to_string())),
+                Expr::IdentExpr(mk_ident("x")),
+            ]),
+        ),
+        Stmt::ExprStmt(Expr::WhileExpr {
+            cond: Box::new(Expr::InfixExpr(Infix::LessThan, Box::new(Expr::IdentExpr(mk_ident("i"))), Box::new(int(10)))),
+            body: vec![Stmt::AssignStmt(
+                mk_ident("i"),
+                Expr::InfixExpr(Infix::Plus, Box::new(Expr::IdentExpr(mk_ident("i"))), Box::new(int(2))),
+            )],
+        }),
+    ];
+    assert_eq!(program, expected);
+}
+
+#[test]
+fn test_optimize_leaves_runtime_errors() {
+    for input in ["1 / 0;", "\"a\" - 1;", "-\"a\";", "x + 1 * 2;"] {
+        let mut program = parse_test_helper(input);
+        optimize(&mut program);
+        assert!(
+            matches!(&program[0], Stmt::ExprStmt(Expr::InfixExpr(..)) | Stmt::ExprStmt(Expr::PrefixExpr(..))),
+            "input: {}, got: {:?}",
+            input,
+            program
+        );
+    }
+}
+
+#[test]
+fn test_optimize_dead_code_elimination() {
+    let input = r#"
+        fn f(x) {
+            if (x) { return 1; x = 2; }
+            if (false) { x = 3; }
+            if (1 < 2) { x } else { 0 }
+        }
+        while (false) { x = 4; }
+        if (true) { y = 5; }
+        if (true) { let z = 6; }
+        return y;
+        y = 7;
+    "#;
+    let mut program = parse_test_helper(input);
+    let warnings = optimize(&mut program);
+
+    let ident = |name: &str| Expr::IdentExpr(mk_ident(name));
+    let int = |i| Expr::LitExpr(Literal::IntLiteral(i));
+    let expected = vec![
+        Stmt::FnStmt {
+            name: mk_ident("f"),
+            params: vec![mk_ident("x")],
+            body: vec![
+                Stmt::ExprValueStmt(Expr::IfExpr {
+                    cond: Box::new(ident("x")),
+                    consequence: vec![Stmt::ReturnStmt(int(1))],
+                    alternative: None,
+                }),
+                Stmt::ExprValueStmt(Expr::LitExpr(Literal::NullLiteral)),
+                Stmt::ExprValueStmt(ident("x")),
+            ],
+        },
+        Stmt::ExprStmt(Expr::LitExpr(Literal::NullLiteral)),
+        Stmt::AssignStmt(mk_ident("y"), int(5)),
+        Stmt::ExprValueStmt(Expr::IfExpr {
+            cond: Box::new(Expr::LitExpr(Literal::BoolLiteral(true))),
+            consequence: vec![Stmt::LetStmt(mk_ident("z"), int(6))],
+            alternative: None,
+        }),
+        Stmt::ReturnStmt(ident("y")),
+    ];
+    assert_eq!(program, expected);
+    assert_eq!(
+        warnings,
+        vec![
+            "unreachable code after `return` was removed (in fn `f`)",
+            "`if` condition is always false, the branch was removed (in fn `f`)",
+            "`if` condition is always true, the `else` branch was removed (in fn `f`)",
+            "`while` condition is always false, the loop body was removed",
+            "unreachable code after `return` was removed",
+        ]
+    );
+}
+
+#[test]
+fn test_long_operator_chain_parses() {
+    let chain = |terms: usize| format!("let x = 0{};", " + 1".repeat(terms));
+
+    assert_eq!(parse_test_helper(&chain(100)).len(), 1);
+    assert_eq!(parse_test_helper(&chain(20_000)).len(), 1);
+}
+
+#[test]
+fn test_deep_nesting_is_rejected() {
+    fn parse(source: &str) -> Result<usize, nom::error::ErrorKind> {
+        let spanned_tokens = Lexer::lex_tokens(source.as_bytes()).unwrap();
+        let spanned = SpannedTokens::new(&spanned_tokens);
+        match Parser::parse_tokens(spanned.to_tokens()) {
+            Ok((_, program)) => Ok(program.len()),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(e.code),
+            Err(nom::Err::Incomplete(_)) => Err(nom::error::ErrorKind::Complete),
+        }
+    }
+    let nested = |open: &str, close: &str, depth: usize| format!("let x = {}1{};", open.repeat(depth), close.repeat(depth));
+
+    // Parser recursion is deep in debug builds; give it room to reach the limit.
+    std::thread::Builder::new()
+        .stack_size(64 << 20)
+        .spawn(move || {
+            assert_eq!(parse(&nested("(", ")", 100)), Ok(1));
+            assert_eq!(parse(&nested("[", "]", 100)), Ok(1));
+            assert_eq!(parse(&nested("(", ")", 5_000)), Err(nom::error::ErrorKind::TooLarge));
+            assert_eq!(parse(&nested("[", "]", 5_000)), Err(nom::error::ErrorKind::TooLarge));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_check_source_diagnostics() {
+    use crate::runners::run_check::{Severity, Stage, check_source};
+
+    assert!(check_source("let x = 1;\nprintln(x);").is_empty());
+
+    let lexer = check_source("let x = 1;\nlet s = \"abc;\n");
+    assert_eq!(lexer.len(), 1);
+    assert_eq!(lexer[0].stage, Stage::Lexer);
+    assert_eq!(lexer[0].location.map(|l| l.line), Some(2));
+
+    let parser = check_source("let x = ;");
+    assert_eq!(parser.len(), 1);
+    assert_eq!((parser[0].stage, parser[0].severity), (Stage::Parser, Severity::Error));
+    assert!(parser[0].location.is_some_and(|l| l.line == 1));
+
+    // A misplaced `await` and an optimizer warning are both reported.
+    let both = check_source("fn f() { return await g(); }\nwhile (false) { println(1); }");
+    assert_eq!(both.len(), 2);
+    assert!(both[0].message.contains("await"));
+    assert_eq!((both[1].stage, both[1].severity), (Stage::Optimizer, Severity::Warning));
+}
+
+#[test]
+fn test_diagnostics_as_json() {
+    use crate::runners::run_check::check_source;
+
+    let lexer = check_source("let s = \"abc;\n");
+    let json: serde_json::Value = serde_json::from_str(&lexer[0].to_json(Some("main.g"))).unwrap();
+    assert_eq!(json["severity"], "error");
+    assert_eq!(json["code"], "unterminated-string");
+    assert_eq!(json["stage"], "lexer");
+    assert_eq!(json["file"], "main.g");
+    let location = lexer[0].location.unwrap();
+    assert_eq!(json["span"], serde_json::json!({ "line": location.line, "column": location.column }));
+
+    let warning = check_source("while (false) { println(1); }");
+    let json: serde_json::Value = serde_json::from_str(&warning[0].to_json(None)).unwrap();
+    assert_eq!((&json["severity"], &json["code"]), (&"warning".into(), &"dead-code".into()));
+    assert!(json["file"].is_null() && json["span"].is_null());
+    assert!(!warning[0].to_json(None).contains('\n'));
+}
+
+#[test]
+fn test_is_unterminated() {
+    use crate::lexer::token::{Token, Tokens};
+    use crate::parser::parser_errors::is_unterminated;
+
+    let unterminated = |input: &str| {
+        let tokens: Vec<Token> = Lexer::lex_tokens(input.as_bytes()).unwrap().into_iter().map(|t| t.node).collect();
+        is_unterminated(&Tokens::new(&tokens))
+    };
+    assert!(unterminated("fn f() {"));
+    assert!(unterminated("let xs = [1,\n2"));
+    assert!(unterminated("println(\"a\","));
+    assert!(!unterminated("fn f() {\n return 1;\n}"));
+    assert!(!unterminated("let x = 1;"));
+    assert!(!unterminated("}"));
+}
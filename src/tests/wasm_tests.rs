@@ -1,5 +1,6 @@
 use crate::vm::obj::Object;
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use crate::vm::sandbox::Capabilities;
 use crate::wasm::type_conversions::{component_val_to_g, g_to_component_val};
 use crate::wasm::wasm_runtime::{WasmModule, WasmRuntime};
 use wasmtime::component::Val;
@@ -18,12 +19,12 @@ fn test_g_int_to_component() {
 
 #[test]
 fn test_g_float_to_component() {
-    let obj = Object::Float(3.14);
+    let obj = Object::Float(2.5);
     let val = g_to_component_val(&obj);
 
     assert!(val.is_ok());
     match val.unwrap() {
-        Val::Float64(n) => assert!((n - 3.14).abs() < 0.001),
+        Val::Float64(n) => assert!((n - 2.5).abs() < 0.001),
         _ => panic!("Expected Float64"),
     }
 }
@@ -56,11 +57,11 @@ fn test_component_val_to_g_i32() {
 
 #[test]
 fn test_component_val_to_g_f64() {
-    let val = Val::Float64(3.14);
+    let val = Val::Float64(2.5);
     let obj = component_val_to_g(&val);
 
     assert!(obj.is_ok());
-    assert_eq!(obj.unwrap(), Object::Float(3.14));
+    assert_eq!(obj.unwrap(), Object::Float(2.5));
 }
 
 #[test]
@@ -81,7 +82,9 @@ fn test_wasm_runtime_creation() {
 #[test]
 fn test_wasm_store_creation() {
     let runtime = WasmRuntime::new().unwrap();
-    let _store = runtime.create_store();
+    let _store = runtime.create_store(Capabilities::all());
+    // No env, network or preopened directory.
+    let _sandboxed = runtime.create_store(Capabilities::none());
 }
 
 #[test]
@@ -101,7 +104,7 @@ fn test_classic_module_from_wat() {
 
     assert!(module.is_ok());
 
-    let mut store = runtime.create_store();
+    let mut store = runtime.create_store(Capabilities::all());
     let instance = module.unwrap().instantiate(&mut store);
     assert!(instance.is_ok());
 }
@@ -125,7 +128,7 @@ fn test_classic_function_call() {
     let runtime = WasmRuntime::new().unwrap();
     let module = WasmModule::load_from_bytes(runtime.engine(), "test_add", wat.as_bytes()).unwrap();
 
-    let mut store = runtime.create_store();
+    let mut store = runtime.create_store(Capabilities::all());
     let instance = module.instantiate(&mut store).unwrap();
 
     let result = instance
@@ -163,7 +166,7 @@ fn test_classic_memory() {
     let module =
         WasmModule::load_from_bytes(runtime.engine(), "test_memory", wat.as_bytes()).unwrap();
 
-    let mut store = runtime.create_store();
+    let mut store = runtime.create_store(Capabilities::all());
     let instance = module.instantiate(&mut store).unwrap();
 
     let memory = instance.get_memory();
@@ -196,13 +199,13 @@ fn test_classic_call_with_g_objects() {
     let runtime = WasmRuntime::new().unwrap();
     let module = WasmModule::load_from_bytes(runtime.engine(), "test_add", wat.as_bytes()).unwrap();
 
-    let mut store = runtime.create_store();
+    let mut store = runtime.create_store(Capabilities::all());
     let instance = module.instantiate(&mut store).unwrap();
 
     let result = {
-        let args = vec![Object::Integer(5), Object::Integer(3)];
+        let args = [Object::Integer(5), Object::Integer(3)];
         let wasm_args: Result<Vec<Val>, RuntimeError> =
-            args.iter().map(|arg| g_to_component_val(arg)).collect();
+            args.iter().map(g_to_component_val).collect();
         let wasm_args = wasm_args.unwrap();
         instance
             .call_func_with_args(&mut store, "add", &wasm_args)
@@ -216,9 +219,9 @@ fn test_classic_call_with_g_objects() {
     }
 
     let mul_result = {
-        let args = vec![Object::Integer(4), Object::Integer(7)];
+        let args = [Object::Integer(4), Object::Integer(7)];
         let wasm_args: Result<Vec<Val>, RuntimeError> =
-            args.iter().map(|arg| g_to_component_val(arg)).collect();
+            args.iter().map(g_to_component_val).collect();
         let wasm_args = wasm_args.unwrap();
         instance
             .call_func_with_args(&mut store, "multiply", &wasm_args)
@@ -248,13 +251,13 @@ fn test_classic_call_with_float() {
     let module =
         WasmModule::load_from_bytes(runtime.engine(), "float_test", wat.as_bytes()).unwrap();
 
-    let mut store = runtime.create_store();
+    let mut store = runtime.create_store(Capabilities::all());
     let instance = module.instantiate(&mut store).unwrap();
 
     let result = {
-        let args = vec![Object::Float(1.5), Object::Float(2.5)];
+        let args = [Object::Float(1.5), Object::Float(2.5)];
         let wasm_args: Result<Vec<Val>, RuntimeError> =
-            args.iter().map(|arg| g_to_component_val(arg)).collect();
+            args.iter().map(g_to_component_val).collect();
         let wasm_args = wasm_args.unwrap();
         instance
             .call_func_with_args(&mut store, "add_float", &wasm_args)
@@ -67,6 +67,12 @@ pub struct Chunk {
     /// Source line mapping using run-length encoding.
     /// Each entry is (byte_count, line_number).
     pub lines: LineInfo,
+    /// Name of the function this chunk is the body of, empty for the
+    /// top-level program. Used by the profiler.
+    pub name: String,
+    /// Call counter and compiled code for the optional JIT tier.
+    #[cfg(feature = "jit")]
+    pub(crate) jit: crate::vm::jit::JitState,
 }
 
 impl Chunk {
@@ -75,6 +81,9 @@ impl Chunk {
             code: Vec::new(),
             constants: Vec::new(),
             lines: LineInfo::new(),
+            name: String::new(),
+            #[cfg(feature = "jit")]
+            jit: Default::default(),
         }
     }
 
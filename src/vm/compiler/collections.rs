@@ -42,7 +42,7 @@ pub fn compile_method_call(
 
     let method_idx = compiler
         .chunk
-        .add_constant(Object::String(method.to_string()));
+        .add_constant(Object::String(method.to_string().into()));
     if let Some(method_idx) = method_idx {
         compiler.emit(Instruction::Constant(method_idx), line);
     }
@@ -64,7 +64,7 @@ pub fn compile_struct_literal(
     for (ident, expr) in fields {
         let field_name_idx = compiler
             .chunk
-            .add_constant(Object::String(ident.name.clone()));
+            .add_constant(Object::String(ident.name.clone().into()));
         if let Some(idx) = field_name_idx {
             compiler.emit(Instruction::Constant(idx), line);
         }
@@ -89,7 +89,7 @@ pub fn compile_field_access(compiler: &mut Compiler, object: &Expr, field: &str,
     compiler.compile_expression(object, line);
     let field_idx = compiler
         .chunk
-        .add_constant(Object::String(field.to_string()));
+        .add_constant(Object::String(field.to_string().into()));
     if let Some(field_idx) = field_idx {
         compiler.emit(Instruction::Constant(field_idx), line);
         compiler.emit(Instruction::GetField, line);
@@ -121,7 +121,7 @@ pub fn compile_struct_stmt(
                 }
                 Literal::FloatLiteral(f) => Object::Float(*f),
                 Literal::BoolLiteral(b) => Object::Boolean(*b),
-                Literal::StringLiteral(s) => Object::String(s.clone()),
+                Literal::StringLiteral(s) => Object::String(s.clone().into()),
                 Literal::NullLiteral => Object::Null,
             },
             _ => Object::Null,
@@ -138,16 +138,17 @@ pub fn compile_struct_stmt(
             }];
             new_params.extend(params.clone());
 
-            let (fn_chunk, _param_count, local_names) =
+            let (mut fn_chunk, _param_count, local_names) =
                 crate::vm::compiler::Compiler::compile_function_body(&new_params, body, false);
+            fn_chunk.name = format!("{}.{}", name.name, ident.name);
 
             let fn_obj = Object::Function(Box::new(crate::vm::obj::FunctionData {
-                params: new_params,
+                params: new_params.into(),
                 chunk: std::sync::Arc::new(fn_chunk),
                 env: std::sync::Arc::new(std::sync::Mutex::new(
                     crate::vm::runtime::env::Environment::new(),
                 )),
-                local_names,
+                local_names: local_names.into(),
             }));
 
             method_map.insert(ident.name.clone(), fn_obj);
@@ -169,7 +170,7 @@ pub fn compile_struct_stmt(
 
     let name_idx = compiler
         .chunk
-        .add_constant(Object::String(name.name.clone()));
+        .add_constant(Object::String(name.name.clone().into()));
     if let Some(name_idx) = name_idx {
         compiler.emit(Instruction::SetGlobal(name_idx), line);
     }
@@ -5,6 +5,15 @@ pub(crate) enum CompilationError {
     ConstantPoolOverflow,
 }
 
+impl CompilationError {
+    /// Stable identifier of the kind of error, for `--error-format=json`.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            CompilationError::ConstantPoolOverflow => "constant-pool-overflow",
+        }
+    }
+}
+
 impl fmt::Display for CompilationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -95,8 +95,8 @@ impl Scope {
          UNSET slots cause idents to stay UNSET (name-based lookup).
     - `fn_params`: this function's params, already assigned 0-based slots.
 
-    Local `let` slots start at `fn_params.len()` so they never collide with
-    params. This matches `Environment::count_slots(params, body)` which
+    Local `let` and nested `fn` slots start at `fn_params.len()` so they
+    never collide with params. This matches `Environment::count_slots(params, body)` which
     allocates `params.len() + let_count` slots for the frame.
     */
     fn process_fn_body(
@@ -122,6 +122,10 @@ impl Scope {
                         local_slot_idx += 1;
                     }
                 }
+                Stmt::FnStmt { name, .. } => {
+                    let_slots.push((name.name.clone(), SlotIndex(local_slot_idx as u16)));
+                    local_slot_idx += 1;
+                }
                 _ => {}
             }
         }
@@ -142,6 +146,11 @@ impl Scope {
                         }
                     }
                 }
+                Stmt::FnStmt { name, .. } => {
+                    if let Some((_, slot)) = let_iter.next() {
+                        name.slot = *slot;
+                    }
+                }
                 _ => {}
             }
         }
@@ -258,6 +267,10 @@ impl Scope {
                         local_slot_idx += 1;
                     }
                 }
+                Stmt::FnStmt { name, .. } => {
+                    let_slots.push((name.name.clone(), SlotIndex(local_slot_idx as u16)));
+                    local_slot_idx += 1;
+                }
                 _ => {}
             }
         }
@@ -278,6 +291,11 @@ impl Scope {
                         }
                     }
                 }
+                Stmt::FnStmt { name, .. } => {
+                    if let Some((_, slot)) = let_iter.next() {
+                        name.slot = *slot;
+                    }
+                }
                 _ => {}
             }
         }
@@ -427,6 +445,13 @@ impl Scope {
                     id.slot = loop_slot;
                     for_locals.push((id.name.clone(), loop_slot));
                 }
+                // `compile_for_expr` keeps the iterable and the counter in the two
+                // slots after the loop variables; reserve them so body lets don't
+                // overwrite them. Empty names never match an identifier.
+                for _ in 0..2 {
+                    let hidden_slot = SlotIndex(for_locals.len() as u16);
+                    for_locals.push((String::new(), hidden_slot));
+                }
                 self.process_block_body(body, &for_locals);
             }
 
@@ -471,9 +496,19 @@ impl Scope {
             }
 
             Expr::PrefixExpr(_, e) => self.process_expr(e, locals),
-            Expr::InfixExpr(_, l, r) => {
-                self.process_expr(l, locals);
-                self.process_expr(r, locals);
+            Expr::InfixExpr(..) => {
+                // Operator chains nest on the left; walk down them in a loop
+                // and process the operands in source order.
+                let mut rights = Vec::new();
+                let mut left = expr;
+                while let Expr::InfixExpr(_, l, r) = left {
+                    rights.push(r);
+                    left = &mut **l;
+                }
+                self.process_expr(left, locals);
+                for r in rights.into_iter().rev() {
+                    self.process_expr(r, locals);
+                }
             }
             Expr::CallExpr {
                 function,
@@ -84,8 +84,10 @@ pub(crate) fn compile_for_expr(
     };
     let iter_slot = base_slot + idents.len() as u8;
     let counter_slot = iter_slot + 1;
+    let start = compiler.chunk.code.len();
 
     compiler.compile_expression(iterable, line);
+    compiler.emit(Instruction::ToIterable, line);
     compiler.emit(Instruction::SetLocal(iter_slot), line);
 
     compiler.emit_constant(Object::Integer(0), line);
@@ -152,6 +154,10 @@ pub(crate) fn compile_for_expr(
         .patch_u16(end_jump.addr, compiler.chunk.current_offset());
 
     compiler.emit_constant(Object::Null, line);
+
+    for ident in idents {
+        compiler.declare_local(ident, start);
+    }
 }
 
 /// Compiles a C-style for loop.
@@ -165,6 +171,7 @@ pub(crate) fn compile_cstyle_for(
 ) {
     use crate::vm::obj::Object;
 
+    let start = compiler.chunk.code.len();
     if let Some(init_stmt) = init {
         compiler.compile_statement(init_stmt, line);
     }
@@ -209,6 +216,10 @@ pub(crate) fn compile_cstyle_for(
     }
 
     compiler.emit_constant(Object::Null, line);
+
+    if let Some(init_stmt) = init {
+        compiler.declare_block_locals(std::slice::from_ref(init_stmt.as_ref()), start);
+    }
 }
 
 /// Emits a `break` instruction and records it for backpatching.
@@ -254,6 +265,7 @@ fn compile_program_body(compiler: &mut Compiler, program: &Program, discard_last
         return;
     }
 
+    let start = compiler.chunk.code.len();
     for (i, stmt) in program.iter().enumerate() {
         let line = 0u16;
         compiler.compile_statement(stmt, line);
@@ -278,4 +290,5 @@ fn compile_program_body(compiler: &mut Compiler, program: &Program, discard_last
             }
         }
     }
+    compiler.declare_block_locals(program, start);
 }
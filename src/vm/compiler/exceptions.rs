@@ -52,6 +52,7 @@ pub(crate) fn compile_try_catch(
     // Catch handler
     if has_catch {
         let catch_addr = compiler.chunk.current_offset();
+        let catch_start = compiler.chunk.code.len();
         compiler
             .chunk
             .patch_u16(push_catch_offset as usize + 1, catch_addr);
@@ -66,7 +67,7 @@ pub(crate) fn compile_try_catch(
             } else {
                 let idx = compiler
                     .chunk
-                    .add_constant(Object::String(ident.name.clone()));
+                    .add_constant(Object::String(ident.name.clone().into()));
                 if let Some(idx) = idx {
                     compiler.emit(Instruction::SetGlobal(idx), line);
                 }
@@ -81,6 +82,9 @@ pub(crate) fn compile_try_catch(
         if let Some(body) = catch_body {
             compile_block_body(compiler, body, line);
         }
+        if let Some(ident) = catch_ident {
+            compiler.declare_local(ident, catch_start);
+        }
 
         // After catch body, go to finally (or end if no finally)
         jumps_to_finally.push(compiler.emit_jump(line));
@@ -121,6 +125,7 @@ pub(crate) fn compile_try_catch(
 
 /// Compiles a block of statements, leaving the last expression's value on the stack.
 fn compile_block_body(compiler: &mut Compiler, body: &Program, line: u16) {
+    let start = compiler.chunk.code.len();
     for (i, stmt) in body.iter().enumerate() {
         compiler.compile_statement(stmt, line);
 
@@ -130,4 +135,5 @@ fn compile_block_body(compiler: &mut Compiler, body: &Program, line: u16) {
                 compiler.emit(Instruction::Pop, line);
         }
     }
+    compiler.declare_block_locals(body, start);
 }
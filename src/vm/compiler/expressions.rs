@@ -22,7 +22,7 @@ pub(crate) fn compile_ident(compiler: &mut Compiler, ident: &Ident, line: u16) {
     } else {
         let idx = compiler
             .chunk
-            .add_constant(Object::String(ident.name.clone()));
+            .add_constant(Object::String(ident.name.clone().into()));
         if let Some(idx) = idx {
             compiler.emit(Instruction::GetGlobal(idx), line);
         }
@@ -36,7 +36,7 @@ pub(crate) fn compile_literal(compiler: &mut Compiler, literal: &Literal, line:
         Literal::BigIntLiteral(b) => Object::BigInteger(Box::new(b.clone())),
         Literal::FloatLiteral(f) => Object::Float(*f),
         Literal::BoolLiteral(b) => Object::Boolean(*b),
-        Literal::StringLiteral(s) => Object::String(s.clone()),
+        Literal::StringLiteral(s) => Object::String(s.clone().into()),
         Literal::NullLiteral => Object::Null,
     };
     compiler.emit_constant(obj, line);
@@ -60,11 +60,29 @@ pub(crate) fn compile_prefix(compiler: &mut Compiler, op: &Prefix, operand: &Exp
 }
 
 /// Compiles an infix (binary) expression: `a + b`, `a == b`, etc.
+///
+/// Operator chains such as `a + b + c` nest on the left, so the chain is
+/// walked in a loop: the innermost left operand is compiled first, then
+/// each operator with its right operand on the way back out.
 pub(crate) fn compile_infix(compiler: &mut Compiler, op: &Infix, left: &Expr, right: &Expr, line: u16) {
+    let mut operators = vec![(op, right)];
+    let mut left = left;
+    while let Expr::InfixExpr(op, inner, right) = left {
+        operators.push((op, right));
+        left = inner;
+    }
+
+    compiler.compile_expression(left, line);
+    for (op, right) in operators.into_iter().rev() {
+        compile_infix_operator(compiler, op, right, line);
+    }
+}
+
+/// Compiles `op` applied to the value on top of the stack and `right`.
+fn compile_infix_operator(compiler: &mut Compiler, op: &Infix, right: &Expr, line: u16) {
     // Short-circuit evaluation for && and ||
     match op {
         Infix::And => {
-            compiler.compile_expression(left, line);
             let jump_patch = compiler.emit_jump_if_false(line);
             compiler.emit(Instruction::Pop, line); // Pop the left value
             compiler.compile_expression(right, line);
@@ -72,7 +90,6 @@ pub(crate) fn compile_infix(compiler: &mut Compiler, op: &Infix, left: &Expr, ri
             return;
         }
         Infix::Or => {
-            compiler.compile_expression(left, line);
             let jump_patch = compiler.emit_jump_if_truthy(line);
             compiler.emit(Instruction::Pop, line);
             compiler.compile_expression(right, line);
@@ -82,8 +99,7 @@ pub(crate) fn compile_infix(compiler: &mut Compiler, op: &Infix, left: &Expr, ri
         _ => {}
     }
 
-    // Standard infix: compile left, compile right, apply operator
-    compiler.compile_expression(left, line);
+    // Standard infix: left is on the stack, compile right, apply operator
     compiler.compile_expression(right, line);
 
     let instr = match op {
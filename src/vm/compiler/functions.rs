@@ -13,7 +13,7 @@ pub fn compile_fn_declaration(
     body: &Program,
     line: u16,
 ) {
-    compile_closure_instruction(compiler, params, body, line);
+    compile_closure_instruction(compiler, &name.name, params, body, line);
     // Stack: [Function]
 
     // Dup so we can store in both locations
@@ -26,7 +26,7 @@ pub fn compile_fn_declaration(
     } else {
         let name_idx = compiler
             .chunk
-            .add_constant(Object::String(name.name.clone()));
+            .add_constant(Object::String(name.name.clone().into()));
         if let Some(name_idx) = name_idx {
             compiler.emit(Instruction::SetGlobal(name_idx), line);
         }
@@ -36,7 +36,7 @@ pub fn compile_fn_declaration(
     // Also store as global for recursive/self-referential calls
     let name_idx = compiler
         .chunk
-        .add_constant(Object::String(name.name.clone()));
+        .add_constant(Object::String(name.name.clone().into()));
     if let Some(name_idx) = name_idx {
         compiler.emit(Instruction::SetGlobal(name_idx), line);
     }
@@ -44,17 +44,20 @@ pub fn compile_fn_declaration(
 }
 
 /// Compiles a function expression: `fn(params) { body }`.
+///
+/// `name` is only used to label the function's chunk, e.g. in profiles.
 pub fn compile_fn_expr(
     compiler: &mut Compiler,
+    name: &str,
     params: &[Ident],
     body: &Program,
     is_async: bool,
     line: u16,
 ) {
     if is_async {
-        compile_async_closure(compiler, params, body, line);
+        compile_async_closure(compiler, name, params, body, line);
     } else {
-        compile_closure_instruction(compiler, params, body, line);
+        compile_closure_instruction(compiler, name, params, body, line);
     }
 }
 
@@ -78,18 +81,20 @@ pub fn compile_await_expr(compiler: &mut Compiler, expr: &Expr, line: u16) {
 
 fn compile_closure_instruction(
     compiler: &mut Compiler,
+    name: &str,
     params: &[Ident],
     body: &Program,
     line: u16,
 ) {
-    let (chunk, _param_count, local_names) = Compiler::compile_function_body(params, body, false);
+    let (mut chunk, _param_count, local_names) = Compiler::compile_function_body(params, body, false);
+    chunk.name = name.to_string();
     let fn_obj = Object::Function(Box::new(crate::vm::obj::FunctionData {
-        params: params.to_vec(),
+        params: params.into(),
         chunk: std::sync::Arc::new(chunk),
         env: std::sync::Arc::new(std::sync::Mutex::new(
             crate::vm::runtime::env::Environment::new(),
         )),
-        local_names,
+        local_names: local_names.into(),
     }));
 
     let fn_idx = compiler.chunk.add_constant(fn_obj);
@@ -106,15 +111,16 @@ fn compile_closure_instruction(
     );
 }
 
-fn compile_async_closure(compiler: &mut Compiler, params: &[Ident], body: &Program, line: u16) {
-    let (chunk, _param_count, local_names) = Compiler::compile_function_body(params, body, true);
+fn compile_async_closure(compiler: &mut Compiler, name: &str, params: &[Ident], body: &Program, line: u16) {
+    let (mut chunk, _param_count, local_names) = Compiler::compile_function_body(params, body, true);
+    chunk.name = name.to_string();
     let fn_obj = Object::AsyncFunction(Box::new(crate::vm::obj::FunctionData {
-        params: params.to_vec(),
+        params: params.into(),
         chunk: std::sync::Arc::new(chunk),
         env: std::sync::Arc::new(std::sync::Mutex::new(
             crate::vm::runtime::env::Environment::new(),
         )),
-        local_names,
+        local_names: local_names.into(),
     }));
 
     let fn_idx = compiler.chunk.add_constant(fn_obj);
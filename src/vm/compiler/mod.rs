@@ -26,10 +26,12 @@ pub(crate) mod functions;
 pub(crate) mod statements;
 
 use crate::ast::ast::{Expr, Ident, Program, Stmt};
+use crate::parser::optimize::optimize;
 use crate::vm::obj::Object;
 use crate::vm::chunk::Chunk;
 use crate::vm::compiler::compute_slots::compute_slots;
 use crate::vm::compiler::compilation_errors::CompilationError;
+use crate::vm::frame::LocalName;
 use crate::vm::instruction::Instruction;
 use ahash::AHasher;
 use std::hash::BuildHasherDefault;
@@ -66,14 +68,19 @@ pub(crate) struct Compiler {
     finally_depth: usize,
     error: Option<CompilationError>,
     struct_templates: HashMap<String, Object>,
+    /// Slot-bound variables declared so far, with the bytecode range of
+    /// their block.
+    locals: Vec<LocalName>,
 }
 
 impl Compiler {
     /// Compiles a program into a bytecode chunk.
     ///
-    /// Runs `compute_slots` on the program to populate slot indices on every `Ident`.
-    /// The program is passed by mutable reference to avoid cloning the entire AST.
+    /// Runs the AST optimizer, then `compute_slots` to populate slot indices
+    /// on every `Ident`. The program is passed by mutable reference to avoid
+    /// cloning the entire AST.
     pub fn compile_program(program: &mut Program) -> Result<Chunk, CompilationError> {
+        optimize(program);
         compute_slots(program);
 
         let mut compiler = Compiler {
@@ -82,6 +89,7 @@ impl Compiler {
             finally_depth: 0,
             error: None,
             struct_templates: HashMap::default(),
+            locals: Vec::new(),
         };
 
         compiler.compile_program_body(program, false);
@@ -95,13 +103,14 @@ impl Compiler {
 
     /// Compiles a function body into a sub-chunk.
     ///
-    /// Returns the compiled chunk, parameter count, and local variable names
-    /// indexed by slot (params first, then lets).
+    /// Returns the compiled chunk, parameter count, and the function's local
+    /// variables with the bytecode ranges they are in scope for, outermost
+    /// first (see [`LocalName`]).
     pub fn compile_function_body(
         params: &[Ident],
         body: &Program,
         is_async: bool,
-    ) -> (Chunk, usize, Vec<String>) {
+    ) -> (Chunk, usize, Vec<LocalName>) {
         // Wrap body in a fake FnStmt so compute_slots assigns param slots correctly.
         let fake_fn = Stmt::FnStmt {
             name: Ident::new("".to_string()),
@@ -118,34 +127,13 @@ impl Compiler {
             _ => unreachable!(),
         };
 
-        // Build local names array: params first (slot 0..N), then lets, then functions
-        let mut local_names: Vec<String> = params.iter().map(|p| p.name.clone()).collect();
-        for stmt in &program {
-            match stmt {
-                crate::ast::ast::Stmt::LetStmt(ident, _) => {
-                    // Ensure the vector is large enough
-                    while local_names.len() <= ident.slot.0 as usize {
-                        local_names.push(String::new());
-                    }
-                    local_names[ident.slot.0 as usize] = ident.name.clone();
-                }
-                crate::ast::ast::Stmt::FnStmt { name, .. } => {
-                    // Also track function declarations so they can be captured by inner closures
-                    while local_names.len() <= name.slot.0 as usize {
-                        local_names.push(String::new());
-                    }
-                    local_names[name.slot.0 as usize] = name.name.clone();
-                }
-                _ => {}
-            }
-        }
-
         let mut compiler = Compiler {
             chunk: Chunk::new(),
             loop_contexts: Vec::new(),
             finally_depth: 0,
             error: None,
             struct_templates: HashMap::default(),
+            locals: Vec::new(),
         };
 
         compiler.compile_program_body(&program, false);
@@ -153,6 +141,14 @@ impl Compiler {
         // Always emit ReturnValue to ensure the stack is cleaned up correctly
         compiler.emit(Instruction::ReturnValue, 0);
 
+        for param in params {
+            compiler.declare_local(param, 0);
+        }
+        // Blocks are recorded as they finish, inner ones first. Order by
+        // scope so that capturing in table order lets inner bindings win.
+        let mut local_names = compiler.locals;
+        local_names.sort_by(|a, b| a.scope.start.cmp(&b.scope.start).then(b.scope.end.cmp(&a.scope.end)));
+
         let param_count = params.len();
 
         if is_async {
@@ -173,6 +169,7 @@ impl Compiler {
             return;
         }
 
+        let start = self.chunk.code.len();
         for (i, stmt) in program.iter().enumerate() {
             let line = self.statement_line(stmt);
             self.compile_statement(stmt, line);
@@ -197,6 +194,40 @@ impl Compiler {
                 }
             }
         }
+        self.declare_block_locals(program, start);
+    }
+
+    /// Records `ident` as naming its slot from `start` up to the current
+    /// offset, so closures created in that range capture it.
+    fn declare_local(&mut self, ident: &Ident, start: usize) {
+        if ident.slot.is_unset() {
+            return;
+        }
+        self.locals.push(LocalName {
+            name: ident.name.clone(),
+            slot: ident.slot.0,
+            scope: start..self.chunk.code.len(),
+        });
+    }
+
+    /// Records the `let` bindings declared directly in `body`, which was
+    /// compiled from `start` up to the current offset.
+    ///
+    /// Nested `fn` declarations are left out on purpose: their closure is
+    /// created before the slot is written, so capturing it would shadow the
+    /// global copy that `compile_fn_declaration` stores for recursive calls.
+    fn declare_block_locals(&mut self, body: &[Stmt], start: usize) {
+        for stmt in body {
+            match stmt {
+                Stmt::LetStmt(ident, _) => self.declare_local(ident, start),
+                Stmt::MultiLetStmt { idents, .. } => {
+                    for ident in idents {
+                        self.declare_local(ident, start);
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
     // ─── Statement dispatch ─────────────────────────────────────────
@@ -260,6 +291,9 @@ impl Compiler {
             Stmt::ThrowStmt(expr) => {
                 exceptions::compile_throw(self, expr, line);
             }
+            Stmt::LineMarker(source_line, _) => {
+                self.emit(Instruction::MarkLine(*source_line), line);
+            }
         }
     }
 
@@ -287,7 +321,7 @@ impl Compiler {
                 control_flow::compile_if_expr(self, cond, consequence, alternative, line);
             }
             Expr::FnExpr { params, body } => {
-                functions::compile_fn_expr(self, params, body, false, line);
+                functions::compile_fn_expr(self, "<anonymous>", params, body, false, line);
             }
             Expr::CallExpr {
                 function,
@@ -354,7 +388,7 @@ impl Compiler {
                 );
             }
             Expr::AsyncFnExpr { params, body } => {
-                functions::compile_fn_expr(self, params, body, true, line);
+                functions::compile_fn_expr(self, "<anonymous>", params, body, true, line);
             }
             Expr::AwaitExpr(expr) => {
                 functions::compile_await_expr(self, expr, line);
@@ -2,7 +2,7 @@
 
 use crate::ast::ast::{Expr, Ident, ImportItems, SlotIndex};
 use crate::vm::obj::Object;
-use crate::vm::compiler::Compiler;
+use crate::vm::compiler::{Compiler, functions};
 use crate::vm::instruction::Instruction;
 
 /// Compiles a `let name = expr;` statement.
@@ -10,14 +10,23 @@ use crate::vm::instruction::Instruction;
 /// For top-level lets (global scope), emits `SetGlobal`.
 /// For function-local lets, emits `SetLocal` using the pre-computed slot.
 pub(crate) fn compile_let_stmt(compiler: &mut Compiler, ident: &Ident, expr: &Expr, line: u16) {
-    compiler.compile_expression(expr, line);
+    match expr {
+        // `let f = fn() {}` and `async fn f() {}` name the function after the binding.
+        Expr::FnExpr { params, body } => {
+            functions::compile_fn_expr(compiler, &ident.name, params, body, false, line);
+        }
+        Expr::AsyncFnExpr { params, body } => {
+            functions::compile_fn_expr(compiler, &ident.name, params, body, true, line);
+        }
+        _ => compiler.compile_expression(expr, line),
+    }
 
     if ident.slot != SlotIndex::UNSET {
         compiler.emit(Instruction::SetLocal(ident.slot.0 as u8), line);
     } else {
         let idx = compiler
             .chunk
-            .add_constant(Object::String(ident.name.clone()));
+            .add_constant(Object::String(ident.name.clone().into()));
         if let Some(idx) = idx {
             compiler.emit(Instruction::SetGlobal(idx), line);
         }
@@ -34,7 +43,7 @@ pub(crate) fn compile_multi_let(compiler: &mut Compiler, idents: &[Ident], value
         } else {
             let idx = compiler
                 .chunk
-                .add_constant(Object::String(ident.name.clone()));
+                .add_constant(Object::String(ident.name.clone().into()));
             if let Some(idx) = idx {
                 compiler.emit(Instruction::SetGlobal(idx), line);
             }
@@ -51,7 +60,7 @@ pub(crate) fn compile_assign(compiler: &mut Compiler, ident: &Ident, expr: &Expr
     } else {
         let idx = compiler
             .chunk
-            .add_constant(Object::String(ident.name.clone()));
+            .add_constant(Object::String(ident.name.clone().into()));
         if let Some(idx) = idx {
             compiler.emit(Instruction::SetGlobal(idx), line);
         }
@@ -86,7 +95,7 @@ pub(crate) fn compile_tuple_assign(
         } else {
             let idx = compiler
                 .chunk
-                .add_constant(Object::String(target.name.clone()));
+                .add_constant(Object::String(target.name.clone().into()));
             if let Some(idx) = idx {
                 compiler.emit(Instruction::SetGlobal(idx), line);
             }
@@ -103,15 +112,16 @@ pub(crate) fn compile_field_assign(
     line: u16,
 ) {
     compiler.compile_expression(object, line);
-    compiler.compile_expression(value, line);
-    // Store field name as constant for the VM to look up
+    // Field name goes below the value: SetField pops value, name, object.
     let idx = compiler
         .chunk
-        .add_constant(Object::String(field.to_string()));
+        .add_constant(Object::String(field.to_string().into()));
     if let Some(idx) = idx {
         compiler.emit(Instruction::Constant(idx), line);
+        compiler.compile_expression(value, line);
         compiler.emit(Instruction::SetField, line);
     }
+    store_updated_target(compiler, object, line);
 }
 
 /// Compiles an index assignment: `arr[i] = expr;`.
@@ -126,6 +136,35 @@ pub(crate) fn compile_index_assign(
     compiler.compile_expression(index, line);
     compiler.compile_expression(value, line);
     compiler.emit(Instruction::SetIndex, line);
+    store_updated_target(compiler, target, line);
+}
+
+/// Stores the collection or struct left on the stack by `SetIndex`/`SetField`
+/// back into the assignment target. Variables and `this` are written back;
+/// for any other target (`a[0][1] = x`, `f().x = y`) the updated copy is
+/// discarded, which only has a visible effect when the target is a `ref`.
+fn store_updated_target(compiler: &mut Compiler, target: &Expr, line: u16) {
+    match target {
+        Expr::IdentExpr(ident) => {
+            if ident.slot != SlotIndex::UNSET {
+                compiler.emit(Instruction::SetLocal(ident.slot.0 as u8), line);
+            } else {
+                let idx = compiler
+                    .chunk
+                    .add_constant(Object::String(ident.name.clone().into()));
+                if let Some(idx) = idx {
+                    compiler.emit(Instruction::SetGlobal(idx), line);
+                }
+            }
+        }
+        // `this` is always at slot 0 in method frames
+        Expr::ThisExpr => {
+            compiler.emit(Instruction::SetLocal(0), line);
+        }
+        _ => {
+            compiler.emit(Instruction::Pop, line);
+        }
+    }
 }
 
 /// Compiles a `return expr;` statement.
@@ -147,7 +186,7 @@ pub(crate) fn compile_import_stmt(
     let module_path = path.join("::");
 
     // Push module path constant
-    let path_idx = compiler.chunk.add_constant(Object::String(module_path));
+    let path_idx = compiler.chunk.add_constant(Object::String(module_path.into()));
     if let Some(path_idx) = path_idx {
         compiler.emit(Instruction::ImportModule(path_idx), line);
     }
@@ -156,19 +195,19 @@ pub(crate) fn compile_import_stmt(
         ImportItems::All => {
             // Store the module object as a global using the last path component
             let module_name = path.last().cloned().unwrap_or_default();
-            let var_idx = compiler.chunk.add_constant(Object::String(module_name));
+            let var_idx = compiler.chunk.add_constant(Object::String(module_name.into()));
             if let Some(var_idx) = var_idx {
                 compiler.emit(Instruction::SetGlobal(var_idx), line);
             }
         }
         ImportItems::Specific(names) => {
             for name in names {
-                let name_idx = compiler.chunk.add_constant(Object::String(name.clone()));
+                let name_idx = compiler.chunk.add_constant(Object::String(name.clone().into()));
                 if let Some(name_idx) = name_idx {
                     compiler.emit(Instruction::Constant(name_idx), line);
                     compiler.emit(Instruction::GetExport, line);
                     // Store as global
-                    let var_idx = compiler.chunk.add_constant(Object::String(name.clone()));
+                    let var_idx = compiler.chunk.add_constant(Object::String(name.clone().into()));
                     if let Some(var_idx) = var_idx {
                         compiler.emit(Instruction::SetGlobal(var_idx), line);
                     }
@@ -176,11 +215,11 @@ pub(crate) fn compile_import_stmt(
             }
         }
         ImportItems::Single(name) => {
-            let name_idx = compiler.chunk.add_constant(Object::String(name.clone()));
+            let name_idx = compiler.chunk.add_constant(Object::String(name.clone().into()));
             if let Some(name_idx) = name_idx {
                 compiler.emit(Instruction::Constant(name_idx), line);
                 compiler.emit(Instruction::GetExport, line);
-                let var_idx = compiler.chunk.add_constant(Object::String(name.clone()));
+                let var_idx = compiler.chunk.add_constant(Object::String(name.clone().into()));
                 if let Some(var_idx) = var_idx {
                     compiler.emit(Instruction::SetGlobal(var_idx), line);
                 }
@@ -0,0 +1,333 @@
+//! Conversions between [`Object`] and Rust types, for host code that passes
+//! values into scripts or reads their results.
+//!
+//! `From` builds an `Object` out of integers, floats, booleans, strings,
+//! vectors, string-keyed maps and options (`None` is `null`). `TryFrom`
+//! goes the other way and fails with [`RuntimeError::TypeMismatch`] when
+//! the object holds something else:
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use g_lang::vm::obj::Object;
+//!
+//! let scores = Object::from(vec![1_i64, 2, 3]);
+//! assert_eq!(Vec::<i64>::try_from(scores).unwrap(), vec![1, 2, 3]);
+//!
+//! let config: HashMap<String, Object> = HashMap::from([("debug".to_string(), true.into())]);
+//! let config = HashMap::<String, bool>::try_from(Object::from(config)).unwrap();
+//! assert_eq!(config["debug"], true);
+//! ```
+//!
+//! With the `serde` feature, `Object` also implements `Serialize` and
+//! `Deserialize`, so it can be read from and written to any serde format.
+//! Only data can be serialized: `null`, numbers, booleans, strings, bytes,
+//! arrays, hashes, ordered maps and structs (as maps of their fields).
+
+use std::collections::HashMap;
+
+use ahash::HashMapExt;
+use num_traits::ToPrimitive;
+
+use crate::vm::obj::{self, Object};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+impl From<i64> for Object {
+    fn from(value: i64) -> Self {
+        Object::Integer(value)
+    }
+}
+
+impl From<i32> for Object {
+    fn from(value: i32) -> Self {
+        Object::Integer(value.into())
+    }
+}
+
+impl From<f64> for Object {
+    fn from(value: f64) -> Self {
+        Object::Float(value)
+    }
+}
+
+impl From<bool> for Object {
+    fn from(value: bool) -> Self {
+        Object::Boolean(value)
+    }
+}
+
+impl From<String> for Object {
+    fn from(value: String) -> Self {
+        Object::String(value.into())
+    }
+}
+
+impl From<&str> for Object {
+    fn from(value: &str) -> Self {
+        Object::String(value.to_string().into())
+    }
+}
+
+impl<T: Into<Object>> From<Vec<T>> for Object {
+    fn from(values: Vec<T>) -> Self {
+        Object::Array(Box::new(values.into_iter().map(Into::into).collect()))
+    }
+}
+
+impl<T: Into<Object>> From<HashMap<String, T>> for Object {
+    #[allow(clippy::mutable_key_type)]
+    fn from(values: HashMap<String, T>) -> Self {
+        let mut hash = obj::HashMap::with_capacity(values.len());
+        for (key, value) in values {
+            hash.insert(Object::from(key), value.into());
+        }
+        Object::Hash(Box::new(hash))
+    }
+}
+
+impl<T: Into<Object>> From<Option<T>> for Object {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Object::Null, Into::into)
+    }
+}
+
+fn mismatch(expected: &str, got: &Object) -> RuntimeError {
+    RuntimeError::TypeMismatch { expected: expected.to_string(), got: got.type_name() }
+}
+
+impl TryFrom<Object> for i64 {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Integer(i) => Ok(i),
+            Object::BigInteger(ref big) => big.to_i64().ok_or_else(|| mismatch("integer that fits in 64 bits", &object)),
+            other => Err(mismatch("integer", &other)),
+        }
+    }
+}
+
+/// Integers convert too, like they do in arithmetic with floats.
+impl TryFrom<Object> for f64 {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Float(f) => Ok(f),
+            Object::Integer(i) => Ok(i as f64),
+            Object::BigInteger(ref big) => big.to_f64().ok_or_else(|| mismatch("float", &object)),
+            other => Err(mismatch("float", &other)),
+        }
+    }
+}
+
+impl TryFrom<Object> for bool {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Boolean(b) => Ok(b),
+            other => Err(mismatch("boolean", &other)),
+        }
+    }
+}
+
+impl TryFrom<Object> for String {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::String(s) => Ok(s.to_string()),
+            other => Err(mismatch("string", &other)),
+        }
+    }
+}
+
+impl<T: TryFrom<Object, Error = RuntimeError>> TryFrom<Object> for Vec<T> {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Array(items) => items.into_iter().map(T::try_from).collect(),
+            other => Err(mismatch("array", &other)),
+        }
+    }
+}
+
+impl<T: TryFrom<Object, Error = RuntimeError>> TryFrom<Object> for HashMap<String, T> {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        let entries: Vec<(Object, Object)> = match object {
+            Object::Hash(hash) => hash.into_iter().collect(),
+            Object::OrderedMap(map) => map.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            other => return Err(mismatch("hash", &other)),
+        };
+        entries
+            .into_iter()
+            .map(|(key, value)| match key {
+                Object::String(key) => Ok((key.to_string(), T::try_from(value)?)),
+                other => Err(mismatch("string key", &other)),
+            })
+            .collect()
+    }
+}
+
+/// `null` is `None`.
+impl<T: TryFrom<Object, Error = RuntimeError>> TryFrom<Object> for Option<T> {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Null => Ok(None),
+            other => T::try_from(other).map(Some),
+        }
+    }
+}
+
+mod serde_impls {
+    use std::fmt;
+
+    use ahash::HashMapExt;
+    use num_bigint::BigInt;
+    use num_traits::ToPrimitive;
+    use serde::de::{self, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{self, SerializeMap, SerializeSeq};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::vm::obj::{self, Object};
+
+    impl Serialize for Object {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Object::Null => serializer.serialize_unit(),
+                Object::Integer(i) => serializer.serialize_i64(*i),
+                Object::BigInteger(big) => match big.to_i128() {
+                    Some(i) => serializer.serialize_i128(i),
+                    None => Err(ser::Error::custom(format!("integer {} is too large to serialize", big))),
+                },
+                Object::Float(f) => serializer.serialize_f64(*f),
+                Object::Boolean(b) => serializer.serialize_bool(*b),
+                Object::String(s) => serializer.serialize_str(s),
+                Object::Bytes(bytes) => serializer.serialize_bytes(bytes),
+                Object::Array(items) => {
+                    let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                    for item in items.iter() {
+                        seq.serialize_element(item)?;
+                    }
+                    seq.end()
+                }
+                Object::Hash(hash) => {
+                    // Sorted, so the output does not change from run to run.
+                    let mut entries: Vec<_> = hash.iter().collect();
+                    entries.sort_by_cached_key(|(k, _)| k.to_string());
+                    let mut map = serializer.serialize_map(Some(entries.len()))?;
+                    for (key, value) in entries {
+                        map.serialize_entry(key, value)?;
+                    }
+                    map.end()
+                }
+                Object::OrderedMap(ordered) => {
+                    let ordered = ordered.lock().unwrap();
+                    let mut map = serializer.serialize_map(None)?;
+                    for (key, value) in ordered.iter() {
+                        map.serialize_entry(key, value)?;
+                    }
+                    map.end()
+                }
+                Object::Struct(s) => {
+                    let mut fields: Vec<_> = s.fields.iter().collect();
+                    fields.sort_by(|a, b| a.0.cmp(b.0));
+                    let mut map = serializer.serialize_map(Some(fields.len()))?;
+                    for (name, value) in fields {
+                        map.serialize_entry(name, value)?;
+                    }
+                    map.end()
+                }
+                other => Err(ser::Error::custom(format!("cannot serialize a value of type {}", other.type_name()))),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Object {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ObjectVisitor)
+        }
+    }
+
+    struct ObjectVisitor;
+
+    impl<'de> Visitor<'de> for ObjectVisitor {
+        type Value = Object;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a null, number, boolean, string, bytes, sequence or map")
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Object, E> {
+            Ok(Object::Null)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Object, E> {
+            Ok(Object::Null)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Object, D::Error> {
+            Object::deserialize(deserializer)
+        }
+
+        fn visit_bool<E: de::Error>(self, b: bool) -> Result<Object, E> {
+            Ok(Object::Boolean(b))
+        }
+
+        fn visit_i64<E: de::Error>(self, i: i64) -> Result<Object, E> {
+            Ok(Object::Integer(i))
+        }
+
+        fn visit_u64<E: de::Error>(self, u: u64) -> Result<Object, E> {
+            Ok(match i64::try_from(u) {
+                Ok(i) => Object::Integer(i),
+                Err(_) => Object::BigInteger(Box::new(BigInt::from(u))),
+            })
+        }
+
+        fn visit_i128<E: de::Error>(self, i: i128) -> Result<Object, E> {
+            Ok(match i64::try_from(i) {
+                Ok(i) => Object::Integer(i),
+                Err(_) => Object::BigInteger(Box::new(BigInt::from(i))),
+            })
+        }
+
+        fn visit_f64<E: de::Error>(self, f: f64) -> Result<Object, E> {
+            Ok(Object::Float(f))
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Object, E> {
+            Ok(Object::from(s))
+        }
+
+        fn visit_string<E: de::Error>(self, s: String) -> Result<Object, E> {
+            Ok(Object::from(s))
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Object, E> {
+            Ok(Object::Bytes(Box::new(bytes.to_vec())))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Object, A::Error> {
+            let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(item) = seq.next_element()? {
+                items.push(item);
+            }
+            Ok(Object::Array(Box::new(items)))
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Object, A::Error> {
+            let mut hash = obj::HashMap::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some((key, value)) = map.next_entry::<Object, Object>()? {
+                hash.insert(key, value);
+            }
+            Ok(Object::Hash(Box::new(hash)))
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Line coverage behind `gl test --coverage`.
+//!
+//! A script parsed for coverage has a [`Stmt::LineMarker`] before every
+//! statement, which the compiler turns into an `OpMarkLine` instruction.
+//! Each one executed counts a hit for its line, so once the tests of a file
+//! have run, the lines with a marker but no hits are the statements no test
+//! reached.
+//!
+//! Hits are process-wide so statements run by nested VMs (async functions
+//! and callbacks invoked from builtins) are counted too.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use crate::ast::ast::Stmt;
+use crate::ast::visitor::{Visitor, walk_stmt};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static HITS: LazyLock<Mutex<HashMap<u16, u64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static FILES: LazyLock<Mutex<Vec<FileCoverage>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// How [`report`] renders the recorded files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageFormat {
+    /// A summary per file with the line ranges that never ran.
+    Text,
+    /// The `lcov` tracefile format read by `genhtml` and most CI services.
+    Lcov,
+}
+
+impl CoverageFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(CoverageFormat::Text),
+            "lcov" => Ok(CoverageFormat::Lcov),
+            _ => Err(format!("unknown coverage format '{}', expected text or lcov", name)),
+        }
+    }
+}
+
+/// Hit counts for the executable lines of one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileCoverage {
+    pub path: String,
+    /// Every line a statement starts on, with how often one ran.
+    pub lines: BTreeMap<u16, u64>,
+}
+
+impl FileCoverage {
+    /// Number of executable lines that ran at least once.
+    pub fn covered(&self) -> usize {
+        self.lines.values().filter(|&&hits| hits > 0).count()
+    }
+}
+
+/// Turns on coverage for every test file run afterwards.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Counts one statement on `line` as executed.
+pub(crate) fn hit(line: u16) {
+    let mut hits = HITS.lock().unwrap_or_else(|e| e.into_inner());
+    *hits.entry(line).or_insert(0) += 1;
+}
+
+/// Forgets the hits counted so far, before running another file.
+pub fn reset() {
+    HITS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// The lines of `program` that statements start on, from its line markers.
+pub fn executable_lines(program: &[Stmt]) -> BTreeSet<u16> {
+    struct Lines(BTreeSet<u16>);
+
+    impl Visitor for Lines {
+        fn visit_stmt(&mut self, stmt: &Stmt) {
+            if let Stmt::LineMarker(line, _) = stmt {
+                self.0.insert(*line);
+            }
+            walk_stmt(self, stmt);
+        }
+    }
+
+    let mut lines = Lines(BTreeSet::new());
+    lines.visit_block(program);
+    lines.0
+}
+
+/// Records the hits counted since the last [`reset`] as the coverage of
+/// `path`, whose statements start on `executable` lines.
+pub fn record_file(path: &str, executable: &BTreeSet<u16>) {
+    let hits = std::mem::take(&mut *HITS.lock().unwrap_or_else(|e| e.into_inner()));
+    let lines = executable
+        .iter()
+        .map(|line| (*line, hits.get(line).copied().unwrap_or(0)))
+        .collect();
+    FILES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(FileCoverage { path: path.to_string(), lines });
+}
+
+/// The files recorded so far, in the order they ran.
+pub fn files() -> Vec<FileCoverage> {
+    FILES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Renders every recorded file.
+pub fn report(format: CoverageFormat) -> String {
+    let files = files();
+    let mut out = String::new();
+    match format {
+        CoverageFormat::Text => {
+            let (mut covered, mut total) = (0, 0);
+            for file in &files {
+                let _ = writeln!(out, "{}", summary(&file.path, file.covered(), file.lines.len()));
+                let missed = missed_ranges(file);
+                if !missed.is_empty() {
+                    let _ = writeln!(out, "    not run: {}", missed.join(", "));
+                }
+                covered += file.covered();
+                total += file.lines.len();
+            }
+            if files.len() > 1 {
+                let _ = writeln!(out, "{}", summary("total", covered, total));
+            }
+        }
+        CoverageFormat::Lcov => {
+            for file in &files {
+                let _ = writeln!(out, "TN:");
+                let _ = writeln!(out, "SF:{}", file.path);
+                for (line, hits) in &file.lines {
+                    let _ = writeln!(out, "DA:{},{}", line, hits);
+                }
+                let _ = writeln!(out, "LF:{}", file.lines.len());
+                let _ = writeln!(out, "LH:{}", file.covered());
+                let _ = writeln!(out, "end_of_record");
+            }
+        }
+    }
+    out
+}
+
+fn summary(name: &str, covered: usize, total: usize) -> String {
+    let percent = if total == 0 { 100.0 } else { covered as f64 * 100.0 / total as f64 };
+    format!("{}: {}/{} lines ({:.1}%)", name, covered, total, percent)
+}
+
+/// Runs of consecutive executable lines that never ran, e.g. `4-6` or `9`.
+fn missed_ranges(file: &FileCoverage) -> Vec<String> {
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    let mut previous = None;
+    for (&line, &hits) in &file.lines {
+        if hits == 0 {
+            match ranges.last_mut() {
+                Some((_, end)) if previous == Some(*end) => *end = line,
+                _ => ranges.push((line, line)),
+            }
+        }
+        previous = Some(line);
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect()
+}
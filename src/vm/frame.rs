@@ -8,12 +8,26 @@
 //! - The stack base (where this frame's slots begin in the VM's stack)
 //! - Local variable names (for closure capture resolution)
 
+use std::ops::Range;
 use std::sync::{Arc, Mutex};
 
 use crate::vm::runtime::env::Environment;
 use crate::vm::obj::Object;
 use crate::vm::chunk::Chunk;
 
+/// A local variable of a function frame: its name, its slot, and the
+/// bytecode range of the block that declares it.
+///
+/// Sibling blocks reuse slots, so a slot can hold different variables at
+/// different points in the function. `OpClosure` captures only the locals
+/// whose range contains the current instruction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalName {
+    pub name: String,
+    pub slot: u16,
+    pub scope: Range<usize>,
+}
+
 /// A single call frame on the VM's call stack.
 ///
 /// Frames are created when functions are called and popped when they
@@ -31,16 +45,16 @@ pub struct CallFrame {
     /// Closure environment — `Some` for closures that capture outer scope,
     /// `None` for top-level functions and the root frame.
     pub closure_env: Option<Arc<Mutex<Environment>>>,
-    /// Local variable names indexed by slot. Used by `OpClosure` to resolve
-    /// captured variable names to stack slot indices.
-    pub local_names: Vec<String>,
+    /// Local variables of the function, outermost scope first. Used by
+    /// `OpClosure` to resolve captured variable names to stack slot indices.
+    pub local_names: Arc<[LocalName]>,
 }
 
 impl CallFrame {
     /// Creates a new frame for a function body execution (for async functions).
     pub fn new_function_body(
         chunk: Arc<Chunk>,
-        local_names: Vec<String>,
+        local_names: Arc<[LocalName]>,
     ) -> Self {
         CallFrame {
             chunk,
@@ -59,13 +73,13 @@ impl CallFrame {
     /// - `slot_count`: total slots needed (params + locals)
     /// - `caller_stack_len`: stack length when this call was made
     /// - `closure_env`: the environment captured at function definition time
-    /// - `local_names`: names of local variables indexed by slot (params first, then lets)
+    /// - `local_names`: local variables and the bytecode ranges they are in scope for
     pub(crate) fn new_function(
         chunk: Arc<Chunk>,
         slots_base: usize,
         caller_stack_len: usize,
         closure_env: Arc<Mutex<Environment>>,
-        local_names: Vec<String>,
+        local_names: Arc<[LocalName]>,
     ) -> Self {
         CallFrame {
             chunk,
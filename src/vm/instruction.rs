@@ -17,6 +17,7 @@
 /// assigned sequentially for compact encoding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum Opcode {
     // ─── Stack operations (0x00–0x0F) ──────────────────────────────
     /// Push constant from pool onto stack. Operand: u16 index.
@@ -92,6 +93,8 @@ pub(crate) enum Opcode {
     OpIndex = 0x52,
     /// Set collection element: `collection, index, value →`.
     OpSetIndex = 0x53,
+    /// Prepare a for-in iterable: hashes become an array of their keys.
+    OpToIterable = 0x54,
 
     // ─── Structs & methods (0x60–0x6F) ─────────────────────────────
     /// Build struct from N field values. Operand: u8 field count.
@@ -126,6 +129,10 @@ pub(crate) enum Opcode {
     OpImportModule = 0x90,
     /// Get named export from module: `module → obj`.
     OpGetExport = 0x91,
+
+    // ─── Coverage (0xA0–0xAF) ──────────────────────────────────────
+    /// Count a statement on a source line as executed. Operand: u16 line.
+    OpMarkLine = 0xA0,
 }
 
 impl Opcode {
@@ -170,6 +177,7 @@ impl Opcode {
             0x51 => Some(Opcode::OpBuildHash),
             0x52 => Some(Opcode::OpIndex),
             0x53 => Some(Opcode::OpSetIndex),
+            0x54 => Some(Opcode::OpToIterable),
             0x60 => Some(Opcode::OpBuildStruct),
             0x61 => Some(Opcode::OpGetField),
             0x62 => Some(Opcode::OpSetField),
@@ -183,6 +191,7 @@ impl Opcode {
             0x81 => Some(Opcode::OpContinue),
             0x90 => Some(Opcode::OpImportModule),
             0x91 => Some(Opcode::OpGetExport),
+            0xA0 => Some(Opcode::OpMarkLine),
             _ => None,
         }
     }
@@ -218,7 +227,7 @@ impl Opcode {
             Opcode::OpClosure => 3, // u8 params + u16 chunk_offset
             Opcode::OpAwait => 0,
             Opcode::OpBuildArray | Opcode::OpBuildHash => 2,
-            Opcode::OpIndex | Opcode::OpSetIndex => 0,
+            Opcode::OpIndex | Opcode::OpSetIndex | Opcode::OpToIterable => 0,
             Opcode::OpBuildStruct => 1,
             Opcode::OpGetField | Opcode::OpSetField => 0,
             Opcode::OpCallMethod => 1,
@@ -230,6 +239,7 @@ impl Opcode {
             Opcode::OpBreak | Opcode::OpContinue => 2,
             Opcode::OpImportModule => 2,
             Opcode::OpGetExport => 0,
+            Opcode::OpMarkLine => 2,
         }
     }
 }
@@ -277,6 +287,7 @@ pub enum Instruction {
     BuildHash(u16),
     Index,
     SetIndex,
+    ToIterable,
     BuildStruct(u8),
     GetField,
     SetField,
@@ -290,6 +301,7 @@ pub enum Instruction {
     Continue(u16),
     ImportModule(u16),
     GetExport,
+    MarkLine(u16),
 }
 
 /// Encode a single instruction into a byte vector.
@@ -390,6 +402,7 @@ pub(crate) fn encode_instruction(code: &mut Vec<u8>, instr: Instruction) {
         }
         Instruction::Index => code.push(Opcode::OpIndex as u8),
         Instruction::SetIndex => code.push(Opcode::OpSetIndex as u8),
+        Instruction::ToIterable => code.push(Opcode::OpToIterable as u8),
         Instruction::BuildStruct(count) => {
             code.push(Opcode::OpBuildStruct as u8);
             code.push(count);
@@ -428,5 +441,9 @@ pub(crate) fn encode_instruction(code: &mut Vec<u8>, instr: Instruction) {
             code.extend_from_slice(&idx.to_be_bytes());
         }
         Instruction::GetExport => code.push(Opcode::OpGetExport as u8),
+        Instruction::MarkLine(line) => {
+            code.push(Opcode::OpMarkLine as u8);
+            code.extend_from_slice(&line.to_be_bytes());
+        }
     }
 }
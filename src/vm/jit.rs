@@ -0,0 +1,596 @@
+//! Optional native-code tier for hot numeric functions (`jit` feature).
+//!
+//! Every function chunk counts its calls. Once a function has been called
+//! [`HOT_CALL_THRESHOLD`] times with integer arguments, its bytecode is
+//! checked for purity: only integer/boolean constants, locals, arithmetic,
+//! comparisons, jumps and returns are allowed, so the function cannot touch
+//! globals, call anything or allocate. Such functions are translated to
+//! Cranelift IR and compiled to native code; everything else keeps running
+//! in the interpreter.
+//!
+//! Because compiled functions are pure, the native code never has to
+//! reproduce runtime errors. Division or modulo by zero make the native
+//! function *bail out*, and the call is simply re-run by the interpreter,
+//! which then reports the error as usual. So does `i64::MIN / -1`, which
+//! traps in hardware but wraps to `i64::MIN` in the interpreter.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use cranelift_codegen::ir::{AbiParam, Block, InstBuilder, MemFlags, Value, condcodes::IntCC, types};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{Linkage, Module, default_libcall_names};
+
+use crate::vm::chunk::Chunk;
+use crate::vm::instruction::Opcode;
+use crate::vm::obj::Object;
+
+/// Calls with integer arguments before a function is considered hot.
+pub(crate) const HOT_CALL_THRESHOLD: u32 = 1_000;
+
+/// Functions with more parameters than this are never compiled.
+const MAX_PARAMS: usize = 8;
+
+// Status codes returned by compiled functions.
+const BAIL_OUT: u32 = 0;
+const RETURNED_INT: u32 = 1;
+const RETURNED_BOOL: u32 = 2;
+const RETURNED_NULL: u32 = 3;
+
+type NativeFn = unsafe extern "C" fn(args: *const i64, out: *mut i64) -> u32;
+
+#[derive(Clone, Copy)]
+struct Compiled {
+    func: NativeFn,
+    params: usize,
+}
+
+/// Per-chunk JIT bookkeeping: the call counter and, once the chunk got hot,
+/// the outcome of compiling it (`None` if it is not a pure numeric function).
+#[derive(Default)]
+pub(crate) struct JitState {
+    calls: AtomicU32,
+    compiled: OnceLock<Option<Compiled>>,
+}
+
+impl JitState {
+    /// Whether the chunk has been compiled to native code.
+    pub(crate) fn is_compiled(&self) -> bool {
+        matches!(self.compiled.get(), Some(Some(_)))
+    }
+}
+
+impl Clone for JitState {
+    /// A cloned chunk starts cold again.
+    fn clone(&self) -> Self {
+        JitState::default()
+    }
+}
+
+impl fmt::Debug for JitState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JitState")
+            .field("calls", &self.calls.load(Ordering::Relaxed))
+            .field("compiled", &self.is_compiled())
+            .finish()
+    }
+}
+
+/// Runs `chunk` natively if it is (or just became) a compiled hot function.
+///
+/// Returns `None` whenever the interpreter has to run the call instead:
+/// the function is still cold, is not compilable, got non-integer
+/// arguments, or bailed out.
+pub(crate) fn try_call(chunk: &Chunk, params: usize, args: &[Object]) -> Option<Object> {
+    if args.len() != params || params > MAX_PARAMS {
+        return None;
+    }
+    let mut ints = [0i64; MAX_PARAMS];
+    for (slot, arg) in ints.iter_mut().zip(args) {
+        match arg {
+            Object::Integer(i) => *slot = *i,
+            _ => return None,
+        }
+    }
+
+    let compiled = match chunk.jit.compiled.get() {
+        Some(compiled) => (*compiled)?,
+        None => {
+            if chunk.jit.calls.fetch_add(1, Ordering::Relaxed) + 1 < HOT_CALL_THRESHOLD {
+                return None;
+            }
+            (*chunk.jit.compiled.get_or_init(|| compile(chunk, params)))?
+        }
+    };
+    if compiled.params != params {
+        return None;
+    }
+
+    let mut out = 0i64;
+    // SAFETY: the function was generated for exactly `params` arguments and
+    // only reads `args[..params]` and writes the single `out` slot.
+    let status = unsafe { (compiled.func)(ints.as_ptr(), &mut out) };
+    match status {
+        RETURNED_INT => Some(Object::Integer(out)),
+        RETURNED_BOOL => Some(Object::Boolean(out != 0)),
+        RETURNED_NULL => Some(Object::Null),
+        _ => None,
+    }
+}
+
+// ─── Analysis ───────────────────────────────────────────────────────
+
+/// Static type of a stack slot or local; all of them are held as `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ty {
+    Int,
+    Bool,
+    Null,
+}
+
+struct Instr {
+    ip: usize,
+    op: Opcode,
+    operand: usize,
+    next: usize,
+}
+
+fn decode(code: &[u8]) -> Option<Vec<Instr>> {
+    let mut instrs = Vec::new();
+    let mut ip = 0;
+    while ip < code.len() {
+        let op = Opcode::from_byte(code[ip])?;
+        let width = op.operand_width();
+        let operand = match width {
+            0 => 0,
+            1 => *code.get(ip + 1)? as usize,
+            2 => u16::from_be_bytes([*code.get(ip + 1)?, *code.get(ip + 2)?]) as usize,
+            _ => return None,
+        };
+        instrs.push(Instr { ip, op, operand, next: ip + 1 + width });
+        ip += 1 + width;
+    }
+    Some(instrs)
+}
+
+fn constant_ty(value: &Object) -> Option<Ty> {
+    match value {
+        Object::Integer(_) => Some(Ty::Int),
+        Object::Boolean(_) => Some(Ty::Bool),
+        Object::Null => Some(Ty::Null),
+        _ => None,
+    }
+}
+
+/// Result of type-checking a chunk: the stack types on entry to every
+/// reachable instruction and the single type each local slot holds.
+struct Analysis {
+    entry: HashMap<usize, Vec<Ty>>,
+    locals: Vec<Option<Ty>>,
+    max_stack: usize,
+}
+
+/// Abstractly interprets the bytecode, rejecting anything that is not a
+/// pure integer/boolean computation or whose types differ between paths.
+fn analyze(chunk: &Chunk, instrs: &[Instr], params: usize) -> Option<Analysis> {
+    let index: HashMap<usize, usize> = instrs.iter().enumerate().map(|(i, ins)| (ins.ip, i)).collect();
+    let mut locals: Vec<Option<Ty>> = vec![Some(Ty::Int); params];
+    let mut entry: HashMap<usize, Vec<Ty>> = HashMap::new();
+    let mut pending = vec![(0usize, Vec::new())];
+    let mut max_stack = 0;
+
+    while let Some((ip, stack)) = pending.pop() {
+        if let Some(seen) = entry.get(&ip) {
+            if *seen != stack {
+                return None;
+            }
+            continue;
+        }
+        entry.insert(ip, stack.clone());
+        let ins = &instrs[*index.get(&ip)?];
+        let mut stack = stack;
+
+        let binary = |stack: &mut Vec<Ty>| -> Option<(Ty, Ty)> {
+            let b = stack.pop()?;
+            let a = stack.pop()?;
+            Some((a, b))
+        };
+        match ins.op {
+            Opcode::OpConstant => stack.push(constant_ty(chunk.constants.get(ins.operand)?)?),
+            Opcode::OpPop => {
+                stack.pop()?;
+            }
+            Opcode::OpDup => stack.push(*stack.last()?),
+            Opcode::OpSwap => {
+                let len = stack.len();
+                if len < 2 {
+                    return None;
+                }
+                stack.swap(len - 1, len - 2);
+            }
+            Opcode::OpGetLocal => stack.push((*locals.get(ins.operand)?)?),
+            Opcode::OpSetLocal => {
+                let ty = stack.pop()?;
+                if locals.len() <= ins.operand {
+                    locals.resize(ins.operand + 1, None);
+                }
+                match locals[ins.operand] {
+                    None => locals[ins.operand] = Some(ty),
+                    Some(existing) if existing == ty => {}
+                    Some(_) => return None,
+                }
+            }
+            Opcode::OpAdd | Opcode::OpSubtract | Opcode::OpMultiply | Opcode::OpDivide | Opcode::OpModulo => {
+                if binary(&mut stack)? != (Ty::Int, Ty::Int) {
+                    return None;
+                }
+                stack.push(Ty::Int);
+            }
+            Opcode::OpLessThan | Opcode::OpGreaterThan | Opcode::OpLessEqual | Opcode::OpGreaterEqual => {
+                if binary(&mut stack)? != (Ty::Int, Ty::Int) {
+                    return None;
+                }
+                stack.push(Ty::Bool);
+            }
+            Opcode::OpEqual | Opcode::OpNotEqual => {
+                binary(&mut stack)?;
+                stack.push(Ty::Bool);
+            }
+            Opcode::OpNot => {
+                stack.pop()?;
+                stack.push(Ty::Bool);
+            }
+            Opcode::OpNegate => {
+                if stack.pop()? != Ty::Int {
+                    return None;
+                }
+                stack.push(Ty::Int);
+            }
+            Opcode::OpJump | Opcode::OpJumpBackward | Opcode::OpBreak | Opcode::OpContinue => {
+                pending.push((ins.operand, stack));
+                continue;
+            }
+            Opcode::OpJumpIfFalse | Opcode::OpJumpIfTruthy => {
+                stack.last()?;
+                pending.push((ins.operand, stack.clone()));
+            }
+            Opcode::OpPopJumpIfFalse => {
+                stack.pop()?;
+                pending.push((ins.operand, stack.clone()));
+            }
+            Opcode::OpReturnValue => {
+                stack.pop()?;
+                continue;
+            }
+            _ => return None,
+        }
+        max_stack = max_stack.max(stack.len());
+        pending.push((ins.next, stack));
+    }
+
+    Some(Analysis { entry, locals, max_stack })
+}
+
+// ─── Code generation ────────────────────────────────────────────────
+
+fn compile(chunk: &Chunk, params: usize) -> Option<Compiled> {
+    if params > MAX_PARAMS {
+        return None;
+    }
+    let instrs = decode(&chunk.code)?;
+    let analysis = analyze(chunk, &instrs, params)?;
+
+    let mut flags = settings::builder();
+    flags.set("use_colocated_libcalls", "false").ok()?;
+    flags.set("is_pic", "false").ok()?;
+    flags.set("opt_level", "speed").ok()?;
+    let isa = cranelift_native::builder().ok()?.finish(settings::Flags::new(flags)).ok()?;
+    let mut module = JITModule::new(JITBuilder::with_isa(isa, default_libcall_names()));
+
+    let ptr = module.target_config().pointer_type();
+    let mut ctx = module.make_context();
+    ctx.func.signature.params.push(AbiParam::new(ptr));
+    ctx.func.signature.params.push(AbiParam::new(ptr));
+    ctx.func.signature.returns.push(AbiParam::new(types::I32));
+    let id = module.declare_function("g_jit_fn", Linkage::Local, &ctx.func.signature).ok()?;
+
+    let mut fn_ctx = FunctionBuilderContext::new();
+    let mut b = FunctionBuilder::new(&mut ctx.func, &mut fn_ctx);
+    Translator::new(&mut b, &instrs, &analysis, params, chunk)?.run();
+    b.seal_all_blocks();
+    b.finalize();
+
+    module.define_function(id, &mut ctx).ok()?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions().ok()?;
+    let code = module.get_finalized_function(id);
+    // The module leaks its code memory on drop, keeping `code` valid for
+    // the rest of the process.
+    drop(module);
+
+    // SAFETY: `code` was generated with exactly the `NativeFn` signature.
+    let func = unsafe { std::mem::transmute::<*const u8, NativeFn>(code) };
+    Some(Compiled { func, params })
+}
+
+fn var(index: usize) -> Variable {
+    Variable::from_u32(index as u32)
+}
+
+struct Translator<'a, 'b> {
+    b: &'a mut FunctionBuilder<'b>,
+    instrs: &'a [Instr],
+    analysis: &'a Analysis,
+    chunk: &'a Chunk,
+    blocks: HashMap<usize, Block>,
+    bail: Block,
+    out: Value,
+    stack_base: usize,
+    depth: usize,
+}
+
+impl<'a, 'b> Translator<'a, 'b> {
+    fn new(
+        b: &'a mut FunctionBuilder<'b>,
+        instrs: &'a [Instr],
+        analysis: &'a Analysis,
+        params: usize,
+        chunk: &'a Chunk,
+    ) -> Option<Self> {
+        let entry = b.create_block();
+        b.append_block_params_for_function_params(entry);
+        b.switch_to_block(entry);
+        let args = b.block_params(entry)[0];
+        let out = b.block_params(entry)[1];
+
+        // Locals first, then one variable per operand stack depth. Every
+        // variable gets a definition up front so all paths see a value.
+        let stack_base = analysis.locals.len().max(params);
+        let zero = b.ins().iconst(types::I64, 0);
+        // Variables are numbered in declaration order, so `var(index)` names
+        // the one declared here.
+        for index in 0..stack_base + analysis.max_stack {
+            let variable = b.declare_var(types::I64);
+            let value = if index < params {
+                b.ins().load(types::I64, MemFlags::trusted(), args, (index * 8) as i32)
+            } else {
+                zero
+            };
+            b.def_var(variable, value);
+        }
+
+        // A block starts at every jump target and after every branch.
+        let mut leaders = BTreeSet::new();
+        leaders.insert(0);
+        for ins in instrs {
+            match ins.op {
+                Opcode::OpJump
+                | Opcode::OpJumpBackward
+                | Opcode::OpBreak
+                | Opcode::OpContinue
+                | Opcode::OpJumpIfFalse
+                | Opcode::OpJumpIfTruthy
+                | Opcode::OpPopJumpIfFalse => {
+                    leaders.insert(ins.operand);
+                    leaders.insert(ins.next);
+                }
+                Opcode::OpReturnValue => {
+                    leaders.insert(ins.next);
+                }
+                _ => {}
+            }
+        }
+        let blocks: HashMap<usize, Block> = leaders
+            .into_iter()
+            .filter(|ip| analysis.entry.contains_key(ip))
+            .map(|ip| (ip, b.create_block()))
+            .collect();
+        b.ins().jump(*blocks.get(&0)?, &[]);
+
+        let bail = b.create_block();
+        b.switch_to_block(bail);
+        let status = b.ins().iconst(types::I32, BAIL_OUT as i64);
+        b.ins().return_(&[status]);
+
+        Some(Translator { b, instrs, analysis, chunk, blocks, bail, out, stack_base, depth: 0 })
+    }
+
+    fn push(&mut self, value: Value) {
+        self.b.def_var(var(self.stack_base + self.depth), value);
+        self.depth += 1;
+    }
+
+    fn pop(&mut self) -> Value {
+        self.depth -= 1;
+        self.b.use_var(var(self.stack_base + self.depth))
+    }
+
+    fn peek(&mut self) -> Value {
+        self.b.use_var(var(self.stack_base + self.depth - 1))
+    }
+
+    fn top_ty(&self, ip: usize, from_top: usize) -> Ty {
+        let stack = &self.analysis.entry[&ip];
+        stack[stack.len() - 1 - from_top]
+    }
+
+    fn bool_value(&mut self, cc: IntCC, a: Value, b: Value) -> Value {
+        let flag = self.b.ins().icmp(cc, a, b);
+        self.b.ins().uextend(types::I64, flag)
+    }
+
+    /// Emits `if b == 0 || (a == i64::MIN && b == -1) { bail }`.
+    fn guard_division(&mut self, a: Value, b: Value) {
+        let zero = self.b.ins().icmp_imm(IntCC::Equal, b, 0);
+        let min = self.b.ins().icmp_imm(IntCC::Equal, a, i64::MIN);
+        let minus_one = self.b.ins().icmp_imm(IntCC::Equal, b, -1);
+        let overflow = self.b.ins().band(min, minus_one);
+        let invalid = self.b.ins().bor(zero, overflow);
+        let ok = self.b.create_block();
+        self.b.ins().brif(invalid, self.bail, &[], ok, &[]);
+        self.b.switch_to_block(ok);
+    }
+
+    /// Branches to `target` when the top value's truthiness equals `when`.
+    fn branch(&mut self, ty: Ty, value: Value, when: bool, target: usize, next: usize) {
+        let (target, next) = (self.blocks[&target], self.blocks[&next]);
+        match ty {
+            // `null` is always falsy.
+            Ty::Null => {
+                self.b.ins().jump(if when { next } else { target }, &[]);
+            }
+            Ty::Int | Ty::Bool => {
+                let (truthy, falsy) = if when { (target, next) } else { (next, target) };
+                self.b.ins().brif(value, truthy, &[], falsy, &[]);
+            }
+        }
+    }
+
+    fn run(mut self) {
+        let mut terminated = true;
+        for ins in self.instrs {
+            if let Some(&block) = self.blocks.get(&ins.ip) {
+                if !terminated {
+                    self.b.ins().jump(block, &[]);
+                }
+                self.b.switch_to_block(block);
+                terminated = false;
+            }
+            let Some(stack) = self.analysis.entry.get(&ins.ip) else {
+                terminated = true;
+                continue;
+            };
+            if terminated {
+                continue;
+            }
+            self.depth = stack.len();
+
+            match ins.op {
+                Opcode::OpConstant => {
+                    let value = match &self.chunk.constants[ins.operand] {
+                        Object::Integer(i) => *i,
+                        Object::Boolean(b) => *b as i64,
+                        _ => 0,
+                    };
+                    let value = self.b.ins().iconst(types::I64, value);
+                    self.push(value);
+                }
+                Opcode::OpPop => {
+                    self.pop();
+                }
+                Opcode::OpDup => {
+                    let value = self.peek();
+                    self.push(value);
+                }
+                Opcode::OpSwap => {
+                    let b = self.pop();
+                    let a = self.pop();
+                    self.push(b);
+                    self.push(a);
+                }
+                Opcode::OpGetLocal => {
+                    let value = self.b.use_var(var(ins.operand));
+                    self.push(value);
+                }
+                Opcode::OpSetLocal => {
+                    let value = self.pop();
+                    self.b.def_var(var(ins.operand), value);
+                }
+                Opcode::OpAdd | Opcode::OpSubtract | Opcode::OpMultiply | Opcode::OpDivide | Opcode::OpModulo => {
+                    let b = self.pop();
+                    let a = self.pop();
+                    let value = match ins.op {
+                        Opcode::OpAdd => self.b.ins().iadd(a, b),
+                        Opcode::OpSubtract => self.b.ins().isub(a, b),
+                        Opcode::OpMultiply => self.b.ins().imul(a, b),
+                        Opcode::OpDivide => {
+                            self.guard_division(a, b);
+                            self.b.ins().sdiv(a, b)
+                        }
+                        _ => {
+                            self.guard_division(a, b);
+                            self.b.ins().srem(a, b)
+                        }
+                    };
+                    self.push(value);
+                }
+                Opcode::OpLessThan | Opcode::OpGreaterThan | Opcode::OpLessEqual | Opcode::OpGreaterEqual => {
+                    let cc = match ins.op {
+                        Opcode::OpLessThan => IntCC::SignedLessThan,
+                        Opcode::OpGreaterThan => IntCC::SignedGreaterThan,
+                        Opcode::OpLessEqual => IntCC::SignedLessThanOrEqual,
+                        _ => IntCC::SignedGreaterThanOrEqual,
+                    };
+                    let b = self.pop();
+                    let a = self.pop();
+                    let value = self.bool_value(cc, a, b);
+                    self.push(value);
+                }
+                Opcode::OpEqual | Opcode::OpNotEqual => {
+                    let same_type = self.top_ty(ins.ip, 0) == self.top_ty(ins.ip, 1);
+                    let b = self.pop();
+                    let a = self.pop();
+                    let equal = ins.op == Opcode::OpEqual;
+                    let value = if same_type {
+                        self.bool_value(if equal { IntCC::Equal } else { IntCC::NotEqual }, a, b)
+                    } else {
+                        // Values of different types never compare equal.
+                        self.b.ins().iconst(types::I64, !equal as i64)
+                    };
+                    self.push(value);
+                }
+                Opcode::OpNot => {
+                    let ty = self.top_ty(ins.ip, 0);
+                    let a = self.pop();
+                    let value = match ty {
+                        Ty::Null => self.b.ins().iconst(types::I64, 1),
+                        Ty::Int | Ty::Bool => {
+                            let zero = self.b.ins().iconst(types::I64, 0);
+                            self.bool_value(IntCC::Equal, a, zero)
+                        }
+                    };
+                    self.push(value);
+                }
+                Opcode::OpNegate => {
+                    let a = self.pop();
+                    let value = self.b.ins().ineg(a);
+                    self.push(value);
+                }
+                Opcode::OpJump | Opcode::OpJumpBackward | Opcode::OpBreak | Opcode::OpContinue => {
+                    self.b.ins().jump(self.blocks[&ins.operand], &[]);
+                    terminated = true;
+                }
+                Opcode::OpJumpIfFalse | Opcode::OpJumpIfTruthy => {
+                    let ty = self.top_ty(ins.ip, 0);
+                    let value = self.peek();
+                    self.branch(ty, value, ins.op == Opcode::OpJumpIfTruthy, ins.operand, ins.next);
+                    terminated = true;
+                }
+                Opcode::OpPopJumpIfFalse => {
+                    let ty = self.top_ty(ins.ip, 0);
+                    let value = self.pop();
+                    self.branch(ty, value, false, ins.operand, ins.next);
+                    terminated = true;
+                }
+                Opcode::OpReturnValue => {
+                    let status = match self.top_ty(ins.ip, 0) {
+                        Ty::Int => RETURNED_INT,
+                        Ty::Bool => RETURNED_BOOL,
+                        Ty::Null => RETURNED_NULL,
+                    };
+                    let value = self.pop();
+                    self.b.ins().store(MemFlags::trusted(), value, self.out, 0);
+                    let status = self.b.ins().iconst(types::I32, status as i64);
+                    self.b.ins().return_(&[status]);
+                    terminated = true;
+                }
+                // `analyze` rejected every other opcode.
+                _ => unreachable!("opcode {:?} is not compiled", ins.op),
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Resource quotas for running untrusted scripts.
+//!
+//! Limits are opt-in: a [`VirtualMachine`](crate::vm::vm::VirtualMachine)
+//! only enforces them after [`set_limits`](crate::vm::vm::VirtualMachine::set_limits)
+//! and shares them with the VMs it starts for async calls.
+//!
+//! The execution budget counts *steps* — loop iterations and function
+//! calls, which is what every runaway script is made of — and checks the
+//! wall-clock deadline every [`CLOCK_CHECK_INTERVAL`] steps. Running out of
+//! either stops the run with
+//! [`RuntimeError::ExecutionLimitExceeded`](crate::vm::runtime::runtime_errors::RuntimeError::ExecutionLimitExceeded),
+//! which `try` cannot catch.
+//!
+//! Builtins whose result size is known from their arguments (`range`,
+//! `bytes(n)`, `repeat`, `pad_start`, ...) ask [`reserve`] first and refuse
+//! to allocate past a limit. Every other value is checked right after it is
+//! produced, outside the VM's arithmetic fast path; such a value is built
+//! from values that were within the limits, so it can overshoot a limit by
+//! about a factor of two (`s + s`) but not without bound.
+//!
+//! Heap usage is estimated by walking the values on the stack and in the
+//! globals, including what `ref`s and `std::collections` values hold. The
+//! walk runs after [`HEAP_CHECK_INTERVAL`] instructions, or after as many
+//! instructions as it visited values last time if that is more, so a large
+//! heap costs about one value visited per instruction instead of a full
+//! walk every few instructions. Growth is therefore noticed late rather than
+//! refused, and the estimate leaves out closure environments and native
+//! handles (files, sockets, databases, ...).
+//!
+//! A value over a limit is thrown as a string exception, so scripts can
+//! catch it; outside of any `try` the run fails with
+//! [`RuntimeError::ResourceLimitExceeded`](crate::vm::runtime::runtime_errors::RuntimeError::ResourceLimitExceeded).
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// Fewest dispatched instructions between two heap measurements.
+pub(crate) const HEAP_CHECK_INTERVAL: usize = 1024;
+
+/// Steps between two looks at the clock.
+pub(crate) const CLOCK_CHECK_INTERVAL: u64 = 256;
+
+/// Upper bounds on what a script may allocate and how long it may run.
+/// `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Approximate bytes held by the values a script can reach.
+    pub max_heap_bytes: Option<usize>,
+    /// Maximum number of elements in an array.
+    pub max_array_len: Option<usize>,
+    /// Maximum number of entries in a hash.
+    pub max_hash_len: Option<usize>,
+    /// Maximum length of a string, in bytes.
+    pub max_string_len: Option<usize>,
+    /// Maximum number of loop iterations and function calls.
+    pub max_steps: Option<u64>,
+    /// Wall-clock time the script may run for, counted from `set_limits`.
+    pub timeout: Option<Duration>,
+}
+
+fn over(what: &str, size: usize, limit: Option<usize>) -> Option<String> {
+    match limit {
+        Some(limit) if size > limit => Some(format!("{} of {} exceeds the limit of {}", what, size, limit)),
+        _ => None,
+    }
+}
+
+impl ResourceLimits {
+    /// Describes how `value` breaks a size limit, if it does. Only the value
+    /// itself is inspected, not what it contains: nested values were checked
+    /// when they were created.
+    pub(crate) fn check_value(&self, value: &Object) -> Option<String> {
+        match value {
+            Object::String(s) => over("string length", s.len(), self.max_string_len),
+            Object::Array(items) => over("array length", items.len(), self.max_array_len),
+            Object::Hash(pairs) => over("hash size", pairs.len(), self.max_hash_len),
+            _ => None,
+        }
+    }
+
+    /// Describes how `used` heap bytes break the heap limit, if they do.
+    pub(crate) fn check_heap(&self, used: usize) -> Option<String> {
+        over("heap usage (bytes)", used, self.max_heap_bytes)
+    }
+}
+
+thread_local! {
+    /// Limits of the VM whose builtin call is running on this thread.
+    static CALL_LIMITS: RefCell<Option<Arc<ActiveLimits>>> = const { RefCell::new(None) };
+    /// The allocation a builtin refused during that call.
+    static REFUSED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// What a builtin is about to allocate.
+pub(crate) enum Alloc {
+    /// A string of this many bytes.
+    String(usize),
+    /// An array of this many elements.
+    Array(usize),
+    /// A byte buffer of this many bytes.
+    Bytes(usize),
+}
+
+/// Runs `call`, a synchronous call into builtins, with `limits` visible to
+/// [`reserve`]. Returns what `call` returned together with the allocation a
+/// builtin refused, if any.
+pub(crate) fn with_call_limits<T>(limits: &Option<Arc<ActiveLimits>>, call: impl FnOnce() -> T) -> (T, Option<String>) {
+    let Some(limits) = limits else {
+        return (call(), None);
+    };
+    let previous = CALL_LIMITS.replace(Some(Arc::clone(limits)));
+    let result = call();
+    CALL_LIMITS.set(previous);
+    (result, REFUSED.take())
+}
+
+/// Checks `alloc` against the limits of the calling VM before a builtin
+/// makes it: against the size limit for its kind and, on its own, against
+/// the heap limit. On error the builtin returns the message as its failure and
+/// the VM raises it as a limit violation.
+pub(crate) fn reserve(alloc: Alloc) -> Result<(), String> {
+    let refused = CALL_LIMITS.with_borrow(|active| {
+        let limits = &active.as_ref()?.limits;
+        match alloc {
+            Alloc::String(len) => over("string length", len, limits.max_string_len).or_else(|| limits.check_heap(len)),
+            Alloc::Array(len) => over("array length", len, limits.max_array_len)
+                .or_else(|| limits.check_heap(len.saturating_mul(size_of::<Object>()))),
+            Alloc::Bytes(len) => limits.check_heap(len),
+        }
+    });
+    match refused {
+        Some(message) => {
+            REFUSED.set(Some(message.clone()));
+            Err(message)
+        }
+        None => Ok(()),
+    }
+}
+
+/// The limits of one run together with the budget it has used so far.
+pub struct ActiveLimits {
+    pub(crate) limits: ResourceLimits,
+    steps: AtomicU64,
+    deadline: Option<Instant>,
+}
+
+impl ActiveLimits {
+    pub(crate) fn new(limits: ResourceLimits) -> Self {
+        let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+        ActiveLimits { limits, steps: AtomicU64::new(0), deadline }
+    }
+
+    /// Counts one step against the budget.
+    pub(crate) fn tick(&self) -> Result<(), RuntimeError> {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max) = self.limits.max_steps
+            && steps > max
+        {
+            return Err(RuntimeError::ExecutionLimitExceeded(format!("ran for more than {} steps", max)));
+        }
+        if steps.is_multiple_of(CLOCK_CHECK_INTERVAL)
+            && let (Some(deadline), Some(timeout)) = (self.deadline, self.limits.timeout)
+            && Instant::now() >= deadline
+        {
+            return Err(RuntimeError::ExecutionLimitExceeded(format!("ran for more than {:?}", timeout)));
+        }
+        Ok(())
+    }
+}
+
+/// A measurement of the heap.
+#[derive(Default)]
+pub(crate) struct HeapSize {
+    /// Approximate bytes held.
+    pub(crate) bytes: usize,
+    /// Values visited to find out, which is what measuring costs.
+    pub(crate) values: usize,
+}
+
+impl std::ops::AddAssign for HeapSize {
+    fn add_assign(&mut self, other: HeapSize) {
+        self.bytes += other.bytes;
+        self.values += other.values;
+    }
+}
+
+/// Estimates the bytes owned by `roots` and everything they contain,
+/// following `ref`s and collections. A shared value is counted once, and
+/// one that another thread holds locked is skipped.
+pub(crate) fn approx_heap_size<'a>(roots: impl IntoIterator<Item = &'a Object>) -> HeapSize {
+    let mut handles = Vec::new();
+    let mut total = measure(roots, &mut handles);
+    let mut seen = HashSet::new();
+    while let Some(handle) = handles.pop() {
+        match &handle {
+            Object::Ref(cell) if seen.insert(Arc::as_ptr(cell) as usize) => {
+                if let Ok(inner) = cell.try_lock() {
+                    total += measure([&*inner], &mut handles);
+                }
+            }
+            Object::Collection(data) if seen.insert(Arc::as_ptr(data) as usize) => {
+                if let Ok(data) = data.try_lock() {
+                    total += measure(&data.items, &mut handles);
+                }
+            }
+            Object::OrderedMap(map) if seen.insert(Arc::as_ptr(map) as usize) => {
+                if let Ok(map) = map.try_lock() {
+                    total += measure(map.iter().flat_map(|(k, v)| [k, v]), &mut handles);
+                }
+            }
+            _ => {}
+        }
+    }
+    total
+}
+
+/// Adds up the bytes owned by `roots`, leaving the shared handles it meets
+/// in `handles` for the caller to open.
+fn measure<'a>(roots: impl IntoIterator<Item = &'a Object>, handles: &mut Vec<Object>) -> HeapSize {
+    let mut pending: Vec<&Object> = roots.into_iter().collect();
+    let mut total = HeapSize::default();
+    while let Some(value) = pending.pop() {
+        total.values += 1;
+        total.bytes += size_of::<Object>();
+        match value {
+            Object::BigInteger(b) => total.bytes += b.bits() as usize / 8,
+            Object::String(s) => total.bytes += s.len(),
+            Object::Bytes(bytes) => total.bytes += bytes.capacity(),
+            Object::Array(items) => pending.extend(items.iter()),
+            Object::Hash(pairs) => {
+                for (k, v) in pairs.iter() {
+                    pending.push(k);
+                    pending.push(v);
+                }
+            }
+            Object::Struct(s) => {
+                total.bytes += s.name.capacity();
+                for (name, field) in &s.fields {
+                    total.bytes += name.capacity();
+                    pending.push(field);
+                }
+            }
+            Object::ReturnValue(inner) | Object::ThrownValue(inner) => pending.push(inner),
+            Object::Ref(_) | Object::Collection(_) | Object::OrderedMap(_) => handles.push(value.clone()),
+            _ => {}
+        }
+    }
+    total
+}
@@ -14,12 +14,29 @@
 //! - `compiler` — AST → bytecode compiler
 //! - `vm` — execution engine
 //! - `ops` — modular operation implementations
+//! - `limits` — opt-in memory quotas for untrusted scripts
+//! - `sandbox` — capability groups (file system, network, ...) hosts can deny
+//! - `profiler` — per-function timing for `gl run --profile`
+//! - `coverage` — executed-line counts for `gl test --coverage`
+//! - `convert` — `From`/`TryFrom` between objects and Rust types, and serde
+//!   support
+//! - `persist` — `Object::to_bytes`/`from_bytes`, for keeping values between runs
+//! - `jit` — optional native-code tier for hot numeric functions (`jit` feature)
 
 pub mod chunk;
 pub mod compiler;
+pub mod convert;
+pub mod coverage;
 pub mod frame;
 pub mod instruction;
+pub mod limits;
+#[cfg(feature = "jit")]
+pub(crate) mod jit;
 pub mod ops;
+pub mod persist;
+pub mod profiler;
+pub mod sandbox;
+#[allow(clippy::module_inception)]
 pub mod vm;
 pub mod runtime;
 pub mod obj;
\ No newline at end of file
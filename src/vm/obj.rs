@@ -7,6 +7,7 @@
 //! ReturnValue, ThrownValue).
 
 use ahash::AHasher;
+use std::any::Any;
 use num_bigint::BigInt;
 use std::fmt;
 use std::hash::{BuildHasherDefault, Hash, Hasher};
@@ -14,6 +15,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::ast::ast::Ident;
 use crate::vm::runtime::env::Environment;
+use crate::vm::frame::LocalName;
 use crate::vm::runtime::runtime_errors::RuntimeError;
 
 #[cfg(feature = "wasm")]
@@ -21,6 +23,118 @@ use crate::wasm::WasmInstance;
 
 pub type HashMap<K, V> = std::collections::HashMap<K, V, BuildHasherDefault<AHasher>>;
 
+/// String value shared between copies, so cloning an `Object::String`
+/// (reading a variable, a constant, an array element...) never copies its
+/// characters. Derefs to `str`.
+///
+/// It holds an `Arc<String>` rather than an `Arc<str>` because the thin
+/// pointer keeps `Object` at 16 bytes.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedString(Arc<String>);
+
+impl SharedString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Appends `s`, in place when no other copy shares this string.
+    pub fn push_str(&mut self, s: &str) {
+        Arc::make_mut(&mut self.0).push_str(s);
+    }
+}
+
+impl std::ops::Deref for SharedString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::borrow::Borrow<str> for SharedString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for SharedString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SharedString {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl AsRef<std::path::Path> for SharedString {
+    fn as_ref(&self) -> &std::path::Path {
+        std::path::Path::new(&*self.0)
+    }
+}
+
+impl AsRef<std::ffi::OsStr> for SharedString {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        std::ffi::OsStr::new(&*self.0)
+    }
+}
+
+impl From<String> for SharedString {
+    fn from(s: String) -> Self {
+        SharedString(Arc::new(s))
+    }
+}
+
+impl From<&str> for SharedString {
+    fn from(s: &str) -> Self {
+        SharedString(Arc::new(s.to_string()))
+    }
+}
+
+impl From<&String> for SharedString {
+    fn from(s: &String) -> Self {
+        SharedString(Arc::new(s.clone()))
+    }
+}
+
+impl From<SharedString> for String {
+    fn from(s: SharedString) -> Self {
+        Arc::unwrap_or_clone(s.0)
+    }
+}
+
+impl PartialEq<str> for SharedString {
+    fn eq(&self, other: &str) -> bool {
+        self.0.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedString {
+    fn eq(&self, other: &&str) -> bool {
+        self.0.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SharedString {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl fmt::Display for SharedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for SharedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
 /// Struct instance with fields and methods. Boxed to reduce the size of the Object enum.
 #[derive(Clone)]
 pub struct StructObject {
@@ -37,12 +151,16 @@ pub struct ModuleObject {
 }
 
 /// Data for a user-defined function.
+///
+/// `params` and `local_names` are shared with every closure instance and call
+/// frame built from the same definition, so cloning a function value or
+/// calling it never copies them.
 #[derive(Clone)]
 pub struct FunctionData {
-    pub params: Vec<Ident>,
+    pub params: Arc<[Ident]>,
     pub chunk: Arc<crate::vm::chunk::Chunk>,
     pub env: Arc<Mutex<Environment>>,
-    pub local_names: Vec<String>,
+    pub local_names: Arc<[LocalName]>,
 }
 
 /// Data for a simple builtin function.
@@ -72,7 +190,59 @@ pub struct BuiltinStdAsyncData {
     pub func: AsyncStdFunction,
 }
 
+/// Data for a function the host registered with
+/// [`Engine::register_fn`](crate::Engine::register_fn) or
+/// [`Engine::register_async_fn`](crate::Engine::register_async_fn).
+#[derive(Clone)]
+pub struct NativeFunctionData {
+    pub name: String,
+    pub func: NativeCallable,
+}
+
+/// The Rust side of a [`NativeFunctionData`]. It gets the arguments as
+/// passed and checks their number itself.
+#[derive(Clone)]
+pub enum NativeCallable {
+    Sync(Arc<NativeFn>),
+    /// Calling it gives the script a future to `await`, like calling an
+    /// `async fn`.
+    Async(Arc<AsyncNativeFn>),
+}
+
+pub type NativeFn = dyn Fn(Vec<Object>) -> Result<Object, RuntimeError> + Send + Sync;
+pub type NativeFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send>>;
+pub type AsyncNativeFn = dyn Fn(Vec<Object>) -> NativeFuture + Send + Sync;
+
+/// A Rust value the host handed to scripts, made by
+/// [`NativeType::wrap`](crate::NativeType::wrap). Copies of the object
+/// share the value, so it works as a handle.
+#[derive(Clone)]
+pub struct NativeObject {
+    pub value: Arc<dyn Any + Send + Sync>,
+    pub methods: Arc<NativeMethods>,
+}
+
+/// The methods scripts can call on the [`NativeObject`]s of one type.
+#[derive(Clone)]
+pub struct NativeMethods {
+    /// What `type()` says the objects are.
+    pub type_name: String,
+    pub methods: HashMap<String, Arc<NativeMethodFn>>,
+}
+
+/// A method of a native type: gets the object's value and the arguments.
+pub type NativeMethodFn = dyn Fn(&(dyn Any + Send + Sync), Vec<Object>) -> Result<Object, RuntimeError> + Send + Sync;
+
+/// Open file returned by `io.open`. Copies of the value share the same
+/// handle, so reads and writes advance one cursor. `reader` is `None` once
+/// the file has been closed.
+pub struct FileHandle {
+    pub path: String,
+    pub reader: Option<std::io::BufReader<std::fs::File>>,
+}
+
 /// Data for a WASM imported function.
+#[cfg(feature = "wasm")]
 #[derive(Clone)]
 pub struct WasmFunctionData {
     pub module_name: String,
@@ -92,20 +262,51 @@ pub struct WasmModuleData {
 /// The universal value type of the G-lang runtime.
 ///
 /// Every expression in a G-lang program evaluates to one of these variants.
-/// Large variants are boxed to keep the enum size small (typically 24-32 bytes
-/// on 64-bit systems instead of 72+ bytes).
+/// Every payload is at most one word, so an `Object` is 16 bytes on 64-bit
+/// systems: large variants are boxed and strings are shared.
+///
+/// The tag is a full word too. With a one-byte tag, storing a small value
+/// (`Integer`, `Boolean`) and then moving it as a whole, which the VM does
+/// on every instruction, defeats store-to-load forwarding and makes the
+/// stack measurably slower.
 #[derive(Clone)]
+#[repr(u64)]
 pub enum Object {
     Integer(i64),
     /// Arbitrary-precision integer. Boxed to reduce enum size.
     BigInteger(Box<BigInt>),
     Float(f64),
     Boolean(bool),
-    String(String),
+    /// Shared immutable string; see [`SharedString`].
+    String(SharedString),
     /// Dynamic array. Boxed to reduce enum size (Vec is 24 bytes).
     Array(Box<Vec<Object>>),
     /// Hash map with Object keys. Boxed to reduce enum size (HashMap is ~48+ bytes).
     Hash(Box<HashMap<Object, Object>>),
+    /// Raw byte buffer. Boxed to reduce enum size.
+    Bytes(Box<Vec<u8>>),
+    /// Open file handle.
+    File(Arc<Mutex<FileHandle>>),
+    /// HTTP server created by `std::server`.
+    #[cfg(not(target_arch = "wasm32"))]
+    Server(Arc<Mutex<crate::std::server::ServerData>>),
+    /// Bound UDP socket created by `std::net`.
+    #[cfg(not(target_arch = "wasm32"))]
+    UdpSocket(Arc<tokio::net::UdpSocket>),
+    /// Queue, stack or deque created by `std::collections`.
+    Collection(Arc<Mutex<crate::std::collections::CollectionData>>),
+    /// Insertion-ordered map created by `std::collections`.
+    OrderedMap(Arc<Mutex<crate::std::collections::OrderedMapData>>),
+    /// SQLite database opened by `std::sqlite`.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+    Sqlite(Arc<Mutex<crate::std::sqlite::SqliteHandle>>),
+    /// Postgres/MySQL connection pool opened by `std::db`.
+    #[cfg(not(target_arch = "wasm32"))]
+    Db(Arc<crate::std::db::DbHandle>),
+    /// Shared mutable cell created by `ref()`. Copies of the value point at
+    /// the same cell, so index and field assignments through one copy are
+    /// visible through every other.
+    Ref(Arc<Mutex<Object>>),
     /// User-defined function. Boxed to reduce size.
     Function(Box<FunctionData>),
     /// Async user-defined function. Boxed to reduce size.
@@ -116,7 +317,12 @@ pub enum Object {
     BuiltinStd(Box<BuiltinStdData>),
     /// Async builtin function.
     BuiltinStdAsync(Box<BuiltinStdAsyncData>),
+    /// Function registered by the program embedding the interpreter.
+    NativeFunction(Box<NativeFunctionData>),
+    /// Opaque value owned by the program embedding the interpreter.
+    Native(Box<NativeObject>),
     /// WASM imported function.
+    #[cfg(feature = "wasm")]
     WasmImportedFunction(Box<WasmFunctionData>),
     /// Struct instance with fields and methods.
     Struct(Box<StructObject>),
@@ -136,21 +342,7 @@ pub enum Object {
     /// Value passed to `throw`.
     ThrownValue(Box<Object>),
     /// Unresolved async computation.
-    Future(
-        Arc<
-            Mutex<
-                Option<
-                    std::pin::Pin<
-                        Box<
-                            dyn std::future::Future<Output = Result<Object, RuntimeError>>
-                                + Send
-                                + 'static,
-                        >,
-                    >,
-                >,
-            >,
-        >,
-    ),
+    Future(Arc<Mutex<Option<NativeFuture>>>),
     /// Compiled WASM module.
     #[cfg(feature = "wasm")]
     WasmModule(Box<WasmModuleData>),
@@ -170,14 +362,39 @@ impl fmt::Debug for Object {
             Object::String(s) => write!(f, "String(\"{}\")", s),
             Object::Array(a) => write!(f, "Array({:?})", a),
             Object::Hash(h) => write!(f, "Hash({:?})", h),
+            Object::Bytes(b) => write!(f, "Bytes({:?})", b),
+            Object::File(h) => write!(f, "File(\"{}\")", h.lock().unwrap().path),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Server(_) => write!(f, "Server(_)"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::UdpSocket(s) => write!(f, "UdpSocket({:?})", s.local_addr().ok()),
+            Object::Collection(c) => match c.try_lock() {
+                Ok(data) => write!(f, "Collection({:?}, {:?})", data.kind, data.items),
+                Err(_) => write!(f, "Collection(..)"),
+            },
+            Object::OrderedMap(m) => match m.try_lock() {
+                Ok(map) => write!(f, "OrderedMap({:?})", *map),
+                Err(_) => write!(f, "OrderedMap(..)"),
+            },
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            Object::Sqlite(h) => write!(f, "Sqlite(\"{}\")", h.lock().unwrap().path),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Db(h) => write!(f, "Db({})", h.scheme),
+            Object::Ref(r) => match r.try_lock() {
+                Ok(inner) => write!(f, "Ref({:?})", *inner),
+                Err(_) => write!(f, "Ref(..)"),
+            },
             Object::Function(d) => write!(f, "Function(params:{:?})", d.params),
             Object::AsyncFunction(d) => write!(f, "AsyncFunction(params:{:?})", d.params),
+            #[cfg(feature = "wasm")]
             Object::WasmImportedFunction(d) => {
                 write!(f, "WasmImportedFunction({}::{})", d.module_name, d.func_name)
             }
             Object::Builtin(d) => write!(f, "Builtin(\"{}\")", d.name),
             Object::BuiltinStd(d) => write!(f, "BuiltinStd(\"{}\")", d.name),
             Object::BuiltinStdAsync(d) => write!(f, "BuiltinStdAsync(\"{}\")", d.name),
+            Object::NativeFunction(d) => write!(f, "NativeFunction(\"{}\")", d.name),
+            Object::Native(n) => write!(f, "Native({})", n.methods.type_name),
             Object::Struct(s) => write!(
                 f,
                 "Struct(name:{}, fields:{:?}, methods:{:?})",
@@ -218,6 +435,20 @@ impl PartialEq for Object {
             (Object::String(a), Object::String(b)) => a == b,
             (Object::Array(a), Object::Array(b)) => a == b,
             (Object::Hash(a), Object::Hash(b)) => a == b,
+            (Object::Bytes(a), Object::Bytes(b)) => a == b,
+            (Object::File(a), Object::File(b)) => Arc::ptr_eq(a, b),
+            #[cfg(not(target_arch = "wasm32"))]
+            (Object::Server(a), Object::Server(b)) => Arc::ptr_eq(a, b),
+            #[cfg(not(target_arch = "wasm32"))]
+            (Object::UdpSocket(a), Object::UdpSocket(b)) => Arc::ptr_eq(a, b),
+            (Object::Collection(a), Object::Collection(b)) => Arc::ptr_eq(a, b),
+            (Object::OrderedMap(a), Object::OrderedMap(b)) => Arc::ptr_eq(a, b),
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            (Object::Sqlite(a), Object::Sqlite(b)) => Arc::ptr_eq(a, b),
+            #[cfg(not(target_arch = "wasm32"))]
+            (Object::Db(a), Object::Db(b)) => Arc::ptr_eq(a, b),
+            (Object::Native(a), Object::Native(b)) => Arc::ptr_eq(&a.value, &b.value),
+            (Object::Ref(a), Object::Ref(b)) => Arc::ptr_eq(a, b),
             (Object::Null, Object::Null) => true,
             (Object::ReturnValue(a), Object::ReturnValue(b)) => a == b,
             (Object::Error(a), Object::Error(b)) => a == b,
@@ -231,12 +462,18 @@ impl PartialEq for Object {
             (Object::BuiltinStdAsync(a), Object::BuiltinStdAsync(b)) => {
                 a.name == b.name && a.min_params == b.min_params && a.max_params == b.max_params
             }
+            (Object::NativeFunction(a), Object::NativeFunction(b)) => match (&a.func, &b.func) {
+                (NativeCallable::Sync(f), NativeCallable::Sync(g)) => Arc::ptr_eq(f, g),
+                (NativeCallable::Async(f), NativeCallable::Async(g)) => Arc::ptr_eq(f, g),
+                _ => false,
+            },
             (Object::Function(a), Object::Function(b)) => {
                 a.params == b.params && Arc::ptr_eq(&a.chunk, &b.chunk) && a.local_names == b.local_names
             }
             (Object::AsyncFunction(a), Object::AsyncFunction(b)) => {
                 a.params == b.params && Arc::ptr_eq(&a.chunk, &b.chunk) && a.local_names == b.local_names
             }
+            #[cfg(feature = "wasm")]
             (Object::WasmImportedFunction(a), Object::WasmImportedFunction(b)) => {
                 a.module_name == b.module_name && a.func_name == b.func_name
             }
@@ -246,9 +483,9 @@ impl PartialEq for Object {
             (Object::Module(a), Object::Module(b)) => {
                 a.name == b.name && a.exports.keys().collect::<Vec<_>>() == b.exports.keys().collect::<Vec<_>>()
             }
-            (Object::Struct(a), Object::Struct(b)) => {
-                a.name == b.name && a.fields == b.fields && a.methods == b.methods
-            }
+            // Instances of the same struct share their method table, so two
+            // structs are equal when their type and fields match.
+            (Object::Struct(a), Object::Struct(b)) => a.name == b.name && a.fields == b.fields,
             #[cfg(feature = "wasm")]
             (Object::WasmModule(a), Object::WasmModule(b)) => {
                 a.name == b.name && a.exports.keys().collect::<Vec<_>>() == b.exports.keys().collect::<Vec<_>>()
@@ -259,6 +496,133 @@ impl PartialEq for Object {
 }
 
 impl Object {
+    /// Returns `true` for integer, bigInteger and float values.
+    pub fn is_number(&self) -> bool {
+        matches!(self, Object::Integer(_) | Object::BigInteger(_) | Object::Float(_))
+    }
+
+    /// Returns `true` for values that can be used as hash keys: numbers,
+    /// booleans, strings, bytes and arrays of such values. Everything else
+    /// is either compared by identity or can change behind the map's back.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Object::Integer(_)
+            | Object::BigInteger(_)
+            | Object::Float(_)
+            | Object::Boolean(_)
+            | Object::String(_)
+            | Object::Bytes(_) => true,
+            Object::Array(items) => items.iter().all(Object::is_hashable),
+            _ => false,
+        }
+    }
+
+    /// Recursively copies arrays, hashes and struct fields so the result
+    /// shares no collection storage with `self`. Functions, modules and
+    /// other handle-like values are copied shallowly. A `ref` reached twice
+    /// is copied once, so the copy has the same shape, cycles included.
+    pub fn deep_copy(&self) -> Object {
+        self.deep_copy_with(&mut std::collections::HashMap::new())
+    }
+
+    /// [`Object::deep_copy`], with the copies made so far of each `ref`,
+    /// keyed by its cell.
+    fn deep_copy_with(
+        &self,
+        copies: &mut std::collections::HashMap<*const Mutex<Object>, Arc<Mutex<Object>>>,
+    ) -> Object {
+        match self {
+            Object::Array(arr) => {
+                Object::Array(Box::new(arr.iter().map(|item| item.deep_copy_with(copies)).collect()))
+            }
+            Object::Hash(hash) => {
+                // Safe: keys are immutable scalars, copying them cannot change their hash.
+                #[allow(clippy::mutable_key_type)]
+                let copied: HashMap<Object, Object> =
+                    hash.iter().map(|(k, v)| (k.clone(), v.deep_copy_with(copies))).collect();
+                Object::Hash(Box::new(copied))
+            }
+            Object::Struct(s) => Object::Struct(Box::new(StructObject {
+                name: s.name.clone(),
+                fields: s.fields.iter().map(|(k, v)| (k.clone(), v.deep_copy_with(copies))).collect(),
+                methods: s.methods.clone(),
+            })),
+            Object::Ref(cell) => {
+                if let Some(copy) = copies.get(&Arc::as_ptr(cell)) {
+                    return Object::Ref(Arc::clone(copy));
+                }
+                let copy = Arc::new(Mutex::new(Object::Null));
+                copies.insert(Arc::as_ptr(cell), Arc::clone(&copy));
+                // Not copied under the lock: the contents may reach this
+                // cell again.
+                let inner = cell.lock().unwrap().clone();
+                let copied = inner.deep_copy_with(copies);
+                *copy.lock().unwrap() = copied;
+                Object::Ref(copy)
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Structural equality that walks into arrays, hashes and struct fields.
+    /// Unlike `==`, integers, bigIntegers and floats compare by numeric value.
+    /// Two `ref`s met again while comparing them count as equal, so cyclic
+    /// values compare in finite time.
+    pub fn deep_equals(&self, other: &Object) -> bool {
+        self.deep_equals_with(other, &mut std::collections::HashSet::new())
+    }
+
+    /// [`Object::deep_equals`], with the pairs of `ref` cells already being
+    /// compared.
+    fn deep_equals_with(
+        &self,
+        other: &Object,
+        seen: &mut std::collections::HashSet<(*const Mutex<Object>, *const Mutex<Object>)>,
+    ) -> bool {
+        match (self, other) {
+            (Object::Array(a), Object::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.deep_equals_with(y, seen))
+            }
+            (Object::Hash(a), Object::Hash(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| v.deep_equals_with(w, seen)))
+            }
+            (Object::Struct(a), Object::Struct(b)) => {
+                a.name == b.name
+                    && a.fields.len() == b.fields.len()
+                    && a.fields
+                        .iter()
+                        .all(|(k, v)| b.fields.get(k).is_some_and(|w| v.deep_equals_with(w, seen)))
+            }
+            (Object::Ref(a), Object::Ref(b)) => {
+                if Arc::ptr_eq(a, b) || !seen.insert((Arc::as_ptr(a), Arc::as_ptr(b))) {
+                    return true;
+                }
+                // Compared outside the locks, which the contents may need.
+                let x = a.lock().unwrap().clone();
+                let y = b.lock().unwrap().clone();
+                x.deep_equals_with(&y, seen)
+            }
+            (Object::Integer(a), Object::BigInteger(b))
+            | (Object::BigInteger(b), Object::Integer(a)) => BigInt::from(*a) == **b,
+            (Object::Integer(i), Object::Float(f)) | (Object::Float(f), Object::Integer(i)) => {
+                *i as f64 == *f
+            }
+            (Object::BigInteger(b), Object::Float(f)) | (Object::Float(f), Object::BigInteger(b)) => {
+                num_traits::ToPrimitive::to_f64(b.as_ref()) == Some(*f)
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// The Rust value inside an [`Object::Native`], if it holds a `T`.
+    pub fn downcast_native<T: Any>(&self) -> Option<&T> {
+        match self {
+            Object::Native(native) => native.value.downcast_ref(),
+            _ => None,
+        }
+    }
+
     pub fn type_name(&self) -> String {
         match self {
             Object::Integer(_) => "integer".to_string(),
@@ -268,12 +632,31 @@ impl Object {
             Object::String(_) => "string".to_string(),
             Object::Array(_) => "array".to_string(),
             Object::Hash(_) => "hash".to_string(),
+            Object::Bytes(_) => "bytes".to_string(),
+            Object::File(_) => "file".to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Server(_) => "server".to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::UdpSocket(_) => "udp socket".to_string(),
+            Object::Collection(c) => c.lock().unwrap().kind.name().to_string(),
+            Object::OrderedMap(_) => "ordered map".to_string(),
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            Object::Sqlite(_) => "database".to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Db(_) => "db connection".to_string(),
+            Object::Ref(_) => "ref".to_string(),
             Object::Function(_) => "function".to_string(),
             Object::AsyncFunction(_) => "async function".to_string(),
+            #[cfg(feature = "wasm")]
             Object::WasmImportedFunction(_) => "wasm imported function".to_string(),
             Object::Builtin(_) => "builtin function".to_string(),
             Object::BuiltinStd(_) => "builtin function".to_string(),
             Object::BuiltinStdAsync(_) => "async builtin function".to_string(),
+            Object::NativeFunction(d) => match d.func {
+                NativeCallable::Sync(_) => "builtin function".to_string(),
+                NativeCallable::Async(_) => "async builtin function".to_string(),
+            },
+            Object::Native(n) => n.methods.type_name.clone(),
             Object::Null => "null".to_string(),
             Object::ReturnValue(_) => "return value".to_string(),
             Object::Error(_) => "error".to_string(),
@@ -290,6 +673,116 @@ impl Object {
     }
 }
 
+impl Object {
+    /// Renders the value for debugging: strings are quoted, hash keys and
+    /// struct fields are sorted, and nested collections are indented.
+    pub fn inspect(&self) -> String {
+        let mut out = String::new();
+        self.write_inspect(&mut out, 0);
+        out
+    }
+
+    fn is_nested_collection(&self) -> bool {
+        match self {
+            Object::Array(a) => !a.is_empty(),
+            Object::Hash(h) => !h.is_empty(),
+            Object::Struct(s) => !s.fields.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn write_inspect(&self, out: &mut String, depth: usize) {
+        match self {
+            Object::String(s) => out.push_str(&format!("{:?}", s)),
+            Object::Array(arr) => {
+                let items: Vec<(String, &Object)> =
+                    arr.iter().map(|v| (String::new(), v)).collect();
+                write_inspect_entries(out, depth, ("[", "]", ""), &items);
+            }
+            Object::Hash(hash) => {
+                let mut entries: Vec<(&Object, &Object)> = hash.iter().collect();
+                // Order keys by type first (booleans, integers, strings), then by value
+                entries.sort_by(|(a, _), (b, _)| match (a, b) {
+                    (Object::Boolean(x), Object::Boolean(y)) => x.cmp(y),
+                    (Object::Integer(x), Object::Integer(y)) => x.cmp(y),
+                    (Object::String(x), Object::String(y)) => x.cmp(y),
+                    _ => inspect_key_rank(a).cmp(&inspect_key_rank(b)),
+                });
+                let items: Vec<(String, &Object)> = entries
+                    .into_iter()
+                    .map(|(k, v)| (format!("{}: ", k.inspect()), v))
+                    .collect();
+                write_inspect_entries(out, depth, ("{", "}", ""), &items);
+            }
+            Object::Struct(s) => {
+                let mut fields: Vec<(&String, &Object)> = s.fields.iter().collect();
+                fields.sort_by_key(|(name, _)| *name);
+                let items: Vec<(String, &Object)> = fields
+                    .into_iter()
+                    .map(|(k, v)| (format!("{}: ", k), v))
+                    .collect();
+                out.push_str(&s.name);
+                out.push(' ');
+                write_inspect_entries(out, depth, ("{", "}", " "), &items);
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+}
+
+fn inspect_key_rank(key: &Object) -> u8 {
+    match key {
+        Object::Boolean(_) => 0,
+        Object::Integer(_) => 1,
+        Object::String(_) => 2,
+        _ => 3,
+    }
+}
+
+/// Writes `items` between `open` and `close`, on one line (separated from
+/// the delimiters by `pad`) when none of the values is a non-empty
+/// collection, and one entry per indented line otherwise.
+fn write_inspect_entries(
+    out: &mut String,
+    depth: usize,
+    (open, close, pad): (&str, &str, &str),
+    items: &[(String, &Object)],
+) {
+    out.push_str(open);
+    if items.is_empty() {
+        out.push_str(close);
+        return;
+    }
+
+    if !items.iter().any(|(_, v)| v.is_nested_collection()) {
+        out.push_str(pad);
+        for (i, (prefix, value)) in items.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(prefix);
+            value.write_inspect(out, depth);
+        }
+        out.push_str(pad);
+        out.push_str(close);
+        return;
+    }
+
+    let indent = "  ".repeat(depth + 1);
+    for (i, (prefix, value)) in items.iter().enumerate() {
+        out.push('\n');
+        out.push_str(&indent);
+        out.push_str(prefix);
+        value.write_inspect(out, depth + 1);
+        if i < items.len() - 1 {
+            out.push(',');
+        }
+    }
+    out.push('\n');
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(close);
+}
+
 impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -328,14 +821,77 @@ impl fmt::Display for Object {
                 fmt_string.push('}');
                 write!(f, "{}", fmt_string)
             }
+            Object::Bytes(ref b) => {
+                write!(f, "bytes[")?;
+                for (i, byte) in b.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", byte)?;
+                }
+                write!(f, "]")
+            }
+            Object::File(ref h) => write!(f, "[file: {}]", h.lock().unwrap().path),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Server(_) => write!(f, "[server]"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::UdpSocket(ref s) => match s.local_addr() {
+                Ok(addr) => write!(f, "[udp socket: {}]", addr),
+                Err(_) => write!(f, "[udp socket]"),
+            },
+            // try_lock so a collection that (indirectly) contains itself
+            // prints instead of deadlocking.
+            Object::Collection(ref c) => {
+                let Ok(data) = c.try_lock() else {
+                    return write!(f, "[...]");
+                };
+                write!(f, "{}[", data.kind.name())?;
+                for (i, item) in data.items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            // Same for an ordered map that contains itself.
+            Object::OrderedMap(ref m) => {
+                let Ok(map) = m.try_lock() else {
+                    return write!(f, "{{...}}");
+                };
+                write!(f, "{{")?;
+                for (i, (k, v)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} : {}", k, v)?;
+                }
+                write!(f, "}}")
+            }
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            Object::Sqlite(ref h) => write!(f, "[database: {}]", h.lock().unwrap().path),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Db(ref h) => write!(f, "[db connection: {}]", h.scheme),
+            // try_lock so a ref that (indirectly) contains itself prints
+            // instead of deadlocking.
+            Object::Ref(ref r) => match r.try_lock() {
+                Ok(inner) => write!(f, "ref({})", *inner),
+                Err(_) => write!(f, "ref(...)"),
+            },
             Object::Function(_) => write!(f, "[function]"),
             Object::AsyncFunction(_) => write!(f, "[async function]"),
+            #[cfg(feature = "wasm")]
             Object::WasmImportedFunction(ref d) => write!(f, "[wasm function: {}::{}]", d.module_name, d.func_name),
             Object::Builtin(ref d) => write!(f, "[built-in function: {}]", d.name),
             Object::BuiltinStd(ref d) => write!(f, "[built-in function: {}]", d.name),
             Object::BuiltinStdAsync(ref d) => {
                 write!(f, "[async built-in function: {}]", d.name)
             }
+            Object::NativeFunction(ref d) => match d.func {
+                NativeCallable::Sync(_) => write!(f, "[built-in function: {}]", d.name),
+                NativeCallable::Async(_) => write!(f, "[async built-in function: {}]", d.name),
+            },
+            Object::Native(ref n) => write!(f, "[{}]", n.methods.type_name),
             Object::Null => write!(f, "null"),
             Object::ReturnValue(ref o) => write!(f, "{}", *o),
             Object::Error(ref e) => write!(f, "{}", e),
@@ -363,30 +919,76 @@ impl fmt::Display for Object {
 
 impl Eq for Object {}
 
+/// Hashes map entries independently of their iteration order, so equal
+/// maps hash equally: each entry is hashed on its own and the results are
+/// summed.
+fn hash_unordered<'a, K: Hash + 'a, V: Hash + 'a, H: Hasher>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    len: usize,
+    state: &mut H,
+) {
+    let sum = entries.fold(0u64, |sum, (k, v)| {
+        let mut hasher = AHasher::default();
+        k.hash(&mut hasher);
+        v.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    len.hash(state);
+    sum.hash(state);
+}
+
+/// Consistent with `PartialEq`: values that compare equal hash equally.
+/// Handle-like values compare by identity, so they hash their pointer.
 #[allow(clippy::all)]
 impl Hash for Object {
     fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
         match *self {
             Object::Integer(ref i) => i.hash(state),
             Object::BigInteger(ref i) => i.hash(state),
+            // `0.0 == -0.0`, so both must hash alike. NaN equals nothing,
+            // so any hash will do for it.
+            Object::Float(f) => (if f == 0.0 { 0.0f64 } else { f }).to_bits().hash(state),
             Object::Boolean(ref b) => b.hash(state),
             Object::String(ref s) => s.hash(state),
-            Object::Function(ref d) => {
-                d.params.hash(state);
-                d.chunk.code.hash(state);
-                d.local_names.hash(state);
+            Object::Bytes(ref b) => b.hash(state),
+            Object::Array(ref items) => items.hash(state),
+            Object::Hash(ref pairs) => hash_unordered(pairs.iter(), pairs.len(), state),
+            Object::Struct(ref s) => {
+                s.name.hash(state);
+                hash_unordered(s.fields.iter(), s.fields.len(), state);
             }
-            Object::AsyncFunction(ref d) => {
+            Object::File(ref h) => Arc::as_ptr(h).hash(state),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Server(ref h) => Arc::as_ptr(h).hash(state),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::UdpSocket(ref h) => Arc::as_ptr(h).hash(state),
+            Object::Collection(ref h) => Arc::as_ptr(h).hash(state),
+            Object::OrderedMap(ref h) => Arc::as_ptr(h).hash(state),
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            Object::Sqlite(ref h) => Arc::as_ptr(h).hash(state),
+            #[cfg(not(target_arch = "wasm32"))]
+            Object::Db(ref h) => Arc::as_ptr(h).hash(state),
+            Object::Ref(ref h) => Arc::as_ptr(h).hash(state),
+            Object::Native(ref n) => Arc::as_ptr(&n.value).cast::<()>().hash(state),
+            Object::Function(ref d) | Object::AsyncFunction(ref d) | Object::Method(ref d) => {
                 d.params.hash(state);
-                d.chunk.code.hash(state);
-                d.local_names.hash(state);
+                Arc::as_ptr(&d.chunk).hash(state);
             }
-            Object::Method(ref d) => {
-                d.params.hash(state);
-                d.chunk.code.hash(state);
-                d.local_names.hash(state);
+            Object::Builtin(ref d) => d.name.hash(state),
+            Object::BuiltinStd(ref d) => d.name.hash(state),
+            Object::BuiltinStdAsync(ref d) => d.name.hash(state),
+            Object::NativeFunction(ref d) => d.name.hash(state),
+            #[cfg(feature = "wasm")]
+            Object::WasmImportedFunction(ref d) => {
+                d.module_name.hash(state);
+                d.func_name.hash(state);
             }
-            _ => "".hash(state),
+            Object::Module(ref m) => m.name.hash(state),
+            #[cfg(feature = "wasm")]
+            Object::WasmModule(ref m) => m.name.hash(state),
+            Object::ReturnValue(ref inner) | Object::ThrownValue(ref inner) => inner.hash(state),
+            Object::Null | Object::Error(_) | Object::Break | Object::Continue | Object::Future(_) => {}
         }
     }
 }
@@ -18,9 +18,9 @@ pub fn add(obj1: Object, obj2: Object) -> Object {
     match (&obj1, &obj2) {
         (Object::Integer(ia), Object::Integer(ib)) => Object::Integer(ia.wrapping_add(*ib)),
         (Object::Float(fa), Object::Float(fb)) => Object::Float(fa + fb),
-        (Object::String(s), Object::String(t)) => Object::String(format!("{}{}", s, t)),
-        (Object::String(s), other) => Object::String(format!("{}{}", s, other)),
-        (other, Object::String(s)) => Object::String(format!("{}{}", other, s)),
+        (Object::String(s), Object::String(t)) => Object::String(format!("{}{}", s, t).into()),
+        (Object::String(s), other) => Object::String(format!("{}{}", s, other).into()),
+        (other, Object::String(s)) => Object::String(format!("{}{}", other, s).into()),
         _ => {
             if matches!(obj1, Object::Float(_)) || matches!(obj2, Object::Float(_)) {
                 let f1 = match obj_to_float(obj1) {
@@ -116,7 +116,8 @@ pub fn divide(obj1: Object, obj2: Object) -> Object {
             if *ib == 0 {
                 Object::Error(Box::new(RuntimeError::DivisionByZero))
             } else {
-                Object::Integer(ia / ib)
+                // Wraps like `+` and `*`: i64::MIN / -1 is i64::MIN.
+                Object::Integer(ia.wrapping_div(*ib))
             }
         }
         (Object::Float(fa), Object::Float(fb)) => {
@@ -165,7 +166,7 @@ pub fn modulo(obj1: Object, obj2: Object) -> Object {
             if *ib == 0 {
                 Object::Error(Box::new(RuntimeError::DivisionByZero))
             } else {
-                Object::Integer(ia % ib)
+                Object::Integer(ia.wrapping_rem(*ib))
             }
         }
         _ => {
@@ -2,19 +2,27 @@
 
 use std::sync::{Arc, Mutex};
 
-use crate::ast::ast::Ident;
 use crate::vm::runtime::runtime_errors::RuntimeError;
 use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::gc::Collector;
 use crate::vm::runtime::module_registry::ModuleRegistry;
-use crate::vm::obj::Object;
+use crate::vm::obj::{FunctionData, NativeCallable, Object};
 use crate::vm::frame::CallFrame;
+use crate::vm::limits::ActiveLimits;
 use crate::vm::vm::{ExecResult, VirtualMachine};
 
+/// Deepest chain of script function calls a run may build up. Frames live
+/// on the heap, so this stops runaway recursion with an error instead of
+/// letting it eat all memory.
+pub(crate) const MAX_CALL_DEPTH: usize = 20_000;
+
 pub fn execute_call(
     stack: &mut Vec<Object>,
     frames: &mut Vec<CallFrame>,
     module_registry: &Arc<Mutex<ModuleRegistry>>,
     globals: &Arc<Mutex<crate::vm::runtime::env::Environment>>,
+    limits: &Option<Arc<ActiveLimits>>,
+    gc: &Collector,
     argc: usize,
 ) -> Result<ExecResult, RuntimeError> {
     if stack.len() < argc + 1 {
@@ -39,6 +47,25 @@ pub fn execute_call(
 
             // Function is at fn_idx, arguments are already on stack at fn_idx+1 onwards
             let fn_idx = stack.len() - argc - 1;
+
+            if frames.len() >= MAX_CALL_DEPTH {
+                stack.truncate(fn_idx);
+                return Ok(ExecResult::ContinueWith(Object::Error(Box::new(RuntimeError::InvalidOperation(
+                    format!("Maximum call depth of {} exceeded", MAX_CALL_DEPTH),
+                )))));
+            }
+
+            // Native code cannot be interrupted, so budgeted runs stay interpreted.
+            #[cfg(feature = "jit")]
+            if argc == params.len()
+                && limits.is_none()
+                && let Some(result) = crate::vm::jit::try_call(chunk, argc, &stack[fn_idx + 1..])
+            {
+                stack.truncate(fn_idx);
+                stack.push(result);
+                return Ok(ExecResult::Continue);
+            }
+
             let caller_stack_len = fn_idx; // return value replaces function at this position
             let slots_base = fn_idx + 1; // arguments are already here
 
@@ -64,18 +91,13 @@ pub fn execute_call(
                 Arc::clone(chunk),
                 slots_base,
                 caller_stack_len,
-                Arc::new(Mutex::new(new_env)),
-                local_names.clone(),
+                new_env.into_shared(gc),
+                Arc::clone(local_names),
             );
             frames.push(frame);
             Ok(ExecResult::Continue)
         }
         Object::AsyncFunction(data) => {
-            let params = &data.params;
-            let chunk = &data.chunk;
-            let closure_env = &data.env;
-            let local_names = &data.local_names;
-
             let args: Vec<Object> = stack.drain(stack.len() - argc..).collect();
             stack.pop();
 
@@ -84,7 +106,7 @@ pub fn execute_call(
                 caller.ip += 2;
             }
 
-            let future = call_async_function_vm(params.to_vec(), Arc::clone(chunk), local_names.clone(), args, closure_env.clone(), Arc::clone(module_registry), Arc::clone(globals));
+            let future = call_async_function_vm(*data, args, Arc::clone(module_registry), Arc::clone(globals), limits.clone());
             stack.push(Object::Future(Arc::new(Mutex::new(Some(future)))));
             Ok(ExecResult::Continue)
         }
@@ -111,6 +133,8 @@ pub fn execute_call(
                     stack.push(result);
                     Ok(ExecResult::Continue)
                 }
+                // `exit` ends the run rather than becoming an error value.
+                Err(e @ RuntimeError::Exit(_)) => Err(e),
                 Err(e) => Ok(ExecResult::ContinueWith(Object::Error(Box::new(e)))),
             }
         }
@@ -170,6 +194,25 @@ pub fn execute_call(
                 ))),
             }
         }
+        Object::NativeFunction(data) => {
+            let args: Vec<Object> = stack.drain(stack.len() - argc..).collect();
+            stack.pop();
+
+            match &data.func {
+                NativeCallable::Sync(func) => match func(args) {
+                    Ok(result) => {
+                        stack.push(result);
+                        Ok(ExecResult::Continue)
+                    }
+                    Err(e @ RuntimeError::Exit(_)) => Err(e),
+                    Err(e) => Ok(ExecResult::ContinueWith(Object::Error(Box::new(e)))),
+                },
+                NativeCallable::Async(func) => {
+                    stack.push(Object::Future(Arc::new(Mutex::new(Some(func(args))))));
+                    Ok(ExecResult::Continue)
+                }
+            }
+        }
         #[cfg(feature = "wasm")]
         Object::WasmImportedFunction(data) => {
             let func_name = &data.func_name;
@@ -260,13 +303,14 @@ pub fn execute_call(
 pub fn execute_closure(
     stack: &mut Vec<Object>,
     frames: &mut [CallFrame],
+    gc: &Collector,
 ) {
     if let Some(top) = stack.pop() {
         match top {
             Object::Function(data) => {
-                let params = data.params.clone();
+                let params = Arc::clone(&data.params);
                 let chunk = Arc::clone(&data.chunk);
-                let local_names = data.local_names.clone();
+                let local_names = Arc::clone(&data.local_names);
 
                 // Only capture names from the *outer* scope, not current local_names.
                 // They are identified by checking what's currently in the caller's frame local_names.
@@ -274,19 +318,13 @@ pub fn execute_closure(
                     // Use closure_env if present, otherwise just use a new root environment.
                     // If at root - the global scope must be linked
                     let outer_env = caller.closure_env.clone().unwrap_or_else(|| {
-                         Arc::new(Mutex::new(Environment::new_root()))
+                         Environment::new_root().into_shared(gc)
                     });
 
                     let mut env = Environment::new_with_outer(outer_env);
 
-                    // Capture variables that are present in the caller's frame.
-                    for name in &caller.local_names {
-                        if !name.is_empty()
-                            && let Some(slot) = caller.local_names.iter().position(|n| n == name) {
-                                let value = caller.get_local(stack, slot).clone();
-                                env.set_by_name(name, value);
-                        }
-                    }
+                    // Capture the caller's locals that are in scope here.
+                    capture_locals(&mut env, caller, stack);
                     env
                 } else {
                     Environment::new_root()
@@ -295,28 +333,22 @@ pub fn execute_closure(
                 stack.push(Object::Function(Box::new(crate::vm::obj::FunctionData {
                     params,
                     chunk,
-                    env: Arc::new(Mutex::new(new_env)),
+                    env: new_env.into_shared(gc),
                     local_names,
                 })));
             }
             Object::AsyncFunction(data) => {
-                let params = data.params.clone();
+                let params = Arc::clone(&data.params);
                 let chunk = Arc::clone(&data.chunk);
-                let local_names = data.local_names.clone();
+                let local_names = Arc::clone(&data.local_names);
 
                 let new_env = if let Some(caller) = frames.last() {
                     let outer_env = caller.closure_env.clone().unwrap_or_else(|| {
-                        Arc::new(Mutex::new(Environment::new_root()))
+                        Environment::new_root().into_shared(gc)
                     });
                     
                     let mut env = Environment::new_with_outer(outer_env);
-                    for name in &caller.local_names {
-                        if !name.is_empty()
-                            && let Some(slot) = caller.local_names.iter().position(|n| n == name) {
-                                let value = caller.get_local(stack, slot).clone();
-                                env.set_by_name(name, value);
-                        }
-                    }
+                    capture_locals(&mut env, caller, stack);
                     env
                 } else {
                     Environment::new()
@@ -324,7 +356,7 @@ pub fn execute_closure(
                 stack.push(Object::AsyncFunction(Box::new(crate::vm::obj::FunctionData {
                     params,
                     chunk,
-                    env: Arc::new(Mutex::new(new_env)),
+                    env: new_env.into_shared(gc),
                     local_names,
                 })));
             }
@@ -335,19 +367,49 @@ pub fn execute_closure(
     }
 }
 
+/// Copies the caller's locals whose scope contains the `OpClosure` being
+/// run into `env`. Inner scopes come later in the table, so a block's
+/// binding overrides a same-named one from an enclosing scope.
+fn capture_locals(env: &mut Environment, caller: &CallFrame, stack: &[Object]) {
+    for local in caller.local_names.iter() {
+        if local.scope.contains(&caller.ip) {
+            let value = caller.get_local(stack, local.slot as usize).clone();
+            env.set_by_name(&local.name, value);
+        }
+    }
+}
+
 pub fn execute_return_value() -> ExecResult {
     ExecResult::Return
 }
 
+/// Runs a script function (sync or async) to completion on a fresh VM.
+/// Used by native code that needs to call back into the script, such as
+/// HTTP server route handlers.
+pub fn call_function_object(
+    func: Object,
+    args: Vec<Object>,
+    module_registry: Arc<Mutex<ModuleRegistry>>,
+    globals: Arc<Mutex<Environment>>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>> {
+    match func {
+        Object::Function(data) | Object::AsyncFunction(data) => {
+            call_async_function_vm(*data, args, module_registry, globals, None)
+        }
+        other => Box::pin(async move {
+            Err(RuntimeError::TypeMismatch { expected: "function".to_string(), got: other.type_name() })
+        }),
+    }
+}
+
 pub fn call_async_function_vm(
-    params: Vec<Ident>,
-    chunk: Arc<crate::vm::chunk::Chunk>,
-    local_names: Vec<String>,
+    function: FunctionData,
     args: Vec<Object>,
-    closure_env: Arc<Mutex<Environment>>,
     module_registry: Arc<Mutex<ModuleRegistry>>,
     caller_globals: Arc<Mutex<Environment>>,
+    limits: Option<Arc<ActiveLimits>>,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Object, RuntimeError>> + Send + 'static>> {
+    let FunctionData { params, chunk, env: closure_env, local_names } = function;
     Box::pin(async move {
         // Use the captured closure_env as the parent for the new environment.
         // This ensures the async function's scope inherits both captured variables 
@@ -362,7 +424,8 @@ pub fn call_async_function_vm(
         
         let slot_count = std::cmp::max(64, params.len() + 10);
         
-        let globals_with_locals = Arc::new(Mutex::new(closure_env_inner));
+        let gc = Arc::clone(&module_registry.lock().unwrap().gc);
+        let globals_with_locals = closure_env_inner.into_shared(&gc);
         let mut vm = VirtualMachine::new_with_slots(
             Arc::clone(&caller_globals), // Still use original globals for the VM's global context
             module_registry,
@@ -372,6 +435,7 @@ pub fn call_async_function_vm(
         
         vm.set_root_local_names(local_names);
         vm.set_root_closure_env(Arc::clone(&globals_with_locals));
+        vm.inherit_limits(limits);
 
         let result = vm.run(Arc::clone(&chunk)).await;
         // Result is already Result<Object, RuntimeError>, which matches our return type
@@ -25,7 +25,7 @@ pub(crate) fn execute_build_hash(stack: &mut Vec<Object>, pair_count: u16) {
         return;
     }
 
-    // Safe: only Integer, Boolean, String (immutable types) are allowed as keys,
+    // Safe: only value types (see `Object::is_hashable`) are allowed as keys,
     // validated at runtime before insertion.
     #[allow(clippy::mutable_key_type)]
     let mut hashmap = HashMap::new();
@@ -33,7 +33,7 @@ pub(crate) fn execute_build_hash(stack: &mut Vec<Object>, pair_count: u16) {
         let value = stack.pop().unwrap();
         let key = stack.pop().unwrap();
         match &key {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+            _ if key.is_hashable() => {
                 hashmap.insert(key, value);
             }
             Object::Error(e) => {
@@ -67,7 +67,11 @@ pub(crate) fn execute_index(stack: &mut Vec<Object>) {
         }
     };
 
-    let result = match collection {
+    stack.push(index_value(&collection, index));
+}
+
+fn index_value(collection: &Object, index: Object) -> Object {
+    match collection {
         Object::Array(arr) => match index {
             Object::Integer(i) => {
                 if i < 0 {
@@ -91,18 +95,38 @@ pub(crate) fn execute_index(stack: &mut Vec<Object>) {
                 "Array index must be an integer".to_string(),
             ))),
         },
-        Object::Hash(mut hash) => match index {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
-                hash.remove(&index).unwrap_or(Object::Null)
+        Object::Bytes(bytes) => match index {
+            Object::Integer(i) => match usize::try_from(i).ok().and_then(|idx| bytes.get(idx)) {
+                Some(byte) => Object::Integer(*byte as i64),
+                None => Object::Error(Box::new(RuntimeError::IndexOutOfBounds {
+                    index: i,
+                    length: bytes.len(),
+                })),
+            },
+            _ => Object::Error(Box::new(RuntimeError::InvalidOperation(
+                "Bytes index must be an integer".to_string(),
+            ))),
+        },
+        Object::Hash(hash) => match index {
+            _ if index.is_hashable() => {
+                hash.get(&index).cloned().unwrap_or(Object::Null)
             }
             _ => Object::Error(Box::new(RuntimeError::NotHashable(index.type_name()))),
         },
+        Object::OrderedMap(map) => match index {
+            _ if index.is_hashable() => {
+                map.lock().unwrap().get(&index).cloned().unwrap_or(Object::Null)
+            }
+            _ => Object::Error(Box::new(RuntimeError::NotHashable(index.type_name()))),
+        },
+        Object::Ref(cell) => index_value(&cell.lock().unwrap(), index),
         other => Object::Error(Box::new(RuntimeError::NotIndexable(other.type_name()))),
-    };
-
-    stack.push(result);
+    }
 }
 
+/// Pops `collection, index, value` and pushes the updated collection so the
+/// compiler can store it back into the assignment target. Assigning through
+/// a `ref` updates the shared cell in place and pushes the same ref.
 pub(crate) fn execute_set_index(stack: &mut Vec<Object>) {
     let value = match stack.pop() {
         Some(v) => v,
@@ -120,7 +144,7 @@ pub(crate) fn execute_set_index(stack: &mut Vec<Object>) {
             ))))
         }
     };
-    let collection = match stack.pop() {
+    let mut collection = match stack.pop() {
         Some(v) => v,
         None => {
             return stack.push(Object::Error(Box::new(RuntimeError::InvalidOperation(
@@ -129,40 +153,71 @@ pub(crate) fn execute_set_index(stack: &mut Vec<Object>) {
         }
     };
 
-    let result = match collection {
-        Object::Array(mut arr) => match index {
-            Object::Integer(i) => {
-                if i < 0 {
-                    Object::Error(Box::new(RuntimeError::IndexOutOfBounds {
-                        index: i,
-                        length: arr.len(),
-                    }))
-                } else {
-                    let idx = i as usize;
-                    if idx >= arr.len() {
-                        Object::Error(Box::new(RuntimeError::IndexOutOfBounds {
-                            index: i,
-                            length: arr.len(),
-                        }))
-                    } else {
-                        arr[idx] = value;
-                        Object::Array(arr)
+    match set_index(&mut collection, index, value) {
+        Ok(()) => stack.push(collection),
+        Err(e) => stack.push(Object::Error(Box::new(e))),
+    }
+}
+
+fn set_index(collection: &mut Object, index: Object, value: Object) -> Result<(), RuntimeError> {
+    match collection {
+        Object::Array(arr) => match index {
+            Object::Integer(i) => match usize::try_from(i).ok().filter(|idx| *idx < arr.len()) {
+                Some(idx) => {
+                    arr[idx] = value;
+                    Ok(())
+                }
+                None => Err(RuntimeError::IndexOutOfBounds {
+                    index: i,
+                    length: arr.len(),
+                }),
+            },
+            _ => Err(RuntimeError::InvalidOperation(
+                "Array index must be an integer".to_string(),
+            )),
+        },
+        Object::Bytes(bytes) => match (index, value) {
+            (Object::Integer(i), Object::Integer(v)) => {
+                let length = bytes.len();
+                match (usize::try_from(i).ok().filter(|idx| *idx < length), u8::try_from(v)) {
+                    (Some(idx), Ok(byte)) => {
+                        bytes[idx] = byte;
+                        Ok(())
                     }
+                    (None, _) => Err(RuntimeError::IndexOutOfBounds { index: i, length }),
+                    (_, Err(_)) => Err(RuntimeError::InvalidOperation(format!(
+                        "Byte value must be between 0 and 255, got {}",
+                        v
+                    ))),
                 }
             }
-            _ => Object::Error(Box::new(RuntimeError::InvalidOperation(
-                "Array index must be an integer".to_string(),
-            ))),
+            (Object::Integer(_), other) => Err(RuntimeError::TypeMismatch {
+                expected: "integer".to_string(),
+                got: other.type_name(),
+            }),
+            _ => Err(RuntimeError::InvalidOperation(
+                "Bytes index must be an integer".to_string(),
+            )),
         },
-        Object::Hash(mut hash) => match index {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+        Object::Hash(hash) => match index {
+            _ if index.is_hashable() => {
                 hash.insert(index, value);
-                Object::Hash(hash)
+                Ok(())
             }
-            _ => Object::Error(Box::new(RuntimeError::NotHashable(index.type_name()))),
+            _ => Err(RuntimeError::NotHashable(index.type_name())),
         },
-        other => Object::Error(Box::new(RuntimeError::NotIndexable(other.type_name()))),
-    };
+        Object::Ref(cell) => set_index(&mut cell.lock().unwrap(), index, value),
+        other => Err(RuntimeError::NotIndexable(other.type_name())),
+    }
+}
 
-    stack.push(result);
+/// Normalizes the value a for-in loop iterates over. Hashes are replaced by
+/// an array of their keys; every other value is left for `GetLen`/`Index`.
+pub(crate) fn execute_to_iterable(stack: &mut [Object]) {
+    if let Some(top) = stack.last_mut()
+        && let Object::Hash(hash) = top
+    {
+        let keys: Vec<Object> = hash.keys().cloned().collect();
+        *top = Object::Array(Box::new(keys));
+    }
 }
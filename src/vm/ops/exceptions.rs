@@ -123,7 +123,7 @@ pub fn handle_throw_result(
         None => {
             // Try to get a string representation of the thrown value
             let msg = match &thrown {
-                Object::String(s) => s.clone(),
+                Object::String(s) => s.to_string(),
                 Object::Integer(i) => i.to_string(),
                 Object::Boolean(b) => b.to_string(),
                 Object::Float(f) => f.to_string(),
@@ -29,17 +29,13 @@ pub fn execute_import_module(
         &parts,
     ));
 
-    match module {
-        Ok(m) => {
-            stack.push(Object::Module(Box::new(ModuleObject {
-                name: m.name,
-                exports: m.exports,
-            })));
-        }
-        Err(e) => {
-            stack.push(Object::Error(Box::new(e)));
-        }
-    }
+    // A module that fails to load stops the script, so its error is
+    // reported with the module's file and position.
+    let module = module?;
+    stack.push(Object::Module(Box::new(ModuleObject {
+        name: module.name,
+        exports: module.exports,
+    })));
 
     Ok(())
 }
@@ -72,7 +68,7 @@ pub fn execute_get_export(stack: &mut Vec<Object>) {
 
     let result = match module_obj {
         Object::Module(m) => {
-            m.exports.get(&export_name).cloned().unwrap_or(Object::Null)
+            m.exports.get(export_name.as_str()).cloned().unwrap_or(Object::Null)
         }
         other => Object::Error(Box::new(RuntimeError::InvalidOperation(format!(
             "Cannot get export from {}",
@@ -129,7 +129,7 @@ pub fn execute_get_global(
             match gv {
                 Some(v) => v,
                 None => {
-                    stack.push(Object::Error(Box::new(RuntimeError::UndefinedVariable(name))));
+                    stack.push(Object::Error(Box::new(RuntimeError::UndefinedVariable(name.into()))));
                     return;
                 }
             }
@@ -1,6 +1,10 @@
 //! Struct operations: build, get field, set field, method call.
 
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use std::sync::{Arc, Mutex};
+
+use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::module_registry::ModuleRegistry;
 use crate::vm::obj::{HashMap, Object, StructObject};
 use ahash::HashMapExt;
 
@@ -46,7 +50,7 @@ pub fn execute_build_struct(
         let value = stack.pop().unwrap();
         let field_name_obj = stack.pop().unwrap();
         let field_name = match field_name_obj {
-            Object::String(s) => s,
+            Object::String(s) => s.to_string(),
             _ => {
                 stack.push(Object::Error(Box::new(RuntimeError::InvalidOperation(
                     "Struct field name must be a string".to_string(),
@@ -96,16 +100,19 @@ pub fn execute_get_field(stack: &mut Vec<Object>) {
         }
     };
 
-    let result = match struct_obj {
-        Object::Struct(s) => s.fields.get(&field_name).cloned().unwrap_or(Object::Null),
-        Object::Module(m) => m.exports.get(&field_name).cloned().unwrap_or(Object::Null),
+    stack.push(field_value(&struct_obj, &field_name));
+}
+
+fn field_value(object: &Object, field_name: &str) -> Object {
+    match object {
+        Object::Struct(s) => s.fields.get(field_name).cloned().unwrap_or(Object::Null),
+        Object::Module(m) => m.exports.get(field_name).cloned().unwrap_or(Object::Null),
+        Object::Ref(cell) => field_value(&cell.lock().unwrap(), field_name),
         other => Object::Error(Box::new(RuntimeError::InvalidOperation(format!(
             "Cannot get field from {}",
             other.type_name(),
         )))),
-    };
-
-    stack.push(result);
+    }
 }
 
 pub fn execute_set_field(stack: &mut Vec<Object>) {
@@ -133,7 +140,7 @@ pub fn execute_set_field(stack: &mut Vec<Object>) {
             ))))
         }
     };
-    let struct_obj = match stack.pop() {
+    let mut struct_obj = match stack.pop() {
         Some(v) => v,
         None => {
             return stack.push(Object::Error(Box::new(RuntimeError::InvalidOperation(
@@ -142,23 +149,33 @@ pub fn execute_set_field(stack: &mut Vec<Object>) {
         }
     };
 
-    let result = match struct_obj {
-        Object::Struct(mut s) => {
+    match set_field(&mut struct_obj, field_name.into(), value) {
+        Ok(()) => stack.push(struct_obj),
+        Err(e) => stack.push(Object::Error(Box::new(e))),
+    }
+}
+
+fn set_field(object: &mut Object, field_name: String, value: Object) -> Result<(), RuntimeError> {
+    match object {
+        Object::Struct(s) => {
             s.fields.insert(field_name, value);
-            Object::Struct(s)
+            Ok(())
         }
-        other => Object::Error(Box::new(RuntimeError::InvalidOperation(format!(
+        Object::Ref(cell) => set_field(&mut cell.lock().unwrap(), field_name, value),
+        other => Err(RuntimeError::InvalidOperation(format!(
             "Cannot set field on {}",
             other.type_name(),
-        )))),
-    };
-
-    stack.push(result);
+        ))),
+    }
 }
 
+// Only server methods need the registry and globals.
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
 pub fn execute_call_method(
     stack: &mut Vec<Object>,
     argc: usize,
+    module_registry: &Arc<Mutex<ModuleRegistry>>,
+    globals: &Arc<Mutex<Environment>>,
 ) -> Result<MethodCallResult, RuntimeError> {
     // Stack layout before this function:
     // [... object, method_name, arg1, arg2, ..., argN]
@@ -205,9 +222,22 @@ pub fn execute_call_method(
         }
     };
 
+    // A struct behind a ref runs its own methods with `this` bound to the
+    // ref, so field assignments inside the method update the shared cell.
+    if let Object::Ref(cell) = &struct_obj
+        && let Some(method) = cell_struct_method(cell, &method_name)
+    {
+        stack.push(method);
+        stack.push(struct_obj);
+        for arg in args {
+            stack.push(arg);
+        }
+        return Ok(MethodCallResult::NeedsCall(argc + 1));
+    }
+
     match &struct_obj {
         Object::Struct(s) => {
-            if let Some(method) = s.methods.get(&method_name) {
+            if let Some(method) = s.methods.get(method_name.as_str()) {
                 stack.push(method.clone());
                 // Prepend 'this' (the struct instance) to the argument list.
                 stack.push(struct_obj.clone());
@@ -216,13 +246,23 @@ pub fn execute_call_method(
                 }
                 Ok(MethodCallResult::NeedsCall(argc + 1))
             } else {
-                Ok(MethodCallResult::Error(Object::Error(Box::new(
-                    RuntimeError::InvalidOperation(format!("Method '{}' not found", method_name)),
-                ))))
+                // User-defined methods shadow the builtin struct methods
+                // (to_hash, from_hash, fields, ...), which are tried next.
+                match crate::vm::runtime::builtins::methods::BuiltinMethods::call_method(
+                    struct_obj,
+                    &method_name,
+                    args,
+                ) {
+                    Ok(result) => {
+                        stack.push(result);
+                        Ok(MethodCallResult::Done)
+                    }
+                    Err(e) => Ok(MethodCallResult::Error(Object::Error(Box::new(e)))),
+                }
             }
         }
         Object::Module(m) => {
-            if let Some(method) = m.exports.get(&method_name) {
+            if let Some(method) = m.exports.get(method_name.as_str()) {
                 stack.push(method.clone());
                 for arg in args {
                     stack.push(arg);
@@ -237,6 +277,22 @@ pub fn execute_call_method(
                 ))))
             }
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        Object::Server(server) => {
+            match crate::std::server::call_server_method(
+                Arc::clone(server),
+                &method_name,
+                args,
+                module_registry,
+                globals,
+            ) {
+                Ok(result) => {
+                    stack.push(result);
+                    Ok(MethodCallResult::Done)
+                }
+                Err(e) => Ok(MethodCallResult::Error(Object::Error(Box::new(e)))),
+            }
+        }
         _ => {
             // Handle built-in methods for other types
             match crate::vm::runtime::builtins::methods::BuiltinMethods::call_method(
@@ -252,4 +308,11 @@ pub fn execute_call_method(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn cell_struct_method(cell: &Arc<Mutex<Object>>, method_name: &str) -> Option<Object> {
+    match &*cell.lock().unwrap() {
+        Object::Struct(s) => s.methods.get(method_name).cloned(),
+        _ => None,
+    }
+}
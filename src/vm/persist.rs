@@ -0,0 +1,271 @@
+//! A binary encoding of values, so hosts and scripts can keep state between
+//! runs or send it to another process.
+//!
+//! [`Object::to_bytes`] encodes `null`, booleans, integers (big ones too),
+//! floats, strings, bytes, arrays, hashes and struct instances;
+//! [`Object::from_bytes`] reads them back. Functions, modules, handles and
+//! other values that only mean something inside one run cannot be encoded.
+//!
+//! The encoding is self-describing: every value starts with a tag byte,
+//! integers are zigzag varints and lengths are varints, after a `GLV` header
+//! and a version byte. Hash entries and struct fields are written in a
+//! fixed order, so equal values encode to equal bytes.
+//!
+//! A struct instance comes back with its name and fields but not its
+//! methods, which are code. A script gets them back with the struct's
+//! `from_hash`, e.g. `Point.from_hash(value.to_hash())`.
+//!
+//! ```
+//! use g_lang::vm::obj::Object;
+//!
+//! let value = Object::from(vec![Object::from(1_i64), Object::from("two")]);
+//! let bytes = value.to_bytes().unwrap();
+//! assert_eq!(Object::from_bytes(&bytes).unwrap(), value);
+//! ```
+
+use ahash::HashMapExt;
+use num_bigint::BigInt;
+
+use crate::vm::obj::{HashMap, Object, StructObject};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+const MAGIC: &[u8] = b"GLV";
+const VERSION: u8 = 1;
+
+/// Arrays, hashes and structs nested deeper than this are rejected when
+/// decoding, so hostile input cannot overflow the stack.
+const MAX_DEPTH: usize = 512;
+
+const NULL: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const INTEGER: u8 = 3;
+const BIG_INTEGER: u8 = 4;
+const FLOAT: u8 = 5;
+const STRING: u8 = 6;
+const BYTES: u8 = 7;
+const ARRAY: u8 = 8;
+const HASH: u8 = 9;
+const STRUCT: u8 = 10;
+
+impl Object {
+    /// Encodes this value, or fails with the type of the first part that
+    /// cannot be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RuntimeError> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        encode(self, &mut out)?;
+        Ok(out)
+    }
+
+    /// Decodes a value written by [`Object::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Object, RuntimeError> {
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            return Err(invalid("missing header"));
+        };
+        let mut reader = Reader { bytes: rest, depth: 0 };
+        match reader.byte()? {
+            VERSION => {}
+            version => return Err(invalid(&format!("unsupported version {}", version))),
+        }
+        let value = reader.value()?;
+        if !reader.bytes.is_empty() {
+            return Err(invalid("trailing bytes"));
+        }
+        Ok(value)
+    }
+}
+
+fn invalid(message: &str) -> RuntimeError {
+    RuntimeError::InvalidArguments(format!("invalid serialized value: {}", message))
+}
+
+fn encode(value: &Object, out: &mut Vec<u8>) -> Result<(), RuntimeError> {
+    match value {
+        Object::Null => out.push(NULL),
+        Object::Boolean(false) => out.push(FALSE),
+        Object::Boolean(true) => out.push(TRUE),
+        Object::Integer(i) => {
+            out.push(INTEGER);
+            write_varint(out, ((i << 1) ^ (i >> 63)) as u64);
+        }
+        Object::BigInteger(big) => {
+            out.push(BIG_INTEGER);
+            write_bytes(out, &big.to_signed_bytes_le());
+        }
+        Object::Float(f) => {
+            out.push(FLOAT);
+            out.extend_from_slice(&f.to_le_bytes());
+        }
+        Object::String(s) => {
+            out.push(STRING);
+            write_bytes(out, s.as_bytes());
+        }
+        Object::Bytes(bytes) => {
+            out.push(BYTES);
+            write_bytes(out, bytes);
+        }
+        Object::Array(items) => {
+            out.push(ARRAY);
+            write_varint(out, items.len() as u64);
+            for item in items.iter() {
+                encode(item, out)?;
+            }
+        }
+        Object::Hash(hash) => {
+            out.push(HASH);
+            write_varint(out, hash.len() as u64);
+            let mut entries: Vec<_> = hash.iter().collect();
+            entries.sort_by_cached_key(|(k, _)| k.to_string());
+            for (key, value) in entries {
+                encode(key, out)?;
+                encode(value, out)?;
+            }
+        }
+        Object::Struct(s) => {
+            out.push(STRUCT);
+            write_bytes(out, s.name.as_bytes());
+            write_varint(out, s.fields.len() as u64);
+            let mut fields: Vec<_> = s.fields.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, value) in fields {
+                write_bytes(out, name.as_bytes());
+                encode(value, out)?;
+            }
+        }
+        other => {
+            return Err(RuntimeError::InvalidOperation(format!("cannot serialize a value of type {}", other.type_name())));
+        }
+    }
+    Ok(())
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, RuntimeError> {
+        let (&byte, rest) = self.bytes.split_first().ok_or_else(|| invalid("unexpected end"))?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], RuntimeError> {
+        if len > self.bytes.len() {
+            return Err(invalid("unexpected end"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, RuntimeError> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(invalid("varint is too long"))
+    }
+
+    /// A length or count, which cannot be more than the bytes left since
+    /// every item takes at least one.
+    fn len(&mut self) -> Result<usize, RuntimeError> {
+        match usize::try_from(self.varint()?) {
+            Ok(len) if len <= self.bytes.len() => Ok(len),
+            _ => Err(invalid("length is past the end")),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], RuntimeError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String, RuntimeError> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid("string is not UTF-8"))
+    }
+
+    fn value(&mut self) -> Result<Object, RuntimeError> {
+        let value = match self.byte()? {
+            NULL => Object::Null,
+            FALSE => Object::Boolean(false),
+            TRUE => Object::Boolean(true),
+            INTEGER => {
+                let n = self.varint()?;
+                Object::Integer((n >> 1) as i64 ^ -((n & 1) as i64))
+            }
+            BIG_INTEGER => Object::BigInteger(Box::new(BigInt::from_signed_bytes_le(self.bytes()?))),
+            FLOAT => {
+                let bytes = self.take(8)?;
+                Object::Float(f64::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            STRING => Object::from(self.string()?),
+            BYTES => Object::Bytes(Box::new(self.bytes()?.to_vec())),
+            tag @ (ARRAY | HASH | STRUCT) => {
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err(invalid("nested too deeply"));
+                }
+                let value = self.container(tag)?;
+                self.depth -= 1;
+                value
+            }
+            tag => return Err(invalid(&format!("unknown tag {}", tag))),
+        };
+        Ok(value)
+    }
+
+    fn container(&mut self, tag: u8) -> Result<Object, RuntimeError> {
+        match tag {
+            ARRAY => {
+                let len = self.len()?;
+                let items = (0..len).map(|_| self.value()).collect::<Result<Vec<_>, _>>()?;
+                Ok(Object::Array(Box::new(items)))
+            }
+            HASH => {
+                let len = self.len()?;
+                #[allow(clippy::mutable_key_type)]
+                let mut hash = HashMap::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.value()?;
+                    if !key.is_hashable() {
+                        return Err(invalid(&format!("hash key of type {}", key.type_name())));
+                    }
+                    let value = self.value()?;
+                    hash.insert(key, value);
+                }
+                Ok(Object::Hash(Box::new(hash)))
+            }
+            _ => {
+                let name = self.string()?;
+                let len = self.len()?;
+                let mut fields = HashMap::with_capacity(len);
+                for _ in 0..len {
+                    let field = self.string()?;
+                    fields.insert(field, self.value()?);
+                }
+                Ok(Object::Struct(Box::new(StructObject { name, fields, methods: HashMap::new() })))
+            }
+        }
+    }
+}
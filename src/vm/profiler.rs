@@ -0,0 +1,186 @@
+//! Execution profiler behind `gl run --profile`.
+//!
+//! When enabled, every VM keeps a shadow stack of the call frames it has
+//! entered. Each time a frame is popped its wall time is charged to the
+//! function's chunk: one call, the elapsed time as *total* time, and the
+//! elapsed time minus that of its callees as *self* time. Self time is also
+//! recorded per call path, which is what the flamegraph output is made of.
+//!
+//! Statistics are process-wide so calls made by nested VMs (async functions
+//! and callbacks invoked from builtins) show up in the same report.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::vm::chunk::Chunk;
+
+/// Name reported for a chunk that is not a function body.
+const SCRIPT_NAME: &str = "<script>";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static STATS: LazyLock<Mutex<Stats>> = LazyLock::new(|| Mutex::new(Stats::default()));
+
+/// How [`report`] renders the collected statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// A table sorted by self time.
+    Text,
+    /// A JSON array with one object per function.
+    Json,
+    /// Folded stacks (`a;b;c <microseconds>`), the input format of
+    /// `flamegraph.pl` and `inferno-flamegraph`.
+    Flamegraph,
+}
+
+impl ProfileFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(ProfileFormat::Text),
+            "json" => Ok(ProfileFormat::Json),
+            "flamegraph" | "folded" => Ok(ProfileFormat::Flamegraph),
+            _ => Err(format!("unknown profile format '{}', expected text, json or flamegraph", name)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct FunctionStats {
+    calls: u64,
+    total: Duration,
+    self_time: Duration,
+}
+
+#[derive(Default)]
+struct Stats {
+    functions: HashMap<String, FunctionStats>,
+    stacks: HashMap<String, Duration>,
+}
+
+/// Turns profiling on for every VM created afterwards.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn chunk_name(chunk: &Chunk) -> &str {
+    if chunk.name.is_empty() { SCRIPT_NAME } else { &chunk.name }
+}
+
+struct OpenFrame {
+    name: String,
+    started: Instant,
+    callees: Duration,
+}
+
+/// One VM's view of the frames it is currently timing.
+#[derive(Default)]
+pub(crate) struct ShadowStack {
+    open: Vec<OpenFrame>,
+}
+
+impl ShadowStack {
+    /// Brings the shadow stack in line with the VM's `frames`, closing the
+    /// frames that returned and opening the ones that were just pushed.
+    pub(crate) fn sync<'a>(&mut self, frames: impl ExactSizeIterator<Item = &'a Chunk>) {
+        let depth = frames.len();
+        while self.open.len() > depth {
+            self.exit();
+        }
+        let now = Instant::now();
+        for chunk in frames.skip(self.open.len()) {
+            self.open.push(OpenFrame { name: chunk_name(chunk).to_string(), started: now, callees: Duration::ZERO });
+        }
+    }
+
+    /// Closes every frame that is still open, e.g. after a runtime error.
+    pub(crate) fn finish(&mut self) {
+        while !self.open.is_empty() {
+            self.exit();
+        }
+    }
+
+    fn exit(&mut self) {
+        let Some(frame) = self.open.pop() else { return };
+        let elapsed = frame.started.elapsed();
+        let self_time = elapsed.saturating_sub(frame.callees);
+        if let Some(caller) = self.open.last_mut() {
+            caller.callees += elapsed;
+        }
+
+        let mut path = String::new();
+        for open in &self.open {
+            path.push_str(&open.name);
+            path.push(';');
+        }
+        path.push_str(&frame.name);
+        // Recursive calls would otherwise count the same time twice.
+        let outermost = !self.open.iter().any(|open| open.name == frame.name);
+
+        let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+        *stats.stacks.entry(path).or_default() += self_time;
+        let entry = stats.functions.entry(frame.name).or_default();
+        entry.calls += 1;
+        entry.self_time += self_time;
+        if outermost {
+            entry.total += elapsed;
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Renders everything recorded so far.
+pub fn report(format: ProfileFormat) -> String {
+    let stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut functions: Vec<(&String, &FunctionStats)> = stats.functions.iter().collect();
+    functions.sort_by(|a, b| b.1.self_time.cmp(&a.1.self_time).then_with(|| a.0.cmp(b.0)));
+
+    let mut out = String::new();
+    match format {
+        ProfileFormat::Text => {
+            let _ = writeln!(out, "{:>10}  {:>12}  {:>12}  function", "calls", "total ms", "self ms");
+            for (name, f) in functions {
+                let _ = writeln!(
+                    out,
+                    "{:>10}  {:>12.3}  {:>12.3}  {}",
+                    f.calls,
+                    millis(f.total),
+                    millis(f.self_time),
+                    name
+                );
+            }
+        }
+        ProfileFormat::Json => {
+            let entries: Vec<serde_json::Value> = functions
+                .into_iter()
+                .map(|(name, f)| {
+                    serde_json::json!({
+                        "function": name,
+                        "calls": f.calls,
+                        "total_ms": millis(f.total),
+                        "self_ms": millis(f.self_time),
+                    })
+                })
+                .collect();
+            out = serde_json::to_string_pretty(&entries).unwrap_or_default();
+            out.push('\n');
+        }
+        ProfileFormat::Flamegraph => {
+            let mut stacks: Vec<(&String, &Duration)> = stats.stacks.iter().collect();
+            stacks.sort();
+            for (path, time) in stacks {
+                let _ = writeln!(out, "{} {}", path, time.as_micros());
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,92 @@
+//! On-disk cache of parsed module ASTs.
+//!
+//! Imported `.g` files are lexed and parsed once and the resulting
+//! [`Program`] is stored under `<base>/.giulio-cache/`, one file per module
+//! file. Each entry records the interpreter version, the nesting limit the
+//! module was parsed under and a hash of the source it was parsed from, so
+//! editing a module, upgrading `gl` or lowering the limit simply misses the
+//! cache and overwrites the entry. The entry holds the tree before the
+//! optimizer runs, so no other option changes what is stored. Cache I/O
+//! failures are never fatal: a broken or unwritable cache just means the
+//! module is parsed again. Nothing is written when scripts are denied the
+//! file system.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::ast::Program;
+use crate::parser::depth_check::exceeds_depth_with_chains;
+
+/// Directory, relative to the module base path, that holds cached ASTs.
+pub(crate) const CACHE_DIR: &str = ".giulio-cache";
+
+/// Deepest tree, counting every link of a chain, that is written to the cache.
+const MAX_CACHED_DEPTH: usize = 512;
+
+#[derive(Deserialize)]
+struct CacheEntry {
+    version: String,
+    max_nesting_depth: usize,
+    source_hash: u64,
+    program: Program,
+}
+
+/// Borrowing twin of [`CacheEntry`]; encodes to the same bytes.
+#[derive(Serialize)]
+struct CacheEntryRef<'a> {
+    version: &'a str,
+    max_nesting_depth: usize,
+    source_hash: u64,
+    program: &'a Program,
+}
+
+/// FNV-1a; unlike `DefaultHasher` its output is stable across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn entry_path(base: &Path, file: &Path) -> PathBuf {
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("module");
+    let key = fnv1a(file.to_string_lossy().as_bytes());
+    base.join(CACHE_DIR).join(format!("{}-{:016x}.ast", stem, key))
+}
+
+/// Returns the cached AST for `file` if it was parsed from exactly `source`
+/// with the nesting limit `max_nesting_depth`.
+pub(crate) fn load(base: &Path, file: &Path, source: &str, max_nesting_depth: usize) -> Option<Program> {
+    let bytes = std::fs::read(entry_path(base, file)).ok()?;
+    let entry: CacheEntry = bincode::deserialize(&bytes).ok()?;
+    (entry.version == env!("CARGO_PKG_VERSION")
+        && entry.max_nesting_depth == max_nesting_depth
+        && entry.source_hash == fnv1a(source.as_bytes()))
+    .then_some(entry.program)
+}
+
+/// Stores the AST parsed from `source` for `file`, ignoring any I/O error.
+/// Trees with long operator or call chains are not stored: encoding and
+/// decoding them recurses once per link.
+pub(crate) fn store(base: &Path, file: &Path, source: &str, max_nesting_depth: usize, program: &Program) {
+    if exceeds_depth_with_chains(program, MAX_CACHED_DEPTH) {
+        return;
+    }
+    let path = entry_path(base, file);
+    let entry = CacheEntryRef {
+        version: env!("CARGO_PKG_VERSION"),
+        max_nesting_depth,
+        source_hash: fnv1a(source.as_bytes()),
+        program,
+    };
+    let Ok(bytes) = bincode::serialize(&entry) else {
+        return;
+    };
+    if let Some(dir) = path.parent()
+        && std::fs::create_dir_all(dir).is_ok()
+    {
+        // Write then rename so a concurrent run never reads a partial entry.
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        if std::fs::write(&tmp, bytes).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+}
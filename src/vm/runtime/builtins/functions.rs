@@ -1,11 +1,12 @@
 use crate::vm::runtime::builtins::impls::{
-    array::*, hash::*, input::*, int::*, output::*, r#type::*, shared::*, string::*, struct_ops::*,
+    array::*, bytes::*, hash::*, input::*, int::*, output::*, process::*, r#type::*, reference::*, shared::*,
+    string::*, struct_ops::*,
 };
 use crate::{
     ast::ast::Ident,
     vm::{
         runtime::builtins::impls::struct_ops::bset_field_fn,
-        obj::{BuiltinFunction, Object}
+        obj::{BuiltinFunction, Object, StdFunction}
     },
 };
 
@@ -45,6 +46,16 @@ impl BuiltinsFunctions {
         "keys",
         "values",
         "clear",
+        "deep_copy",
+        "deep_equals",
+        "range",
+        "printf",
+        "inspect",
+        "parse_int",
+        "parse_float",
+        "bytes",
+        "ref",
+        "exit",
     ];
 
     pub(crate) fn new() -> Self {
@@ -60,8 +71,10 @@ impl BuiltinsFunctions {
             add_builtin(Self::BUILTIN_NAMES[3], 1, 1, bstruct_name_fn),
             // I/O
             add_builtin(Self::BUILTIN_NAMES[4], 1, usize::MAX, bprint_fn),
-            add_builtin(Self::BUILTIN_NAMES[5], 1, usize::MAX, bprintln_fn),
+            add_builtin(Self::BUILTIN_NAMES[5], 0, usize::MAX, bprintln_fn),
             add_builtin(Self::BUILTIN_NAMES[6], 0, 1, binput_fn),
+            add_builtin(Self::BUILTIN_NAMES[29], 1, usize::MAX, bprintf_fn),
+            add_builtin(Self::BUILTIN_NAMES[30], 1, 1, binspect_fn),
             // Core
             add_builtin(Self::BUILTIN_NAMES[7], 1, 1, btype_fn),
             add_builtin(Self::BUILTIN_NAMES[8], 1, 1, bisempty_fn),
@@ -86,6 +99,20 @@ impl BuiltinsFunctions {
             add_builtin(Self::BUILTIN_NAMES[23], 1, 1, bkeys_fn),
             add_builtin(Self::BUILTIN_NAMES[24], 1, 1, bvalues_fn),
             add_builtin(Self::BUILTIN_NAMES[25], 1, 1, bclear_fn),
+            // Copying & comparison
+            add_builtin(Self::BUILTIN_NAMES[26], 1, 1, bdeepcopy_fn),
+            add_builtin(Self::BUILTIN_NAMES[27], 2, 2, bdeepequals_fn),
+            // Parsing
+            add_builtin(Self::BUILTIN_NAMES[31], 1, 2, bparseint_fn),
+            add_builtin(Self::BUILTIN_NAMES[32], 1, 1, bparsefloat_fn),
+            // Binary data
+            add_builtin(Self::BUILTIN_NAMES[33], 1, 1, bnewbytes_fn),
+            // Shared references
+            add_builtin(Self::BUILTIN_NAMES[34], 1, 1, bref_fn),
+            // Sequences
+            add_builtin(Self::BUILTIN_NAMES[28], 1, 3, brange_fn),
+            // Process
+            add_std_builtin(Self::BUILTIN_NAMES[35], 0, 1, bexit_fn),
         ]
     }
 }
@@ -107,3 +134,17 @@ fn add_builtin(
         })),
     )
 }
+
+/// Like [`add_builtin`], for a builtin that fails with a specific
+/// [`RuntimeError`](crate::vm::runtime::runtime_errors::RuntimeError).
+fn add_std_builtin(name: &str, min_param: usize, max_param: usize, func: StdFunction) -> (Ident, Object) {
+    (
+        Ident::new(name.to_owned()),
+        Object::BuiltinStd(Box::new(crate::vm::obj::BuiltinStdData {
+            name: name.to_owned(),
+            min_params: min_param,
+            max_params: max_param,
+            func,
+        })),
+    )
+}
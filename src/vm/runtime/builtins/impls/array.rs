@@ -1,4 +1,13 @@
+use crate::vm::limits::{self, Alloc};
 use crate::vm::obj::Object;
+use crate::vm::ops::arithmetic::{greater_than, less_than};
+use crate::vm::runtime::type_converters::normalize_int;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+#[cfg(feature = "random")]
+use crate::std::random::with_rng;
+#[cfg(feature = "random")]
+use rand::seq::SliceRandom;
 
 pub(crate) fn bhead_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.into_iter().next() {
@@ -53,3 +62,237 @@ pub(crate) fn bpush_fn(args: Vec<Object>) -> Result<Object, String> {
         (None, _) => Err("push() expects 2 arguments, got 1".to_string()),
     }
 }
+
+pub(crate) fn brange_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut bounds = Vec::with_capacity(args.len());
+    for arg in &args {
+        match arg {
+            Object::Integer(i) => bounds.push(*i),
+            o => return Err(format!("range() expects integers, got {}", o.type_name())),
+        }
+    }
+    let (start, end, step) = match bounds.as_slice() {
+        [end] => (0, *end, 1),
+        [start, end] => (*start, *end, 1),
+        [start, end, step] => (*start, *end, *step),
+        _ => return Err(format!("range() expects 1 to 3 arguments, got {}", args.len())),
+    };
+    if step == 0 {
+        return Err("range() step cannot be zero".to_string());
+    }
+    let span = if step > 0 { end as i128 - start as i128 } else { start as i128 - end as i128 };
+    let stride = step.unsigned_abs() as i128;
+    let count = (span.max(0) + stride - 1) / stride;
+    limits::reserve(Alloc::Array(usize::try_from(count).unwrap_or(usize::MAX)))?;
+
+    let mut items = Vec::new();
+    let mut current = start;
+    while (step > 0 && current < end) || (step < 0 && current > end) {
+        items.push(Object::Integer(current));
+        current = match current.checked_add(step) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    Ok(Object::Array(Box::new(items)))
+}
+
+// Method only
+pub(crate) fn bpop_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(mut arr)) => match arr.pop() {
+            None => Err("pop() cannot pop from empty array".to_string()),
+            Some(last) => Ok(Object::Array(Box::new(vec![last, Object::Array(arr)]))),
+        },
+        Some(o) => Err(format!("pop() expects array, got {}", o.type_name())),
+        None => Err("pop() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bshift_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(mut arr)) => match arr.len() {
+            0 => Err("shift() cannot shift from empty array".to_string()),
+            _ => {
+                let first = arr.remove(0);
+                Ok(Object::Array(Box::new(vec![first, Object::Array(arr)])))
+            }
+        },
+        Some(o) => Err(format!("shift() expects array, got {}", o.type_name())),
+        None => Err("shift() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn binsert_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next(), args.next()) {
+        (Some(Object::Array(mut arr)), Some(Object::Integer(idx)), Some(value)) => {
+            if idx < 0 || idx as usize > arr.len() {
+                return Err(format!(
+                    "insert() index {} out of bounds (array length: {})",
+                    idx,
+                    arr.len()
+                ));
+            }
+            arr.insert(idx as usize, value);
+            Ok(Object::Array(arr))
+        }
+        (Some(Object::Array(_)), Some(o), Some(_)) => Err(format!(
+            "insert() index must be integer, got {}",
+            o.type_name()
+        )),
+        (Some(Object::Array(_)), _, _) => Err("insert() expects 3 arguments".to_string()),
+        (Some(o), _, _) => Err(format!("insert() expects array, got {}", o.type_name())),
+        (None, _, _) => Err("insert() expects 3 arguments, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn breverse_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(mut arr)) => {
+            arr.reverse();
+            Ok(Object::Array(arr))
+        }
+        Some(o) => Err(format!("reverse() expects array, got {}", o.type_name())),
+        None => Err("reverse() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+#[cfg(feature = "random")]
+pub(crate) fn bshuffle_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(mut arr)) => {
+            with_rng(|rng| arr.shuffle(rng));
+            Ok(Object::Array(arr))
+        }
+        Some(o) => Err(format!("shuffle() expects array, got {}", o.type_name())),
+        None => Err("shuffle() expects 1 argument, got 0".to_string()),
+    }
+}
+
+/// Adds up a numeric array. Integers are summed as BigInt so the result
+/// never wraps, and any float element promotes the whole sum to float.
+fn sum_numbers(name: &str, arr: &[Object]) -> Result<Object, String> {
+    let mut int_total = BigInt::zero();
+    let mut float_total = 0.0;
+    let mut has_float = false;
+
+    for item in arr {
+        match item {
+            Object::Integer(i) => int_total += *i,
+            Object::BigInteger(b) => int_total += b.as_ref(),
+            Object::Float(f) => {
+                float_total += f;
+                has_float = true;
+            }
+            o => {
+                return Err(format!(
+                    "{}() expects array of numbers, got element of type {}",
+                    name,
+                    o.type_name()
+                ))
+            }
+        }
+    }
+
+    if has_float {
+        match int_total.to_f64() {
+            Some(f) => Ok(Object::Float(float_total + f)),
+            None => Err(format!("{}() integer total too large for float", name)),
+        }
+    } else {
+        Ok(normalize_int(int_total))
+    }
+}
+
+/// Picks the smallest (or largest) element of a numeric array using the
+/// VM's mixed int/bigInteger/float comparison rules.
+fn extreme_number(name: &str, arr: Vec<Object>, pick_max: bool) -> Result<Object, String> {
+    let mut iter = arr.into_iter();
+    let mut best = match iter.next() {
+        Some(first) => first,
+        None => return Err(format!("{}() cannot be applied to an empty array", name)),
+    };
+    if !best.is_number() {
+        return Err(format!(
+            "{}() expects array of numbers, got element of type {}",
+            name,
+            best.type_name()
+        ));
+    }
+
+    for item in iter {
+        if !item.is_number() {
+            return Err(format!(
+                "{}() expects array of numbers, got element of type {}",
+                name,
+                item.type_name()
+            ));
+        }
+        let replace = if pick_max {
+            greater_than(item.clone(), best.clone())
+        } else {
+            less_than(item.clone(), best.clone())
+        };
+        match replace {
+            Object::Boolean(true) => best = item,
+            Object::Boolean(false) => {}
+            other => return Err(format!("{}() comparison failed: {}", name, other)),
+        }
+    }
+
+    Ok(best)
+}
+
+// Method only
+pub(crate) fn bsum_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(arr)) => sum_numbers("sum", &arr),
+        Some(o) => Err(format!("sum() expects array, got {}", o.type_name())),
+        None => Err("sum() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn barray_min_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(arr)) => extreme_number("min", *arr, false),
+        Some(o) => Err(format!("min() expects array, got {}", o.type_name())),
+        None => Err("min() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn barray_max_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(arr)) => extreme_number("max", *arr, true),
+        Some(o) => Err(format!("max() expects array, got {}", o.type_name())),
+        None => Err("max() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bavg_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Array(arr)) => {
+            if arr.is_empty() {
+                return Err("avg() cannot be applied to an empty array".to_string());
+            }
+            let total = match sum_numbers("avg", &arr)? {
+                Object::Integer(i) => i as f64,
+                Object::BigInteger(b) => b
+                    .to_f64()
+                    .ok_or_else(|| "avg() integer total too large for float".to_string())?,
+                Object::Float(f) => f,
+                _ => unreachable!("sum_numbers only returns numbers"),
+            };
+            Ok(Object::Float(total / arr.len() as f64))
+        }
+        Some(o) => Err(format!("avg() expects array, got {}", o.type_name())),
+        None => Err("avg() expects 1 argument, got 0".to_string()),
+    }
+}
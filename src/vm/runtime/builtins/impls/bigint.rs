@@ -0,0 +1,75 @@
+use crate::vm::obj::Object;
+use crate::vm::runtime::type_converters::normalize_int;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+fn as_bigint(obj: &Object) -> Option<BigInt> {
+    match obj {
+        Object::Integer(i) => Some(BigInt::from(*i)),
+        Object::BigInteger(b) => Some((**b).clone()),
+        _ => None,
+    }
+}
+
+// Method only
+pub(crate) fn bbigpow_fn(args: Vec<Object>) -> Result<Object, String> {
+    match (args.first(), args.get(1)) {
+        (Some(Object::BigInteger(base)), Some(Object::Integer(exp))) => {
+            if *exp < 0 {
+                return Err("pow() does not support negative exponents".to_string());
+            }
+            match exp.to_u32() {
+                Some(exp) => Ok(normalize_int(base.pow(exp))),
+                None => Err(format!("pow() exponent {} is too large", exp)),
+            }
+        }
+        (Some(Object::BigInteger(_)), Some(o)) => Err(format!(
+            "pow() exponent must be integer, got {}",
+            o.type_name()
+        )),
+        (Some(Object::BigInteger(_)), None) => Err("pow() expects 2 arguments, got 1".to_string()),
+        (Some(o), _) => Err(format!("pow() expects bigInteger, got {}", o.type_name())),
+        (None, _) => Err("pow() expects 2 arguments, got 0".to_string()),
+    }
+}
+
+// Method only - Integer, BigInteger
+pub(crate) fn bmodpow_fn(args: Vec<Object>) -> Result<Object, String> {
+    if args.len() != 3 {
+        return Err(format!("mod_pow() expects 3 arguments, got {}", args.len()));
+    }
+    let mut values = Vec::with_capacity(3);
+    for arg in &args {
+        match as_bigint(arg) {
+            Some(v) => values.push(v),
+            None => {
+                return Err(format!(
+                    "mod_pow() expects integer or bigInteger, got {}",
+                    arg.type_name()
+                ))
+            }
+        }
+    }
+    let (base, exp, modulus) = (&values[0], &values[1], &values[2]);
+    if exp.is_negative() {
+        return Err("mod_pow() does not support negative exponents".to_string());
+    }
+    if modulus.is_zero() {
+        return Err("mod_pow() modulus cannot be zero".to_string());
+    }
+    Ok(normalize_int(base.modpow(exp, modulus)))
+}
+
+/// Formats an integer in `radix` (2–36), used by `to_string(radix)`.
+pub(crate) fn to_radix_string(obj: &Object, radix: i64) -> Result<String, String> {
+    if !(2..=36).contains(&radix) {
+        return Err(format!("to_string() radix must be between 2 and 36, got {}", radix));
+    }
+    match as_bigint(obj) {
+        Some(n) => Ok(n.to_str_radix(radix as u32)),
+        None => Err(format!(
+            "to_string() radix is only supported for integers, got {}",
+            obj.type_name()
+        )),
+    }
+}
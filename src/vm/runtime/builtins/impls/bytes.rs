@@ -0,0 +1,79 @@
+use crate::vm::limits::{self, Alloc};
+use crate::vm::obj::Object;
+
+/// Converts an array of integers in `0..=255` into raw bytes.
+fn array_to_bytes(name: &str, arr: &[Object]) -> Result<Vec<u8>, String> {
+    arr.iter()
+        .map(|item| match item {
+            Object::Integer(i) => u8::try_from(*i)
+                .map_err(|_| format!("{}() byte values must be between 0 and 255, got {}", name, i)),
+            o => Err(format!("{}() expects an array of integers, got {}", name, o.type_name())),
+        })
+        .collect()
+}
+
+pub(crate) fn bnewbytes_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(Object::Bytes(Box::new(s.as_bytes().to_vec()))),
+        Some(Object::Array(arr)) => Ok(Object::Bytes(Box::new(array_to_bytes("bytes", arr)?))),
+        Some(Object::Bytes(bytes)) => Ok(Object::Bytes(bytes.clone())),
+        Some(Object::Integer(n)) if *n >= 0 => {
+            let len = *n as usize;
+            limits::reserve(Alloc::Bytes(len))?;
+            let mut bytes = Vec::new();
+            bytes
+                .try_reserve_exact(len)
+                .map_err(|_| format!("bytes() cannot allocate {} bytes", len))?;
+            bytes.resize(len, 0);
+            Ok(Object::Bytes(Box::new(bytes)))
+        }
+        Some(Object::Integer(n)) => Err(format!("bytes() length must be non-negative, got {}", n)),
+        Some(o) => Err(format!(
+            "bytes() expects string, array, integer, or bytes, got {}",
+            o.type_name()
+        )),
+        None => Err("bytes() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bstrtobytes_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(Object::Bytes(Box::new(s.as_bytes().to_vec()))),
+        Some(o) => Err(format!("to_bytes() expects string, got {}", o.type_name())),
+        None => Err("to_bytes() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bbytestostring_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Bytes(bytes)) => String::from_utf8(bytes.to_vec())
+            .map(|s| Object::String(s.into()))
+            .map_err(|e| format!("to_string() bytes are not valid UTF-8: {}", e)),
+        Some(o) => Err(format!("to_string() expects bytes, got {}", o.type_name())),
+        None => Err("to_string() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bbytestoarray_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Bytes(bytes)) => Ok(Object::Array(Box::new(
+            bytes.iter().map(|b| Object::Integer(*b as i64)).collect(),
+        ))),
+        Some(o) => Err(format!("to_array() expects bytes, got {}", o.type_name())),
+        None => Err("to_array() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bbytestohex_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Bytes(bytes)) => Ok(Object::String(
+            bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>().into(),
+        )),
+        Some(o) => Err(format!("to_hex() expects bytes, got {}", o.type_name())),
+        None => Err("to_hex() expects 1 argument, got 0".to_string()),
+    }
+}
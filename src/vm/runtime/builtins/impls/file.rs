@@ -0,0 +1,143 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+
+use crate::vm::obj::Object;
+
+/// Locks the handle in `args[0]` and runs `op` on the open reader with the
+/// remaining arguments. Every file method fails once the file is closed.
+fn with_open_file(
+    name: &str,
+    args: Vec<Object>,
+    op: impl FnOnce(&mut BufReader<File>, Vec<Object>) -> std::io::Result<Result<Object, String>>,
+) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match args.next() {
+        Some(Object::File(handle)) => {
+            let mut handle = handle.lock().unwrap();
+            let path = handle.path.clone();
+            match handle.reader.as_mut() {
+                Some(reader) => op(reader, args.collect())
+                    .map_err(|e| format!("{}() failed on '{}': {}", name, path, e))?,
+                None => Err(format!("{}() called on closed file '{}'", name, path)),
+            }
+        }
+        Some(o) => Err(format!("{}() expects file, got {}", name, o.type_name())),
+        None => Err(format!("{}() expects a file", name)),
+    }
+}
+
+/// Reads `n` bytes, or everything up to the end of the file when no count is given.
+fn read_chunk(name: &str, reader: &mut BufReader<File>, count: Option<&Object>) -> std::io::Result<Result<Vec<u8>, String>> {
+    let mut buf = Vec::new();
+    match count {
+        None => {
+            reader.read_to_end(&mut buf)?;
+        }
+        Some(Object::Integer(n)) if *n >= 0 => {
+            reader.by_ref().take(*n as u64).read_to_end(&mut buf)?;
+        }
+        Some(Object::Integer(n)) => return Ok(Err(format!("{}() count must be non-negative, got {}", name, n))),
+        Some(o) => return Ok(Err(format!("{}() count must be integer, got {}", name, o.type_name()))),
+    }
+    Ok(Ok(buf))
+}
+
+// Method only
+pub(crate) fn bfileread_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("read", args, |reader, rest| {
+        Ok(read_chunk("read", reader, rest.first())?.and_then(|buf| {
+            String::from_utf8(buf)
+                .map(|s| Object::String(s.into()))
+                .map_err(|e| format!("read() data is not valid UTF-8: {}", e))
+        }))
+    })
+}
+
+// Method only
+pub(crate) fn bfilereadbytes_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("read_bytes", args, |reader, rest| {
+        Ok(read_chunk("read_bytes", reader, rest.first())?.map(|buf| Object::Bytes(Box::new(buf))))
+    })
+}
+
+// Method only
+// Returns the next line without its line ending, or null at end of file.
+pub(crate) fn bfilereadline_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("read_line", args, |reader, _| {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(Ok(Object::Null));
+        }
+        let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+        let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+        Ok(Ok(Object::String(trimmed.to_string().into())))
+    })
+}
+
+// Method only
+// Returns the number of bytes written.
+pub(crate) fn bfilewrite_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("write", args, |reader, rest| {
+        let data = match rest.first() {
+            Some(Object::String(s)) => s.as_bytes().to_vec(),
+            Some(Object::Bytes(b)) => b.to_vec(),
+            Some(o) => return Ok(Err(format!("write() expects string or bytes, got {}", o.type_name()))),
+            None => return Ok(Err("write() expects 1 argument, got 0".to_string())),
+        };
+        // Seeking to the current position drops any read-ahead buffer so the
+        // write lands where the script expects it.
+        reader.stream_position().and_then(|pos| reader.seek(SeekFrom::Start(pos)))?;
+        reader.get_mut().write_all(&data)?;
+        Ok(Ok(Object::Integer(data.len() as i64)))
+    })
+}
+
+// Method only
+// seek(pos) moves to an absolute offset; seek(offset, "current" | "end") is
+// relative. Returns the new position.
+pub(crate) fn bfileseek_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("seek", args, |reader, rest| {
+        let target = match (rest.first(), rest.get(1)) {
+            (Some(Object::Integer(pos)), None) if *pos >= 0 => SeekFrom::Start(*pos as u64),
+            (Some(Object::Integer(pos)), Some(Object::String(whence))) => match whence.as_str() {
+                "start" if *pos >= 0 => SeekFrom::Start(*pos as u64),
+                "current" => SeekFrom::Current(*pos),
+                "end" => SeekFrom::End(*pos),
+                "start" => return Ok(Err(format!("seek() position must be non-negative, got {}", pos))),
+                other => return Ok(Err(format!("seek() whence must be \"start\", \"current\" or \"end\", got \"{}\"", other))),
+            },
+            (Some(Object::Integer(pos)), None) => return Ok(Err(format!("seek() position must be non-negative, got {}", pos))),
+            (Some(Object::Integer(_)), Some(o)) => return Ok(Err(format!("seek() whence must be string, got {}", o.type_name()))),
+            (Some(o), _) => return Ok(Err(format!("seek() position must be integer, got {}", o.type_name()))),
+            (None, _) => return Ok(Err("seek() expects at least 1 argument, got 0".to_string())),
+        };
+        Ok(Ok(Object::Integer(reader.seek(target)? as i64)))
+    })
+}
+
+// Method only
+pub(crate) fn bfiletell_fn(args: Vec<Object>) -> Result<Object, String> {
+    with_open_file("tell", args, |reader, _| Ok(Ok(Object::Integer(reader.stream_position()? as i64))))
+}
+
+// Method only
+// Closing twice is allowed and does nothing.
+pub(crate) fn bfileclose_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::File(handle)) => {
+            handle.lock().unwrap().reader = None;
+            Ok(Object::Null)
+        }
+        Some(o) => Err(format!("close() expects file, got {}", o.type_name())),
+        None => Err("close() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bfileisclosed_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::File(handle)) => Ok(Object::Boolean(handle.lock().unwrap().reader.is_none())),
+        Some(o) => Err(format!("is_closed() expects file, got {}", o.type_name())),
+        None => Err("is_closed() expects 1 argument, got 0".to_string()),
+    }
+}
@@ -22,3 +22,64 @@ pub(crate) fn btofloat_fn(args: Vec<Object>) -> Result<Object, String> {
         None => Err("to_float() expects 1 argument, got 0".to_string()),
     }
 }
+
+// Method only
+pub(crate) fn bisnan_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Float(f)) => Ok(Object::Boolean(f.is_nan())),
+        Some(o) => Err(format!("is_nan() expects float, got {}", o.type_name())),
+        None => Err("is_nan() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bisfinite_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Float(f)) => Ok(Object::Boolean(f.is_finite())),
+        Some(o) => Err(format!("is_finite() expects float, got {}", o.type_name())),
+        None => Err("is_finite() expects 1 argument, got 0".to_string()),
+    }
+}
+
+/// Most decimal places the decimal-place methods give, as in JavaScript's
+/// `toFixed`.
+const MAX_DIGITS: i64 = 100;
+
+/// Unpacks `(float, digits)` for the decimal-place methods.
+fn float_and_digits(name: &str, args: Vec<Object>) -> Result<(f64, usize), String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(Object::Float(f)), Some(Object::Integer(n))) if (0..=MAX_DIGITS).contains(&n) => Ok((f, n as usize)),
+        (Some(Object::Float(_)), Some(Object::Integer(n))) => Err(format!(
+            "{}() digits must be between 0 and {}, got {}",
+            name, MAX_DIGITS, n
+        )),
+        (Some(Object::Float(_)), Some(o)) => Err(format!(
+            "{}() digits must be integer, got {}",
+            name,
+            o.type_name()
+        )),
+        (Some(Object::Float(_)), None) => Err(format!("{}() expects 2 arguments, got 1", name)),
+        (Some(o), _) => Err(format!("{}() expects float, got {}", name, o.type_name())),
+        (None, _) => Err(format!("{}() expects 2 arguments, got 0", name)),
+    }
+}
+
+// Method only
+pub(crate) fn btofixed_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (f, digits) = float_and_digits("to_fixed", args)?;
+    Ok(Object::String(format!("{:.*}", digits, f).into()))
+}
+
+// Method only
+pub(crate) fn broundto_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (f, digits) = float_and_digits("round_to", args)?;
+    if !f.is_finite() {
+        return Ok(Object::Float(f));
+    }
+    // Going through the decimal string avoids the drift of `(f * 10^n).round() / 10^n`
+    match format!("{:.*}", digits, f).parse::<f64>() {
+        Ok(rounded) => Ok(Object::Float(rounded)),
+        Err(e) => Err(format!("round_to() failed: {}", e)),
+    }
+}
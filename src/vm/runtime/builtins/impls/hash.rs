@@ -9,12 +9,12 @@ pub(crate) fn bset_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next(), args.next()) {
         (Some(Object::Hash(mut hash)), Some(key), Some(value)) => match &key {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+            _ if key.is_hashable() => {
                 hash.insert(key, value);
                 Ok(Object::Hash(hash))
             }
             _ => Err(format!(
-                "set() key must be integer, boolean, or string, got {}",
+                "set() key must be a number, boolean, string, bytes or array, got {}",
                 key.type_name()
             )),
         },
@@ -28,11 +28,11 @@ pub(crate) fn bhas_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next()) {
         (Some(Object::Hash(hash)), Some(key)) => match &key {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+            _ if key.is_hashable() => {
                 Ok(Object::Boolean(hash.contains_key(&key)))
             }
             _ => Err(format!(
-                "has() key must be integer, boolean, or string, got {}",
+                "has() key must be a number, boolean, string, bytes or array, got {}",
                 key.type_name()
             )),
         },
@@ -63,10 +63,99 @@ pub(crate) fn bvalues_fn(args: Vec<Object>) -> Result<Object, String> {
     }
 }
 
+// Method only
+pub(crate) fn bentries_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::Hash(hash)) => {
+            let entries: Vec<Object> = hash
+                .iter()
+                .map(|(k, v)| Object::Array(Box::new(vec![k.clone(), v.clone()])))
+                .collect();
+            Ok(Object::Array(Box::new(entries)))
+        }
+        Some(o) => Err(format!("entries() expects hash, got {}", o.type_name())),
+        None => Err("entries() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bgetor_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next(), args.next()) {
+        (Some(Object::Hash(hash)), Some(key), Some(default)) => match &key {
+            _ if key.is_hashable() => {
+                Ok(hash.get(&key).cloned().unwrap_or(default))
+            }
+            _ => Err(format!(
+                "get_or() key must be a number, boolean, string, bytes or array, got {}",
+                key.type_name()
+            )),
+        },
+        (Some(Object::Hash(_)), _, _) => Err("get_or() expects 3 arguments".to_string()),
+        (Some(o), _, _) => Err(format!("get_or() expects hash, got {}", o.type_name())),
+        (None, _, _) => Err("get_or() expects 3 arguments, got 0".to_string()),
+    }
+}
+
+// Method only
+// Like set(), this returns the resulting hash; the key is only written when
+// it is missing, so existing values are left untouched.
+pub(crate) fn bsetdefault_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next(), args.next()) {
+        (Some(Object::Hash(mut hash)), Some(key), Some(default)) => match &key {
+            _ if key.is_hashable() => {
+                hash.entry(key).or_insert(default);
+                Ok(Object::Hash(hash))
+            }
+            _ => Err(format!(
+                "set_default() key must be a number, boolean, string, bytes or array, got {}",
+                key.type_name()
+            )),
+        },
+        (Some(Object::Hash(_)), _, _) => Err("set_default() expects 3 arguments".to_string()),
+        (Some(o), _, _) => Err(format!("set_default() expects hash, got {}", o.type_name())),
+        (None, _, _) => Err("set_default() expects 3 arguments, got 0".to_string()),
+    }
+}
+
+/// Copies every entry of `other` over `base`, later keys winning.
+fn merge_into(name: &str, args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(Object::Hash(mut base)), Some(Object::Hash(other))) => {
+            base.extend(*other);
+            Ok(Object::Hash(base))
+        }
+        (Some(Object::Hash(_)), Some(o)) => Err(format!(
+            "{}() expects hash argument, got {}",
+            name,
+            o.type_name()
+        )),
+        (Some(Object::Hash(_)), None) => Err(format!("{}() expects 2 arguments, got 1", name)),
+        (Some(o), _) => Err(format!("{}() expects hash, got {}", name, o.type_name())),
+        (None, _) => Err(format!("{}() expects 2 arguments, got 0", name)),
+    }
+}
+
+// Method only
+pub(crate) fn bmerge_fn(args: Vec<Object>) -> Result<Object, String> {
+    merge_into("merge", args)
+}
+
+// Method only
+// Hashes are values today, so update() returns the updated hash just like
+// merge(). Once hashes become reference types it will modify the receiver
+// in place and merge() will remain the non-mutating variant.
+pub(crate) fn bupdate_fn(args: Vec<Object>) -> Result<Object, String> {
+    merge_into("update", args)
+}
+
 pub(crate) fn bclear_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.first() {
         Some(Object::Hash(_)) => Ok(Object::Hash(Box::new(HashMap::new()))),
-        Some(o) => Err(format!("clear() expects hash, got {}", o.type_name())),
+        Some(Object::Array(_)) => Ok(Object::Array(Box::default())),
+        Some(o) => Err(format!("clear() expects hash or array, got {}", o.type_name())),
         None => Err("clear() expects 1 argument, got 0".to_string()),
     }
 }
@@ -12,12 +12,12 @@ pub(crate) fn binput_fn(args: Vec<Object>) -> Result<Object, String> {
             
             let mut input = String::new();
             io::stdin().read_line(&mut input).expect("Failed to read line");
-            Ok(Object::String(input.trim_end().to_string()))
+            Ok(Object::String(input.trim_end().to_string().into()))
         }
         Some(Object::Null) | None=> {
             let mut input = String::new();
             io::stdin().read_line(&mut input).expect("Failed to read line");
-            Ok(Object::String(input.trim_end().to_string()))
+            Ok(Object::String(input.trim_end().to_string().into()))
         }
         _ => Err("Invalid argument to input()".to_string())
     }
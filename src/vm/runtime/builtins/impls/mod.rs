@@ -4,7 +4,12 @@ pub(crate) mod output;
 pub(crate) mod string;
 pub(crate) mod r#type;
 pub(crate) mod int;
+pub(crate) mod bigint;
+pub(crate) mod bytes;
+pub(crate) mod file;
 pub(crate) mod hash;
+pub(crate) mod reference;
 pub(crate) mod shared;
 pub(crate) mod struct_ops;
-pub(crate) mod float;
\ No newline at end of file
+pub(crate) mod float;
+pub(crate) mod process;
\ No newline at end of file
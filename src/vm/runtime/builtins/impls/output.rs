@@ -1,25 +1,104 @@
+use std::cell::RefCell;
+
 use crate::vm::obj::Object;
 
-// Function only
-pub(crate) fn bprint_fn(args: Vec<Object>) -> Result<Object, String> {
-    for (i, obj) in args.iter().enumerate() {
-        if i > 0 {
-            print!("");
+thread_local! {
+    /// While set, the printing builtins on this thread append here instead
+    /// of writing to standard output.
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn write_out(text: &str) {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(buffer) => buffer.push_str(text),
+        None => print!("{}", text),
+    })
+}
+
+/// Runs `run` and returns what it printed. The builtins run on the polling
+/// thread, so this needs a current-thread runtime.
+#[cfg(test)]
+pub(crate) async fn capture<F: std::future::Future>(run: F) -> (F::Output, String) {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(String::new()));
+    let result = run.await;
+    let printed = CAPTURED.with(|captured| captured.borrow_mut().take()).unwrap_or_default();
+    (result, printed)
+}
+
+/// Joins print arguments with single spaces, the way `print`/`println` display them.
+pub(crate) fn join_args(args: &[Object]) -> String {
+    args.iter()
+        .map(|obj| obj.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Substitutes each `{}` in `template` with the next argument. `{{` and `}}`
+/// produce literal braces.
+pub(crate) fn format_placeholders(name: &str, template: &str, args: &[Object]) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut values = args.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                out.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                match values.next() {
+                    Some(v) => out.push_str(&v.to_string()),
+                    None => {
+                        return Err(format!(
+                            "{}() format string has more placeholders than arguments",
+                            name
+                        ))
+                    }
+                }
+            }
+            _ => out.push(c),
         }
-        print!("{}", obj);
     }
+    if values.next().is_some() {
+        return Err(format!(
+            "{}() received more arguments than format placeholders",
+            name
+        ));
+    }
+    Ok(out)
+}
+
+// Function only
+pub(crate) fn bprint_fn(args: Vec<Object>) -> Result<Object, String> {
+    write_out(&join_args(&args));
     Ok(Object::Null)
 }
 
 // Function only
 pub(crate) fn bprintln_fn(args: Vec<Object>) -> Result<Object, String> {
-    for (i, obj) in args.iter().enumerate() {
-        if i > 0 {
-            print!("");
+    write_out(&format!("{}\n", join_args(&args)));
+    Ok(Object::Null)
+}
+
+// Function only
+pub(crate) fn bprintf_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.split_first() {
+        Some((Object::String(template), rest)) => {
+            write_out(&format_placeholders("printf", template, rest)?);
+            Ok(Object::Null)
         }
-        print!("{}", obj);
+        Some((o, _)) => Err(format!(
+            "printf() expects a format string, got {}",
+            o.type_name()
+        )),
+        None => Err("printf() expects at least 1 argument, got 0".to_string()),
     }
-    println!();
-    Ok(Object::Null)
 }
 
+pub(crate) fn binspect_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(obj) => Ok(Object::String(obj.inspect().into())),
+        None => Err("inspect() expects 1 argument, got 0".to_string()),
+    }
+}
@@ -0,0 +1,16 @@
+use crate::vm::obj::Object;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// `exit(code?)` ends the script with status `code`, 0 if not given. It
+/// unwinds as [`RuntimeError::Exit`] so the caller decides what to do with
+/// the status instead of the process ending under it.
+pub(crate) fn bexit_fn(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    match args.first() {
+        None | Some(Object::Null) => Err(RuntimeError::Exit(0)),
+        Some(Object::Integer(code)) => match i32::try_from(*code) {
+            Ok(code) => Err(RuntimeError::Exit(code)),
+            Err(_) => Err(RuntimeError::InvalidArguments(format!("exit status {} is out of range", code))),
+        },
+        Some(other) => Err(RuntimeError::TypeMismatch { expected: "integer".to_string(), got: other.type_name() }),
+    }
+}
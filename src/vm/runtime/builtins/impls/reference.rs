@@ -0,0 +1,46 @@
+use std::sync::{Arc, Mutex};
+
+use crate::vm::obj::Object;
+use crate::vm::runtime::builtins::methods::BuiltinMethods;
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+/// `ref(value)` wraps a value in a shared mutable cell.
+pub(crate) fn bref_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(value) => Ok(Object::Ref(Arc::new(Mutex::new(value)))),
+        None => Err("ref() expects 1 argument, got 0".to_string()),
+    }
+}
+
+/// Method calls on refs. `get` returns a copy of the current value and `set`
+/// replaces it; any other method is forwarded to a copy of the wrapped value,
+/// so `r.len()` works but mutation has to go through `set` or an index/field
+/// assignment on the ref.
+pub(crate) fn call_ref_method(
+    cell: &Arc<Mutex<Object>>,
+    method_name: &str,
+    args: Vec<Object>,
+) -> Result<Object, RuntimeError> {
+    match method_name {
+        "get" => {
+            if !args.is_empty() {
+                return Err(RuntimeError::WrongNumberOfArguments { min: 0, max: 0, got: args.len() });
+            }
+            Ok(cell.lock().unwrap().clone())
+        }
+        "set" => {
+            let got = args.len();
+            match <[Object; 1]>::try_from(args) {
+                Ok([value]) => {
+                    *cell.lock().unwrap() = value;
+                    Ok(Object::Null)
+                }
+                Err(_) => Err(RuntimeError::WrongNumberOfArguments { min: 1, max: 1, got }),
+            }
+        }
+        _ => {
+            let inner = cell.lock().unwrap().clone();
+            BuiltinMethods::call_method(inner, method_name, args)
+        }
+    }
+}
@@ -1,12 +1,21 @@
-use num_bigint::ToBigInt;
+use num_bigint::{BigInt, ToBigInt};
 use num_traits::ToPrimitive;
 
 use crate::vm::obj::Object;
+use crate::vm::runtime::builtins::impls::bigint::to_radix_string;
+use crate::vm::runtime::type_converters::normalize_int;
 
 // Method only
 pub(crate) fn btostring_fn(args: Vec<Object>) -> Result<Object, String> {
-    match args.first() {
-        Some(obj) => Ok(Object::String(format!("{}", obj))),
+    match (args.first(), args.get(1)) {
+        (Some(obj), Some(Object::Integer(radix))) => {
+            Ok(Object::String(to_radix_string(obj, *radix)?.into()))
+        }
+        (Some(_), Some(o)) => Err(format!(
+            "to_string() radix must be integer, got {}",
+            o.type_name()
+        )),
+        (Some(obj), None) => Ok(Object::String(format!("{}", obj).into())),
         _ => Err(format!(
             "to_string() expects 1 argument, got {}",
             args.len()
@@ -45,8 +54,9 @@ pub(crate) fn bisempty_fn(args: Vec<Object>) -> Result<Object, String> {
         Some(Object::String(s)) => Ok(Object::Boolean(s.is_empty())),
         Some(Object::Array(arr)) => Ok(Object::Boolean(arr.is_empty())),
         Some(Object::Hash(hash)) => Ok(Object::Boolean(hash.is_empty())),
+        Some(Object::Bytes(bytes)) => Ok(Object::Boolean(bytes.is_empty())),
         Some(o) => Err(format!(
-            "is_empty() expects string, array, or hash, got {}",
+            "is_empty() expects string, array, hash, or bytes, got {}",
             o.type_name()
         )),
         None => Err("is_empty() expects 1 argument, got 0".to_string()),
@@ -59,8 +69,9 @@ pub(crate) fn blen_fn(args: Vec<Object>) -> Result<Object, String> {
         Some(Object::String(s)) => Ok(Object::Integer(s.len() as i64)),
         Some(Object::Array(arr)) => Ok(Object::Integer(arr.len() as i64)),
         Some(Object::Hash(hash)) => Ok(Object::Integer(hash.len() as i64)),
+        Some(Object::Bytes(bytes)) => Ok(Object::Integer(bytes.len() as i64)),
         Some(o) => Err(format!(
-            "len() expects string, array, or hash, got {}",
+            "len() expects string, array, hash, or bytes, got {}",
             o.type_name()
         )),
         None => Err("len() expects 1 argument, got 0".to_string()),
@@ -72,12 +83,12 @@ pub(crate) fn bremove_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next()) {
         (Some(Object::Hash(mut hash)), Some(key)) => match &key {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+            _ if key.is_hashable() => {
                 hash.remove(&key);
                 Ok(Object::Hash(hash))
             }
             _ => Err(format!(
-                "remove() key must be integer, boolean, or string, got {}",
+                "remove() key must be a number, boolean, string, bytes or array, got {}",
                 key.type_name()
             )),
         },
@@ -119,7 +130,7 @@ pub(crate) fn bget_fn(args: Vec<Object>) -> Result<Object, String> {
                     chars.len()
                 ));
             }
-            Ok(Object::String(chars[index].to_string()))
+            Ok(Object::String(chars[index].to_string().into()))
         }
         (Some(Object::Array(vec)), Some(Object::Integer(idx))) => {
             let i = idx;
@@ -137,11 +148,11 @@ pub(crate) fn bget_fn(args: Vec<Object>) -> Result<Object, String> {
             Ok(vec[index].clone())
         }
         (Some(Object::Hash(hash)), Some(key)) => match &key {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => {
+            _ if key.is_hashable() => {
                 Ok(hash.get(&key).cloned().unwrap_or(Object::Null))
             }
             _ => Err(format!(
-                "get() key must be integer, boolean, or string, got {}",
+                "get() key must be a number, boolean, string, bytes or array, got {}",
                 key.type_name()
             )),
         },
@@ -157,7 +168,7 @@ pub(crate) fn bcontains_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next()) {
         (Some(Object::String(s)), Some(Object::String(sub))) => {
-            Ok(Object::Boolean(s.contains(&sub)))
+            Ok(Object::Boolean(s.contains(sub.as_str())))
         }
         (Some(Object::Array(arr)), Some(item)) => Ok(Object::Boolean(arr.contains(&item))),
         (Some(o), _) => Err(format!(
@@ -195,7 +206,7 @@ pub(crate) fn bslice_fn(args: Vec<Object>) -> Result<Object, String> {
                 return Err("slice() indices out of bounds".to_string());
             }
             let result: String = chars[start as usize..end as usize].iter().collect();
-            Ok(Object::String(result))
+            Ok(Object::String(result.into()))
         }
         (Some(Object::Array(vec)), Some(Object::Integer(start)), end_opt) => {
             let len = vec.len() as i64;
@@ -221,8 +232,32 @@ pub(crate) fn bslice_fn(args: Vec<Object>) -> Result<Object, String> {
             }
             Ok(Object::Array(Box::new(vec[start as usize..end as usize].to_vec())))
         }
+        (Some(Object::Bytes(bytes)), Some(Object::Integer(start)), end_opt) => {
+            let len = bytes.len() as i64;
+            let start = if start < 0 { len + start } else { start };
+            let end = match end_opt {
+                Some(Object::Integer(e)) => {
+                    if e < 0 {
+                        len + e
+                    } else {
+                        e
+                    }
+                }
+                None => len,
+                Some(o) => {
+                    return Err(format!(
+                        "slice() end must be integer, got {}",
+                        o.type_name()
+                    ))
+                }
+            };
+            if start < 0 || start > len || end > len || start > end {
+                return Err("slice() indices out of bounds".to_string());
+            }
+            Ok(Object::Bytes(Box::new(bytes[start as usize..end as usize].to_vec())))
+        }
         (Some(o), _, _) => Err(format!(
-            "slice() expects string or array, got {}",
+            "slice() expects string, array, or bytes, got {}",
             o.type_name()
         )),
         (None, _, _) => Err("slice() expects at least 2 arguments, got 1".to_string()),
@@ -236,4 +271,51 @@ pub(crate) fn bisnum_fn(args:Vec<Object>) -> Result<Object, String> {
         Some(Object::Integer(_)) | Some(Object::BigInteger(_)) | Some(Object::Float(_)) => Ok(Object::Boolean(true)),
         _ => Ok(Object::Boolean(false))
     }
-}
\ No newline at end of file
+}
+
+pub(crate) fn bdeepcopy_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(obj) => Ok(obj.deep_copy()),
+        None => Err("deep_copy() expects 1 argument, got 0".to_string()),
+    }
+}
+
+pub(crate) fn bdeepequals_fn(args: Vec<Object>) -> Result<Object, String> {
+    match (args.first(), args.get(1)) {
+        (Some(a), Some(b)) => Ok(Object::Boolean(a.deep_equals(b))),
+        _ => Err(format!("deep_equals() expects 2 arguments, got {}", args.len())),
+    }
+}
+
+// Lenient counterpart of to_int(): returns null instead of an error when the
+// text is not a number in the given radix.
+pub(crate) fn bparseint_fn(args: Vec<Object>) -> Result<Object, String> {
+    let radix = match args.get(1) {
+        None => 10,
+        Some(Object::Integer(r)) if (2..=36).contains(r) => *r as u32,
+        Some(Object::Integer(r)) => {
+            return Err(format!("parse_int() radix must be between 2 and 36, got {}", r))
+        }
+        Some(o) => return Err(format!("parse_int() radix must be integer, got {}", o.type_name())),
+    };
+    match args.first() {
+        Some(Object::String(s)) => Ok(BigInt::parse_bytes(s.trim().as_bytes(), radix)
+            .map(normalize_int)
+            .unwrap_or(Object::Null)),
+        Some(o) => Err(format!("parse_int() expects string, got {}", o.type_name())),
+        None => Err("parse_int() expects 1 or 2 arguments, got 0".to_string()),
+    }
+}
+
+// Lenient counterpart of to_float(): returns null when the text is not a number.
+pub(crate) fn bparsefloat_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s
+            .trim()
+            .parse::<f64>()
+            .map(Object::Float)
+            .unwrap_or(Object::Null)),
+        Some(o) => Err(format!("parse_float() expects string, got {}", o.type_name())),
+        None => Err("parse_float() expects 1 argument, got 0".to_string()),
+    }
+}
@@ -1,9 +1,11 @@
+use crate::vm::limits::{self, Alloc};
 use crate::vm::obj::Object;
+use regex::Regex;
 
 // Method only
 pub(crate) fn btoupper_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.first() {
-        Some(Object::String(s)) => Ok(Object::String(s.to_uppercase())),
+        Some(Object::String(s)) => Ok(Object::String(s.to_uppercase().into())),
         Some(o) => Err(format!("to_upper() expects string, got {}", o.type_name())),
         None => Err("to_upper() expects 1 argument, got 0".to_string()),
     }
@@ -12,18 +14,60 @@ pub(crate) fn btoupper_fn(args: Vec<Object>) -> Result<Object, String> {
 // Method only
 pub(crate) fn btolower_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.first() {
-        Some(Object::String(s)) => Ok(Object::String(s.to_lowercase())),
+        Some(Object::String(s)) => Ok(Object::String(s.to_lowercase().into())),
         Some(o) => Err(format!("to_lower() expects a string, got {}", o.type_name())),
         None => Err("to_lower() expects 1 argument, got 0".to_string())
     }
 }
 
+/// Uppercases the first character of `word` and lowercases the rest.
+fn capitalize_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+// Method only
+pub(crate) fn bcapitalize_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(Object::String(capitalize_word(s).into())),
+        Some(o) => Err(format!("capitalize() expects string, got {}", o.type_name())),
+        None => Err("capitalize() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn btitlecase_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => {
+            // Split on whitespace boundaries while keeping the original spacing intact
+            let mut result = String::with_capacity(s.len());
+            let mut word = String::new();
+            for c in s.chars() {
+                if c.is_whitespace() {
+                    result.push_str(&capitalize_word(&word));
+                    word.clear();
+                    result.push(c);
+                } else {
+                    word.push(c);
+                }
+            }
+            result.push_str(&capitalize_word(&word));
+            Ok(Object::String(result.into()))
+        }
+        Some(o) => Err(format!("title_case() expects string, got {}", o.type_name())),
+        None => Err("title_case() expects 1 argument, got 0".to_string()),
+    }
+}
+
 // Method only
 pub(crate) fn bstartswith_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next()) {
         (Some(Object::String(s)), Some(Object::String(prefix))) => {
-            Ok(Object::Boolean(s.starts_with(&prefix)))
+            Ok(Object::Boolean(s.starts_with(prefix.as_str())))
         }
         (Some(o), _) => Err(format!(
             "starts_with() expects string, got {}",
@@ -38,7 +82,7 @@ pub(crate) fn bendswith_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next()) {
         (Some(Object::String(s)), Some(Object::String(suffix))) => {
-            Ok(Object::Boolean(s.ends_with(&suffix)))
+            Ok(Object::Boolean(s.ends_with(suffix.as_str())))
         }
         (Some(o), _) => Err(format!("ends_with() expects string, got {}", o.type_name())),
         (None, _) => Err("ends_with() expects 2 arguments, got 1".to_string()),
@@ -49,8 +93,8 @@ pub(crate) fn breplace_fn(args: Vec<Object>) -> Result<Object, String> {
     let mut args = args.into_iter();
     match (args.next(), args.next(), args.next()) {
         (Some(Object::String(s)), Some(Object::String(old)), Some(Object::String(new))) => {
-            let new_string = s.replace(&old, &new);
-            Ok(Object::String(new_string))
+            let new_string = s.replace(old.as_str(), &new);
+            Ok(Object::String(new_string.into()))
         }
         (Some(o), _, _) => Err(format!("replace() expects string, got {}", o.type_name())),
         (None, Some(_), Some(_)) => Err("replace() expects 3 arguments, got 1".to_string()),
@@ -65,7 +109,7 @@ pub(crate) fn bsplit_fn(args: Vec<Object>) -> Result<Object, String> {
         (Some(Object::String(s)), Some(Object::String(delimiter))) => {
             let parts: Vec<Object> = s
                 .split(delimiter.as_str())
-                .map(|part| Object::String(part.to_string()))
+                .map(|part| Object::String(part.to_string().into()))
                 .collect();
             Ok(Object::Array(Box::new(parts)))
         }
@@ -78,9 +122,224 @@ pub(crate) fn btrim_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.first() {
         Some(Object::String(s)) => {
             let trimmed_str = s.trim().to_string();
-            Ok(Object::String(trimmed_str))
+            Ok(Object::String(trimmed_str.into()))
         }
         Some(o) => Err(format!("trim() expects string, got {}", o.type_name())),
         None => Err("trim() expects 1 argument, got 0".to_string()),
     }
 }
+
+/// Stretches `s` to `width` characters, cycling through `fill` the same way
+/// JavaScript's `padStart`/`padEnd` do. The result is allocated up front and
+/// fallibly, so a width too large for memory is an error rather than an abort.
+fn pad(name: &str, args: Vec<Object>, at_start: bool) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    let (s, width, fill) = match (args.next(), args.next(), args.next()) {
+        (Some(Object::String(s)), Some(Object::Integer(width)), None) => (s, width, " ".into()),
+        (Some(Object::String(s)), Some(Object::Integer(width)), Some(Object::String(fill))) => {
+            (s, width, fill)
+        }
+        (Some(Object::String(_)), Some(Object::Integer(_)), Some(o)) => {
+            return Err(format!("{}() fill must be string, got {}", name, o.type_name()))
+        }
+        (Some(Object::String(_)), Some(o), _) => {
+            return Err(format!("{}() width must be integer, got {}", name, o.type_name()))
+        }
+        (Some(Object::String(_)), None, _) => {
+            return Err(format!("{}() expects 2 or 3 arguments, got 1", name))
+        }
+        (Some(o), _, _) => return Err(format!("{}() expects string, got {}", name, o.type_name())),
+        (None, _, _) => return Err(format!("{}() expects 2 or 3 arguments, got 0", name)),
+    };
+
+    let len = s.chars().count();
+    if width <= len as i64 || fill.is_empty() {
+        return Ok(Object::String(s));
+    }
+    let needed = width as usize - len;
+    let fill_len = fill.chars().count();
+    let partial: usize = fill.chars().take(needed % fill_len).map(char::len_utf8).sum();
+    let padding_bytes = (needed / fill_len).saturating_mul(fill.len()).saturating_add(partial);
+    let total = s.len().saturating_add(padding_bytes);
+    limits::reserve(Alloc::String(total))?;
+
+    let mut out = String::new();
+    out.try_reserve_exact(total)
+        .map_err(|_| format!("{}() cannot allocate a string of {} bytes", name, total))?;
+    if !at_start {
+        out.push_str(&s);
+    }
+    out.extend(fill.chars().cycle().take(needed));
+    if at_start {
+        out.push_str(&s);
+    }
+    Ok(Object::String(out.into()))
+}
+
+// Method only
+pub(crate) fn bpadstart_fn(args: Vec<Object>) -> Result<Object, String> {
+    pad("pad_start", args, true)
+}
+
+// Method only
+pub(crate) fn bpadend_fn(args: Vec<Object>) -> Result<Object, String> {
+    pad("pad_end", args, false)
+}
+
+// Method only
+pub(crate) fn bchars_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => {
+            let chars: Vec<Object> = s.chars().map(|c| Object::String(c.to_string().into())).collect();
+            Ok(Object::Array(Box::new(chars)))
+        }
+        Some(o) => Err(format!("chars() expects string, got {}", o.type_name())),
+        None => Err("chars() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bbytes_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.first() {
+        Some(Object::String(s)) => {
+            let bytes: Vec<Object> = s.bytes().map(|b| Object::Integer(b as i64)).collect();
+            Ok(Object::Array(Box::new(bytes)))
+        }
+        Some(o) => Err(format!("bytes() expects string, got {}", o.type_name())),
+        None => Err("bytes() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Method only
+pub(crate) fn bcharat_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(Object::String(s)), Some(Object::Integer(idx))) => {
+            // Negative indices count from the end, matching slice()
+            let len = s.chars().count() as i64;
+            let i = if idx < 0 { len + idx } else { idx };
+            if i < 0 || i >= len {
+                return Err(format!(
+                    "char_at() index {} out of bounds (string length: {})",
+                    idx, len
+                ));
+            }
+            let c = s.chars().nth(i as usize).unwrap_or_default();
+            Ok(Object::String(c.to_string().into()))
+        }
+        (Some(Object::String(_)), Some(o)) => Err(format!(
+            "char_at() index must be integer, got {}",
+            o.type_name()
+        )),
+        (Some(Object::String(_)), None) => Err("char_at() expects 2 arguments, got 1".to_string()),
+        (Some(o), _) => Err(format!("char_at() expects string, got {}", o.type_name())),
+        (None, _) => Err("char_at() expects 2 arguments, got 0".to_string()),
+    }
+}
+
+/// Unpacks the `(string, substring)` pair shared by the search methods.
+fn search_args(name: &str, args: Vec<Object>) -> Result<(String, String), String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(Object::String(s)), Some(Object::String(sub))) => Ok((s.into(), sub.into())),
+        (Some(Object::String(_)), Some(o)) => Err(format!(
+            "{}() expects string argument, got {}",
+            name,
+            o.type_name()
+        )),
+        (Some(Object::String(_)), None) => Err(format!("{}() expects 2 arguments, got 1", name)),
+        (Some(o), _) => Err(format!("{}() expects string, got {}", name, o.type_name())),
+        (None, _) => Err(format!("{}() expects 2 arguments, got 0", name)),
+    }
+}
+
+/// Converts a byte offset into a character index, since string indices are
+/// character based everywhere else (get, slice, char_at).
+fn char_index(s: &str, byte_idx: Option<usize>) -> Object {
+    match byte_idx {
+        Some(b) => Object::Integer(s[..b].chars().count() as i64),
+        None => Object::Integer(-1),
+    }
+}
+
+// Method only
+pub(crate) fn bfind_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (s, sub) = search_args("find", args)?;
+    Ok(char_index(&s, s.find(&sub)))
+}
+
+// Method only
+pub(crate) fn brfind_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (s, sub) = search_args("rfind", args)?;
+    Ok(char_index(&s, s.rfind(&sub)))
+}
+
+// Method only
+pub(crate) fn bcount_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (s, sub) = search_args("count", args)?;
+    if sub.is_empty() {
+        return Err("count() substring cannot be empty".to_string());
+    }
+    Ok(Object::Integer(s.matches(&sub).count() as i64))
+}
+
+/// Unpacks `(string, pattern)` and compiles the pattern for the regex methods.
+fn regex_args(
+    name: &str,
+    args: &mut std::vec::IntoIter<Object>,
+) -> Result<(String, Regex), String> {
+    match (args.next(), args.next()) {
+        (Some(Object::String(s)), Some(Object::String(pattern))) => Regex::new(&pattern)
+            .map(|re| (s.into(), re))
+            .map_err(|e| format!("{}() invalid pattern: {}", name, e)),
+        (Some(Object::String(_)), Some(o)) => Err(format!(
+            "{}() pattern must be string, got {}",
+            name,
+            o.type_name()
+        )),
+        (Some(Object::String(_)), None) => Err(format!("{}() expects a pattern argument", name)),
+        (Some(o), _) => Err(format!("{}() expects string, got {}", name, o.type_name())),
+        (None, _) => Err(format!("{}() expects a string and a pattern", name)),
+    }
+}
+
+// Method only
+pub(crate) fn bmatches_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (s, re) = regex_args("matches", &mut args.into_iter())?;
+    Ok(Object::Boolean(re.is_match(&s)))
+}
+
+// Method only
+pub(crate) fn bmatch_fn(args: Vec<Object>) -> Result<Object, String> {
+    let (s, re) = regex_args("match", &mut args.into_iter())?;
+    match re.captures(&s) {
+        Some(caps) => {
+            // Index 0 is the whole match, followed by each capture group
+            let groups: Vec<Object> = caps
+                .iter()
+                .map(|m| match m {
+                    Some(m) => Object::String(m.as_str().to_string().into()),
+                    None => Object::Null,
+                })
+                .collect();
+            Ok(Object::Array(Box::new(groups)))
+        }
+        None => Ok(Object::Null),
+    }
+}
+
+// Method only
+pub(crate) fn breplaceregex_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    let (s, re) = regex_args("replace_regex", &mut args)?;
+    match args.next() {
+        Some(Object::String(repl)) => {
+            Ok(Object::String(re.replace_all(&s, repl.as_str()).into_owned().into()))
+        }
+        Some(o) => Err(format!(
+            "replace_regex() replacement must be string, got {}",
+            o.type_name()
+        )),
+        None => Err("replace_regex() expects 3 arguments, got 2".to_string()),
+    }
+}
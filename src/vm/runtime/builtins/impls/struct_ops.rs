@@ -1,4 +1,4 @@
-use crate::vm::obj::Object;
+use crate::vm::obj::{HashMap, Object};
 
 pub fn bset_field_fn(args: Vec<Object>) -> Result<Object, String> {
     if args.len() != 3 {
@@ -13,7 +13,7 @@ pub fn bset_field_fn(args: Vec<Object>) -> Result<Object, String> {
             Object::String(field_name),
             new_value,
         ) => {
-            s.fields.insert(field_name.clone(), new_value.clone());
+            s.fields.insert(field_name.to_string(), new_value.clone());
             Ok(Object::Struct(s))
         }
         (o, _, _) => Err(format!("set_field() expects struct, got {}", o.type_name())),
@@ -31,7 +31,7 @@ pub fn bget_field_fn(args: Vec<Object>) -> Result<Object, String> {
         (
             Object::Struct(s),
             Object::String(field_name),
-        ) => match s.fields.get(&field_name) {
+        ) => match s.fields.get(field_name.as_str()) {
             Some(value) => Ok(value.clone()),
             None => Err(format!("get_field() field '{}' does not exist", field_name)),
         },
@@ -42,7 +42,7 @@ pub fn bget_field_fn(args: Vec<Object>) -> Result<Object, String> {
 pub fn bstruct_fields_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.into_iter().next() {
         Some(Object::Struct(s)) => {
-            let field_names: Vec<Object> = s.fields.keys().cloned().map(Object::String).collect();
+            let field_names: Vec<Object> = s.fields.keys().cloned().map(|s| Object::String(s.into())).collect();
             Ok(Object::Array(Box::new(field_names)))
         }
         Some(o) => Err(format!("fields() expects struct, got {}", o.type_name())),
@@ -52,8 +52,64 @@ pub fn bstruct_fields_fn(args: Vec<Object>) -> Result<Object, String> {
 
 pub fn bstruct_name_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.into_iter().next() {
-        Some(Object::Struct(s)) => Ok(Object::String(s.name)),
+        Some(Object::Struct(s)) => Ok(Object::String(s.name.into())),
         Some(o) => Err(format!("name() expects struct, got {}", o.type_name())),
         None => Err("name() expects 1 argument, got 0".to_string()),
     }
 }
+
+pub fn bstruct_to_hash_fn(args: Vec<Object>) -> Result<Object, String> {
+    match args.into_iter().next() {
+        Some(Object::Struct(s)) => {
+            // Keys are strings, which are safe to hash.
+            #[allow(clippy::mutable_key_type)]
+            let hash: HashMap<Object, Object> = s
+                .fields
+                .into_iter()
+                .map(|(name, value)| (Object::String(name.into()), value))
+                .collect();
+            Ok(Object::Hash(Box::new(hash)))
+        }
+        Some(o) => Err(format!("to_hash() expects struct, got {}", o.type_name())),
+        None => Err("to_hash() expects 1 argument, got 0".to_string()),
+    }
+}
+
+// Called on the struct name (`Point.from_hash(h)`), which evaluates to the
+// struct template, so unspecified fields keep their declared defaults.
+// Keys that are not declared fields (methods included) are rejected.
+pub fn bstruct_from_hash_fn(args: Vec<Object>) -> Result<Object, String> {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(Object::Struct(mut template)), Some(Object::Hash(hash))) => {
+            for (key, value) in *hash {
+                match key {
+                    Object::String(name) => match template.fields.get_mut(&*name) {
+                        Some(field) => *field = value,
+                        None => {
+                            return Err(format!(
+                                "from_hash() struct {} has no field '{}'",
+                                template.name, name
+                            ))
+                        }
+                    },
+                    other => {
+                        return Err(format!(
+                            "from_hash() keys must be strings, got {}",
+                            other.type_name()
+                        ))
+                    }
+                }
+            }
+            Ok(Object::Struct(template))
+        }
+        (Some(Object::Struct(_)), Some(o)) => {
+            Err(format!("from_hash() expects hash, got {}", o.type_name()))
+        }
+        (Some(Object::Struct(_)), None) => {
+            Err("from_hash() expects 2 arguments, got 1".to_string())
+        }
+        (Some(o), _) => Err(format!("from_hash() expects struct, got {}", o.type_name())),
+        (None, _) => Err("from_hash() expects 2 arguments, got 0".to_string()),
+    }
+}
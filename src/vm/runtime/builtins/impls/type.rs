@@ -2,7 +2,7 @@ use crate::vm::obj::Object;
 
 pub(crate) fn btype_fn(args: Vec<Object>) -> Result<Object, String> {
     match args.into_iter().next() {
-        Some(obj) => Ok(Object::String(obj.type_name())),
+        Some(obj) => Ok(Object::String(obj.type_name().into())),
         _ => Err("type() requires one argument".to_string()),
     }
 }
\ No newline at end of file
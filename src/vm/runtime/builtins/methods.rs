@@ -1,5 +1,6 @@
 use crate::vm::runtime::builtins::impls::{
-    array::*, float::*, hash::*, int::*, shared::*, string::*, struct_ops::*,
+    array::*, bigint::*, bytes::*, file::*, float::*, hash::*, int::*, reference::*, shared::*, string::*,
+    struct_ops::*,
 };
 use crate::vm::{obj::Object, runtime::runtime_errors::RuntimeError};
 
@@ -12,7 +13,16 @@ impl BuiltinMethods {
         args: Vec<Object>,
     ) -> Result<Object, RuntimeError> {
         match (&object, method_name) {
+            (Object::Native(native), _) if native.methods.methods.contains_key(method_name) => {
+                (native.methods.methods[method_name])(native.value.as_ref(), args)
+            }
+
             // Conversion methods
+            (Object::Bytes(_), "to_string") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bbytestostring_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
             (
                 Object::Integer(_)
                 | Object::Float(_)
@@ -42,16 +52,21 @@ impl BuiltinMethods {
             }
 
             // Shared methods
-            (Object::Array(_) | Object::String(_) | Object::Hash(_), "len") => {
+            (Object::Array(_) | Object::String(_) | Object::Hash(_) | Object::Bytes(_), "len") => {
                 let mut all_args = vec![object];
                 all_args.extend(args);
                 blen_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
-            (Object::String(_) | Object::Array(_) | Object::Hash(_), "is_empty") => {
+            (Object::String(_) | Object::Array(_) | Object::Hash(_) | Object::Bytes(_), "is_empty") => {
                 let mut all_args = vec![object];
                 all_args.extend(args);
                 bisempty_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
+            (Object::Hash(_) | Object::Array(_), "deep_copy") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bdeepcopy_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
             (Object::Hash(_) | Object::Array(_), "remove") => {
                 let mut all_args = vec![object];
                 all_args.extend(args);
@@ -84,6 +99,76 @@ impl BuiltinMethods {
                 all_args.extend(args);
                 btolower_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
+            (Object::String(_), "capitalize") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bcapitalize_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "title_case") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                btitlecase_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "pad_start") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bpadstart_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "pad_end") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bpadend_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "chars") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bchars_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "bytes") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bbytes_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "to_bytes") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bstrtobytes_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "char_at") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bcharat_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "find") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfind_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "rfind") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                brfind_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "count") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bcount_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "matches") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bmatches_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "match") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bmatch_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::String(_), "replace_regex") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                breplaceregex_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
             (Object::String(_), "starts_with") => {
                 let mut all_args = vec![object];
                 all_args.extend(args);
@@ -131,6 +216,139 @@ impl BuiltinMethods {
                 all_args.extend(args);
                 bcons_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
+            (Object::Array(_), "pop") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bpop_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "shift") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bshift_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "insert") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                binsert_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "reverse") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                breverse_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            #[cfg(feature = "random")]
+            (Object::Array(_), "shuffle") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bshuffle_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            #[cfg(not(feature = "random"))]
+            (Object::Array(_), "shuffle") => Err(RuntimeError::InvalidOperation(
+                "shuffle() needs g-lang built with the 'random' feature".to_string(),
+            )),
+            (Object::Array(_), "sum") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bsum_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "min") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                barray_min_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "max") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                barray_max_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Array(_), "avg") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bavg_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+
+            // Bytes methods
+            (Object::Bytes(_), "slice") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bslice_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Bytes(_), "to_array") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bbytestoarray_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Bytes(_), "to_hex") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bbytestohex_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+
+            // File methods
+            (Object::File(_), "read") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfileread_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "read_bytes") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfilereadbytes_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "read_line") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfilereadline_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "write") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfilewrite_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "seek") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfileseek_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "tell") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfiletell_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "close") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfileclose_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::File(_), "is_closed") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bfileisclosed_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+
+            // Socket methods
+            #[cfg(not(target_arch = "wasm32"))]
+            (Object::UdpSocket(socket), _) => {
+                crate::std::net::call_udp_method(std::sync::Arc::clone(socket), method_name, args)
+            }
+
+            // Ref methods
+            (Object::Ref(cell), _) => call_ref_method(cell, method_name, args),
+
+            // Collection methods
+            (Object::Collection(collection), _) => {
+                crate::std::collections::call_collection_method(collection, method_name, args)
+            }
+
+            (Object::OrderedMap(map), _) => {
+                crate::std::collections::call_ordered_map_method(map, method_name, args)
+            }
+
+            // Database methods
+            #[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+            (Object::Sqlite(handle), _) => crate::std::sqlite::call_sqlite_method(handle, method_name, args),
+            #[cfg(not(target_arch = "wasm32"))]
+            (Object::Db(handle), _) => crate::std::db::call_db_method(handle, method_name, args),
 
             // Int methods
             (Object::Integer(_), "pow") => {
@@ -153,6 +371,40 @@ impl BuiltinMethods {
                 all_args.extend(args);
                 babs_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
+            (Object::Integer(_) | Object::BigInteger(_), "mod_pow") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bmodpow_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+
+            // BigInteger methods
+            (Object::BigInteger(_), "pow") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bbigpow_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+
+            // Float methods
+            (Object::Float(_), "is_nan") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bisnan_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Float(_), "is_finite") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bisfinite_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Float(_), "to_fixed") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                btofixed_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Float(_), "round_to") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                broundto_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
 
             // Hash methods
             (Object::Hash(_), "set") => {
@@ -175,7 +427,32 @@ impl BuiltinMethods {
                 all_args.extend(args);
                 bvalues_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
-            (Object::Hash(_), "clear") => {
+            (Object::Hash(_), "entries") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bentries_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Hash(_), "get_or") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bgetor_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Hash(_), "set_default") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bsetdefault_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Hash(_), "merge") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bmerge_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Hash(_), "update") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bupdate_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Hash(_) | Object::Array(_), "clear") => {
                 let mut all_args = vec![object];
                 all_args.extend(args);
                 bclear_fn(all_args).map_err(RuntimeError::InvalidArguments)
@@ -202,6 +479,16 @@ impl BuiltinMethods {
                 all_args.extend(args);
                 bstruct_name_fn(all_args).map_err(RuntimeError::InvalidArguments)
             }
+            (Object::Struct(_), "to_hash") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bstruct_to_hash_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
+            (Object::Struct(_), "from_hash") => {
+                let mut all_args = vec![object];
+                all_args.extend(args);
+                bstruct_from_hash_fn(all_args).map_err(RuntimeError::InvalidArguments)
+            }
 
             // Method not found for this type
             _ => Err(RuntimeError::InvalidOperation(format!(
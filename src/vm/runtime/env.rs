@@ -15,6 +15,7 @@
 
 use crate::ast::ast::Ident;
 use crate::vm::runtime::builtins::functions::BuiltinsFunctions;
+use crate::vm::runtime::gc::Collector;
 use crate::vm::obj::Object;
 use ahash::{AHasher, HashMapExt};
 use std::hash::BuildHasherDefault;
@@ -64,6 +65,35 @@ impl Environment {
         }
     }
 
+    /// Moves the environment behind an `Arc<Mutex<_>>` and registers it with
+    /// the engine's cycle collector. Use this for every environment a
+    /// closure can capture.
+    pub(crate) fn into_shared(self, gc: &Collector) -> Arc<Mutex<Environment>> {
+        let shared = Arc::new(Mutex::new(self));
+        gc.register(&shared);
+        shared
+    }
+
+    /// Calls `f` with every environment this one keeps alive directly: its
+    /// parent and the environments of closures stored in it, including
+    /// closures nested in arrays, hashes and struct fields. Values with
+    /// shared interiors (refs, collections, futures) are not followed, so
+    /// the collector sees whatever they hold as an outside reference.
+    pub(crate) fn for_each_env_ref(&self, f: &mut impl FnMut(&Arc<Mutex<Environment>>)) {
+        if let Some(parent) = &self.parent {
+            f(parent);
+        }
+        for value in self.store.values() {
+            visit_env_refs(value, f);
+        }
+    }
+
+    /// Empties the environment and returns what it held, for the cycle
+    /// collector to drop once it has released its locks.
+    pub(crate) fn take_contents(&mut self) -> (HashMap<String, Object>, Option<Arc<Mutex<Environment>>>) {
+        (std::mem::take(&mut self.store), self.parent.take())
+    }
+
     fn fill_env_with_builtins(hashmap: &mut HashMap<String, Object>) {
         let builtins_functions = BuiltinsFunctions::new();
         let builtins = builtins_functions.get_builtins();
@@ -88,6 +118,11 @@ impl Environment {
         }
     }
 
+    /// Values bound directly in this environment, not in its parents.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &Object> {
+        self.store.values()
+    }
+
     pub(crate) fn get_by_name(&self, name: &str) -> Option<Object> {
         match self.store.get(name) {
             Some(o) => Some(o.clone()),
@@ -110,3 +145,26 @@ impl Environment {
 
     
 }
+
+fn visit_env_refs(value: &Object, f: &mut impl FnMut(&Arc<Mutex<Environment>>)) {
+    match value {
+        Object::Function(d) | Object::AsyncFunction(d) | Object::Method(d) => f(&d.env),
+        Object::Array(items) => {
+            for item in items.iter() {
+                visit_env_refs(item, f);
+            }
+        }
+        Object::Hash(hash) => {
+            for item in hash.values() {
+                visit_env_refs(item, f);
+            }
+        }
+        Object::Struct(s) => {
+            for item in s.fields.values().chain(s.methods.values()) {
+                visit_env_refs(item, f);
+            }
+        }
+        Object::ReturnValue(inner) | Object::ThrownValue(inner) => visit_env_refs(inner, f),
+        _ => {}
+    }
+}
@@ -0,0 +1,140 @@
+//! Cycle collection for closure environments.
+//!
+//! A closure keeps its defining [`Environment`] alive through an `Arc`, and an
+//! environment can in turn hold closures: assigning a new function to a
+//! captured variable stores it in the environment its own scope chain points
+//! back to. Such groups keep each other alive after the program has dropped
+//! every handle to them, which makes long-running REPL sessions and servers
+//! grow without bound.
+//!
+//! Each engine has its own [`Collector`], shared by the VMs it starts, so
+//! engines never scan or lock each other's environments. Every environment
+//! created for a call or a closure goes through [`Collector::register`].
+//! [`Collector::collect_cycles`] then does a trial deletion over the
+//! registered environments: references between them are subtracted from the
+//! `Arc` strong counts, anything left over is an outside reference (the VM
+//! stack, globals, a native handle), and whatever cannot be reached from an
+//! environment with outside references is garbage. Garbage environments are
+//! emptied, which breaks the cycle and lets the `Arc`s free normally.
+
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use ahash::HashMapExt;
+
+use crate::vm::obj::HashMap;
+use crate::vm::runtime::env::Environment;
+
+/// Fewest registrations between two automatic collections.
+const COLLECT_EVERY: usize = 10_000;
+
+#[derive(Default)]
+struct Registry {
+    envs: Vec<Weak<Mutex<Environment>>>,
+    since_collect: usize,
+    /// Environments still alive after the last collection.
+    live: usize,
+}
+
+/// The environments of one engine, and the cycle collector over them.
+#[derive(Default)]
+pub struct Collector {
+    registry: Mutex<Registry>,
+}
+
+impl Collector {
+    /// Tracks `env` for cycle collection. A collection runs once the
+    /// registrations since the last one reach [`COLLECT_EVERY`] or the
+    /// number of environments that survived it, whichever is larger, so
+    /// the scans stay proportional to the allocations.
+    pub(crate) fn register(&self, env: &Arc<Mutex<Environment>>) {
+        let due = {
+            let mut registry = self.registry.lock().unwrap();
+            registry.envs.push(Arc::downgrade(env));
+            registry.since_collect += 1;
+            registry.since_collect >= COLLECT_EVERY.max(registry.live)
+        };
+        if due {
+            self.collect_cycles();
+        }
+    }
+
+    /// Frees environments that are only kept alive by references among
+    /// themselves. Returns how many environments were released.
+    ///
+    /// Environments that are locked while the collector runs are treated as
+    /// reachable, so it is safe to call at any time; at worst a cycle survives
+    /// until the next collection.
+    pub fn collect_cycles(&self) -> usize {
+        let weak = {
+            let mut registry = self.registry.lock().unwrap();
+            registry.since_collect = 0;
+            registry.envs.retain(|w| w.strong_count() > 0);
+            registry.envs.clone()
+        };
+        let released = collect(&weak);
+        self.registry.lock().unwrap().live = weak.len() - released;
+        released
+    }
+}
+
+/// Runs one trial deletion over `weak`, returning how many environments
+/// it emptied.
+fn collect(weak: &[Weak<Mutex<Environment>>]) -> usize {
+    let nodes: Vec<Arc<Mutex<Environment>>> = weak.iter().filter_map(Weak::upgrade).collect();
+    let index: HashMap<*const Mutex<Environment>, usize> = {
+        let mut index = HashMap::with_capacity(nodes.len());
+        for (i, node) in nodes.iter().enumerate() {
+            index.insert(Arc::as_ptr(node), i);
+        }
+        index
+    };
+
+    // Hold every lock for the whole scan so edges can't move between the
+    // moment they are counted and the moment the strong counts are read.
+    let mut guards: Vec<Option<MutexGuard<'_, Environment>>> =
+        nodes.iter().map(|node| node.try_lock().ok()).collect();
+
+    let mut internal = vec![0usize; nodes.len()];
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (i, guard) in guards.iter().enumerate() {
+        if let Some(env) = guard {
+            env.for_each_env_ref(&mut |target| {
+                if let Some(&j) = index.get(&Arc::as_ptr(target)) {
+                    internal[j] += 1;
+                    edges[i].push(j);
+                }
+            });
+        }
+    }
+
+    // `nodes` itself holds one strong reference to each environment.
+    let mut reachable: Vec<bool> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| guards[i].is_none() || Arc::strong_count(node) - 1 > internal[i])
+        .collect();
+    let mut pending: Vec<usize> = (0..nodes.len()).filter(|&i| reachable[i]).collect();
+    while let Some(i) = pending.pop() {
+        for &j in &edges[i] {
+            if !reachable[j] {
+                reachable[j] = true;
+                pending.push(j);
+            }
+        }
+    }
+
+    // Take the contents out under the lock but drop them only after every
+    // guard is released, since dropping may free other environments.
+    let mut released = Vec::new();
+    for (i, guard) in guards.iter_mut().enumerate() {
+        if !reachable[i]
+            && let Some(env) = guard
+        {
+            released.push(env.take_contents());
+        }
+    }
+    drop(guards);
+    let count = released.len();
+    drop(released);
+    count
+}
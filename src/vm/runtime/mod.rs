@@ -3,14 +3,20 @@
 //! # Modules
 //!
 //! - `env` — scoped variable environments with O(1) slot-based lookups
+//! - `gc` — cycle collection for closure environments
 //! - `obj` — the [`Object`] enum representing all runtime values
 //! - `builtins` — standard library functions (string, math, io, http, etc.)
 //! - `module_registry` — module loading, caching, and WASM integration
+//! - `plugin_loader` — external programs imported as `plugin::` modules
+//! - `ast_cache` — on-disk cache of parsed module ASTs
 //! - `helpers` — shared evaluation utilities
 
+pub(crate) mod ast_cache;
 pub(crate) mod env;
+pub(crate) mod gc;
 pub(crate) mod builtins;
 pub(crate) mod module_registry;
 pub(crate) mod wasm_loader;
+pub(crate) mod plugin_loader;
 pub(crate) mod runtime_errors;
 pub(crate) mod type_converters;
\ No newline at end of file
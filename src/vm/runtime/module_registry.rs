@@ -1,16 +1,56 @@
 use crate::std::math::*;
 use crate::std::string::*;
 use crate::std::time::*;
+#[cfg(not(target_arch = "wasm32"))]
 use crate::std::io::*;
 use crate::std::json::*;
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
 use crate::std::http::*;
 use crate::std::env::*;
-use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::std::server::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::std::net::*;
+use crate::std::base64::*;
+#[cfg(feature = "random")]
+use crate::std::random::*;
+use crate::std::collections::*;
+use crate::std::set::*;
+use crate::std::log::*;
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+use crate::std::sqlite::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::std::db::*;
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+use crate::std::zip::*;
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+use crate::std::gzip::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::std::python::*;
+use crate::std::encoding::*;
+use crate::std::template::*;
+use crate::std::semver::*;
+use crate::std::ini::*;
+use crate::std::test::*;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
 use tokio::fs;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use crate::ast::ast::Program;
-use crate::vm::obj::{Object, HashMap};
+use crate::lexer::lexer::Lexer;
+use crate::lexer::token::{Location, SpannedTokens};
+use crate::package::installed_module_file;
+use crate::parser::await_ctx_helpers::validate_await_usage;
+use crate::parser::depth_check::{with_nesting_limit, DEFAULT_MAX_NESTING_DEPTH};
+use crate::parser::parser::Parser;
+use crate::parser::parser_errors::convert_nom_error;
+use crate::vm::obj::{NativeCallable, NativeFunctionData, Object, HashMap};
+use crate::vm::runtime::ast_cache;
+use crate::vm::runtime::gc::Collector;
+use crate::vm::runtime::plugin_loader::DEFAULT_PLUGIN_TIMEOUT;
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use crate::vm::sandbox::{default_capabilities, Capabilities, Capability};
 use ahash::HashMapExt;
 
 #[cfg(feature = "wasm")]
@@ -18,8 +58,26 @@ use crate::wasm::{WasmRuntime, WasmStore};
 
 pub struct ModuleRegistry {
     pub(crate) loaded_modules: HashMap<String, Module>,
-    stdlib: HashMap<String, Module>,
+    /// Constructors of the stdlib modules' export tables, keyed by module
+    /// path. A module is built on its first import and then cached in
+    /// `loaded_modules`.
+    stdlib: HashMap<&'static str, ExportsBuilder>,
     pub(crate) base_path: PathBuf,
+    /// Directories tried in order when a module is not under `base_path`,
+    /// before installed packages.
+    pub(crate) search_paths: Vec<PathBuf>,
+    /// What scripts importing through this registry may do.
+    pub(crate) capabilities: Capabilities,
+    /// Nesting limit for parsing the modules imported through this registry.
+    pub(crate) max_nesting_depth: usize,
+    /// How long a plugin may take to answer before it is killed.
+    pub(crate) plugin_timeout: Duration,
+    /// Generator of `std::random` and `math.random`, seeded per engine.
+    #[cfg(feature = "random")]
+    pub(crate) rng: RngState,
+    /// Cycle collector for the environments of the scripts run through
+    /// this registry.
+    pub(crate) gc: Arc<Collector>,
     #[cfg(feature = "wasm")]
     pub(crate) wasm_runtime: Option<WasmRuntime>,
     #[cfg(feature = "wasm")]
@@ -37,7 +95,7 @@ impl ModuleRegistry {
         #[cfg(feature = "wasm")]
         let (wasm_runtime, wasm_store) = match WasmRuntime::new() {
             Ok(runtime) => {
-                let store = runtime.create_store();
+                let store = runtime.create_store(default_capabilities());
                 (Some(runtime), Some(store))
             }
             Err(e) => {
@@ -50,136 +108,70 @@ impl ModuleRegistry {
             loaded_modules: HashMap::new(),
             stdlib: HashMap::new(),
             base_path,
+            search_paths: Vec::new(),
+            capabilities: default_capabilities(),
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            plugin_timeout: DEFAULT_PLUGIN_TIMEOUT,
+            #[cfg(feature = "random")]
+            rng: RngState::default(),
+            gc: Arc::default(),
             #[cfg(feature = "wasm")]
             wasm_runtime,
             #[cfg(feature = "wasm")]
             wasm_store,
         };
         
-        registry.load_stdlib();
+        registry.register_stdlib();
         
         registry
     }
     
-    fn load_stdlib(&mut self) {
-        // String modules
-        let mut string_exports = HashMap::new();
-        
-        string_exports.insert("join".to_string(), create_builtin("join", 2, 2, string_join));
-        string_exports.insert("reverse".to_string(), create_builtin("reverse", 1, 1, string_reverse));
-        string_exports.insert("repeat".to_string(), create_builtin("repeat", 2, 2, string_repeat));
-        string_exports.insert("chars".to_string(), create_builtin("chars", 1, 1, string_chars));
-        
-        self.stdlib.insert("std::string".to_string(), Module {
-            name: "std::string".to_string(),
-            exports: string_exports,
-        });
-
-        // Math modules
-        let mut math_exports = HashMap::new();
-        
-        math_exports.insert("clamp".to_string(), create_builtin("clamp", 3, 3, math_clamp));
-        math_exports.insert("random".to_string(), create_builtin("random", 0, 2, math_random));
-        math_exports.insert("round".to_string(), create_builtin("round", 1, 1, math_round));
-        math_exports.insert("floor".to_string(), create_builtin("floor", 1, 1, math_floor));
-        math_exports.insert("ceil".to_string(), create_builtin("ceil", 1, 1, math_ceil));
-        math_exports.insert("sqrt".to_string(), create_builtin("sqrt", 1, 1, math_sqrt));
-        math_exports.insert("sin".to_string(), create_builtin("sin", 1, 1, math_sin));
-        math_exports.insert("cos".to_string(), create_builtin("cos", 1, 1, math_cos));
-        math_exports.insert("tan".to_string(), create_builtin("tan", 1, 1, math_tan));
-        math_exports.insert("log".to_string(), create_builtin("log", 1, 1, math_log));
-        math_exports.insert("log10".to_string(), create_builtin("log10", 1, 1, math_log10));
-        math_exports.insert("abs".to_string(), create_builtin("abs", 1, 1, math_abs_int));
-        math_exports.insert("min".to_string(), create_builtin("min", 2, 2, math_min_int));
-        math_exports.insert("max".to_string(), create_builtin("max", 2, 2, math_max_int));
-        math_exports.insert("PI".to_string(), math_pi());
-        math_exports.insert("E".to_string(), math_e());
-
-        self.stdlib.insert("std::math".to_string(), Module {
-            name: "std::math".to_string(),
-            exports: math_exports,
-        });
-
-        // Time modules
-        let mut time_exports = HashMap::new();
-
-        time_exports.insert("now".to_string(), create_builtin("now", 0, 0, time_now));
-        time_exports.insert("sleep".to_string(), create_builtin_async("sleep", 1, 1, time_sleep_wrapper));
-
-        self.stdlib.insert("std::time".to_string(), Module {
-            name: "std::time".to_string(),
-            exports: time_exports,
-        });
-
-        // IO modules
-        let mut io_exports = HashMap::new();
-        
-        io_exports.insert("read_file".to_string(), create_builtin("read_file", 1, 1, io_read_file));
-        io_exports.insert("read_file_async".to_string(), create_builtin_async("read_file_async", 1, 1, io_read_file_wrapper));
-        io_exports.insert("write_file".to_string(), create_builtin("write_file", 2, 2, io_write_file));
-        io_exports.insert("write_file_async".to_string(), create_builtin_async("write_file_async", 2, 2, io_write_file_wrapper));
-        io_exports.insert("append_file".to_string(), create_builtin("append_file", 2, 2, io_append_file));
-        io_exports.insert("append_file_async".to_string(), create_builtin_async("append_file_async", 2, 2, io_append_file_wrapper));
-
-        io_exports.insert("exists".to_string(), create_builtin("exists", 1, 1, io_exists));
-        io_exports.insert("is_file".to_string(), create_builtin("is_file", 1, 1, io_is_file));
-
-        io_exports.insert("is_dir".to_string(), create_builtin("is_dir", 1, 1, io_is_dir));
-
-        io_exports.insert("list_dir".to_string(), create_builtin("list_dir", 1, 1, io_list_dir));
-        io_exports.insert("list_dir_async".to_string(), create_builtin_async("list_dir_async", 1, 1, io_list_dir_wrapper));
-
-        io_exports.insert("create_dir".to_string(), create_builtin("create_dir", 1, 1, io_create_dir));
-        io_exports.insert("create_dir_async".to_string(), create_builtin_async("create_dir_async", 1, 1, io_create_dir_wrapper));
-        io_exports.insert("delete_file".to_string(), create_builtin("delete_file", 1, 1, io_delete_file));
-        io_exports.insert("delete_file_async".to_string(), create_builtin_async("delete_file_async", 1, 1, io_delete_file_wrapper));
-        io_exports.insert("delete_dir".to_string(), create_builtin("delete_dir", 1, 1, io_delete_dir));
-        io_exports.insert("delete_dir_async".to_string(), create_builtin_async("delete_dir_async", 1, 1, io_delete_dir_wrapper));
-
-        self.stdlib.insert("std::io".to_string(), Module {
-            name: "std::io".to_string(),
-            exports: io_exports,
-        });
-
-        // JSON modules
-        let mut json_exports = HashMap::new();
-        
-        json_exports.insert("serialize".to_string(), create_builtin("serialize", 1, 1, json_serialize));
-        json_exports.insert("deserialize".to_string(), create_builtin("deserialize", 1, 1, json_deserialize));
-        json_exports.insert("prettify".to_string(), create_builtin("prettify", 1, 1, json_prettify));
-        json_exports.insert("validate".to_string(), create_builtin("validate", 1, 1, json_validate));
-
-        self.stdlib.insert("std::json".to_string(), Module {
-            name: "std::json".to_string(),
-            exports: json_exports,
-        });
+    /// Registers the constructor of every stdlib module the capabilities
+    /// allow. Nothing is built here: `load_module` builds a module's exports
+    /// on its first import.
+    fn register_stdlib(&mut self) {
+        for (path, build_exports) in stdlib_modules() {
+            if self.allows_module(path) {
+                self.stdlib.insert(path, *build_exports);
+            }
+        }
+    }
 
-        // HTTP modules
-        let mut http_exports = HashMap::new();
-        
-        http_exports.insert("get".to_string(), create_builtin_async("get", 1, 1, http_get));
-        http_exports.insert("post".to_string(), create_builtin_async("post", 2, 2, http_post));
-        http_exports.insert("put".to_string(), create_builtin_async("put", 2, 2, http_put));
-        http_exports.insert("delete".to_string(), create_builtin_async("delete", 1, 1, http_delete));
-
-        self.stdlib.insert("std::http".to_string(), Module {
-            name: "std::http".to_string(),
-            exports: http_exports,
+    /// Restricts later imports to what `capabilities` allow. Modules
+    /// imported before keep working where they were imported.
+    pub(crate) fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+        self.stdlib.clear();
+        self.register_stdlib();
+        // Rebuilt on their next import, with or without their restricted
+        // functions.
+        self.loaded_modules.retain(|path, _| {
+            required_capability(path).is_none()
+                && !RESTRICTED_EXPORTS.iter().any(|(module, _, _)| module == path)
+                && !path.starts_with("wasm::")
         });
+        // WASM modules get their WASI access from the store, so they are
+        // instantiated again in one that matches.
+        #[cfg(feature = "wasm")]
+        if let Some(runtime) = &self.wasm_runtime {
+            self.wasm_store = Some(runtime.create_store(capabilities));
+        }
+    }
 
-        // Env modules
-        let mut env_exports = HashMap::new();
-
-        env_exports.insert("args".to_string(), create_builtin("args", 0, 0, env_args));
-
-        self.stdlib.insert("std::env".to_string(), Module {
-            name: "std::env".to_string(),
-            exports: env_exports,
-        });
+    fn allows_module(&self, module_path: &str) -> bool {
+        required_capability(module_path).is_none_or(|capability| self.capabilities.allows(capability))
     }
-    
+
     pub async fn load_module(module_registry_arc: Arc<Mutex<Self>>, path: &[String]) -> Result<Module, RuntimeError> {
         let module_path = path.join("::");
+
+        if let Some(capability) = required_capability(&module_path)
+            && !module_registry_arc.lock().unwrap().capabilities.allows(capability)
+        {
+            return Err(RuntimeError::PermissionDenied(format!(
+                "'{}' needs {} access, which is disabled", module_path, capability
+            )));
+        }
         
         let loaded_module = {
             let registry = module_registry_arc.lock().unwrap();
@@ -191,13 +183,27 @@ impl ModuleRegistry {
         }
         
         let stdlib_module = {
-            let registry = module_registry_arc.lock().unwrap();
-            registry.stdlib.get(&module_path).cloned()
+            let mut registry = module_registry_arc.lock().unwrap();
+            registry.stdlib.get(module_path.as_str()).copied().map(|build_exports| {
+                let mut exports = build_exports();
+                restrict_exports(&module_path, &mut exports, registry.capabilities);
+                #[cfg(feature = "random")]
+                bind_exports(&module_path, &mut exports, &registry.rng);
+                let module = Module { name: module_path.clone(), exports };
+                registry.loaded_modules.insert(module_path.clone(), module.clone());
+                module
+            })
         };
 
         if let Some(module) = stdlib_module {
             return Ok(module);
         }
+
+        if let Some((_, feature)) = MISSING_STDLIB.iter().find(|(path, _)| *path == module_path) {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "{} needs g-lang built with the '{}' feature", module_path, feature
+            )));
+        }
         
         ModuleRegistry::load_user_module(module_registry_arc, path).await
     }
@@ -206,29 +212,30 @@ impl ModuleRegistry {
         if path.first().map(|s| s.as_str()) == Some("wasm") {
             return ModuleRegistry::load_wasm_module(module_registry_arc, &path[1..]).await;
         }
+        if path.first().map(|s| s.as_str()) == Some("plugin") {
+            return ModuleRegistry::load_plugin_module(module_registry_arc, &path[1..]).await;
+        }
 
-        let base_path = { module_registry_arc.lock().unwrap().base_path.clone() };
-        let mut file_path = base_path;
-        
-        for part in path {
-            if part == "super" {
-                if !file_path.pop() {
-                    return Err(RuntimeError::InvalidOperation(
-                        "Cannot use 'super::' at root level".to_string()
-                    ));
-                }
-            } else {
-                file_path.push(part);
+        let (base_path, search_paths) = {
+            let registry = module_registry_arc.lock().unwrap();
+            (registry.base_path.clone(), registry.search_paths.clone())
+        };
+        let mut file_path = module_file(&base_path, path)?;
+        if !file_path.exists() {
+            let searched = search_paths.iter().map(|dir| module_file(dir, path)).collect::<Result<Vec<_>, _>>()?;
+            if let Some(found) = searched.into_iter().find(|file| file.exists()) {
+                file_path = found;
+            } else if let Some(installed) = installed_module_file(&base_path, path) {
+                file_path = installed;
             }
         }
-        file_path.set_extension("g");
         
         let source = fs::read_to_string(&file_path).await
             .map_err(|e| RuntimeError::InvalidOperation(
                 format!("Failed to load module '{}': {}", path.join("::"), e)
             ))?;
         
-        let module = ModuleRegistry::parse_and_extract_module(Arc::clone(&module_registry_arc), &source, path).await?;
+        let module = ModuleRegistry::parse_and_extract_module(Arc::clone(&module_registry_arc), &source, &file_path, path).await?;
         
         let module_path = path.join("::");
         module_registry_arc.lock().unwrap().loaded_modules.insert(module_path.clone(), module.clone());
@@ -236,35 +243,57 @@ impl ModuleRegistry {
         Ok(module)
     }
     
-    async fn parse_and_extract_module(module_registry_arc: Arc<Mutex<Self>>, source: &str, path: &[String]) -> Result<Module, RuntimeError> {
-        use crate::{lexer::lexer::Lexer, parser::parser::Parser};
+    async fn parse_and_extract_module(module_registry_arc: Arc<Mutex<Self>>, source: &str, file_path: &Path, path: &[String]) -> Result<Module, RuntimeError> {
         use crate::vm::compiler::compute_slots::compute_slots;
         
-        let spanned_tokens = Lexer::lex_tokens(source.as_bytes())
-            .map_err(|e| RuntimeError::InvalidOperation(
-                format!("Failed to lex module: {}", e)
-            ))?;
-        
-        let spanned = crate::lexer::token::SpannedTokens::new(&spanned_tokens);
-        let tokens = spanned.to_tokens();
-        
-        let mut program = Parser::parse_tokens(tokens)
-            .map_err(|e| RuntimeError::InvalidOperation(
-                format!("Failed to parse module: {:?}", e)
-            ))?
-            .1;
+        let (base_path, max_nesting_depth, may_write_cache) = {
+            let registry = module_registry_arc.lock().unwrap();
+            (registry.base_path.clone(), registry.max_nesting_depth, registry.capabilities.allows(Capability::FileSystem))
+        };
+
+        let mut program = match ast_cache::load(&base_path, file_path, source, max_nesting_depth) {
+            Some(program) => program,
+            None => {
+                let program = parse_module(source, max_nesting_depth).map_err(|(location, message)| {
+                    // The position is already part of the error prefix.
+                    let message = match location {
+                        Some(loc) => message.strip_suffix(&format!(" at {}", loc)).unwrap_or(&message).to_string(),
+                        None => message,
+                    };
+                    RuntimeError::ModuleError { file: display_path(file_path), location, message }
+                })?;
+                if may_write_cache {
+                    ast_cache::store(&base_path, file_path, source, max_nesting_depth, &program);
+                }
+                program
+            }
+        };
         
         compute_slots(&mut program);
         
-        let base_path = { module_registry_arc.lock().unwrap().base_path.clone() };
         let registry_arc_for_eval = Arc::new(Mutex::new(ModuleRegistry::new(base_path)));
+        registry_arc_for_eval.lock().unwrap().search_paths = module_registry_arc.lock().unwrap().search_paths.clone();
+        let capabilities = module_registry_arc.lock().unwrap().capabilities;
+        registry_arc_for_eval.lock().unwrap().set_capabilities(capabilities);
+        registry_arc_for_eval.lock().unwrap().max_nesting_depth = max_nesting_depth;
+        registry_arc_for_eval.lock().unwrap().plugin_timeout = module_registry_arc.lock().unwrap().plugin_timeout;
+        #[cfg(feature = "random")]
+        {
+            let rng = Arc::clone(&module_registry_arc.lock().unwrap().rng);
+            registry_arc_for_eval.lock().unwrap().rng = rng;
+        }
+        let gc = Arc::clone(&module_registry_arc.lock().unwrap().gc);
+        registry_arc_for_eval.lock().unwrap().gc = gc;
         
         let loaded_modules_for_eval = { module_registry_arc.lock().unwrap().loaded_modules.clone() };
-        let wasm_runtime_for_eval = { module_registry_arc.lock().unwrap().wasm_runtime.clone() };
         for (key, val) in loaded_modules_for_eval {
             registry_arc_for_eval.lock().unwrap().loaded_modules.insert(key.clone(), val.clone());
         }
-        registry_arc_for_eval.lock().unwrap().wasm_runtime = wasm_runtime_for_eval;
+        #[cfg(feature = "wasm")]
+        {
+            let wasm_runtime_for_eval = module_registry_arc.lock().unwrap().wasm_runtime.clone();
+            registry_arc_for_eval.lock().unwrap().wasm_runtime = wasm_runtime_for_eval;
+        }
         
         let exports = ModuleRegistry::extract_exports(program, registry_arc_for_eval).await?;
         
@@ -279,6 +308,176 @@ impl ModuleRegistry {
     }
 }
 
+/// Parses a user module, or returns the position and message of its first
+/// lexer or parser error.
+/// The `.g` file `path` names relative to `dir`.
+fn module_file(dir: &Path, path: &[String]) -> Result<PathBuf, RuntimeError> {
+    let mut file_path = dir.to_path_buf();
+    for part in path {
+        if part == "super" {
+            if !file_path.pop() {
+                return Err(RuntimeError::InvalidOperation(
+                    "Cannot use 'super::' at root level".to_string()
+                ));
+            }
+        } else {
+            file_path.push(part);
+        }
+    }
+    file_path.set_extension("g");
+    Ok(file_path)
+}
+
+fn parse_module(source: &str, max_nesting_depth: usize) -> Result<Program, (Option<Location>, String)> {
+    with_nesting_limit(max_nesting_depth, || parse_module_source(source))
+}
+
+fn parse_module_source(source: &str) -> Result<Program, (Option<Location>, String)> {
+    let spanned_tokens = Lexer::lex_tokens(source.as_bytes()).map_err(|e| (Some(e.location()), e.to_string()))?;
+    let tokens = SpannedTokens::new(&spanned_tokens).to_tokens();
+
+    let program = match Parser::parse_syntax(tokens) {
+        Ok((_, program)) => program,
+        Err(e) => {
+            let error = match &e {
+                nom::Err::Error(err) | nom::Err::Failure(err) => {
+                    let error_index = tokens.token.len() - err.input.token.len();
+                    convert_nom_error(&e, "", &spanned_tokens, error_index)
+                }
+                nom::Err::Incomplete(_) => return Err((None, "Unexpected end of input".to_string())),
+            };
+            return Err((error.location(), error.to_string()));
+        }
+    };
+    validate_await_usage(&program).map_err(|e| (e.location(), e.to_string()))?;
+    Ok(program)
+}
+
+/// `path` as shown in errors, without the leading `./` of a module loaded
+/// relative to the working directory.
+fn display_path(path: &Path) -> String {
+    path.strip_prefix(".").unwrap_or(path).display().to_string()
+}
+
+/// Tokio has no file system on wasm32, where reading a module file fails
+/// like reading a missing one.
+#[cfg(target_arch = "wasm32")]
+mod fs {
+    pub(super) async fn read_to_string(path: impl AsRef<std::path::Path>) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// Builds the export table of one stdlib module.
+type ExportsBuilder = fn() -> HashMap<String, Object>;
+
+/// Every stdlib module with the constructor of its export table.
+const STDLIB: &[(&str, ExportsBuilder)] = &[
+    ("std::string", string_exports),
+    ("std::math", math_exports),
+    ("std::time", time_exports),
+    ("std::json", json_exports),
+    ("std::env", env_exports),
+    ("std::base64", base64_exports),
+    #[cfg(feature = "random")]
+    ("std::random", random_exports),
+    ("std::collections", collections_exports),
+    ("std::set", set_exports),
+    ("std::log", log_exports),
+    ("std::encoding", encoding_exports),
+    ("std::template", template_exports),
+    ("std::semver", semver_exports),
+    ("std::ini", ini_exports),
+    ("std::test", test_exports),
+];
+
+/// The stdlib modules that need the file system or the network, which a
+/// wasm32 build does not have.
+#[cfg(not(target_arch = "wasm32"))]
+const NATIVE_STDLIB: &[(&str, ExportsBuilder)] = &[
+    ("std::io", io_exports),
+    #[cfg(feature = "http")]
+    ("std::http", http_exports),
+    ("std::server", server_exports),
+    ("std::net", net_exports),
+    #[cfg(feature = "sqlite")]
+    ("std::sqlite", sqlite_exports),
+    ("std::db", db_exports),
+    #[cfg(feature = "archive")]
+    ("std::zip", zip_exports),
+    #[cfg(feature = "archive")]
+    ("std::gzip", gzip_exports),
+    ("std::python", python_exports),
+];
+#[cfg(target_arch = "wasm32")]
+const NATIVE_STDLIB: &[(&str, ExportsBuilder)] = &[];
+
+/// Stdlib modules behind a cargo feature this build was made without, with
+/// that feature.
+const MISSING_STDLIB: &[(&str, &str)] = &[
+    #[cfg(not(feature = "random"))]
+    ("std::random", "random"),
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "http")))]
+    ("std::http", "http"),
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "sqlite")))]
+    ("std::sqlite", "sqlite"),
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "archive")))]
+    ("std::zip", "archive"),
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "archive")))]
+    ("std::gzip", "archive"),
+];
+
+/// The capability importing `module_path` needs, if any.
+fn required_capability(module_path: &str) -> Option<Capability> {
+    match module_path {
+        "std::io" | "std::sqlite" | "std::zip" | "std::gzip" => Some(Capability::FileSystem),
+        "std::http" | "std::server" | "std::net" | "std::db" => Some(Capability::Network),
+        "std::python" => Some(Capability::Process),
+        "std::env" => Some(Capability::Env),
+        path if path.starts_with("plugin::") => Some(Capability::Process),
+        _ => None,
+    }
+}
+
+/// Functions of stdlib modules that are otherwise safe to import which
+/// need a capability.
+const RESTRICTED_EXPORTS: &[(&str, &str, Capability)] = &[
+    ("std::json", "read_lines", Capability::FileSystem),
+    ("std::json", "write_lines", Capability::FileSystem),
+    ("std::log", "to_file", Capability::FileSystem),
+    ("std::template", "render_file", Capability::FileSystem),
+    ("std::ini", "read_file", Capability::FileSystem),
+    ("std::ini", "write_file", Capability::FileSystem),
+];
+
+/// Replaces the functions of `exports` that need a capability not in
+/// `capabilities` with ones that fail with `PermissionDenied`.
+fn restrict_exports(module_path: &str, exports: &mut HashMap<String, Object>, capabilities: Capabilities) {
+    let module_name = module_path.rsplit("::").next().unwrap_or(module_path);
+    for (_, name, capability) in RESTRICTED_EXPORTS.iter().filter(|(module, _, _)| *module == module_path) {
+        if capabilities.allows(*capability) {
+            continue;
+        }
+        let message = format!("{}.{} needs {} access, which is disabled", module_name, name, capability);
+        let func = NativeCallable::Sync(Arc::new(move |_| Err(RuntimeError::PermissionDenied(message.clone()))));
+        exports.insert(name.to_string(), Object::NativeFunction(Box::new(NativeFunctionData { name: name.to_string(), func })));
+    }
+}
+
+fn stdlib_modules() -> impl Iterator<Item = &'static (&'static str, ExportsBuilder)> {
+    STDLIB.iter().chain(NATIVE_STDLIB)
+}
+
+/// Paths of all stdlib modules, e.g. `std::math`.
+pub(crate) fn stdlib_module_paths() -> impl Iterator<Item = &'static str> {
+    stdlib_modules().map(|(path, _)| *path)
+}
+
+/// Builds the exports of the stdlib module at `path`, without a registry.
+pub(crate) fn stdlib_exports(path: &str) -> Option<HashMap<String, Object>> {
+    stdlib_modules().find(|(p, _)| *p == path).map(|(_, build_exports)| build_exports())
+}
+
 fn create_builtin(name: &str, min: usize, max: usize, func: fn(Vec<Object>) -> Result<Object, RuntimeError>) -> Object {
     Object::BuiltinStd(Box::new(crate::vm::obj::BuiltinStdData {
         name: name.to_string(),
@@ -295,4 +494,396 @@ fn create_builtin_async(name: &str, min: usize, max: usize, func: fn(Vec<Object>
         max_params: max,
         func,
     }))
-}
\ No newline at end of file
+}
+
+// String modules
+fn string_exports() -> HashMap<String, Object> {
+    let mut string_exports = HashMap::new();
+    
+    string_exports.insert("join".to_string(), create_builtin("join", 2, 2, string_join));
+    string_exports.insert("reverse".to_string(), create_builtin("reverse", 1, 1, string_reverse));
+    string_exports.insert("repeat".to_string(), create_builtin("repeat", 2, 2, string_repeat));
+    string_exports.insert("chars".to_string(), create_builtin("chars", 1, 1, string_chars));
+    string_exports.insert("to_snake_case".to_string(), create_builtin("to_snake_case", 1, 1, string_to_snake_case));
+    string_exports.insert("to_camel_case".to_string(), create_builtin("to_camel_case", 1, 1, string_to_camel_case));
+    string_exports.insert("to_kebab_case".to_string(), create_builtin("to_kebab_case", 1, 1, string_to_kebab_case));
+    string_exports.insert("slugify".to_string(), create_builtin("slugify", 1, 1, string_slugify));
+    string_exports.insert("graphemes".to_string(), create_builtin("graphemes", 1, 1, string_graphemes));
+    string_exports.insert("char_count".to_string(), create_builtin("char_count", 1, 1, string_char_count));
+    string_exports.insert("normalize".to_string(), create_builtin("normalize", 1, 2, string_normalize));
+    string_exports.insert("width".to_string(), create_builtin("width", 1, 1, string_width));
+
+    string_exports
+}
+
+// Math modules
+fn math_exports() -> HashMap<String, Object> {
+    let mut math_exports = HashMap::new();
+    
+    math_exports.insert("clamp".to_string(), create_builtin("clamp", 3, 3, math_clamp));
+    #[cfg(feature = "random")]
+    math_exports.insert("random".to_string(), create_builtin("random", 0, 2, math_random));
+    math_exports.insert("round".to_string(), create_builtin("round", 1, 1, math_round));
+    math_exports.insert("floor".to_string(), create_builtin("floor", 1, 1, math_floor));
+    math_exports.insert("ceil".to_string(), create_builtin("ceil", 1, 1, math_ceil));
+    math_exports.insert("sqrt".to_string(), create_builtin("sqrt", 1, 1, math_sqrt));
+    math_exports.insert("sin".to_string(), create_builtin("sin", 1, 1, math_sin));
+    math_exports.insert("cos".to_string(), create_builtin("cos", 1, 1, math_cos));
+    math_exports.insert("tan".to_string(), create_builtin("tan", 1, 1, math_tan));
+    math_exports.insert("log".to_string(), create_builtin("log", 1, 1, math_log));
+    math_exports.insert("log10".to_string(), create_builtin("log10", 1, 1, math_log10));
+    math_exports.insert("pow".to_string(), create_builtin("pow", 2, 2, math_pow));
+    math_exports.insert("exp".to_string(), create_builtin("exp", 1, 1, math_exp));
+    math_exports.insert("atan2".to_string(), create_builtin("atan2", 2, 2, math_atan2));
+    math_exports.insert("hypot".to_string(), create_builtin("hypot", 2, 2, math_hypot));
+    math_exports.insert("asin".to_string(), create_builtin("asin", 1, 1, math_asin));
+    math_exports.insert("acos".to_string(), create_builtin("acos", 1, 1, math_acos));
+    math_exports.insert("atan".to_string(), create_builtin("atan", 1, 1, math_atan));
+    math_exports.insert("sinh".to_string(), create_builtin("sinh", 1, 1, math_sinh));
+    math_exports.insert("cosh".to_string(), create_builtin("cosh", 1, 1, math_cosh));
+    math_exports.insert("tanh".to_string(), create_builtin("tanh", 1, 1, math_tanh));
+    math_exports.insert("degrees".to_string(), create_builtin("degrees", 1, 1, math_degrees));
+    math_exports.insert("radians".to_string(), create_builtin("radians", 1, 1, math_radians));
+    math_exports.insert("trunc".to_string(), create_builtin("trunc", 1, 1, math_trunc));
+    math_exports.insert("sign".to_string(), create_builtin("sign", 1, 1, math_sign));
+    math_exports.insert("gcd".to_string(), create_builtin("gcd", 2, 2, math_gcd));
+    math_exports.insert("lcm".to_string(), create_builtin("lcm", 2, 2, math_lcm));
+    math_exports.insert("factorial".to_string(), create_builtin("factorial", 1, 1, math_factorial));
+    math_exports.insert("is_prime".to_string(), create_builtin("is_prime", 1, 1, math_is_prime));
+    math_exports.insert("fib".to_string(), create_builtin("fib", 1, 1, math_fib));
+    math_exports.insert("isqrt".to_string(), create_builtin("isqrt", 1, 1, math_isqrt));
+    math_exports.insert("abs".to_string(), create_builtin("abs", 1, 1, math_abs_int));
+    math_exports.insert("min".to_string(), create_builtin("min", 2, 2, math_min_int));
+    math_exports.insert("max".to_string(), create_builtin("max", 2, 2, math_max_int));
+    math_exports.insert("PI".to_string(), math_pi());
+    math_exports.insert("E".to_string(), math_e());
+
+    math_exports
+}
+
+// Time modules
+fn time_exports() -> HashMap<String, Object> {
+    let mut time_exports = HashMap::new();
+
+    time_exports.insert("now".to_string(), create_builtin("now", 0, 0, time_now));
+    time_exports.insert("sleep".to_string(), create_builtin_async("sleep", 1, 1, time_sleep_wrapper));
+    time_exports.insert("stopwatch".to_string(), create_builtin("stopwatch", 0, 0, time_stopwatch));
+    time_exports.insert("elapsed".to_string(), create_builtin("elapsed", 1, 1, time_elapsed));
+    time_exports.insert("duration".to_string(), create_builtin("duration", 1, 2, time_duration));
+    time_exports.insert("interval".to_string(), create_builtin("interval", 1, 1, time_interval));
+    time_exports.insert("tick".to_string(), create_builtin_async("tick", 1, 1, time_tick_wrapper));
+    time_exports.insert("stop_interval".to_string(), create_builtin("stop_interval", 1, 1, time_stop_interval));
+
+    time_exports
+}
+
+// IO modules
+#[cfg(not(target_arch = "wasm32"))]
+fn io_exports() -> HashMap<String, Object> {
+    let mut io_exports = HashMap::new();
+    
+    io_exports.insert("read_file".to_string(), create_builtin("read_file", 1, 1, io_read_file));
+    io_exports.insert("read_file_async".to_string(), create_builtin_async("read_file_async", 1, 1, io_read_file_wrapper));
+    io_exports.insert("write_file".to_string(), create_builtin("write_file", 2, 2, io_write_file));
+    io_exports.insert("write_file_async".to_string(), create_builtin_async("write_file_async", 2, 2, io_write_file_wrapper));
+    io_exports.insert("append_file".to_string(), create_builtin("append_file", 2, 2, io_append_file));
+    io_exports.insert("append_file_async".to_string(), create_builtin_async("append_file_async", 2, 2, io_append_file_wrapper));
+    io_exports.insert("open".to_string(), create_builtin("open", 1, 2, io_open));
+    io_exports.insert("read_bytes".to_string(), create_builtin("read_bytes", 1, 1, io_read_bytes));
+    io_exports.insert("read_bytes_async".to_string(), create_builtin_async("read_bytes_async", 1, 1, io_read_bytes_wrapper));
+    io_exports.insert("write_bytes".to_string(), create_builtin("write_bytes", 2, 2, io_write_bytes));
+    io_exports.insert("write_bytes_async".to_string(), create_builtin_async("write_bytes_async", 2, 2, io_write_bytes_wrapper));
+
+    io_exports.insert("exists".to_string(), create_builtin("exists", 1, 1, io_exists));
+    io_exports.insert("is_file".to_string(), create_builtin("is_file", 1, 1, io_is_file));
+
+    io_exports.insert("is_dir".to_string(), create_builtin("is_dir", 1, 1, io_is_dir));
+    io_exports.insert("metadata".to_string(), create_builtin("metadata", 1, 1, io_metadata));
+    io_exports.insert("metadata_async".to_string(), create_builtin_async("metadata_async", 1, 1, io_metadata_wrapper));
+    io_exports.insert("file_size".to_string(), create_builtin("file_size", 1, 1, io_file_size));
+
+    io_exports.insert("list_dir".to_string(), create_builtin("list_dir", 1, 1, io_list_dir));
+    io_exports.insert("list_dir_async".to_string(), create_builtin_async("list_dir_async", 1, 1, io_list_dir_wrapper));
+    io_exports.insert("walk".to_string(), create_builtin("walk", 1, 1, io_walk));
+    io_exports.insert("glob".to_string(), create_builtin("glob", 1, 1, io_glob));
+
+    io_exports.insert("create_dir".to_string(), create_builtin("create_dir", 1, 1, io_create_dir));
+    io_exports.insert("create_dir_async".to_string(), create_builtin_async("create_dir_async", 1, 1, io_create_dir_wrapper));
+    io_exports.insert("delete_file".to_string(), create_builtin("delete_file", 1, 1, io_delete_file));
+    io_exports.insert("delete_file_async".to_string(), create_builtin_async("delete_file_async", 1, 1, io_delete_file_wrapper));
+    io_exports.insert("delete_dir".to_string(), create_builtin("delete_dir", 1, 1, io_delete_dir));
+    io_exports.insert("delete_dir_async".to_string(), create_builtin_async("delete_dir_async", 1, 1, io_delete_dir_wrapper));
+
+    io_exports.insert("read_line".to_string(), create_builtin("read_line", 0, 0, io_read_line));
+    io_exports.insert("read_line_async".to_string(), create_builtin_async("read_line_async", 0, 0, io_read_line_wrapper));
+    io_exports.insert("read_all_stdin".to_string(), create_builtin("read_all_stdin", 0, 0, io_read_all_stdin));
+    io_exports.insert("read_all_stdin_async".to_string(), create_builtin_async("read_all_stdin_async", 0, 0, io_read_all_stdin_wrapper));
+    io_exports.insert("lines".to_string(), create_builtin("lines", 0, 0, io_lines));
+    io_exports.insert("lines_async".to_string(), create_builtin_async("lines_async", 0, 0, io_lines_wrapper));
+
+    io_exports.insert("copy".to_string(), create_builtin("copy", 2, 2, io_copy));
+    io_exports.insert("copy_async".to_string(), create_builtin_async("copy_async", 2, 2, io_copy_wrapper));
+    io_exports.insert("move".to_string(), create_builtin("move", 2, 2, io_move));
+    io_exports.insert("move_async".to_string(), create_builtin_async("move_async", 2, 2, io_move_wrapper));
+    io_exports.insert("rename".to_string(), create_builtin("rename", 2, 2, io_rename));
+    io_exports.insert("rename_async".to_string(), create_builtin_async("rename_async", 2, 2, io_rename_wrapper));
+
+    io_exports
+}
+
+// JSON modules
+fn json_exports() -> HashMap<String, Object> {
+    let mut json_exports = HashMap::new();
+    
+    json_exports.insert("serialize".to_string(), create_builtin("serialize", 1, 1, json_serialize));
+    json_exports.insert("serialize_pretty".to_string(), create_builtin("serialize_pretty", 1, 2, json_serialize_pretty));
+    json_exports.insert("deserialize".to_string(), create_builtin("deserialize", 1, 1, json_deserialize));
+    json_exports.insert("deserialize_lenient".to_string(), create_builtin("deserialize_lenient", 1, 1, json_deserialize_lenient));
+    json_exports.insert("read_lines".to_string(), create_builtin("read_lines", 1, 1, json_read_lines));
+    json_exports.insert("write_lines".to_string(), create_builtin("write_lines", 2, 2, json_write_lines));
+    json_exports.insert("prettify".to_string(), create_builtin("prettify", 1, 1, json_prettify));
+    json_exports.insert("validate".to_string(), create_builtin("validate", 1, 1, json_validate));
+
+    json_exports
+}
+
+// HTTP modules
+#[cfg(all(not(target_arch = "wasm32"), feature = "http"))]
+fn http_exports() -> HashMap<String, Object> {
+    let mut http_exports = HashMap::new();
+    
+    http_exports.insert("get".to_string(), create_builtin_async("get", 1, 2, http_get));
+    http_exports.insert("post".to_string(), create_builtin_async("post", 2, 3, http_post));
+    http_exports.insert("put".to_string(), create_builtin_async("put", 2, 3, http_put));
+    http_exports.insert("delete".to_string(), create_builtin_async("delete", 1, 2, http_delete));
+
+    http_exports
+}
+
+// Env modules
+fn env_exports() -> HashMap<String, Object> {
+    let mut env_exports = HashMap::new();
+
+    env_exports.insert("args".to_string(), create_builtin("args", 0, 0, env_args));
+    env_exports.insert("get".to_string(), create_builtin("get", 1, 1, env_get));
+    env_exports.insert("set".to_string(), create_builtin("set", 2, 2, env_set));
+    env_exports.insert("remove".to_string(), create_builtin("remove", 1, 1, env_remove));
+    env_exports.insert("vars".to_string(), create_builtin("vars", 0, 0, env_vars));
+    env_exports.insert("cwd".to_string(), create_builtin("cwd", 0, 0, env_cwd));
+    env_exports.insert("set_cwd".to_string(), create_builtin("set_cwd", 1, 1, env_set_cwd));
+    env_exports.insert("home_dir".to_string(), create_builtin("home_dir", 0, 0, env_home_dir));
+
+    env_exports
+}
+
+// Server modules
+#[cfg(not(target_arch = "wasm32"))]
+fn server_exports() -> HashMap<String, Object> {
+    let mut server_exports = HashMap::new();
+
+    server_exports.insert("new".to_string(), create_builtin("new", 0, 1, server_new));
+
+    server_exports
+}
+
+// Net modules
+#[cfg(not(target_arch = "wasm32"))]
+fn net_exports() -> HashMap<String, Object> {
+    let mut net_exports = HashMap::new();
+
+    net_exports.insert("udp_bind".to_string(), create_builtin_async("udp_bind", 1, 1, net_udp_bind));
+
+    net_exports
+}
+
+// Base64 modules
+fn base64_exports() -> HashMap<String, Object> {
+    let mut base64_exports = HashMap::new();
+
+    base64_exports.insert("encode".to_string(), create_builtin("encode", 1, 1, base64_encode));
+    base64_exports.insert("decode".to_string(), create_builtin("decode", 1, 1, base64_decode));
+    base64_exports.insert("decode_bytes".to_string(), create_builtin("decode_bytes", 1, 1, base64_decode_bytes));
+    base64_exports.insert("encode_url".to_string(), create_builtin("encode_url", 1, 1, base64_encode_url));
+    base64_exports.insert("decode_url".to_string(), create_builtin("decode_url", 1, 1, base64_decode_url));
+    base64_exports.insert("decode_url_bytes".to_string(), create_builtin("decode_url_bytes", 1, 1, base64_decode_url_bytes));
+
+    base64_exports
+}
+
+// Random modules
+#[cfg(feature = "random")]
+fn random_exports() -> HashMap<String, Object> {
+    let mut random_exports = HashMap::new();
+
+    random_exports.insert("seed".to_string(), create_builtin("seed", 0, 1, random_seed));
+    random_exports.insert("float".to_string(), create_builtin("float", 0, 0, random_float));
+    random_exports.insert("gaussian".to_string(), create_builtin("gaussian", 0, 2, random_gaussian));
+    random_exports.insert("choice".to_string(), create_builtin("choice", 1, 1, random_choice));
+    random_exports.insert("sample".to_string(), create_builtin("sample", 2, 2, random_sample));
+    random_exports.insert("shuffle".to_string(), create_builtin("shuffle", 1, 1, random_shuffle));
+
+    random_exports
+}
+
+// Collections modules
+fn collections_exports() -> HashMap<String, Object> {
+    let mut collections_exports = HashMap::new();
+
+    collections_exports.insert("queue".to_string(), create_builtin("queue", 0, 1, collections_queue));
+    collections_exports.insert("stack".to_string(), create_builtin("stack", 0, 1, collections_stack));
+    collections_exports.insert("deque".to_string(), create_builtin("deque", 0, 1, collections_deque));
+    collections_exports.insert("ordered_map".to_string(), create_builtin("ordered_map", 0, 1, collections_ordered_map));
+
+    collections_exports
+}
+
+// Set modules
+fn set_exports() -> HashMap<String, Object> {
+    let mut set_exports = HashMap::new();
+
+    set_exports.insert("from".to_string(), create_builtin("from", 1, 1, set_from));
+    set_exports.insert("union".to_string(), create_builtin("union", 2, 2, set_union));
+    set_exports.insert("intersection".to_string(), create_builtin("intersection", 2, 2, set_intersection));
+    set_exports.insert("difference".to_string(), create_builtin("difference", 2, 2, set_difference));
+    set_exports.insert("is_subset".to_string(), create_builtin("is_subset", 2, 2, set_is_subset));
+
+    set_exports
+}
+
+// Log modules
+fn log_exports() -> HashMap<String, Object> {
+    let mut log_exports = HashMap::new();
+
+    log_exports.insert("debug".to_string(), create_builtin("debug", 1, 2, log_debug));
+    log_exports.insert("info".to_string(), create_builtin("info", 1, 2, log_info));
+    log_exports.insert("warn".to_string(), create_builtin("warn", 1, 2, log_warn));
+    log_exports.insert("error".to_string(), create_builtin("error", 1, 2, log_error));
+    log_exports.insert("set_level".to_string(), create_builtin("set_level", 1, 1, log_set_level));
+    log_exports.insert("level".to_string(), create_builtin("level", 0, 0, log_level));
+    log_exports.insert("to_file".to_string(), create_builtin("to_file", 1, 1, log_to_file));
+
+    log_exports
+}
+
+// SQLite modules
+#[cfg(all(not(target_arch = "wasm32"), feature = "sqlite"))]
+fn sqlite_exports() -> HashMap<String, Object> {
+    let mut sqlite_exports = HashMap::new();
+
+    sqlite_exports.insert("open".to_string(), create_builtin("open", 1, 1, sqlite_open));
+
+    sqlite_exports
+}
+
+// Database modules
+#[cfg(not(target_arch = "wasm32"))]
+fn db_exports() -> HashMap<String, Object> {
+    let mut db_exports = HashMap::new();
+
+    db_exports.insert("connect".to_string(), create_builtin_async("connect", 1, 1, db_connect));
+
+    db_exports
+}
+
+// Zip modules
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+fn zip_exports() -> HashMap<String, Object> {
+    let mut zip_exports = HashMap::new();
+
+    zip_exports.insert("create".to_string(), create_builtin("create", 2, 2, zip_create));
+    zip_exports.insert("extract".to_string(), create_builtin("extract", 2, 2, zip_extract));
+    zip_exports.insert("list".to_string(), create_builtin("list", 1, 1, zip_list));
+
+    zip_exports
+}
+
+// Gzip modules
+#[cfg(all(not(target_arch = "wasm32"), feature = "archive"))]
+fn gzip_exports() -> HashMap<String, Object> {
+    let mut gzip_exports = HashMap::new();
+
+    gzip_exports.insert("compress".to_string(), create_builtin("compress", 1, 2, gzip_compress));
+    gzip_exports.insert("decompress".to_string(), create_builtin("decompress", 1, 1, gzip_decompress));
+    gzip_exports.insert("tar_create".to_string(), create_builtin("tar_create", 2, 2, gzip_tar_create));
+    gzip_exports.insert("tar_extract".to_string(), create_builtin("tar_extract", 2, 2, gzip_tar_extract));
+
+    gzip_exports
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn python_exports() -> HashMap<String, Object> {
+    let mut python_exports = HashMap::new();
+
+    python_exports.insert("exec".to_string(), create_builtin("exec", 1, 1, python_exec));
+    python_exports.insert("eval".to_string(), create_builtin("eval", 1, 1, python_eval));
+    python_exports.insert("module".to_string(), create_builtin("module", 1, 1, python_module));
+    python_exports.insert("call".to_string(), create_builtin("call", 1, usize::MAX, python_call));
+
+    python_exports
+}
+
+// Encoding modules
+fn encoding_exports() -> HashMap<String, Object> {
+    let mut encoding_exports = HashMap::new();
+
+    encoding_exports.insert("url_encode".to_string(), create_builtin("url_encode", 1, 1, encoding_url_encode));
+    encoding_exports.insert("url_decode".to_string(), create_builtin("url_decode", 1, 1, encoding_url_decode));
+    encoding_exports.insert("hex_encode".to_string(), create_builtin("hex_encode", 1, 1, encoding_hex_encode));
+    encoding_exports.insert("hex_decode".to_string(), create_builtin("hex_decode", 1, 1, encoding_hex_decode));
+    encoding_exports.insert("hex_decode_bytes".to_string(), create_builtin("hex_decode_bytes", 1, 1, encoding_hex_decode_bytes));
+    encoding_exports.insert("html_escape".to_string(), create_builtin("html_escape", 1, 1, encoding_html_escape));
+    encoding_exports.insert("html_unescape".to_string(), create_builtin("html_unescape", 1, 1, encoding_html_unescape));
+    encoding_exports.insert("serialize".to_string(), create_builtin("serialize", 1, 1, encoding_serialize));
+    encoding_exports.insert("deserialize".to_string(), create_builtin("deserialize", 1, 1, encoding_deserialize));
+
+    encoding_exports
+}
+
+// Template modules
+fn template_exports() -> HashMap<String, Object> {
+    let mut template_exports = HashMap::new();
+
+    template_exports.insert("render".to_string(), create_builtin("render", 1, 2, template_render));
+    template_exports.insert("render_file".to_string(), create_builtin("render_file", 1, 2, template_render_file));
+
+    template_exports
+}
+
+// Semver modules
+fn semver_exports() -> HashMap<String, Object> {
+    let mut semver_exports = HashMap::new();
+
+    semver_exports.insert("parse".to_string(), create_builtin("parse", 1, 1, semver_parse));
+    semver_exports.insert("valid".to_string(), create_builtin("valid", 1, 1, semver_valid));
+    semver_exports.insert("compare".to_string(), create_builtin("compare", 2, 2, semver_compare));
+    semver_exports.insert("satisfies".to_string(), create_builtin("satisfies", 2, 2, semver_satisfies));
+    semver_exports.insert("max_satisfying".to_string(), create_builtin("max_satisfying", 2, 2, semver_max_satisfying));
+    semver_exports.insert("sort".to_string(), create_builtin("sort", 1, 1, semver_sort));
+    semver_exports.insert("increment".to_string(), create_builtin("increment", 2, 2, semver_increment));
+
+    semver_exports
+}
+
+// INI modules
+fn ini_exports() -> HashMap<String, Object> {
+    let mut ini_exports = HashMap::new();
+
+    ini_exports.insert("parse".to_string(), create_builtin("parse", 1, 1, ini_parse));
+    ini_exports.insert("emit".to_string(), create_builtin("emit", 1, 1, ini_emit));
+    ini_exports.insert("read_file".to_string(), create_builtin("read_file", 1, 1, ini_read_file));
+    ini_exports.insert("write_file".to_string(), create_builtin("write_file", 2, 2, ini_write_file));
+
+    ini_exports
+}
+
+// Test modules
+fn test_exports() -> HashMap<String, Object> {
+    let mut test_exports = HashMap::new();
+
+    test_exports.insert("assert".to_string(), create_builtin("assert", 1, 2, test_assert));
+    test_exports.insert("assert_eq".to_string(), create_builtin("assert_eq", 2, 3, test_assert_eq));
+    test_exports.insert("assert_ne".to_string(), create_builtin("assert_ne", 2, 3, test_assert_ne));
+    test_exports.insert("fail".to_string(), create_builtin("fail", 0, 1, test_fail));
+
+    test_exports
+}
@@ -0,0 +1,218 @@
+//! Plugins: `import plugin::name` runs an external program and exposes the
+//! functions it offers as the module's exports.
+//!
+//! The program is `plugins/name` under the base path or a search path, or
+//! else `gl-plugin-name` on `PATH`. It talks JSON-RPC 2.0 over its standard
+//! input and output, one message per line; its standard error is the
+//! interpreter's. The interpreter first sends `initialize`, to which the
+//! plugin answers `{"functions": [...]}` with the names it exports. Calling
+//! one of them sends a request with that method name and the arguments as
+//! `params`, converted like `std::json` converts values, and waits for the
+//! converted `result`; an `error` fails the call with its message.
+//!
+//! The plugin runs until the last of its functions is dropped. A plugin
+//! that does not answer a request within the registry's plugin timeout is
+//! killed and the request fails.
+
+use crate::vm::runtime::module_registry::Module;
+use crate::vm::runtime::module_registry::ModuleRegistry;
+use std::sync::{Arc, Mutex};
+use crate::vm::runtime::runtime_errors::RuntimeError;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::std::json::{json_to_object, object_to_json};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::vm::obj::{HashMap, NativeCallable, NativeFunctionData, Object};
+#[cfg(not(target_arch = "wasm32"))]
+use ahash::HashMapExt;
+#[cfg(not(target_arch = "wasm32"))]
+use serde_json::{json, Value};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::{Child, Command, Stdio};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// How long a plugin may take to answer a request before it is killed.
+pub const DEFAULT_PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl ModuleRegistry {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn load_plugin_module(module_registry_arc: Arc<Mutex<Self>>, path: &[String]) -> Result<Module, RuntimeError> {
+        if path.is_empty() {
+            return Err(RuntimeError::InvalidOperation("plugin name is required".to_string()));
+        }
+        let name = path.join("::");
+
+        let (base_path, search_paths, timeout) = {
+            let registry = module_registry_arc.lock().unwrap();
+            (registry.base_path.clone(), registry.search_paths.clone(), registry.plugin_timeout)
+        };
+        let program = std::iter::once(&base_path)
+            .chain(&search_paths)
+            .map(|dir| plugin_file(dir, path))
+            .find(|file| file.is_file())
+            .unwrap_or_else(|| PathBuf::from(format!("gl-plugin-{}", path.join("-"))));
+
+        let plugin = Plugin::start(&name, &program, timeout)?;
+        let functions = match plugin.request("initialize", json!({ "interpreter": "gl", "version": env!("CARGO_PKG_VERSION") }))? {
+            Value::Object(mut result) => result.remove("functions"),
+            _ => None,
+        };
+        let Some(Value::Array(functions)) = functions else {
+            return Err(plugin.error("'initialize' must return an object with a 'functions' array"));
+        };
+
+        let plugin = Arc::new(plugin);
+        let mut exports = HashMap::new();
+        for function in functions {
+            let Value::String(function) = function else {
+                return Err(plugin.error("function names must be strings"));
+            };
+            exports.insert(function.clone(), plugin_function(&plugin, function));
+        }
+
+        let module = Module { name: name.clone(), exports };
+        module_registry_arc.lock().unwrap().loaded_modules.insert(format!("plugin::{}", name), module.clone());
+        Ok(module)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn load_plugin_module(_module_registry_arc: Arc<Mutex<Self>>, path: &[String]) -> Result<Module, RuntimeError> {
+        Err(RuntimeError::InvalidOperation(
+            format!("Plugins are not supported on this platform, cannot load 'plugin::{}'", path.join("::"))
+        ))
+    }
+}
+
+/// The executable `path` names under the `plugins` directory of `dir`.
+#[cfg(not(target_arch = "wasm32"))]
+fn plugin_file(dir: &Path, path: &[String]) -> PathBuf {
+    let mut file = dir.join("plugins");
+    file.extend(path);
+    if cfg!(windows) {
+        file.set_extension("exe");
+    }
+    file
+}
+
+/// The export `function` of `plugin`.
+#[cfg(not(target_arch = "wasm32"))]
+fn plugin_function(plugin: &Arc<Plugin>, function: String) -> Object {
+    let name = function.clone();
+    let plugin = Arc::clone(plugin);
+    let func = NativeCallable::Sync(Arc::new(move |args: Vec<Object>| {
+        let params = args.iter().map(object_to_json).collect::<Result<Vec<_>, _>>()?;
+        plugin.request(&function, Value::Array(params)).map(json_to_object)
+    }));
+    Object::NativeFunction(Box::new(NativeFunctionData { name, func }))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Plugin {
+    name: String,
+    child: Mutex<Child>,
+    timeout: Duration,
+    /// One request at a time, so each answer is read by whoever asked.
+    io: Mutex<PluginIo>,
+}
+
+/// The plugin's standard input and output, each served by a thread of its
+/// own so that waiting for an answer can give up after the timeout. The
+/// interpreter may be inside `block_on` here, so this does not use tokio.
+#[cfg(not(target_arch = "wasm32"))]
+struct PluginIo {
+    requests: Sender<String>,
+    lines: Receiver<std::io::Result<String>>,
+    next_id: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Plugin {
+    fn start(name: &str, program: &Path, timeout: Duration) -> Result<Self, RuntimeError> {
+        let mut child = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| RuntimeError::InvalidOperation(format!("Failed to start plugin '{}' ({}): {}", name, program.display(), e)))?;
+        let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(RuntimeError::InvalidOperation(format!("Failed to start plugin '{}'", name)));
+        };
+
+        let (requests, outgoing) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            for message in outgoing {
+                if stdin.write_all(message.as_bytes()).and_then(|_| stdin.flush()).is_err() {
+                    return;
+                }
+            }
+        });
+        let (incoming, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if incoming.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let io = PluginIo { requests, lines, next_id: 0 };
+        Ok(Plugin { name: name.to_string(), child: Mutex::new(child), timeout, io: Mutex::new(io) })
+    }
+
+    /// Sends the request `method` and waits for its answer. Lines that do
+    /// not answer it, such as notifications, are skipped. If no answer comes
+    /// within the timeout the plugin is killed.
+    fn request(&self, method: &str, params: Value) -> Result<Value, RuntimeError> {
+        let mut io = self.io.lock().unwrap();
+        let id = io.next_id;
+        io.next_id += 1;
+
+        let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string();
+        message.push('\n');
+        io.requests.send(message).map_err(|_| self.error("could not send a request: it exited"))?;
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let line = match io.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => line.map_err(|e| self.error(&format!("could not read its answer: {}", e)))?,
+                Err(RecvTimeoutError::Timeout) => {
+                    let _ = self.child.lock().unwrap().kill();
+                    return Err(self.error(&format!("no answer to '{}' within {} ms, stopped it", method, self.timeout.as_millis())));
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(self.error("it exited")),
+            };
+            let mut response: Value = match serde_json::from_str(&line) {
+                Ok(response) => response,
+                Err(e) => return Err(self.error(&format!("invalid JSON-RPC message: {}", e))),
+            };
+            if response.get("id") != Some(&json!(id)) {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                let message = error.get("message").and_then(Value::as_str).map_or_else(|| error.to_string(), str::to_string);
+                return Err(self.error(&message));
+            }
+            return Ok(response.get_mut("result").map(Value::take).unwrap_or(Value::Null));
+        }
+    }
+
+    fn error(&self, message: &str) -> RuntimeError {
+        RuntimeError::InvalidOperation(format!("plugin '{}': {}", self.name, message))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        let child = self.child.get_mut().unwrap();
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
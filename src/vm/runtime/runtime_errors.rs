@@ -23,6 +23,10 @@ pub enum ParserError {
     AwaitOutsideAsync {
         location: Option<Location>,
     },
+    NestingTooDeep {
+        limit: usize,
+        location: Option<Location>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +44,75 @@ pub enum RuntimeError {
     EmptyArray,
     InvalidArguments(String),
     UncaughtException(String),
+    /// A [`ResourceLimits`](crate::vm::limits::ResourceLimits) quota was hit
+    /// outside of any `try`.
+    ResourceLimitExceeded(String),
+    /// The step or time budget of the run is used up.
+    ExecutionLimitExceeded(String),
+    /// A `std::test` assertion did not hold.
+    AssertionFailed(String),
+    /// An imported module failed to load; `file` is the module's source file
+    /// and `location` where in it the problem is, when known.
+    ModuleError { file: String, location: Option<Location>, message: String },
+    /// The script used something that needs a capability the host denied
+    /// (see [`crate::vm::sandbox`]).
+    PermissionDenied(String),
+    /// The script called `exit`; the run ends with this status. `try` does
+    /// not catch it.
+    Exit(i32),
+}
+
+impl ParserError {
+    /// Where in the source the error was found, if known.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            ParserError::UnexpectedToken { location, .. }
+            | ParserError::ExpectedToken { location, .. }
+            | ParserError::InvalidExpression { location, .. }
+            | ParserError::UnexpectedEOF { location }
+            | ParserError::AwaitOutsideAsync { location }
+            | ParserError::NestingTooDeep { location, .. } => *location,
+        }
+    }
+
+    /// Stable identifier of the kind of error, for `--error-format=json`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::UnexpectedToken { .. } => "unexpected-token",
+            ParserError::ExpectedToken { .. } => "expected-token",
+            ParserError::InvalidExpression { .. } => "invalid-expression",
+            ParserError::UnexpectedEOF { .. } => "unexpected-eof",
+            ParserError::AwaitOutsideAsync { .. } => "await-outside-async",
+            ParserError::NestingTooDeep { .. } => "nesting-too-deep",
+        }
+    }
+}
+
+impl RuntimeError {
+    /// Stable identifier of the kind of error, for `--error-format=json`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeError::TypeMismatch { .. } => "type-mismatch",
+            RuntimeError::UndefinedVariable(_) => "undefined-variable",
+            RuntimeError::InvalidOperation(_) => "invalid-operation",
+            RuntimeError::DivisionByZero => "division-by-zero",
+            RuntimeError::ModuloByZero => "modulo-by-zero",
+            RuntimeError::IndexOutOfBounds { .. } => "index-out-of-bounds",
+            RuntimeError::WrongNumberOfArguments { .. } => "wrong-number-of-arguments",
+            RuntimeError::NotCallable(_) => "not-callable",
+            RuntimeError::NotHashable(_) => "not-hashable",
+            RuntimeError::NotIndexable(_) => "not-indexable",
+            RuntimeError::EmptyArray => "empty-array",
+            RuntimeError::InvalidArguments(_) => "invalid-arguments",
+            RuntimeError::UncaughtException(_) => "uncaught-exception",
+            RuntimeError::ResourceLimitExceeded(_) => "resource-limit-exceeded",
+            RuntimeError::ExecutionLimitExceeded(_) => "execution-limit-exceeded",
+            RuntimeError::AssertionFailed(_) => "assertion-failed",
+            RuntimeError::ModuleError { .. } => "module-error",
+            RuntimeError::PermissionDenied(_) => "permission-denied",
+            RuntimeError::Exit(_) => "exit",
+        }
+    }
 }
 
 impl fmt::Display for ParserError {
@@ -88,6 +161,13 @@ impl fmt::Display for ParserError {
                     write!(f, "Cannot use 'await' outside of an async function")
                 }
             }
+            ParserError::NestingTooDeep { limit, location } => {
+                if let Some(loc) = location {
+                    write!(f, "Code is nested too deeply (limit is {} levels) at {}", limit, loc)
+                } else {
+                    write!(f, "Code is nested too deeply (limit is {} levels)", limit)
+                }
+            }
         }
     }
 }
@@ -128,6 +208,15 @@ impl fmt::Display for RuntimeError {
             RuntimeError::EmptyArray => write!(f, "Cannot perform operation on empty array"),
             RuntimeError::InvalidArguments(s) => write!(f, "Invalid arguments: {}", s),
             RuntimeError::UncaughtException(s) => write!(f, "Uncaught exception: {}", s),
+            RuntimeError::ResourceLimitExceeded(s) => write!(f, "Resource limit exceeded: {}", s),
+            RuntimeError::ExecutionLimitExceeded(s) => write!(f, "Execution limit exceeded: {}", s),
+            RuntimeError::AssertionFailed(s) => write!(f, "Assertion failed: {}", s),
+            RuntimeError::ModuleError { file, location: Some(loc), message } => {
+                write!(f, "error in {}:{}:{}: {}", file, loc.line, loc.column, message)
+            }
+            RuntimeError::ModuleError { file, location: None, message } => write!(f, "error in {}: {}", file, message),
+            RuntimeError::PermissionDenied(s) => write!(f, "Permission denied: {}", s),
+            RuntimeError::Exit(code) => write!(f, "exit({})", code),
         }
     }
 }
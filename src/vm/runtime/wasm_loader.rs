@@ -1,14 +1,16 @@
-#[cfg(feature = "wasm")]
 use crate::vm::runtime::module_registry::Module;
 use crate::vm::runtime::module_registry::ModuleRegistry;
-use tokio::fs;
 use std::sync::{Arc, Mutex};
-use crate::vm::obj::{Object, HashMap};
 use crate::vm::runtime::runtime_errors::RuntimeError;
-use ahash::HashMapExt;
 
+#[cfg(feature = "wasm")]
+use crate::vm::obj::{Object, HashMap};
 #[cfg(feature = "wasm")]
 use crate::wasm::WasmModule;
+#[cfg(feature = "wasm")]
+use ahash::HashMapExt;
+#[cfg(feature = "wasm")]
+use tokio::fs;
 
 impl ModuleRegistry {
     #[cfg(feature = "wasm")]
@@ -68,7 +70,8 @@ impl ModuleRegistry {
         };
 
         let component = wasm_module.component();
-        let mut store = runtime.create_store();
+        let capabilities = module_registry_arc.lock().unwrap().capabilities;
+        let mut store = runtime.create_store(capabilities);
         let instance = wasm_module.instantiate(&mut store)?;
 
         let export_names = instance.get_export_names(&mut store, component);
@@ -106,7 +109,7 @@ impl ModuleRegistry {
     }
 
     #[cfg(not(feature = "wasm"))]
-    async fn load_wasm_module(_module_registry_arc: Arc<Mutex<Self>>, path: &[String]) -> Result<Module, RuntimeError> {
+    pub async fn load_wasm_module(_module_registry_arc: Arc<Mutex<Self>>, path: &[String]) -> Result<Module, RuntimeError> {
         Err(RuntimeError::InvalidOperation(
             format!("WASM support not enabled, cannot load module '{}'", path.join("::"))
         ))
@@ -0,0 +1,118 @@
+//! Capability groups a host can take away from scripts.
+//!
+//! Every [`ModuleRegistry`](crate::vm::runtime::module_registry::ModuleRegistry)
+//! carries a set of [`Capabilities`]. The stdlib modules that need a denied
+//! capability are left out of it, importing one fails with
+//! [`RuntimeError::PermissionDenied`](crate::vm::runtime::runtime_errors::RuntimeError::PermissionDenied),
+//! and the few functions of other modules that need one (`json.read_lines`,
+//! `log.to_file`, ...) fail with that error when called. `wasm::` modules
+//! get a WASI context with only the allowed access. Together with
+//! [`ResourceLimits`](crate::vm::limits::ResourceLimits) this is what makes
+//! running an untrusted script safe.
+//!
+//! New registries start from [`default_capabilities`], which is everything
+//! unless the host changed it with [`set_default_capabilities`], like `gl`
+//! does for `--deny`. An [`Engine`](crate::Engine) sets its own with
+//! [`Engine::set_capabilities`](crate::Engine::set_capabilities).
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A group of things scripts can do outside the interpreter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Reading and writing files: `std::io`, `std::sqlite`, `std::zip`,
+    /// `std::gzip`, the file functions of `std::json`, `std::ini`,
+    /// `std::log` and `std::template`, and the current directory for
+    /// `wasm::` modules.
+    FileSystem,
+    /// `std::http`, `std::server`, `std::net`, `std::db` and sockets for
+    /// `wasm::` modules.
+    Network,
+    /// Running other programs: `plugin::` imports and `std::python`.
+    Process,
+    /// Environment variables, arguments and the working directory:
+    /// `std::env`, and the environment and arguments of `wasm::` modules.
+    Env,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [Capability::FileSystem, Capability::Network, Capability::Process, Capability::Env];
+
+    /// The capability named `name` on the command line: `fs`, `net`,
+    /// `process` or `env`.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "fs" => Ok(Capability::FileSystem),
+            "net" => Ok(Capability::Network),
+            "process" => Ok(Capability::Process),
+            "env" => Ok(Capability::Env),
+            other => Err(format!("unknown capability '{}', expected fs, net, process or env", other)),
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Capability::FileSystem => write!(f, "file system"),
+            Capability::Network => write!(f, "network"),
+            Capability::Process => write!(f, "process"),
+            Capability::Env => write!(f, "environment"),
+        }
+    }
+}
+
+/// The capabilities scripts are allowed. The default allows everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    denied: u8,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl Capabilities {
+    /// Every capability allowed.
+    pub const fn all() -> Self {
+        Capabilities { denied: 0 }
+    }
+
+    /// No capability allowed.
+    pub const fn none() -> Self {
+        Capabilities { denied: u8::MAX }
+    }
+
+    /// These capabilities without `capability`.
+    pub fn deny(self, capability: Capability) -> Self {
+        Capabilities { denied: self.denied | capability.bit() }
+    }
+
+    /// These capabilities with `capability`.
+    pub fn allow(self, capability: Capability) -> Self {
+        Capabilities { denied: self.denied & !capability.bit() }
+    }
+
+    pub fn allows(self, capability: Capability) -> bool {
+        self.denied & capability.bit() == 0
+    }
+}
+
+static DEFAULT_DENIED: AtomicU8 = AtomicU8::new(0);
+
+/// The capabilities of every module registry created from now on.
+pub fn default_capabilities() -> Capabilities {
+    Capabilities { denied: DEFAULT_DENIED.load(Ordering::Relaxed) }
+}
+
+/// Sets [`default_capabilities`] for registries created afterwards.
+pub fn set_default_capabilities(capabilities: Capabilities) {
+    DEFAULT_DENIED.store(capabilities.denied, Ordering::Relaxed);
+}
@@ -27,12 +27,18 @@ use std::sync::{Arc, Mutex};
 
 use crate::vm::runtime::runtime_errors::RuntimeError;
 use crate::vm::runtime::env::Environment;
+use crate::vm::runtime::gc::Collector;
+#[cfg(feature = "random")]
+use crate::std::random::{self, RngState};
 use crate::vm::runtime::module_registry::ModuleRegistry;
 use crate::vm::obj::Object;
 use crate::vm::chunk::Chunk;
-use crate::vm::frame::CallFrame;
+use crate::vm::coverage;
+use crate::vm::frame::{CallFrame, LocalName};
 use crate::vm::instruction::Opcode;
+use crate::vm::limits::{self, ActiveLimits, HEAP_CHECK_INTERVAL, ResourceLimits};
 use crate::vm::ops;
+use crate::vm::profiler::{self, ShadowStack};
 use crate::vm::ops::exceptions::{handle_throw_result, ExceptionHandler};
 
 /// The result of executing a single instruction.
@@ -61,9 +67,25 @@ pub struct VirtualMachine {
     /// Flag indicating a return is pending (set when returning from finally block)
     pending_return: bool,
     /// Local names for the root frame (function parameters and local variables)
-    root_local_names: Vec<String>,
+    root_local_names: Arc<[LocalName]>,
     /// Closure environment for the root frame (used for functions defined in async contexts)
     root_closure_env: Option<Arc<Mutex<Environment>>>,
+    /// Frames being timed, when `gl run --profile` is active.
+    profile: Option<ShadowStack>,
+    /// Resource quotas; `None` when the script may allocate freely.
+    limits: Option<Arc<ActiveLimits>>,
+    /// Dispatched instructions since the heap was last measured.
+    steps_since_heap_check: usize,
+    /// Dispatched instructions between two heap measurements, which grows
+    /// with the heap so that measuring it stays cheap.
+    heap_check_interval: usize,
+    /// An allocation the builtin just called refused to make.
+    refused_alloc: Option<String>,
+    /// Cycle collector of the engine this VM runs for.
+    gc: Arc<Collector>,
+    /// Generator `random.seed(n)` set for the engine this VM runs for.
+    #[cfg(feature = "random")]
+    rng: RngState,
 }
 
 impl VirtualMachine {
@@ -72,6 +94,9 @@ impl VirtualMachine {
         globals: Arc<Mutex<Environment>>,
         module_registry: Arc<Mutex<ModuleRegistry>>,
     ) -> Self {
+        let gc = Arc::clone(&module_registry.lock().unwrap().gc);
+        #[cfg(feature = "random")]
+        let rng = Arc::clone(&module_registry.lock().unwrap().rng);
         VirtualMachine {
             stack: Vec::with_capacity(1024),
             frames: Vec::with_capacity(64),
@@ -79,13 +104,21 @@ impl VirtualMachine {
             module_registry,
             exception_handlers: Vec::with_capacity(16),
             pending_return: false,
-            root_local_names: Vec::new(),
+            root_local_names: Arc::from([]),
             root_closure_env: None,
+            profile: profiler::is_enabled().then(ShadowStack::default),
+            limits: None,
+            steps_since_heap_check: 0,
+            heap_check_interval: HEAP_CHECK_INTERVAL,
+            refused_alloc: None,
+            gc,
+            #[cfg(feature = "random")]
+            rng,
         }
     }
     
     /// Sets the local names for the root frame (used for function bodies)
-    pub fn set_root_local_names(&mut self, names: Vec<String>) {
+    pub fn set_root_local_names(&mut self, names: Arc<[LocalName]>) {
         self.root_local_names = names;
     }
 
@@ -94,6 +127,16 @@ impl VirtualMachine {
         self.root_closure_env = Some(env);
     }
 
+    /// Enforces `limits` on this VM and on the VMs it starts for async calls.
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = Some(Arc::new(ActiveLimits::new(limits)));
+    }
+
+    /// Shares the limits of the VM that spawned this one.
+    pub(crate) fn inherit_limits(&mut self, limits: Option<Arc<ActiveLimits>>) {
+        self.limits = limits;
+    }
+
     /// Creates a new VM with pre-initialized stack slots (for async function calls)
     pub fn new_with_slots(
         globals: Arc<Mutex<Environment>>,
@@ -101,6 +144,9 @@ impl VirtualMachine {
         slot_count: usize,
         initial_values: Vec<Object>,
     ) -> Self {
+        let gc = Arc::clone(&module_registry.lock().unwrap().gc);
+        #[cfg(feature = "random")]
+        let rng = Arc::clone(&module_registry.lock().unwrap().rng);
         let mut vm = VirtualMachine {
             stack: Vec::with_capacity(1024),
             frames: Vec::with_capacity(64),
@@ -108,8 +154,16 @@ impl VirtualMachine {
             module_registry,
             exception_handlers: Vec::with_capacity(16),
             pending_return: false,
-            root_local_names: Vec::new(),
+            root_local_names: Arc::from([]),
             root_closure_env: None,
+            profile: profiler::is_enabled().then(ShadowStack::default),
+            limits: None,
+            steps_since_heap_check: 0,
+            heap_check_interval: HEAP_CHECK_INTERVAL,
+            refused_alloc: None,
+            gc,
+            #[cfg(feature = "random")]
+            rng,
         };
         vm.stack.resize(slot_count, Object::Null);
         for (i, val) in initial_values.into_iter().enumerate() {
@@ -131,7 +185,7 @@ impl VirtualMachine {
             self.stack.resize(slot_count, Object::Null);
         }
         
-        let local_names = std::mem::take(&mut self.root_local_names);
+        let local_names = std::mem::replace(&mut self.root_local_names, Arc::from([]));
         self.frames
             .push(CallFrame::new_function_body(Arc::clone(&chunk), local_names));
         
@@ -142,6 +196,9 @@ impl VirtualMachine {
         }
 
         let mut result = self.execute().await;
+        if let Some(profile) = &mut self.profile {
+            profile.finish();
+        }
         
         // If the result is a Future, we need to await it
         // This handles the case where an async main() function is called at top level
@@ -187,6 +244,11 @@ impl VirtualMachine {
     /// fall through to the async dispatcher.
     async fn execute(&mut self) -> Result<Object, RuntimeError> {
         'outer_loop: loop {
+            // Frames are only pushed and popped outside the sync loop.
+            if let Some(profile) = &mut self.profile {
+                profile.sync(self.frames.iter().map(|f| &*f.chunk));
+            }
+
             // Get current frame once per iteration
             let frame = match self.frames.last_mut() {
                 Some(f) => f,
@@ -284,7 +346,7 @@ impl VirtualMachine {
                                 if let Some(v) = gv {
                                     self.stack.push(v);
                                 } else {
-                                    return Err(RuntimeError::UndefinedVariable(name.clone()));
+                                    return Err(RuntimeError::UndefinedVariable(name.to_string()));
                                 }
                             }
                         }
@@ -332,17 +394,23 @@ impl VirtualMachine {
                             match (a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => *ia = ia.wrapping_add(ib),
                                 (Object::Float(fa), Object::Float(fb)) => *fa += fb,
-                                (a_val, b_val) => {
-                                    let result = match (a_val.clone(), b_val) {
-                                        (Object::String(s), Object::String(t)) => Object::String(format!("{}{}", s, t)),
-                                        (Object::String(s), other) => Object::String(format!("{}{}", s, other)),
-                                        (other, Object::String(s)) => Object::String(format!("{}{}", other, s)),
+                                (a_val, b_val) if self.limits.is_none() => {
+                                    let result = match (std::mem::replace(a_val, Object::Null), b_val) {
+                                        (Object::String(mut s), Object::String(t)) => { s.push_str(&t); Object::String(s) }
+                                        (Object::String(s), other) => Object::String(format!("{}{}", s, other).into()),
+                                        (other, Object::String(s)) => Object::String(format!("{}{}", other, s).into()),
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::add(a, b),
                                     };
                                     *a_val = result;
                                 }
+                                // Concatenation can outgrow the limits, so let the
+                                // dispatcher run it and check the result.
+                                (_, b_val) => {
+                                    self.stack.push(b_val);
+                                    break 'sync_loop;
+                                }
                             }
                         }
                         ip += 1;
@@ -355,7 +423,7 @@ impl VirtualMachine {
                                 (Object::Integer(ia), Object::Integer(ib)) => *ia = ia.wrapping_sub(ib),
                                 (Object::Float(fa), Object::Float(fb)) => *fa -= fb,
                                 (a_val, b_val) => {
-                                    let result = match (a_val.clone(), b_val) {
+                                    let result = match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::subtract(a, b),
@@ -374,7 +442,7 @@ impl VirtualMachine {
                                 (Object::Integer(ia), Object::Integer(ib)) => *ia = ia.wrapping_mul(ib),
                                 (Object::Float(fa), Object::Float(fb)) => *fa *= fb,
                                 (a_val, b_val) => {
-                                    let result = match (a_val.clone(), b_val) {
+                                    let result = match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::multiply(a, b),
@@ -389,17 +457,17 @@ impl VirtualMachine {
                     0x23 => { // OpDivide
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => {
                                     if ib == 0 { return Err(RuntimeError::DivisionByZero); }
-                                    Object::Integer(ia / ib)
+                                    Object::Integer(ia.wrapping_div(ib))
                                 }
                                 (Object::Float(fa), Object::Float(fb)) => {
                                     if fb == 0.0 { return Err(RuntimeError::DivisionByZero); }
-                                    Object::Float(fa / fb)
+                                    Object::Float(*fa / fb)
                                 }
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::divide(a, b),
@@ -414,13 +482,13 @@ impl VirtualMachine {
                     0x24 => { // OpModulo
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => {
                                     if ib == 0 { return Err(RuntimeError::DivisionByZero); }
-                                    Object::Integer(ia % ib)
+                                    Object::Integer(ia.wrapping_rem(ib))
                                 }
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::modulo(a, b),
@@ -451,11 +519,11 @@ impl VirtualMachine {
                     0x27 => { // OpLessThan
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => Object::Boolean(*ia < ib),
                                 (Object::Float(fa), Object::Float(fb)) => Object::Boolean(*fa < fb),
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::less_than(a, b),
@@ -470,11 +538,11 @@ impl VirtualMachine {
                     0x28 => { // OpGreaterThan
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => Object::Boolean(*ia > ib),
                                 (Object::Float(fa), Object::Float(fb)) => Object::Boolean(*fa > fb),
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::greater_than(a, b),
@@ -489,11 +557,11 @@ impl VirtualMachine {
                     0x29 => { // OpLessEqual
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => Object::Boolean(*ia <= ib),
                                 (Object::Float(fa), Object::Float(fb)) => Object::Boolean(*fa <= fb),
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::less_equal(a, b),
@@ -508,11 +576,11 @@ impl VirtualMachine {
                     0x2A => { // OpGreaterEqual
                         let b = self.stack.pop().unwrap_or(Object::Null);
                         if let Some(a) = self.stack.last_mut() {
-                            let result = match (&*a, b) {
+                            let result = match (&mut *a, b) {
                                 (Object::Integer(ia), Object::Integer(ib)) => Object::Boolean(*ia >= ib),
                                 (Object::Float(fa), Object::Float(fb)) => Object::Boolean(*fa >= fb),
                                 (a_val, b_val) => {
-                                    match (a_val.clone(), b_val) {
+                                    match (std::mem::replace(a_val, Object::Null), b_val) {
                                         (Object::Error(e), _) => return Err(*e),
                                         (_, Object::Error(e)) => return Err(*e),
                                         (a, b) => ops::arithmetic::greater_equal(a, b),
@@ -547,7 +615,7 @@ impl VirtualMachine {
                                 Object::Integer(i) => *i = i.wrapping_neg(),
                                 Object::Float(f) => *f = -*f,
                                 a_val => {
-                                    *a_val = ops::arithmetic::execute_negate(a_val.clone());
+                                    *a_val = ops::arithmetic::execute_negate(std::mem::replace(a_val, Object::Null));
                                 }
                             }
                         }
@@ -560,6 +628,7 @@ impl VirtualMachine {
                             Object::Array(arr) => arr.len() as i64,
                             Object::String(s) => s.len() as i64,
                             Object::Hash(h) => h.len() as i64,
+                            Object::Bytes(b) => b.len() as i64,
                             _ => {
                                 return Err(RuntimeError::InvalidOperation(format!("Cannot get length of {}", a.type_name())));
                             }
@@ -632,6 +701,9 @@ impl VirtualMachine {
                         continue 'sync_loop;
                     }
                     0x31 => { // OpJumpBackward
+                        if let Some(limits) = &self.limits {
+                            limits.tick()?;
+                        }
                         ip = u16::from_be_bytes([code[ip + 1], code[ip + 2]]) as usize;
                         continue 'sync_loop;
                     }
@@ -693,6 +765,14 @@ impl VirtualMachine {
             let frame_count_before = self.frames.len();
             let result = self.dispatch(&chunk, &opcode, &read_u8, &read_u16).await?;
 
+            // Calls and `continue` count against the step budget, like the
+            // backward jump that ends every loop iteration.
+            if let Some(limits) = &self.limits
+                && (self.frames.len() > frame_count_before || matches!(result, ExecResult::ContinueLoop))
+            {
+                limits.tick()?;
+            }
+
             match result {
                 ExecResult::Continue => {
                     if self.frames.len() == frame_count_before
@@ -738,7 +818,7 @@ impl VirtualMachine {
                         }
                         Ok(_) => {}
                         Err(RuntimeError::UncaughtException(msg)) => {
-                            return Ok(Object::ThrownValue(Box::new(Object::String(msg))));
+                            return Ok(Object::ThrownValue(Box::new(Object::String(msg.into()))));
                         }
                         Err(e) => {
                             return Err(e);
@@ -777,6 +857,83 @@ impl VirtualMachine {
                     }
                 }
             }
+
+            if let Some(limits) = self.limits.clone()
+                && let Some(message) = self.check_limits(&limits, self.frames.len() == frame_count_before)
+                && let Some(value) = self.raise_limit(message)?
+            {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Checks the value an instruction just produced and, periodically, the
+    /// estimated heap usage against `limits`. A value that is too large is
+    /// popped so that it is not kept alive by the exception it causes.
+    fn check_limits(&mut self, active: &ActiveLimits, produced_value: bool) -> Option<String> {
+        let limits = &active.limits;
+        if let Some(message) = self.refused_alloc.take() {
+            // The builtin failed with this message; its error value stands
+            // for the allocation and is replaced by the exception.
+            self.stack.pop();
+            return Some(message);
+        }
+        if produced_value
+            && let Some(message) = self.stack.last().and_then(|value| limits.check_value(value))
+        {
+            self.stack.pop();
+            return Some(message);
+        }
+
+        limits.max_heap_bytes?;
+        self.steps_since_heap_check += 1;
+        if self.steps_since_heap_check < self.heap_check_interval {
+            return None;
+        }
+        self.steps_since_heap_check = 0;
+        let globals = self.globals.lock().unwrap();
+        let heap = limits::approx_heap_size(self.stack.iter().chain(globals.values()));
+        self.heap_check_interval = heap.values.max(HEAP_CHECK_INTERVAL);
+        limits.check_heap(heap.bytes)
+    }
+
+    /// Runs a synchronous call into builtins with this VM's limits visible
+    /// to them, keeping an allocation they refused for `check_limits`.
+    fn call_with_limits<T>(
+        &mut self,
+        call: impl FnOnce(&mut Self) -> Result<T, RuntimeError>,
+    ) -> Result<T, RuntimeError> {
+        let limits = self.limits.clone();
+        let (result, refused) = limits::with_call_limits(&limits, || call(self));
+        self.refused_alloc = refused.filter(|_| result.is_ok());
+        result
+    }
+
+    /// Runs a call into builtin methods with the engine's generator as the
+    /// one `shuffle` draws from.
+    #[cfg(feature = "random")]
+    fn with_engine_rng<T>(&mut self, call: impl FnOnce(&mut Self) -> T) -> T {
+        let rng = Arc::clone(&self.rng);
+        random::with_current(&rng, || call(self))
+    }
+
+    #[cfg(not(feature = "random"))]
+    fn with_engine_rng<T>(&mut self, call: impl FnOnce(&mut Self) -> T) -> T {
+        call(self)
+    }
+
+    /// Throws a limit violation as a string exception, or fails the run with
+    /// `ResourceLimitExceeded` when no `try` is active. Returns the value the
+    /// VM finishes with if the exception escapes.
+    fn raise_limit(&mut self, message: String) -> Result<Option<Object>, RuntimeError> {
+        let error = RuntimeError::ResourceLimitExceeded(message);
+        if self.exception_handlers.is_empty() {
+            return Err(error);
+        }
+        self.stack.push(Object::ThrownValue(Box::new(Object::String(error.to_string().into()))));
+        match handle_throw_result(&mut self.stack, &mut self.exception_handlers, &mut self.frames)? {
+            ExecResult::Throw => Ok(Some(self.stack.pop().unwrap_or(Object::Null))),
+            _ => Ok(None),
         }
     }
 
@@ -961,6 +1118,7 @@ impl VirtualMachine {
                     Object::Array(arr) => arr.len() as i64,
                     Object::String(s) => s.len() as i64,
                     Object::Hash(h) => h.len() as i64,
+                    Object::Bytes(b) => b.len() as i64,
                     _ => {
                         return Ok(ExecResult::ContinueWith(Object::Error(
                             Box::new(RuntimeError::InvalidOperation(format!(
@@ -1022,33 +1180,45 @@ impl VirtualMachine {
             }
             Opcode::OpCall => {
                 let argc = read_u8(1) as usize;
-                ops::calls::execute_call(
-                    &mut self.stack,
-                    &mut self.frames,
-                    &self.module_registry,
-                    &self.globals,
-                    argc,
-                )
+                self.call_with_limits(|vm| {
+                    ops::calls::execute_call(
+                        &mut vm.stack,
+                        &mut vm.frames,
+                        &vm.module_registry,
+                        &vm.globals,
+                        &vm.limits,
+                        &vm.gc,
+                        argc,
+                    )
+                })
             }
             Opcode::OpCallBuiltin => {
                 let argc = read_u8(1) as usize;
-                ops::calls::execute_call(
-                    &mut self.stack,
-                    &mut self.frames,
-                    &self.module_registry,
-                    &self.globals,
-                    argc,
-                )
+                self.call_with_limits(|vm| {
+                    ops::calls::execute_call(
+                        &mut vm.stack,
+                        &mut vm.frames,
+                        &vm.module_registry,
+                        &vm.globals,
+                        &vm.limits,
+                        &vm.gc,
+                        argc,
+                    )
+                })
             }
             Opcode::OpCallAsync => {
                 let argc = read_u8(1) as usize;
-                ops::calls::execute_call(
-                    &mut self.stack,
-                    &mut self.frames,
-                    &self.module_registry,
-                    &self.globals,
-                    argc,
-                )
+                self.call_with_limits(|vm| {
+                    ops::calls::execute_call(
+                        &mut vm.stack,
+                        &mut vm.frames,
+                        &vm.module_registry,
+                        &vm.globals,
+                        &vm.limits,
+                        &vm.gc,
+                        argc,
+                    )
+                })
             }
             Opcode::OpReturnValue => {
                 // Check if there's an active finally block we need to jump to
@@ -1068,6 +1238,7 @@ impl VirtualMachine {
                 ops::calls::execute_closure(
                     &mut self.stack,
                     &mut self.frames,
+                    &self.gc,
                 );
                 Ok(ExecResult::Continue)
             }
@@ -1140,6 +1311,17 @@ impl VirtualMachine {
             }
             Opcode::OpSetIndex => {
                 ops::collections::execute_set_index(&mut self.stack);
+                // The result is stored straight into the target, so a failed
+                // assignment must not be written into the variable.
+                if let Some(Object::Error(_)) = self.stack.last()
+                    && let Some(Object::Error(e)) = self.stack.pop()
+                {
+                    return Err(*e);
+                }
+                Ok(ExecResult::Continue)
+            }
+            Opcode::OpToIterable => {
+                ops::collections::execute_to_iterable(&mut self.stack);
                 Ok(ExecResult::Continue)
             }
             Opcode::OpBuildStruct => {
@@ -1153,19 +1335,35 @@ impl VirtualMachine {
             }
             Opcode::OpSetField => {
                 ops::structs::execute_set_field(&mut self.stack);
+                // The result is stored straight into the target, so a failed
+                // assignment must not be written into the variable.
+                if let Some(Object::Error(_)) = self.stack.last()
+                    && let Some(Object::Error(e)) = self.stack.pop()
+                {
+                    return Err(*e);
+                }
                 Ok(ExecResult::Continue)
             }
             Opcode::OpCallMethod => {
                 let argc = read_u8(1) as usize;
-                match ops::structs::execute_call_method(&mut self.stack, argc)? {
+                let method_call = self.call_with_limits(|vm| {
+                    vm.with_engine_rng(|vm| {
+                        ops::structs::execute_call_method(&mut vm.stack, argc, &vm.module_registry, &vm.globals)
+                    })
+                })?;
+                match method_call {
                     ops::structs::MethodCallResult::NeedsCall(new_argc) => {
-                        ops::calls::execute_call(
-                            &mut self.stack,
-                            &mut self.frames,
-                            &self.module_registry,
-                            &self.globals,
-                            new_argc,
-                        )
+                        self.call_with_limits(|vm| {
+                            ops::calls::execute_call(
+                                &mut vm.stack,
+                                &mut vm.frames,
+                                &vm.module_registry,
+                                &vm.globals,
+                                &vm.limits,
+                                &vm.gc,
+                                new_argc,
+                            )
+                        })
                     }
                     ops::structs::MethodCallResult::Done => {
                         Ok(ExecResult::Continue)
@@ -1224,6 +1422,10 @@ impl VirtualMachine {
                 ops::modules::execute_get_export(&mut self.stack);
                 Ok(ExecResult::Continue)
             }
+            Opcode::OpMarkLine => {
+                coverage::hit(read_u16(1));
+                Ok(ExecResult::Continue)
+            }
         }
     }
 }
\ No newline at end of file
@@ -6,6 +6,7 @@
 //! instantiation with unified function-call and memory APIs.
 
 use crate::vm::runtime::runtime_errors::RuntimeError;
+use crate::vm::sandbox::{default_capabilities, Capabilities, Capability};
 use std::path::Path;
 use wasmtime::component::types::ComponentItem;
 use wasmtime::component::{
@@ -37,40 +38,41 @@ impl WasmRuntime {
         &self.engine
     }
 
-    /// Constructs a WASI Preview 2 context with inherited stdio, env, args,
-    /// network access, and the current directory preopened.
-    pub fn create_wasi_ctx() -> WasiCtx {
-        WasiCtxBuilder::new()
-            .inherit_stdio()
-            .inherit_env()
-            .inherit_args()
-            .inherit_network()
-            .preopened_dir(".", ".", DirPerms::all(), FilePerms::all())
-            .unwrap()
-            .allow_blocking_current_thread(true)
-            .build()
+    /// A WASI builder with inherited stdio and whatever `capabilities`
+    /// grants: env and args with `Env`, network access with `Network`, and
+    /// the current directory preopened with `FileSystem`.
+    fn wasi_builder(capabilities: Capabilities) -> WasiCtxBuilder {
+        let mut builder = WasiCtxBuilder::new();
+        builder.inherit_stdio().allow_blocking_current_thread(true);
+        if capabilities.allows(Capability::Env) {
+            builder.inherit_env().inherit_args();
+        }
+        if capabilities.allows(Capability::Network) {
+            builder.inherit_network();
+        }
+        if capabilities.allows(Capability::FileSystem) {
+            builder.preopened_dir(".", ".", DirPerms::all(), FilePerms::all()).unwrap();
+        }
+        builder
+    }
+
+    /// Constructs a WASI Preview 2 context limited to `capabilities`.
+    pub fn create_wasi_ctx(capabilities: Capabilities) -> WasiCtx {
+        Self::wasi_builder(capabilities).build()
     }
 
     /// Constructs a WASI Preview 1 context (classic WASI) with the same
     /// permissions as `create_wasi_ctx`.
-    pub fn create_wasi_p1_ctx() -> WasiP1Ctx {
-        WasiCtxBuilder::new()
-            .inherit_stdio()
-            .inherit_env()
-            .inherit_args()
-            .inherit_network()
-            .preopened_dir(".", ".", DirPerms::all(), FilePerms::all())
-            .unwrap()
-            .allow_blocking_current_thread(true)
-            .build_p1()
+    pub fn create_wasi_p1_ctx(capabilities: Capabilities) -> WasiP1Ctx {
+        Self::wasi_builder(capabilities).build_p1()
     }
 
-    /// Creates a new `Store<WasmContext>` with both WASI P1 and P2 contexts
-    /// and a fresh resource table.
-    pub fn create_store(&self) -> Store<WasmContext> {
+    /// Creates a new `Store<WasmContext>` with both WASI P1 and P2 contexts,
+    /// limited to `capabilities`, and a fresh resource table.
+    pub fn create_store(&self, capabilities: Capabilities) -> Store<WasmContext> {
         let context = WasmContext {
-            wasi: Self::create_wasi_ctx(),
-            wasi_p1: Self::create_wasi_p1_ctx(),
+            wasi: Self::create_wasi_ctx(capabilities),
+            wasi_p1: Self::create_wasi_p1_ctx(capabilities),
             table: ResourceTable::new(),
         };
         Store::new(&self.engine, context)
@@ -498,12 +500,13 @@ impl WasiView for WasmContext {
     }
 }
 
-// Default implementation creates fresh WASI contexts
+// Default implementation creates fresh WASI contexts with the default
+// capabilities
 impl Default for WasmContext {
     fn default() -> Self {
         Self {
-            wasi: WasmRuntime::create_wasi_ctx(),
-            wasi_p1: WasmRuntime::create_wasi_p1_ctx(),
+            wasi: WasmRuntime::create_wasi_ctx(default_capabilities()),
+            wasi_p1: WasmRuntime::create_wasi_p1_ctx(default_capabilities()),
             table: ResourceTable::new(),
         }
     }